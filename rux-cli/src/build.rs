@@ -1,120 +1,233 @@
+use std::cell::Cell;
 use std::path::{Path, PathBuf};
-use std::process::Command;
-use anyhow::{Context, Result};
+use anyhow::Result;
+use std::time::Duration;
+use rux_compiler::{CompilerOptions, PassTimings};
+use crate::cache::{BuildCache, CacheStats};
+use crate::commands::{print_json, with_file, OutputFormat};
 
-pub struct BuildSystem;
+pub struct BuildSystem {
+    format: OutputFormat,
+    profile: bool,
+    minify: bool,
+    compress: bool,
+    src_dir: PathBuf,
+    target_dir: PathBuf,
+    rux_core: Option<PathBuf>,
+    cache_stats: Cell<CacheStats>,
+}
 
 impl BuildSystem {
     pub fn new() -> Self {
-        Self
+        Self::with_format(OutputFormat::Human)
+    }
+    
+    pub fn with_format(format: OutputFormat) -> Self {
+        Self {
+            format,
+            profile: false,
+            minify: false,
+            compress: false,
+            src_dir: PathBuf::from("src"),
+            target_dir: PathBuf::from("target/rux"),
+            rux_core: workspace_rux_core(),
+            cache_stats: Cell::new(CacheStats::default()),
+        }
+    }
+    
+    /// Reports per-pass compile times once the project is compiled
+    pub fn with_profile(mut self, profile: bool) -> Self {
+        self.profile = profile;
+        self
+    }
+    
+    /// Writes `index.html` without formatting whitespace
+    pub fn with_minify(mut self, minify: bool) -> Self {
+        self.minify = minify;
+        self
+    }
+    
+    /// Writes `.gz` and `.br` copies of each web artifact next to it
+    pub fn with_compress(mut self, compress: bool) -> Self {
+        self.compress = compress;
+        self
+    }
+    
+    /// Directory the `.rsx` sources are read from, `src` by default
+    pub fn with_src_dir(mut self, src_dir: impl Into<PathBuf>) -> Self {
+        self.src_dir = src_dir.into();
+        self
+    }
+    
+    /// Directory the generated crate is written to, `target/rux` by default
+    pub fn with_target_dir(mut self, target_dir: impl Into<PathBuf>) -> Self {
+        self.target_dir = target_dir.into();
+        self
+    }
+    
+    /// rux-core checkout the generated crate depends on by path. `None`
+    /// depends on the crates.io release matching this CLI; the default is
+    /// the workspace's rux-core when the CLI was built from the workspace.
+    pub fn with_rux_core(mut self, rux_core: Option<PathBuf>) -> Self {
+        self.rux_core = rux_core;
+        self
+    }
+    
+    /// How much generated code the last build reused from `out_dir/.cache`
+    pub fn cache_stats(&self) -> CacheStats {
+        self.cache_stats.get()
+    }
+    
+    /// Progress output. Goes to stderr in JSON mode so stdout holds only
+    /// the diagnostics array.
+    pub fn status(&self, message: &str) {
+        match self.format {
+            OutputFormat::Human => println!("{}", message),
+            OutputFormat::Json => eprintln!("{}", message),
+        }
     }
     
     pub fn build_web(&self, out_dir: &Path, release: bool) -> Result<()> {
-        println!("Building for web target...");
+        self.status("Building for web target...");
         
-        // 1. Find all .rsx files
-        let rsx_files = Self::find_rsx_files(Path::new("src"))?;
-        println!("Found {} .rsx files", rsx_files.len());
-        
-        // 2. Compile .rsx files to Rust
-        let mut compiler = rux_compiler::Compiler::new();
-        let mut generated_rust = String::new();
-        
-        generated_rust.push_str("// Auto-generated from .rsx files\n");
-        generated_rust.push_str("use rux_core::virtual_tree::{VirtualNode, NodeType, PropValue};\n");
-        generated_rust.push_str("use std::collections::HashMap;\n\n");
-        
-        for rsx_file in &rsx_files {
-            println!("Compiling {:?}...", rsx_file);
-            match compiler.compile_file(rsx_file) {
-                Ok(ast) => {
-                    let mut codegen = rux_compiler::CodeGenerator::new();
-                    match codegen.generate_rust_code(&ast) {
-                        Ok(rust_code) => {
-                            generated_rust.push_str(&format!("// From {:?}\n", rsx_file));
-                            generated_rust.push_str(&rust_code);
-                            generated_rust.push_str("\n\n");
-                        }
-                        Err(e) => {
-                            eprintln!("Error generating code for {:?}: {}", rsx_file, e);
-                            return Err(e.into());
-                        }
-                    }
-                }
-                Err(e) => {
-                    eprintln!("Error compiling {:?}: {}", rsx_file, e);
-                    return Err(e.into());
-                }
-            }
-        }
+        // 1-2. Compile all .rsx files in the source directory as one project
+        let generated_rust = self.compile_project(out_dir, release)?;
         
         // 3. Write generated Rust code
-        std::fs::create_dir_all(out_dir)?;
-        let generated_path = out_dir.join("generated.rs");
-        std::fs::write(&generated_path, generated_rust)?;
-        println!("Generated Rust code: {:?}", generated_path);
+        let crate_dir = self.write_generated_crate(&generated_rust)?;
+        self.status(&format!("Generated Rust crate: {:?}", crate_dir));
         
         // 4. Compile to WASM (would use wasm-pack in real implementation)
-        println!("WASM compilation would happen here (requires wasm-pack)");
+        self.status("WASM compilation would happen here (requires wasm-pack)");
         
         // 5. Generate HTML entry point
-        let html = Self::generate_html();
+        std::fs::create_dir_all(out_dir)?;
+        let html = self.generate_html();
         let html_path = out_dir.join("index.html");
         std::fs::write(&html_path, html)?;
-        println!("Generated HTML: {:?}", html_path);
+        self.status(&format!("Generated HTML: {:?}", html_path));
+        
+        // 6. Precompress what the browser downloads
+        if self.compress {
+            for path in crate::compress::compress_artifacts(out_dir)? {
+                self.status(&format!("Compressed: {:?}", path));
+            }
+        }
         
         Ok(())
     }
     
     pub fn build_desktop(&self, out_dir: &Path, release: bool) -> Result<()> {
-        println!("Building for desktop target...");
+        self.status("Building for desktop target...");
         
-        // 1. Find all .rsx files
-        let rsx_files = Self::find_rsx_files(Path::new("src"))?;
-        println!("Found {} .rsx files", rsx_files.len());
+        // 1-2. Compile all .rsx files in the source directory as one project
+        let generated_rust = self.compile_project(out_dir, release)?;
         
-        // 2. Compile .rsx files to Rust
-        let mut compiler = rux_compiler::Compiler::new();
-        let mut generated_rust = String::new();
+        // 3. Write generated Rust code
+        let crate_dir = self.write_generated_crate(&generated_rust)?;
+        self.status(&format!("Generated Rust crate: {:?}", crate_dir));
         
-        generated_rust.push_str("// Auto-generated from .rsx files\n");
-        generated_rust.push_str("use rux_core::virtual_tree::{VirtualNode, NodeType, PropValue};\n");
-        generated_rust.push_str("use std::collections::HashMap;\n\n");
-        
-        for rsx_file in &rsx_files {
-            println!("Compiling {:?}...", rsx_file);
-            match compiler.compile_file(rsx_file) {
-                Ok(ast) => {
-                    let mut codegen = rux_compiler::CodeGenerator::new();
-                    match codegen.generate_rust_code(&ast) {
-                        Ok(rust_code) => {
-                            generated_rust.push_str(&format!("// From {:?}\n", rsx_file));
-                            generated_rust.push_str(&rust_code);
-                            generated_rust.push_str("\n\n");
-                        }
-                        Err(e) => {
-                            eprintln!("Error generating code for {:?}: {}", rsx_file, e);
-                            return Err(e.into());
-                        }
-                    }
-                }
-                Err(e) => {
-                    eprintln!("Error compiling {:?}: {}", rsx_file, e);
-                    return Err(e.into());
-                }
+        // 4. Compile to native binary (would use cargo build in real implementation)
+        self.status("Native compilation would happen here (requires cargo build)");
+        
+        Ok(())
+    }
+    
+    /// Writes the generated code as a crate under the target directory:
+    /// `src/generated.rs` holds the code and `src/lib.rs` re-exports it.
+    /// The manifest has its own `[workspace]` so the crate builds on its
+    /// own even when the target directory is inside another workspace.
+    fn write_generated_crate(&self, generated_rust: &str) -> Result<PathBuf> {
+        let src = self.target_dir.join("src");
+        std::fs::create_dir_all(&src)?;
+        std::fs::write(src.join("generated.rs"), generated_rust)?;
+        std::fs::write(src.join("lib.rs"), "// Auto-generated by rux build\n\npub mod generated;\n\npub use generated::*;\n")?;
+        
+        let rux_core = match &self.rux_core {
+            Some(path) => format!("{{ path = {:?} }}", path.display().to_string()),
+            None => format!("{:?}", env!("CARGO_PKG_VERSION")),
+        };
+        let manifest = format!(
+            "[package]\nname = \"rux-generated\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[dependencies]\nrux-core = {}\n\n[workspace]\n",
+            rux_core
+        );
+        std::fs::write(self.target_dir.join("Cargo.toml"), manifest)?;
+        Ok(self.target_dir.clone())
+    }
+    
+    fn compile_project(&self, out_dir: &Path, release: bool) -> Result<String> {
+        let src_dir = self.src_dir.as_path();
+        let rsx_files = Self::find_rsx_files(src_dir)?;
+        self.status(&format!("Found {} .rsx files", rsx_files.len()));
+        
+        let options = CompilerOptions {
+            profile: self.profile,
+            ..Self::compiler_options(release)
+        };
+        let mut compiler = rux_compiler::Compiler::new_with_options(options);
+        let result = compiler.compile_project(src_dir);
+        
+        if self.format == OutputFormat::Json {
+            // Warnings from a project build are not attributed to a file yet
+            let mut diagnostics: Vec<_> = compiler
+                .warnings()
+                .diagnostics()
+                .iter()
+                .map(|warning| with_file(warning.to_json(), None))
+                .collect();
+            if let Err(e) = &result {
+                diagnostics.push(with_file(e.to_json(), compiler.error_file()));
+            }
+            print_json(&mut std::io::stdout(), diagnostics)?;
+        } else {
+            for warning in compiler.warnings().diagnostics() {
+                eprintln!("{}", warning);
+            }
+            match (&result, compiler.error_file()) {
+                (Err(e), Some(path)) => eprintln!("Error compiling {:?}: {}", path, e),
+                (Err(e), None) => eprintln!("Error compiling project: {}", e),
+                _ => {}
             }
         }
+        let ast = result?;
         
-        // 3. Write generated Rust code
-        std::fs::create_dir_all(out_dir)?;
-        let generated_path = out_dir.join("generated.rs");
-        std::fs::write(&generated_path, generated_rust)?;
-        println!("Generated Rust code: {:?}", generated_path);
+        let mut cache = BuildCache::open(out_dir)?;
+        let rust_code = match compiler.generate_rust_cached(&ast, &mut cache) {
+            Ok(rust_code) => rust_code,
+            Err(e) => {
+                eprintln!("Error generating code: {}", e);
+                return Err(e.into());
+            }
+        };
+        cache.prune()?;
+        let stats = cache.stats();
+        self.cache_stats.set(stats);
+        self.status(&format!("Reused {} cached items, generated {}", stats.reused, stats.generated));
         
-        // 4. Compile to native binary (would use cargo build in real implementation)
-        println!("Native compilation would happen here (requires cargo build)");
+        if self.profile {
+            self.report_timings(compiler.timings());
+        }
         
-        Ok(())
+        let mut generated_rust = String::new();
+        generated_rust.push_str("// Auto-generated from .rsx files\n");
+        generated_rust.push_str(&rust_code);
+        Ok(generated_rust)
+    }
+    
+    fn report_timings(&self, timings: &[PassTimings]) {
+        for file in timings {
+            self.status(&file.to_string());
+        }
+        let total: Duration = timings.iter().map(PassTimings::total).sum();
+        self.status(&format!("Total: {:.2}ms", total.as_secs_f64() * 1000.0));
+    }
+    
+    fn compiler_options(release: bool) -> CompilerOptions {
+        if release {
+            CompilerOptions::release()
+        } else {
+            CompilerOptions::debug()
+        }
     }
     
     fn find_rsx_files(dir: &Path) -> Result<Vec<PathBuf>> {
@@ -131,8 +244,8 @@ impl BuildSystem {
         Ok(files)
     }
     
-    fn generate_html() -> String {
-        r#"<!DOCTYPE html>
+    fn generate_html(&self) -> String {
+        let html = r#"<!DOCTYPE html>
 <html lang="en">
 <head>
     <meta charset="UTF-8">
@@ -148,7 +261,12 @@ impl BuildSystem {
         });
     </script>
 </body>
-</html>"#.to_string()
+</html>"#;
+        if self.minify {
+            rux_core::minify_html(html)
+        } else {
+            html.to_string()
+        }
     }
 }
 
@@ -157,3 +275,14 @@ impl Default for BuildSystem {
         Self::new()
     }
 }
+
+/// The rux-core next to this CLI's sources when it was built from the rux
+/// workspace, whose API may be ahead of the last release
+fn workspace_rux_core() -> Option<PathBuf> {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("..").join("rux-core");
+    if path.join("Cargo.toml").is_file() {
+        path.canonicalize().ok()
+    } else {
+        None
+    }
+}
@@ -0,0 +1,83 @@
+use anyhow::Result;
+use rux_compiler::CodegenCache;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// How much of a build's code came from the cache
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub reused: usize,
+    pub generated: usize,
+}
+
+/// Generated Rust from earlier builds, one file per key under
+/// `out_dir/.cache`. Entries written by another compiler version are
+/// thrown away when the cache is opened.
+pub struct BuildCache {
+    dir: PathBuf,
+    used: HashSet<u64>,
+    stats: CacheStats,
+}
+
+impl BuildCache {
+    pub const DIR_NAME: &'static str = ".cache";
+
+    pub fn open(out_dir: &Path) -> Result<Self> {
+        let dir = out_dir.join(Self::DIR_NAME);
+        let version_path = dir.join("version");
+        let version = std::fs::read_to_string(&version_path).ok();
+        if version.as_deref() != Some(rux_compiler::VERSION) {
+            if dir.exists() {
+                std::fs::remove_dir_all(&dir)?;
+            }
+            std::fs::create_dir_all(&dir)?;
+            std::fs::write(&version_path, rux_compiler::VERSION)?;
+        }
+
+        Ok(Self {
+            dir,
+            used: HashSet::new(),
+            stats: CacheStats::default(),
+        })
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+
+    /// Removes the entries this build didn't use, so the cache only holds
+    /// code for the current sources
+    pub fn prune(&self) -> Result<()> {
+        for entry in std::fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            let key = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| u64::from_str_radix(stem, 16).ok());
+            if path.extension().and_then(|s| s.to_str()) == Some("rs") && !key.is_some_and(|key| self.used.contains(&key)) {
+                std::fs::remove_file(path)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn entry(&self, key: u64) -> PathBuf {
+        self.dir.join(format!("{:016x}.rs", key))
+    }
+}
+
+impl CodegenCache for BuildCache {
+    fn get(&mut self, key: u64) -> Option<String> {
+        self.used.insert(key);
+        let code = std::fs::read_to_string(self.entry(key)).ok()?;
+        self.stats.reused += 1;
+        Some(code)
+    }
+
+    fn put(&mut self, key: u64, code: &str) {
+        self.used.insert(key);
+        self.stats.generated += 1;
+        // A failed write only costs a cache miss on the next build
+        let _ = std::fs::write(self.entry(key), code);
+    }
+}
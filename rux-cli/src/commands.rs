@@ -1,5 +1,9 @@
-use clap::{Args, Parser, Subcommand};
-use std::path::PathBuf;
+use crate::config::{BuildConfig, DevConfig, ProjectConfig};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use serde_json::Value;
+use std::io::{Read, Write};
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
 
 #[derive(Parser)]
 #[command(name = "rux")]
@@ -7,6 +11,10 @@ use std::path::PathBuf;
 pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
+    
+    /// Log compiler passes; repeat (-vv) for more detail
+    #[arg(short, long, action = clap::ArgAction::Count, global = true)]
+    pub verbose: u8,
 }
 
 #[derive(Subcommand)]
@@ -23,28 +31,157 @@ pub enum Commands {
 
 #[derive(Args)]
 pub struct BuildArgs {
-    /// Target platform (web, desktop, android, ios)
-    #[arg(short, long, default_value = "web")]
-    pub target: String,
+    /// Target platform (web, desktop, android, ios) [default: web]
+    #[arg(short, long)]
+    pub target: Option<String>,
     
-    /// Output directory
-    #[arg(short, long, default_value = "dist")]
-    pub out_dir: PathBuf,
+    /// Directory the .rsx sources are read from [default: src]
+    #[arg(long)]
+    pub src_dir: Option<PathBuf>,
     
-    /// Release mode
+    /// Output directory [default: dist]
     #[arg(short, long)]
+    pub out_dir: Option<PathBuf>,
+    
+    /// Directory the generated Rust crate is written to [default: target/rux]
+    #[arg(long)]
+    pub target_dir: Option<PathBuf>,
+    
+    /// rux-core checkout the generated crate depends on by path, instead
+    /// of the release matching this CLI
+    #[arg(long)]
+    pub rux_core: Option<PathBuf>,
+    
+    /// Release mode
+    #[arg(short, long, overrides_with = "no_release")]
     pub release: bool,
+    
+    /// Debug mode, even if `rux.toml` sets `release`
+    #[arg(long, overrides_with = "release")]
+    pub no_release: bool,
+    
+    /// Strip formatting whitespace from generated HTML
+    #[arg(long, overrides_with = "no_minify")]
+    pub minify: bool,
+    
+    /// Keep generated HTML as is, even if `rux.toml` sets `minify`
+    #[arg(long, overrides_with = "minify")]
+    pub no_minify: bool,
+    
+    /// Write gzip `.gz` and brotli `.br` copies of each web artifact
+    #[arg(long, overrides_with = "no_compress")]
+    pub compress: bool,
+    
+    /// Skip the compressed copies, even if `rux.toml` sets `compress`
+    #[arg(long, overrides_with = "compress")]
+    pub no_compress: bool,
+    
+    /// Diagnostic output format
+    #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+    pub format: OutputFormat,
+    
+    /// Print how long each compiler pass took; also enabled by RUX_PROFILE
+    #[arg(long)]
+    pub profile: bool,
+}
+
+/// How errors and warnings are reported
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable text on stderr
+    Human,
+    /// A JSON array of diagnostics on stdout
+    Json,
+}
+
+impl BuildArgs {
+    /// Fills in settings not given on the command line from `rux.toml`.
+    /// A `--no-*` flag wins over the config turning its setting on.
+    pub fn merge_config(&mut self, config: &BuildConfig) {
+        if self.target.is_none() {
+            self.target = config.target.clone();
+        }
+        if self.src_dir.is_none() {
+            self.src_dir = config.src_dir.clone();
+        }
+        if self.out_dir.is_none() {
+            self.out_dir = config.out_dir.clone();
+        }
+        if self.target_dir.is_none() {
+            self.target_dir = config.target_dir.clone();
+        }
+        if self.rux_core.is_none() {
+            self.rux_core = config.rux_core.clone();
+        }
+        if !self.no_release {
+            self.release |= config.release.unwrap_or(false);
+        }
+        if !self.no_minify {
+            self.minify |= config.minify.unwrap_or(false);
+        }
+        if !self.no_compress {
+            self.compress |= config.compress.unwrap_or(false);
+        }
+    }
+    
+    pub fn target(&self) -> &str {
+        self.target.as_deref().unwrap_or("web")
+    }
+    
+    pub fn src_dir(&self) -> &Path {
+        self.src_dir.as_deref().unwrap_or(Path::new("src"))
+    }
+    
+    pub fn out_dir(&self) -> &Path {
+        self.out_dir.as_deref().unwrap_or(Path::new("dist"))
+    }
+    
+    pub fn target_dir(&self) -> &Path {
+        self.target_dir.as_deref().unwrap_or(Path::new("target/rux"))
+    }
+    
+    /// Whether pass timings are printed, through `--profile` or a set
+    /// `RUX_PROFILE` environment variable
+    pub fn profile(&self) -> bool {
+        self.profile || std::env::var_os("RUX_PROFILE").is_some()
+    }
 }
 
 #[derive(Args)]
 pub struct DevArgs {
-    /// Port for development server
-    #[arg(short, long, default_value_t = 3000)]
-    pub port: u16,
+    /// Port for development server [default: 3000]
+    #[arg(short, long)]
+    pub port: Option<u16>,
+    
+    /// Address to listen on; `0.0.0.0` makes the server reachable from other
+    /// devices on the network
+    #[arg(long, default_value = "127.0.0.1")]
+    pub host: IpAddr,
     
     /// Open browser automatically
-    #[arg(short, long)]
+    #[arg(short, long, overrides_with = "no_open")]
     pub open: bool,
+    
+    /// Leave the browser alone, even if `rux.toml` sets `open`
+    #[arg(long, overrides_with = "open")]
+    pub no_open: bool,
+}
+
+impl DevArgs {
+    /// Fills in settings not given on the command line from `rux.toml`.
+    /// `--no-open` wins over the config turning `open` on.
+    pub fn merge_config(&mut self, config: &DevConfig) {
+        if self.port.is_none() {
+            self.port = config.port;
+        }
+        if !self.no_open {
+            self.open |= config.open.unwrap_or(false);
+        }
+    }
+    
+    pub fn port(&self) -> u16 {
+        self.port.unwrap_or(3000)
+    }
 }
 
 #[derive(Args)]
@@ -59,38 +196,95 @@ pub struct NewArgs {
 
 #[derive(Args)]
 pub struct CheckArgs {
-    /// Files to check
+    /// Files to check; `-` reads source from stdin
     pub files: Vec<PathBuf>,
+    
+    /// Read source from stdin, same as passing `-`
+    #[arg(long)]
+    pub stdin: bool,
+    
+    /// Diagnostic output format
+    #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+    pub format: OutputFormat,
+    
+    /// Print an intermediate representation instead of checking
+    #[arg(long, value_enum, conflicts_with = "format")]
+    pub emit: Option<Emit>,
+    
+    /// Re-run the check whenever a watched .rsx file changes
+    #[arg(short, long)]
+    pub watch: bool,
+}
+
+impl CheckArgs {
+    /// Whether source is read from stdin, through `--stdin` or `-`
+    pub fn reads_stdin(&self) -> bool {
+        self.stdin || self.files.iter().any(|file| file.as_os_str() == "-")
+    }
+}
+
+/// Intermediate representations `check --emit` can dump
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Emit {
+    /// Lexer output, one token per line
+    Tokens,
+    /// The parsed AST
+    Ast,
+    /// Generated Rust code
+    Rust,
 }
 
 pub fn handle_build(args: BuildArgs) -> anyhow::Result<()> {
+    build_in(args, &std::env::current_dir()?)
+}
+
+/// Runs `build` as if started in `dir`. Paths are resolved against the
+/// project root, the nearest directory at or above `dir` with a
+/// `rux.toml`, or `dir` itself if there is none.
+pub fn build_in(mut args: BuildArgs, dir: &Path) -> anyhow::Result<()> {
     use crate::build::BuildSystem;
     
-    println!("Building for target: {}", args.target);
-    println!("Output directory: {:?}", args.out_dir);
-    println!("Release mode: {}", args.release);
+    let root = ProjectConfig::find_root(dir).unwrap_or_else(|| dir.to_path_buf());
+    args.merge_config(&ProjectConfig::load(&root)?.build);
+    let out_dir = root.join(args.out_dir());
+    let mut build_system = BuildSystem::with_format(args.format)
+        .with_profile(args.profile())
+        .with_minify(args.minify)
+        .with_compress(args.compress)
+        .with_src_dir(root.join(args.src_dir()))
+        .with_target_dir(root.join(args.target_dir()));
+    if let Some(rux_core) = &args.rux_core {
+        build_system = build_system.with_rux_core(Some(root.join(rux_core)));
+    }
     
-    let build_system = BuildSystem::new();
+    build_system.status(&format!("Building for target: {}", args.target()));
+    build_system.status(&format!("Output directory: {:?}", out_dir));
+    build_system.status(&format!("Release mode: {}", args.release));
     
-    match args.target.as_str() {
-        "web" => build_system.build_web(&args.out_dir, args.release)?,
-        "desktop" => build_system.build_desktop(&args.out_dir, args.release)?,
+    match args.target() {
+        "web" => build_system.build_web(&out_dir, args.release)?,
+        "desktop" => build_system.build_desktop(&out_dir, args.release)?,
         _ => {
-            return Err(anyhow::anyhow!("Unknown target: {}", args.target));
+            return Err(anyhow::anyhow!("Unknown target: {}", args.target()));
         }
     }
     
-    println!("✅ Build complete!");
+    build_system.status("✅ Build complete!");
     Ok(())
 }
 
-pub async fn handle_dev(args: DevArgs) -> anyhow::Result<()> {
+pub async fn handle_dev(mut args: DevArgs) -> anyhow::Result<()> {
     use crate::file_watcher::FileWatcher;
     use crate::dev_server::DevServer;
-    use rux_compiler::IncrementalCompiler;
-    use std::time::Duration;
+    use rux_compiler::{CompilerOptions, IncrementalCompiler};
+    
+    let cwd = std::env::current_dir()?;
+    let root = ProjectConfig::find_root(&cwd).unwrap_or(cwd);
+    let config = ProjectConfig::load(&root)?;
+    args.merge_config(&config.dev);
+    let src_dir = root.join(config.build.src_dir.as_deref().unwrap_or(Path::new("src")));
     
-    println!("Starting development server on port {}", args.port);
+    println!("Starting development server on port {}", args.port());
     if args.open {
         println!("Opening browser...");
         // Would open browser here
@@ -98,17 +292,18 @@ pub async fn handle_dev(args: DevArgs) -> anyhow::Result<()> {
     
     // Initialize file watcher
     let mut watcher = FileWatcher::new()?;
-    if std::path::Path::new("src").exists() {
-        watcher.watch_directory(std::path::Path::new("src"))?;
+    if src_dir.exists() {
+        watcher.watch_directory(&src_dir)?;
     }
     
     // Initialize incremental compiler
-    let mut compiler = IncrementalCompiler::new();
+    let mut compiler = IncrementalCompiler::with_root(&src_dir).with_options(CompilerOptions::debug());
     
     // Start dev server in background
-    let server = DevServer::new(args.port);
-    let server_port = args.port;
-    tokio::spawn(async move {
+    let out_dir = root.join(config.build.out_dir.unwrap_or_else(|| PathBuf::from("dist")));
+    let server = DevServer::new(args.port()).with_host(args.host).with_out_dir(out_dir);
+    let state = server.state();
+    let server_task = tokio::spawn(async move {
         if let Err(e) = server.start().await {
             eprintln!("Dev server error: {}", e);
         }
@@ -116,24 +311,59 @@ pub async fn handle_dev(args: DevArgs) -> anyhow::Result<()> {
     
     println!("Watching for file changes...");
     
-    // Main loop: watch for changes and recompile
+    let shutdown = async {
+        // Without a handler there's no way to stop cleanly, so wait forever
+        if tokio::signal::ctrl_c().await.is_err() {
+            std::future::pending::<()>().await;
+        }
+    };
+    dev_loop(&watcher, &mut compiler, &state, shutdown).await;
+    
+    server_task.abort();
+    println!("Dev server stopped");
+    Ok(())
+}
+
+/// Recompiles whenever a watched file changes, updating `state` with the
+/// result, until `shutdown` completes. Changes that arrived before the
+/// shutdown are still compiled, so the build output is never left stale.
+pub async fn dev_loop(
+    watcher: &crate::file_watcher::FileWatcher,
+    compiler: &mut rux_compiler::IncrementalCompiler,
+    state: &crate::dev_server::DevState,
+    shutdown: impl std::future::Future<Output = ()>,
+) {
+    let mut ticks = tokio::time::interval(std::time::Duration::from_millis(100));
+    tokio::pin!(shutdown);
     loop {
-        let changed = watcher.check_for_changes();
-        if !changed.is_empty() {
-            println!("Files changed: {:?}", changed);
-            match compiler.compile_incremental(&changed) {
-                Ok(_) => {
-                    println!("✅ Recompiled successfully");
-                    // Would send WebSocket update to clients
-                }
-                Err(e) => {
-                    eprintln!("❌ Compilation error: {}", e);
-                }
-            }
+        tokio::select! {
+            _ = ticks.tick() => recompile(&watcher.check_for_changes(), compiler, state),
+            _ = &mut shutdown => break,
+        }
+    }
+    
+    println!("Shutting down...");
+    recompile(&watcher.check_for_changes(), compiler, state);
+}
+
+fn recompile(
+    changed: &[PathBuf],
+    compiler: &mut rux_compiler::IncrementalCompiler,
+    state: &crate::dev_server::DevState,
+) {
+    if changed.is_empty() {
+        return;
+    }
+    println!("Files changed: {:?}", changed);
+    match compiler.compile_incremental(changed) {
+        Ok(_) => {
+            println!("✅ Recompiled successfully");
+            state.clear_error();
+        }
+        Err(e) => {
+            eprintln!("❌ Compilation error: {}", e);
+            state.set_error(&e);
         }
-        
-        // Sleep briefly to avoid busy-waiting
-        tokio::time::sleep(Duration::from_millis(100)).await;
     }
 }
 
@@ -149,16 +379,243 @@ pub fn handle_new(args: NewArgs) -> anyhow::Result<()> {
 }
 
 pub fn handle_check(args: CheckArgs) -> anyhow::Result<()> {
+    if !args.watch {
+        return run_check(&args, &mut std::io::stdout().lock());
+    }
+    if args.reads_stdin() {
+        return Err(anyhow::anyhow!("--watch can't be used with source from stdin"));
+    }
+    
+    let watcher = check_watcher(&args)?;
+    let mut stdout = std::io::stdout();
+    report_check(&args, &mut stdout)?;
+    loop {
+        watch_iteration(&args, &watcher, None, &mut stdout)?;
+    }
+}
+
+/// Watcher for `check --watch`: the given files, or `src/` if none are given
+pub fn check_watcher(args: &CheckArgs) -> anyhow::Result<crate::file_watcher::FileWatcher> {
+    let mut watcher = crate::file_watcher::FileWatcher::new()?;
     if args.files.is_empty() {
-        // Check all .rsx files in src/
-        println!("Checking all .rsx files...");
+        let src = Path::new("src");
+        if src.exists() {
+            watcher.watch_directory(src)?;
+        }
     } else {
         for file in &args.files {
-            println!("Checking {:?}...", file);
-            let mut compiler = rux_compiler::Compiler::new();
-            compiler.compile_file(file)?;
+            watcher.watch_file(file)?;
+        }
+    }
+    Ok(watcher)
+}
+
+/// One `check --watch` cycle. Waits up to `timeout` for changes and, if
+/// there are any, clears the screen and checks again. Returns whether the
+/// check ran.
+pub fn watch_iteration(
+    args: &CheckArgs,
+    watcher: &crate::file_watcher::FileWatcher,
+    timeout: Option<std::time::Duration>,
+    out: &mut impl Write,
+) -> anyhow::Result<bool> {
+    let changed = watcher.wait_for_changes(timeout);
+    if changed.is_empty() {
+        return Ok(false);
+    }
+    
+    // Clear the screen and move the cursor home
+    write!(out, "\x1b[2J\x1b[H")?;
+    report_check(args, out)?;
+    Ok(true)
+}
+
+/// Runs a check in watch mode, where a failed check is reported rather than
+/// ending the command
+fn report_check(args: &CheckArgs, out: &mut impl Write) -> anyhow::Result<()> {
+    if let Err(e) = run_check(args, out) {
+        writeln!(out, "{:?}", e)?;
+    }
+    writeln!(out, "Watching for changes...")?;
+    out.flush()?;
+    Ok(())
+}
+
+/// Name used for source read from stdin in output and diagnostics
+pub const STDIN_NAME: &str = "<stdin>";
+
+/// Runs the `check` command, writing its output to `out`
+pub fn run_check(args: &CheckArgs, out: &mut impl Write) -> anyhow::Result<()> {
+    run_check_with_input(args, &mut std::io::stdin().lock(), out)
+}
+
+/// Runs the `check` command, reading `-`/`--stdin` source from `input`
+pub fn run_check_with_input(args: &CheckArgs, input: &mut impl Read, out: &mut impl Write) -> anyhow::Result<()> {
+    let inputs = check_inputs(args, input)?;
+    
+    if let Some(emit) = args.emit {
+        for input in &inputs {
+            writeln!(out, "{}", emit_source(&input.source()?, input.name(), emit)?)?;
         }
+        return Ok(());
     }
     
+    if args.format == OutputFormat::Json {
+        return check_json(&inputs, out);
+    }
+    
+    if inputs.is_empty() {
+        writeln!(out, "No .rsx files to check")?;
+    }
+    for input in &inputs {
+        writeln!(out, "Checking {:?}...", input.name())?;
+        let mut compiler = rux_compiler::Compiler::new();
+        input.compile(&mut compiler)?;
+        for warning in compiler.warnings().diagnostics() {
+            eprintln!("{:?}: {}", input.name(), warning);
+        }
+    }
+    
+    Ok(())
+}
+
+/// Something `check` reads source from
+enum CheckInput {
+    File(PathBuf),
+    Stdin(String),
+}
+
+impl CheckInput {
+    fn name(&self) -> &Path {
+        match self {
+            CheckInput::File(path) => path,
+            CheckInput::Stdin(_) => Path::new(STDIN_NAME),
+        }
+    }
+    
+    fn source(&self) -> anyhow::Result<String> {
+        match self {
+            CheckInput::File(path) => Ok(std::fs::read_to_string(path)?),
+            CheckInput::Stdin(source) => Ok(source.clone()),
+        }
+    }
+    
+    fn compile(&self, compiler: &mut rux_compiler::Compiler) -> rux_compiler::Result<rux_compiler::AST> {
+        match self {
+            CheckInput::File(path) => compiler.compile_file(path),
+            CheckInput::Stdin(source) => compiler.compile_string(source, STDIN_NAME),
+        }
+    }
+}
+
+/// Stdin, if requested, followed by the files named on the command line.
+/// With neither, every .rsx file under `src/`, as `check --watch` watches.
+fn check_inputs(args: &CheckArgs, input: &mut impl Read) -> anyhow::Result<Vec<CheckInput>> {
+    let mut inputs = Vec::new();
+    if args.reads_stdin() {
+        let mut source = String::new();
+        input.read_to_string(&mut source)?;
+        inputs.push(CheckInput::Stdin(source));
+    }
+    inputs.extend(
+        args.files
+            .iter()
+            .filter(|file| file.as_os_str() != "-")
+            .cloned()
+            .map(CheckInput::File),
+    );
+    if inputs.is_empty() {
+        inputs.extend(rsx_files(Path::new("src"))?.into_iter().map(CheckInput::File));
+    }
+    Ok(inputs)
+}
+
+/// .rsx files under `dir` in path order, none if it doesn't exist
+fn rsx_files(dir: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut files = Vec::new();
+    for entry in walkdir::WalkDir::new(dir).sort_by_file_name() {
+        let path = entry?.into_path();
+        if path.extension().and_then(|s| s.to_str()) == Some("rsx") {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
+
+/// Dumps one representation of a source. Tokens and the AST come straight
+/// from the lexer and parser so they can be inspected even if later passes
+/// fail.
+fn emit_source(source: &str, name: &Path, emit: Emit) -> anyhow::Result<String> {
+    use rux_compiler::{CodeGenerator, Compiler, Error, Lexer, Parser, Severity};
+    
+    let output = match emit {
+        Emit::Tokens => {
+            let tokens = Lexer::new(source).tokenize()?;
+            tokens
+                .iter()
+                .map(|t| format!("{}:{} {:?}", t.span.line, t.span.column, t.token))
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+        Emit::Ast => {
+            let tokens = Lexer::new(source).tokenize()?;
+            let mut parser = Parser::new(tokens, source.to_string());
+            let ast = parser.parse()?;
+            // Recovered errors still leave an AST, but not one of valid source
+            if let Some(error) = parser.diagnostics().iter().find(|d| d.severity == Severity::Error) {
+                let err = Error::parser(error.message.clone(), source.to_string(), error.span.to_source_span());
+                return Err(match &error.help {
+                    Some(help) => err.with_help(help.clone()),
+                    None => err,
+                }
+                .into());
+            }
+            ast.pretty()
+        }
+        Emit::Rust => {
+            let mut compiler = Compiler::new();
+            let ast = compiler.compile_string(source, &name.display().to_string())?;
+            CodeGenerator::new().generate_rust_code(&ast)?
+        }
+    };
+    Ok(output)
+}
+
+/// Checks every file and prints all of their diagnostics as one JSON array,
+/// failing afterwards if any file had an error
+fn check_json(inputs: &[CheckInput], out: &mut impl Write) -> anyhow::Result<()> {
+    let mut diagnostics = Vec::new();
+    let mut failed = 0;
+    
+    for input in inputs {
+        let mut compiler = rux_compiler::Compiler::new();
+        let result = input.compile(&mut compiler);
+        for warning in compiler.warnings().diagnostics() {
+            diagnostics.push(with_file(warning.to_json(), Some(input.name())));
+        }
+        if let Err(e) = result {
+            diagnostics.push(with_file(e.to_json(), Some(input.name())));
+            failed += 1;
+        }
+    }
+    
+    print_json(out, diagnostics)?;
+    if failed > 0 {
+        return Err(anyhow::anyhow!("{} file(s) failed to check", failed));
+    }
+    Ok(())
+}
+
+/// Sets the `file` field of a serialized diagnostic
+pub fn with_file(mut diagnostic: Value, file: Option<&Path>) -> Value {
+    diagnostic["file"] = file.map_or(Value::Null, |f| Value::String(f.display().to_string()));
+    diagnostic
+}
+
+pub fn print_json(out: &mut impl Write, diagnostics: Vec<Value>) -> anyhow::Result<()> {
+    writeln!(out, "{}", serde_json::to_string_pretty(&Value::Array(diagnostics))?)?;
     Ok(())
 }
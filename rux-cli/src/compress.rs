@@ -0,0 +1,74 @@
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+/// Extensions of the build outputs browsers download
+const COMPRESSED_EXTENSIONS: &[&str] = &["html", "js", "wasm", "css"];
+
+/// Writes `.gz` and `.br` copies next to every downloadable artifact in
+/// `out_dir`, for servers that serve precompressed files. Returns the files
+/// written.
+pub fn compress_artifacts(out_dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut written = Vec::new();
+    let mut artifacts: Vec<PathBuf> = std::fs::read_dir(out_dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .and_then(|s| s.to_str())
+                    .is_some_and(|ext| COMPRESSED_EXTENSIONS.contains(&ext))
+        })
+        .collect();
+    artifacts.sort();
+
+    for path in artifacts {
+        let data = std::fs::read(&path)?;
+        for (extension, compressed) in [("gz", gzip(&data)), ("br", brotli(&data)?)] {
+            let mut compressed_path = path.clone().into_os_string();
+            compressed_path.push(".");
+            compressed_path.push(extension);
+            let compressed_path = PathBuf::from(compressed_path);
+            std::fs::write(&compressed_path, compressed)?;
+            written.push(compressed_path);
+        }
+    }
+    Ok(written)
+}
+
+/// Gzip stream of `data` at the best compression level. The header has no
+/// timestamp, so the same input always gives the same bytes.
+pub fn gzip(data: &[u8]) -> Vec<u8> {
+    let deflated = miniz_oxide::deflate::compress_to_vec(data, 10);
+
+    let mut out = Vec::with_capacity(deflated.len() + 18);
+    // Magic, deflate method, no flags, no mtime, max compression, unknown OS
+    out.extend_from_slice(&[0x1f, 0x8b, 8, 0, 0, 0, 0, 0, 2, 255]);
+    out.extend_from_slice(&deflated);
+    out.extend_from_slice(&crc32(data).to_le_bytes());
+    out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    out
+}
+
+/// Brotli stream of `data` at the best quality
+pub fn brotli(data: &[u8]) -> Result<Vec<u8>> {
+    let params = brotli::enc::BrotliEncoderParams {
+        quality: 11,
+        lgwin: 22,
+        ..Default::default()
+    };
+    let mut out = Vec::new();
+    brotli::BrotliCompress(&mut &data[..], &mut out, &params)?;
+    Ok(out)
+}
+
+/// CRC-32 as used by gzip (IEEE polynomial, reflected)
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xedb8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
@@ -0,0 +1,105 @@
+use anyhow::{anyhow, Context, Result};
+use std::path::{Path, PathBuf};
+use toml_edit::{DocumentMut, Item};
+
+/// Settings from a project's `rux.toml`. Every field is optional; command
+/// line flags take precedence over anything set here.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProjectConfig {
+    pub build: BuildConfig,
+    pub dev: DevConfig,
+}
+
+/// The `[build]` table. Paths are relative to the project root.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BuildConfig {
+    pub target: Option<String>,
+    pub src_dir: Option<PathBuf>,
+    pub out_dir: Option<PathBuf>,
+    pub target_dir: Option<PathBuf>,
+    pub rux_core: Option<PathBuf>,
+    pub release: Option<bool>,
+    pub minify: Option<bool>,
+    pub compress: Option<bool>,
+}
+
+/// The `[dev]` table
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DevConfig {
+    pub port: Option<u16>,
+    pub open: Option<bool>,
+}
+
+impl ProjectConfig {
+    pub const FILE_NAME: &'static str = "rux.toml";
+
+    /// Loads `rux.toml` from `dir`. A project without one gets the defaults.
+    pub fn load(dir: &Path) -> Result<Self> {
+        let path = dir.join(Self::FILE_NAME);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let source = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {:?}", path))?;
+        Self::parse(&source).with_context(|| format!("Invalid {:?}", path))
+    }
+
+    /// The project root for `dir`: the nearest directory at or above it
+    /// holding a `rux.toml`
+    pub fn find_root(dir: &Path) -> Option<PathBuf> {
+        dir.ancestors()
+            .find(|ancestor| ancestor.join(Self::FILE_NAME).is_file())
+            .map(Path::to_path_buf)
+    }
+
+    pub fn parse(source: &str) -> Result<Self> {
+        let document: DocumentMut = source.parse()?;
+
+        let mut config = Self::default();
+        if let Some(build) = document.get("build") {
+            config.build = BuildConfig {
+                target: string(build, "build", "target")?,
+                src_dir: string(build, "build", "src_dir")?.map(PathBuf::from),
+                out_dir: string(build, "build", "out_dir")?.map(PathBuf::from),
+                target_dir: string(build, "build", "target_dir")?.map(PathBuf::from),
+                rux_core: string(build, "build", "rux_core")?.map(PathBuf::from),
+                release: boolean(build, "build", "release")?,
+                minify: boolean(build, "build", "minify")?,
+                compress: boolean(build, "build", "compress")?,
+            };
+        }
+        if let Some(dev) = document.get("dev") {
+            let port = match dev.get("port") {
+                None => None,
+                Some(item) => {
+                    let port = item.as_integer().ok_or_else(|| anyhow!("dev.port must be an integer"))?;
+                    Some(u16::try_from(port).map_err(|_| anyhow!("dev.port {} is out of range", port))?)
+                }
+            };
+            config.dev = DevConfig {
+                port,
+                open: boolean(dev, "dev", "open")?,
+            };
+        }
+        Ok(config)
+    }
+}
+
+fn string(table: &Item, table_name: &str, key: &str) -> Result<Option<String>> {
+    table
+        .get(key)
+        .map(|item| {
+            item.as_str()
+                .map(str::to_string)
+                .ok_or_else(|| anyhow!("{}.{} must be a string", table_name, key))
+        })
+        .transpose()
+}
+
+fn boolean(table: &Item, table_name: &str, key: &str) -> Result<Option<bool>> {
+    table
+        .get(key)
+        .map(|item| item.as_bool().ok_or_else(|| anyhow!("{}.{} must be a boolean", table_name, key)))
+        .transpose()
+}
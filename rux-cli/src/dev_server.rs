@@ -1,69 +1,269 @@
 use axum::{
-    extract::ws::{WebSocket, WebSocketUpgrade},
-    response::{Html, Response},
+    body::Body,
+    extract::ws::{Message, WebSocket, WebSocketUpgrade},
+    extract::State,
+    http::{header, StatusCode},
+    middleware,
+    response::Response,
     routing::get,
     Router,
 };
-use std::net::SocketAddr;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket};
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use tokio::sync::broadcast;
 use tower_http::services::ServeDir;
 use tower_http::cors::CorsLayer;
 
+/// Message telling connected pages to reload
+const RELOAD: &str = "reload";
+
+/// State the dev loop shares with the server: the latest compile error and
+/// a channel to the connected pages
+pub struct DevState {
+    error: RwLock<Option<String>>,
+    updates: broadcast::Sender<&'static str>,
+}
+
+impl DevState {
+    pub fn new() -> Self {
+        Self {
+            error: RwLock::new(None),
+            updates: broadcast::channel(16).0,
+        }
+    }
+    
+    /// Shows `error` in place of the app until `clear_error`. Open pages
+    /// reload to display it.
+    pub fn set_error(&self, error: &rux_compiler::Error) {
+        let mut report = String::new();
+        let handler = miette::GraphicalReportHandler::new_themed(miette::GraphicalTheme::unicode_nocolor());
+        if handler.render_report(&mut report, error).is_err() {
+            report = error.to_string();
+        }
+        *self.error.write().unwrap() = Some(report);
+        self.reload();
+    }
+    
+    /// Called after a successful compile; open pages reload to pick it up
+    pub fn clear_error(&self) {
+        *self.error.write().unwrap() = None;
+        self.reload();
+    }
+    
+    /// The rendered report of the error being shown, if any
+    pub fn error(&self) -> Option<String> {
+        self.error.read().unwrap().clone()
+    }
+    
+    pub fn subscribe(&self) -> broadcast::Receiver<&'static str> {
+        self.updates.subscribe()
+    }
+    
+    fn reload(&self) {
+        // Nobody may be connected yet
+        let _ = self.updates.send(RELOAD);
+    }
+}
+
+impl Default for DevState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 pub struct DevServer {
+    host: IpAddr,
     port: u16,
+    out_dir: PathBuf,
+    state: Arc<DevState>,
 }
 
 impl DevServer {
     pub fn new(port: u16) -> Self {
-        Self { port }
+        Self {
+            host: IpAddr::V4(Ipv4Addr::LOCALHOST),
+            port,
+            out_dir: PathBuf::from("dist"),
+            state: Arc::new(DevState::new()),
+        }
     }
     
-    pub async fn start(&self) -> anyhow::Result<()> {
-        let app = Router::new()
-            .route("/", get(index_handler))
+    /// Handle for reporting compile results to the server
+    pub fn state(&self) -> Arc<DevState> {
+        self.state.clone()
+    }
+    
+    /// Address to listen on, `127.0.0.1` by default. `0.0.0.0` serves other
+    /// devices on the network too.
+    pub fn with_host(mut self, host: IpAddr) -> Self {
+        self.host = host;
+        self
+    }
+    
+    pub fn addr(&self) -> SocketAddr {
+        SocketAddr::new(self.host, self.port)
+    }
+    
+    /// Build output directory whose `index.html`, JS and WASM are served,
+    /// `dist` by default
+    pub fn with_out_dir(mut self, out_dir: impl Into<PathBuf>) -> Self {
+        self.out_dir = out_dir.into();
+        self
+    }
+    
+    /// Routes of the server: `/ws` for hot reload and everything else from
+    /// the build output, with content types guessed from the extension so
+    /// `.wasm` is served as `application/wasm`. HTML pages get the reload
+    /// client injected, or are replaced by an error overlay while the last
+    /// compile failed.
+    pub fn router(&self) -> Router {
+        let files = ServeDir::new(&self.out_dir).append_index_html_on_directories(true);
+        Router::new()
             .route("/ws", get(ws_handler))
-            .nest_service("/dist", ServeDir::new("dist"))
-            .layer(CorsLayer::permissive());
-        
-        let addr = SocketAddr::from(([127, 0, 0, 1], self.port));
-        println!("🚀 RUX dev server running on http://{}", addr);
-        
-        let listener = tokio::net::TcpListener::bind(addr).await?;
-        axum::serve(listener, app).await?;
-        
+            .fallback_service(files)
+            .layer(middleware::map_response_with_state(self.state.clone(), inject_into_html_response))
+            .with_state(self.state.clone())
+            .layer(CorsLayer::permissive())
+    }
+    
+    pub async fn start(&self) -> anyhow::Result<()> {
+        let listener = self.bind().await?;
+        let addr = listener.local_addr()?;
+        if addr.ip().is_unspecified() {
+            println!("🚀 RUX dev server running on http://localhost:{}", addr.port());
+            if let Some(ip) = network_ip() {
+                println!("   On your network: http://{}", SocketAddr::new(ip, addr.port()));
+            }
+        } else {
+            println!("🚀 RUX dev server running on http://{}", addr);
+        }
+        self.serve(listener).await
+    }
+    
+    /// Binds the listener `start` serves on
+    pub async fn bind(&self) -> std::io::Result<tokio::net::TcpListener> {
+        tokio::net::TcpListener::bind(self.addr()).await
+    }
+    
+    /// Serves on an already bound listener, e.g. one on an ephemeral port
+    pub async fn serve(&self, listener: tokio::net::TcpListener) -> anyhow::Result<()> {
+        axum::serve(listener, self.router()).await?;
         Ok(())
     }
 }
 
-async fn index_handler() -> Html<&'static str> {
-    Html(r#"
-<!DOCTYPE html>
+/// The address other devices on the network can reach this machine at:
+/// the local side of a route to a public address. Connecting a UDP socket
+/// sends nothing.
+fn network_ip() -> Option<IpAddr> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).ok()?;
+    socket.connect((Ipv4Addr::new(8, 8, 8, 8), 80)).ok()?;
+    Some(socket.local_addr().ok()?.ip())
+}
+
+/// Marks the injected script so a page is never given two
+const RELOAD_MARKER: &str = "data-rux-reload";
+
+/// Connects to `/ws` and reloads the page when the server sends `reload`
+const RELOAD_CLIENT: &str = r#"<script data-rux-reload>
+(() => {
+    const protocol = location.protocol === 'https:' ? 'wss://' : 'ws://';
+    const socket = new WebSocket(protocol + location.host + '/ws');
+    socket.addEventListener('message', (event) => {
+        if (event.data === 'reload') location.reload();
+    });
+})();
+</script>"#;
+
+/// Adds the reload client before `</body>`, or at the end of documents
+/// without one. Pages that already have it are returned unchanged.
+pub fn inject_reload_client(html: &str) -> String {
+    if html.contains(RELOAD_MARKER) {
+        return html.to_string();
+    }
+    match html.to_ascii_lowercase().rfind("</body>") {
+        Some(index) => format!("{}{}\n{}", &html[..index], RELOAD_CLIENT, &html[index..]),
+        None => format!("{}\n{}", html, RELOAD_CLIENT),
+    }
+}
+
+/// Full-screen page showing a compile error report
+pub fn error_overlay(report: &str) -> String {
+    let page = format!(
+        r#"<!DOCTYPE html>
 <html>
 <head>
-    <title>RUX Dev Server</title>
     <meta charset="utf-8">
+    <title>Compile error - RUX</title>
 </head>
-<body>
-    <div id="root"></div>
-    <script type="module">
-        // WASM loading would go here
-        console.log('RUX dev server loaded');
-    </script>
+<body style="margin: 0; background: #1e1e1e;">
+    <div id="rux-error-overlay" style="position: fixed; inset: 0; overflow: auto; padding: 2rem; color: #ff8080; font: 14px/1.5 monospace;">
+        <h1 style="font-size: 1.25rem; color: #ff5555;">Failed to compile</h1>
+        <pre style="white-space: pre-wrap;">{}</pre>
+    </div>
 </body>
-</html>
-    "#)
+</html>"#,
+        escape_html(report)
+    );
+    inject_reload_client(&page)
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+async fn inject_into_html_response(State(state): State<Arc<DevState>>, response: Response) -> Response {
+    let is_html = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("text/html"));
+    // Partial and encoded bodies can't be edited as text
+    let editable = response.status() == StatusCode::OK && !response.headers().contains_key(header::CONTENT_ENCODING);
+    if !is_html || !editable {
+        return response;
+    }
+    
+    if let Some(report) = state.error() {
+        let (mut parts, _) = response.into_parts();
+        parts.status = StatusCode::INTERNAL_SERVER_ERROR;
+        parts.headers.remove(header::CONTENT_LENGTH);
+        parts.headers.remove(header::LAST_MODIFIED);
+        return Response::from_parts(parts, Body::from(error_overlay(&report)));
+    }
+    
+    let (mut parts, body) = response.into_parts();
+    let Ok(bytes) = axum::body::to_bytes(body, usize::MAX).await else {
+        return Response::from_parts(parts, Body::empty());
+    };
+    let html = inject_reload_client(&String::from_utf8_lossy(&bytes));
+    // The length changed; it is recomputed from the new body
+    parts.headers.remove(header::CONTENT_LENGTH);
+    Response::from_parts(parts, Body::from(html))
 }
 
-async fn ws_handler(ws: WebSocketUpgrade) -> Response {
-    ws.on_upgrade(handle_websocket)
+async fn ws_handler(State(state): State<Arc<DevState>>, ws: WebSocketUpgrade) -> Response {
+    ws.on_upgrade(move |socket| handle_websocket(socket, state.subscribe()))
 }
 
-async fn handle_websocket(mut socket: WebSocket) {
-    // WebSocket handler for hot reload
-    // Would send updates when files change
-    while let Some(msg) = socket.recv().await {
-        if let Ok(msg) = msg {
-            // Handle WebSocket messages
-            let _ = msg;
+/// Forwards reload messages to the page until either side closes
+async fn handle_websocket(mut socket: WebSocket, mut updates: broadcast::Receiver<&'static str>) {
+    loop {
+        tokio::select! {
+            update = updates.recv() => match update {
+                Ok(message) => {
+                    if socket.send(Message::Text(message.to_string())).await.is_err() {
+                        break;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            message = socket.recv() => match message {
+                Some(Ok(_)) => {}
+                _ => break,
+            },
         }
     }
 }
@@ -1,12 +1,18 @@
 use notify::{Watcher, RecommendedWatcher, RecursiveMode, Event, EventKind};
 use std::path::{Path, PathBuf};
-use std::sync::mpsc::{Receiver, Sender};
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
 use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+/// How long the watcher must be quiet before a burst of events is reported.
+/// Editors often write a file several times per save.
+pub const DEBOUNCE: Duration = Duration::from_millis(50);
 
 pub struct FileWatcher {
     watcher: RecommendedWatcher,
     receiver: Receiver<notify::Result<Event>>,
     watched_files: HashSet<PathBuf>,
+    watched_dirs: Vec<PathBuf>,
 }
 
 impl FileWatcher {
@@ -18,37 +24,68 @@ impl FileWatcher {
             watcher,
             receiver: rx,
             watched_files: HashSet::new(),
+            watched_dirs: Vec::new(),
         })
     }
     
     pub fn watch_directory(&mut self, path: &Path) -> notify::Result<()> {
         self.watcher.watch(path, RecursiveMode::Recursive)?;
+        // Event paths are absolute even when the watched path isn't
+        self.watched_dirs.push(std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf()));
         Ok(())
     }
     
+    /// Watches a single file. Its directory is watched too, so changes to
+    /// sibling files are filtered out.
     pub fn watch_file(&mut self, path: &Path) -> notify::Result<()> {
+        // Events carry the path we registered, so make it match exactly
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
         if let Some(parent) = path.parent() {
             self.watcher.watch(parent, RecursiveMode::NonRecursive)?;
         }
-        self.watched_files.insert(path.to_path_buf());
+        self.watched_files.insert(path);
         Ok(())
     }
     
+    /// Blocks until an `.rsx` file changes or `timeout` passes (`None` waits
+    /// forever), then keeps collecting until events stop for `DEBOUNCE`.
+    /// Each changed path is reported once.
+    pub fn wait_for_changes(&self, timeout: Option<Duration>) -> Vec<PathBuf> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        let mut changed = Vec::new();
+        
+        while changed.is_empty() {
+            let result = match deadline {
+                Some(deadline) => {
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+                    self.receiver.recv_timeout(remaining)
+                }
+                None => self.receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+            };
+            match result {
+                Ok(Ok(event)) => changed.extend(self.changed_paths(event)),
+                Ok(Err(_)) => {}
+                Err(_) => return changed,
+            }
+        }
+        
+        while let Ok(result) = self.receiver.recv_timeout(DEBOUNCE) {
+            if let Ok(event) = result {
+                changed.extend(self.changed_paths(event));
+            }
+        }
+        
+        changed.sort();
+        changed.dedup();
+        changed
+    }
+    
     pub fn check_for_changes(&self) -> Vec<PathBuf> {
         let mut changed_files = Vec::new();
         
         // Non-blocking check for file changes
         while let Ok(Ok(event)) = self.receiver.try_recv() {
-            match event.kind {
-                EventKind::Modify(_) | EventKind::Create(_) | EventKind::Remove(_) => {
-                    for path in event.paths {
-                        if path.extension().and_then(|s| s.to_str()) == Some("rsx") {
-                            changed_files.push(path);
-                        }
-                    }
-                }
-                _ => {}
-            }
+            changed_files.extend(self.changed_paths(event));
         }
         
         changed_files
@@ -56,24 +93,28 @@ impl FileWatcher {
     
     pub fn wait_for_change(&self) -> notify::Result<Vec<PathBuf>> {
         match self.receiver.recv() {
-            Ok(Ok(event)) => {
-                match event.kind {
-                    EventKind::Modify(_) | EventKind::Create(_) | EventKind::Remove(_) => {
-                        let mut changed = Vec::new();
-                        for path in event.paths {
-                            if path.extension().and_then(|s| s.to_str()) == Some("rsx") {
-                                changed.push(path);
-                            }
-                        }
-                        Ok(changed)
-                    }
-                    _ => Ok(Vec::new()),
-                }
-            }
+            Ok(Ok(event)) => Ok(self.changed_paths(event)),
             Ok(Err(e)) => Err(e),
             Err(_) => Ok(Vec::new()),
         }
     }
+    
+    /// Watched `.rsx` paths touched by a create, modify or remove event
+    fn changed_paths(&self, event: Event) -> Vec<PathBuf> {
+        match event.kind {
+            EventKind::Modify(_) | EventKind::Create(_) | EventKind::Remove(_) => event
+                .paths
+                .into_iter()
+                .filter(|path| path.extension().and_then(|s| s.to_str()) == Some("rsx"))
+                .filter(|path| self.is_watched(path))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+    
+    fn is_watched(&self, path: &Path) -> bool {
+        self.watched_files.contains(path) || self.watched_dirs.iter().any(|dir| path.starts_with(dir))
+    }
 }
 
 impl Default for FileWatcher {
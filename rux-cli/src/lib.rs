@@ -0,0 +1,10 @@
+// CLI tool for RUX
+
+pub mod commands;
+pub mod config;
+pub mod file_watcher;
+pub mod dev_server;
+pub mod build;
+pub mod cache;
+pub mod compress;
+pub mod logging;
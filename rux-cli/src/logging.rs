@@ -0,0 +1,221 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::{self, Write as _};
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing_core::span::Current;
+use tracing::{Event, Level, Metadata, Subscriber};
+
+/// Most verbose level shown for a count of `-v` flags: warnings by default,
+/// debug for `-v` and everything for `-vv`
+pub fn level_for_verbosity(verbose: u8) -> Level {
+    match verbose {
+        0 => Level::WARN,
+        1 => Level::DEBUG,
+        _ => Level::TRACE,
+    }
+}
+
+/// Installs a `StderrLogger` for `verbose` as the process-wide subscriber.
+/// Does nothing if one is already installed.
+pub fn init(verbose: u8) {
+    let _ = tracing::subscriber::set_global_default(StderrLogger::new(level_for_verbosity(verbose)));
+}
+
+/// Prints events at or above a level to stderr, prefixed with the spans
+/// they happened in, e.g. `DEBUG compile{file=app.rsx}:pass{name=optimize}: inlined component component=Card`
+pub struct StderrLogger {
+    max_level: Level,
+    next_id: AtomicU64,
+    /// Every open span, keyed by id
+    spans: Mutex<HashMap<u64, SpanData>>,
+    output: Mutex<Box<dyn Write + Send>>,
+}
+
+struct SpanData {
+    metadata: &'static Metadata<'static>,
+    /// Name and fields, e.g. `compile{file=app.rsx}`
+    label: String,
+    parent: Option<u64>,
+    /// Handles to the span, plus one per open child span. The span is
+    /// dropped once this reaches zero.
+    refs: usize,
+}
+
+thread_local! {
+    /// Spans entered on this thread, innermost last
+    static CURRENT: RefCell<Vec<u64>> = const { RefCell::new(Vec::new()) };
+}
+
+impl StderrLogger {
+    pub fn new(max_level: Level) -> Self {
+        Self {
+            max_level,
+            next_id: AtomicU64::new(1),
+            spans: Mutex::new(HashMap::new()),
+            output: Mutex::new(Box::new(io::stderr())),
+        }
+    }
+
+    /// Writes to `output` instead of stderr
+    pub fn with_output(self, output: impl Write + Send + 'static) -> Self {
+        Self {
+            output: Mutex::new(Box::new(output)),
+            ..self
+        }
+    }
+
+    /// `compile{file=app.rsx}:pass{name=optimize}` for the span entered last on this
+    /// thread and its parents, which may have been entered on another
+    fn scope(&self) -> String {
+        let spans = self.spans.lock().unwrap_or_else(|e| e.into_inner());
+        let mut labels = Vec::new();
+        let mut next = CURRENT.with(|current| current.borrow().last().copied());
+        while let Some(span) = next.and_then(|id| spans.get(&id)) {
+            labels.push(span.label.as_str());
+            next = span.parent;
+        }
+        labels.reverse();
+        labels.join(":")
+    }
+}
+
+impl Subscriber for StderrLogger {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        *metadata.level() <= self.max_level
+    }
+
+    fn max_level_hint(&self) -> Option<tracing::level_filters::LevelFilter> {
+        Some(self.max_level.into())
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let mut fields = FieldWriter::default();
+        span.record(&mut fields);
+        let label = if fields.fields.is_empty() {
+            span.metadata().name().to_string()
+        } else {
+            format!("{}{{{}}}", span.metadata().name(), fields.fields.trim_start())
+        };
+        let parent = if span.is_contextual() {
+            CURRENT.with(|current| current.borrow().last().copied())
+        } else {
+            span.parent().map(Id::into_u64)
+        };
+
+        let mut spans = self.spans.lock().unwrap_or_else(|e| e.into_inner());
+        // A child keeps its parent open, so it can still name it
+        let parent = parent.filter(|parent| match spans.get_mut(parent) {
+            Some(parent) => {
+                parent.refs += 1;
+                true
+            }
+            None => false,
+        });
+        spans.insert(id, SpanData { metadata: span.metadata(), label, parent, refs: 1 });
+        Id::from_u64(id)
+    }
+
+    fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut fields = FieldWriter::default();
+        event.record(&mut fields);
+        let scope = self.scope();
+        let separator = if scope.is_empty() { "" } else { ": " };
+        let mut output = self.output.lock().unwrap_or_else(|e| e.into_inner());
+        let _ = writeln!(
+            output,
+            "{:>5} {}{}{}{}",
+            event.metadata().level(),
+            scope,
+            separator,
+            fields.message,
+            fields.fields
+        );
+    }
+
+    fn enter(&self, span: &Id) {
+        CURRENT.with(|current| current.borrow_mut().push(span.into_u64()));
+    }
+
+    fn exit(&self, span: &Id) {
+        CURRENT.with(|current| {
+            let mut current = current.borrow_mut();
+            if let Some(pos) = current.iter().rposition(|id| *id == span.into_u64()) {
+                current.remove(pos);
+            }
+        });
+    }
+
+    /// Lets `Span::current()` find the span entered last on this thread,
+    /// e.g. to hand it to worker threads
+    fn current_span(&self) -> Current {
+        let Some(id) = CURRENT.with(|current| current.borrow().last().copied()) else {
+            return Current::none();
+        };
+        match self.spans.lock().unwrap_or_else(|e| e.into_inner()).get(&id) {
+            Some(span) => Current::new(Id::from_u64(id), span.metadata),
+            None => Current::none(),
+        }
+    }
+
+    fn clone_span(&self, span: &Id) -> Id {
+        if let Some(span) = self.spans.lock().unwrap_or_else(|e| e.into_inner()).get_mut(&span.into_u64()) {
+            span.refs += 1;
+        }
+        span.clone()
+    }
+
+    fn try_close(&self, span: Id) -> bool {
+        release(&mut self.spans.lock().unwrap_or_else(|e| e.into_inner()), span.into_u64())
+    }
+}
+
+/// Drops one reference to span `id`, removing it once none are left.
+/// Returns whether it was removed.
+fn release(spans: &mut HashMap<u64, SpanData>, id: u64) -> bool {
+    let Some(span) = spans.get_mut(&id) else {
+        return false;
+    };
+    span.refs -= 1;
+    if span.refs > 0 {
+        return false;
+    }
+    // The span no longer holds its parent open
+    if let Some(parent) = spans.remove(&id).and_then(|span| span.parent) {
+        release(spans, parent);
+    }
+    true
+}
+
+/// Collects an event's message and its other fields as ` key=value` pairs
+#[derive(Default)]
+struct FieldWriter {
+    message: String,
+    fields: String,
+}
+
+impl Visit for FieldWriter {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{:?}", value);
+        } else {
+            let _ = write!(self.fields, " {}={:?}", field.name(), value);
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            let _ = write!(self.fields, " {}={}", field.name(), value);
+        }
+    }
+}
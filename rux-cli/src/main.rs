@@ -1,16 +1,12 @@
 // CLI tool for RUX
 
-mod commands;
-mod file_watcher;
-mod dev_server;
-mod build;
-
 use clap::Parser;
-use commands::{Cli, Commands, handle_build, handle_dev, handle_new, handle_check};
+use rux_cli::commands::{Cli, Commands, handle_build, handle_dev, handle_new, handle_check};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
+    rux_cli::logging::init(cli.verbose);
     
     match cli.command {
         Commands::Build(args) => handle_build(args)?,
@@ -0,0 +1,151 @@
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+    use rux_cli::build::BuildSystem;
+    use rux_cli::cache::{BuildCache, CacheStats};
+    use rux_cli::commands::{build_in, Cli, Commands};
+    use rux_cli::compress::crc32;
+    use std::path::Path;
+
+    #[test]
+    fn test_rebuild_regenerates_only_changed_file() {
+        let project = tempfile::tempdir().unwrap();
+        let root = project.path();
+        let src = root.join("src");
+        let out = root.join("dist");
+        std::fs::create_dir_all(&src).unwrap();
+        std::fs::write(src.join("header.rsx"), "fn Header() -> Element { <h1>Title</h1> }").unwrap();
+        std::fs::write(src.join("footer.rsx"), "fn Footer() -> Element { <p>Old</p> }").unwrap();
+
+        let generated_path = root.join("target").join("src").join("generated.rs");
+        let build_system = BuildSystem::new().with_src_dir(&src).with_target_dir(root.join("target"));
+        build_system.build_web(&out, false).unwrap();
+        assert_eq!(build_system.cache_stats(), CacheStats { reused: 0, generated: 2 });
+        let first = std::fs::read_to_string(&generated_path).unwrap();
+
+        build_system.build_web(&out, false).unwrap();
+        assert_eq!(build_system.cache_stats(), CacheStats { reused: 2, generated: 0 });
+        assert_eq!(std::fs::read_to_string(&generated_path).unwrap(), first);
+
+        std::fs::write(src.join("footer.rsx"), "fn Footer() -> Element { <p>New</p> }").unwrap();
+        build_system.build_web(&out, false).unwrap();
+        assert_eq!(build_system.cache_stats(), CacheStats { reused: 1, generated: 1 });
+        let generated = std::fs::read_to_string(&generated_path).unwrap();
+        assert!(generated.contains("New"));
+        assert!(!generated.contains("Old"));
+
+        // Entries from another compiler version are not trusted
+        std::fs::write(out.join(BuildCache::DIR_NAME).join("version"), "0.0.0-old").unwrap();
+        build_system.build_web(&out, false).unwrap();
+        assert_eq!(build_system.cache_stats(), CacheStats { reused: 0, generated: 2 });
+    }
+
+    #[test]
+    fn test_minify_shrinks_index_html() {
+        let project = tempfile::tempdir().unwrap();
+        let root = project.path();
+        let src = root.join("src");
+        std::fs::create_dir_all(&src).unwrap();
+        std::fs::write(src.join("app.rsx"), "fn App() -> Element { <div>Hi</div> }").unwrap();
+
+        let pretty_out = root.join("pretty");
+        BuildSystem::new().with_src_dir(&src).with_target_dir(root.join("target")).build_web(&pretty_out, false).unwrap();
+        let minified_out = root.join("minified");
+        BuildSystem::new().with_src_dir(&src).with_target_dir(root.join("target")).with_minify(true).build_web(&minified_out, false).unwrap();
+
+        let pretty = std::fs::read_to_string(pretty_out.join("index.html")).unwrap();
+        let minified = std::fs::read_to_string(minified_out.join("index.html")).unwrap();
+
+        assert!(minified.len() < pretty.len());
+        assert!(minified.contains("<body><div id=\"root\"></div><script type=\"module\">"));
+        // Script content is left alone
+        assert!(minified.contains("init().then(() => {\n"));
+    }
+
+    #[test]
+    fn test_compress_writes_gzip_and_brotli_siblings() {
+        let project = tempfile::tempdir().unwrap();
+        let root = project.path();
+        let src = root.join("src");
+        let out = root.join("dist");
+        std::fs::create_dir_all(&src).unwrap();
+        std::fs::write(src.join("app.rsx"), "fn App() -> Element { <div>Hi</div> }").unwrap();
+
+        BuildSystem::new().with_src_dir(&src).with_target_dir(root.join("target")).with_compress(true).build_web(&out, false).unwrap();
+        let html = std::fs::read(out.join("index.html")).unwrap();
+        let gz = std::fs::read(out.join("index.html.gz")).unwrap();
+        let br = std::fs::read(out.join("index.html.br")).unwrap();
+        let generated_gz = out.join("generated.rs.gz").exists();
+
+        assert!(!generated_gz);
+        assert_eq!(&gz[..3], &[0x1f, 0x8b, 8]);
+        let (body, trailer) = gz[10..].split_at(gz.len() - 18);
+        assert_eq!(miniz_oxide::inflate::decompress_to_vec(body).unwrap(), html);
+        assert_eq!(trailer[..4], crc32(&html).to_le_bytes());
+        assert_eq!(trailer[4..], (html.len() as u32).to_le_bytes());
+
+        let mut decompressed = Vec::new();
+        brotli::BrotliDecompress(&mut &br[..], &mut decompressed).unwrap();
+        assert_eq!(decompressed, html);
+    }
+
+    #[test]
+    fn test_crc32_check_value() {
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+    }
+
+    #[test]
+    fn test_build_from_subdirectory_uses_project_root() {
+        let project = tempfile::tempdir().unwrap();
+        let root = project.path();
+        let nested = root.join("app").join("widgets");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(
+            root.join("rux.toml"),
+            "[build]\nsrc_dir = \"app\"\nout_dir = \"public\"\ntarget_dir = \"build/generated\"\n",
+        )
+        .unwrap();
+        std::fs::write(nested.join("button.rsx"), "fn Button() -> Element { <button>Go</button> }").unwrap();
+
+        let Commands::Build(args) = Cli::try_parse_from(["rux", "build"]).unwrap().command else {
+            panic!("Expected build command");
+        };
+        build_in(args, &nested).unwrap();
+
+        let crate_dir = root.join("build").join("generated");
+        let lib = std::fs::read_to_string(crate_dir.join("src").join("lib.rs"));
+        let generated = std::fs::read_to_string(crate_dir.join("src").join("generated.rs"));
+        let manifest = std::fs::read_to_string(crate_dir.join("Cargo.toml"));
+        let html = root.join("public").join("index.html").exists();
+        let stray = nested.join("public").exists() || nested.join("target").exists();
+
+        assert!(lib.unwrap().contains("pub use generated::*;"));
+        assert!(generated.unwrap().contains("pub fn button()"));
+        let workspace_core = Path::new(env!("CARGO_MANIFEST_DIR")).join("../rux-core").canonicalize().unwrap();
+        assert!(
+            manifest.unwrap().contains(&format!("rux-core = {{ path = {:?} }}", workspace_core.display().to_string())),
+            "a workspace build depends on the workspace's rux-core"
+        );
+        assert!(html);
+        assert!(!stray);
+    }
+
+    #[test]
+    fn test_build_depends_on_configured_rux_core() {
+        let project = tempfile::tempdir().unwrap();
+        let root = project.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("rux.toml"), "[build]\nrux_core = \"vendor/rux-core\"\n").unwrap();
+        std::fs::write(root.join("src").join("app.rsx"), "fn App() -> Element { <div>Hi</div> }").unwrap();
+
+        let Commands::Build(args) = Cli::try_parse_from(["rux", "build"]).unwrap().command else {
+            panic!("Expected build command");
+        };
+        build_in(args, root).unwrap();
+
+        let manifest = std::fs::read_to_string(root.join("target").join("rux").join("Cargo.toml"));
+
+        let core = root.join("vendor/rux-core").display().to_string();
+        assert!(manifest.unwrap().contains(&format!("rux-core = {{ path = {:?} }}", core)));
+    }
+}
@@ -0,0 +1,186 @@
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+    use rux_cli::commands::{check_watcher, run_check, watch_iteration, Cli, Commands};
+    use std::process::{Command, Output};
+    use std::time::Duration;
+    use tempfile::NamedTempFile;
+
+    fn write_source(source: &str) -> NamedTempFile {
+        let file = tempfile::Builder::new().suffix(".rsx").tempfile().unwrap();
+        std::fs::write(file.path(), source).unwrap();
+        file
+    }
+
+    /// Runs the binary in a fresh project whose `src/` holds `files`
+    fn rux_in_project(files: &[(&str, &str)], args: &[&str]) -> Output {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(root.path().join("src")).unwrap();
+        for (file, source) in files {
+            std::fs::write(root.path().join("src").join(file), source).unwrap();
+        }
+        Command::new(env!("CARGO_BIN_EXE_rux")).args(args).current_dir(root.path()).output().unwrap()
+    }
+
+    fn check(args: &[&str]) -> String {
+        let cli = Cli::try_parse_from(args).unwrap();
+        let Commands::Check(check_args) = cli.command else {
+            panic!("Expected check command");
+        };
+        let mut out = Vec::new();
+        run_check(&check_args, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_json_check_without_files_discovers_src() {
+        let output = rux_in_project(
+            &[("ok.rsx", "fn one() -> i32 { 1 }"), ("bad.rsx", "fn label() -> String { \"on: \" + true }")],
+            &["check", "--format", "json"],
+        );
+        assert!(!output.status.success());
+
+        let diagnostics: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        let diagnostics = diagnostics.as_array().unwrap();
+        assert_eq!(diagnostics.len(), 1, "{:?}", diagnostics);
+        assert!(diagnostics[0]["file"].as_str().unwrap().ends_with("bad.rsx"));
+        assert_eq!(diagnostics[0]["code"], "rux::type_check");
+    }
+
+    #[test]
+    fn test_emit_ast() {
+        let file = write_source("fn App() -> Element { <div>Hi</div> }");
+        let output = check(&["rux", "check", "--emit", "ast", file.path().to_str().unwrap()]);
+
+        assert!(output.contains("Component(App)"));
+        assert!(output.contains("JSX(div)"));
+    }
+
+    #[test]
+    fn test_emit_without_files_discovers_src() {
+        let output = rux_in_project(&[("app.rsx", "fn App() -> Element { <div>Hi</div> }")], &["check", "--emit", "ast"]);
+        assert!(output.status.success());
+        assert!(String::from_utf8_lossy(&output.stdout).contains("Component(App)"));
+    }
+
+    #[test]
+    fn test_emit_conflicts_with_json_format() {
+        let err = Cli::try_parse_from(["rux", "check", "--emit", "ast", "--format", "json"]).err().unwrap();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ArgumentConflict);
+    }
+
+    #[test]
+    fn test_emit_ast_reports_recovered_errors() {
+        let file = write_source("fn total() -> i32 {\n    let a = 1\n    a\n}");
+        let cli = Cli::try_parse_from(["rux", "check", "--emit", "ast", file.path().to_str().unwrap()]).unwrap();
+        let Commands::Check(check_args) = cli.command else {
+            panic!("Expected check command");
+        };
+
+        let err = run_check(&check_args, &mut Vec::new()).unwrap_err();
+        assert!(err.to_string().contains("Missing ';'"), "{}", err);
+    }
+
+    #[test]
+    fn test_emit_tokens_and_rust() {
+        let file = write_source("fn double(x: i32) -> i32 { x * 2 }");
+
+        let tokens = check(&["rux", "check", "--emit", "tokens", file.path().to_str().unwrap()]);
+        assert!(tokens.lines().next().unwrap().starts_with("1:1 Fn"));
+
+        let rust = check(&["rux", "check", "--emit", "rust", file.path().to_str().unwrap()]);
+        assert!(rust.contains("fn double"));
+    }
+
+    #[test]
+    fn test_watch_reruns_check_on_change() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("app.rsx");
+        std::fs::write(&file, "fn one() -> i32 { 1 }").unwrap();
+
+        let cli = Cli::try_parse_from(["rux", "check", "--watch", file.to_str().unwrap()]).unwrap();
+        let Commands::Check(args) = cli.command else {
+            panic!("Expected check command");
+        };
+        assert!(args.watch);
+        let watcher = check_watcher(&args).unwrap();
+
+        // Nothing has changed yet
+        let mut out = Vec::new();
+        assert!(!watch_iteration(&args, &watcher, Some(Duration::from_millis(100)), &mut out).unwrap());
+        assert!(out.is_empty());
+
+        std::fs::write(&file, "fn two() -> i32 { 2 }").unwrap();
+        let ran = watch_iteration(&args, &watcher, Some(Duration::from_secs(5)), &mut out).unwrap();
+        assert!(ran);
+
+        let output = String::from_utf8(out).unwrap();
+        assert!(output.starts_with("\x1b[2J\x1b[H"));
+        assert!(output.contains("Checking"));
+        assert!(output.contains("app.rsx"));
+        assert!(output.ends_with("Watching for changes...\n"));
+    }
+
+    #[test]
+    fn test_verbose_flag_sets_log_level() {
+        use rux_cli::logging::level_for_verbosity;
+
+        let quiet = Cli::try_parse_from(["rux", "check", "app.rsx"]).unwrap();
+        let after = Cli::try_parse_from(["rux", "check", "-v", "app.rsx"]).unwrap();
+        let before = Cli::try_parse_from(["rux", "-vv", "check", "app.rsx"]).unwrap();
+
+        assert_eq!(level_for_verbosity(quiet.verbose), tracing::Level::WARN);
+        assert_eq!(level_for_verbosity(after.verbose), tracing::Level::DEBUG);
+        assert_eq!(level_for_verbosity(before.verbose), tracing::Level::TRACE);
+    }
+
+    /// Log output shared with the test that installed the logger
+    #[derive(Clone, Default)]
+    struct SharedOutput(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl std::io::Write for SharedOutput {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_logger_prefixes_events_with_their_spans() {
+        use rux_cli::logging::StderrLogger;
+
+        let output = SharedOutput::default();
+        let logger = StderrLogger::new(tracing::Level::DEBUG).with_output(output.clone());
+        tracing::subscriber::with_default(logger, || {
+            let compile = tracing::debug_span!("compile", file = "app.rsx");
+            // A clone keeps the span open after the original is dropped
+            let handle = compile.clone();
+            drop(compile);
+
+            let _entered = handle.enter();
+            tracing::debug!(items = 2, "parsed");
+            // Like the parser threads, which get the global logger in `rux`
+            let dispatch = tracing::dispatcher::get_default(|dispatch| dispatch.clone());
+            std::thread::scope(|scope| {
+                let parent = tracing::Span::current();
+                scope.spawn(move || {
+                    tracing::dispatcher::with_default(&dispatch, || {
+                        let _parent = parent.enter();
+                        let _optimize = tracing::debug_span!("optimize").entered();
+                        tracing::debug!("inlined component");
+                    });
+                });
+            });
+            tracing::trace!("too verbose");
+        });
+
+        let output = String::from_utf8(output.0.lock().unwrap().clone()).unwrap();
+        assert_eq!(
+            output,
+            "DEBUG compile{file=app.rsx}: parsed items=2\nDEBUG compile{file=app.rsx}:optimize: inlined component\n"
+        );
+    }
+}
@@ -0,0 +1,113 @@
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+    use rux_cli::commands::{BuildArgs, Cli, Commands, DevArgs};
+    use rux_cli::config::ProjectConfig;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    fn write_config(source: &str) -> TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(ProjectConfig::FILE_NAME), source).unwrap();
+        dir
+    }
+
+    fn build_args(args: &[&str]) -> BuildArgs {
+        let cli = Cli::try_parse_from(args).unwrap();
+        let Commands::Build(build_args) = cli.command else {
+            panic!("Expected build command");
+        };
+        build_args
+    }
+
+    fn dev_args(args: &[&str]) -> DevArgs {
+        let cli = Cli::try_parse_from(args).unwrap();
+        let Commands::Dev(dev_args) = cli.command else {
+            panic!("Expected dev command");
+        };
+        dev_args
+    }
+
+    #[test]
+    fn test_config_provides_build_defaults() {
+        let dir = write_config(
+            "[build]\ntarget = \"desktop\"\nout_dir = \"out\"\nrelease = true\nminify = true\n\n[dev]\nport = 8080\nopen = true\n",
+        );
+        let config = ProjectConfig::load(dir.path()).unwrap();
+        assert_eq!(config.dev.port, Some(8080));
+        assert_eq!(config.dev.open, Some(true));
+
+        let mut args = build_args(&["rux", "build"]);
+        args.merge_config(&config.build);
+
+        assert_eq!(args.target(), "desktop");
+        assert_eq!(args.out_dir(), Path::new("out"));
+        assert!(args.release);
+        assert!(args.minify);
+    }
+
+    #[test]
+    fn test_cli_flags_override_config() {
+        let dir = write_config("[build]\ntarget = \"desktop\"\nout_dir = \"out\"\n");
+        let config = ProjectConfig::load(dir.path()).unwrap();
+
+        let mut args = build_args(&["rux", "build", "--target", "web"]);
+        args.merge_config(&config.build);
+
+        assert_eq!(args.target(), "web");
+        assert_eq!(args.out_dir(), Path::new("out"));
+        assert!(!args.release);
+    }
+
+    #[test]
+    fn test_no_flags_override_config() {
+        let dir = write_config("[build]\nrelease = true\nminify = true\ncompress = true\n");
+        let config = ProjectConfig::load(dir.path()).unwrap();
+
+        let mut args = build_args(&["rux", "build", "--no-release", "--no-minify", "--no-compress"]);
+        args.merge_config(&config.build);
+        assert!(!args.release);
+        assert!(!args.minify);
+        assert!(!args.compress);
+
+        let mut args = build_args(&["rux", "build", "--no-release", "--release"]);
+        args.merge_config(&config.build);
+        assert!(args.release, "the last of a flag and its `--no-` form wins");
+    }
+
+    #[test]
+    fn test_no_open_overrides_config() {
+        let dir = write_config("[dev]\nopen = true\n");
+        let config = ProjectConfig::load(dir.path()).unwrap();
+
+        let mut args = dev_args(&["rux", "dev"]);
+        args.merge_config(&config.dev);
+        assert!(args.open);
+
+        let mut args = dev_args(&["rux", "dev", "--no-open"]);
+        args.merge_config(&config.dev);
+        assert!(!args.open);
+
+        let mut args = dev_args(&["rux", "dev", "--no-open", "--open"]);
+        args.merge_config(&config.dev);
+        assert!(args.open, "the last of a flag and its `--no-` form wins");
+    }
+
+    #[test]
+    fn test_missing_config_uses_defaults() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = ProjectConfig::load(dir.path()).unwrap();
+
+        let mut args = build_args(&["rux", "build"]);
+        args.merge_config(&config.build);
+
+        assert_eq!(args.target(), "web");
+        assert_eq!(args.out_dir(), Path::new("dist"));
+    }
+
+    #[test]
+    fn test_invalid_config_value() {
+        let err = ProjectConfig::parse("[dev]\nport = \"eighty\"\n").unwrap_err();
+        assert!(err.to_string().contains("dev.port must be an integer"));
+    }
+}
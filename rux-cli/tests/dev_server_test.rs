@@ -0,0 +1,141 @@
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+    use rux_cli::commands::{dev_loop, Cli, Commands};
+    use rux_cli::dev_server::{inject_reload_client, DevServer, DevState};
+    use rux_cli::file_watcher::FileWatcher;
+    use rux_compiler::IncrementalCompiler;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// Sends a GET and returns the raw response
+    async fn get(addr: std::net::SocketAddr, path: &str) -> String {
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let request = format!("GET {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n", path);
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).await.unwrap();
+        String::from_utf8_lossy(&response).into_owned()
+    }
+
+    #[tokio::test]
+    async fn test_serves_build_output_with_content_types() {
+        let dir = tempfile::tempdir().unwrap();
+        let out_dir = dir.path();
+        std::fs::write(out_dir.join("index.html"), "<div id=\"root\"></div>").unwrap();
+        std::fs::write(out_dir.join("rux_web_bg.wasm"), b"\0asm\x01\0\0\0").unwrap();
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = DevServer::new(addr.port()).with_out_dir(out_dir);
+        tokio::spawn(async move { server.serve(listener).await });
+
+        let wasm = get(addr, "/rux_web_bg.wasm").await;
+        let index = get(addr, "/").await;
+        let missing = get(addr, "/missing.js").await;
+
+        assert!(wasm.starts_with("HTTP/1.1 200"));
+        assert!(wasm.to_lowercase().contains("content-type: application/wasm"));
+        assert!(index.to_lowercase().contains("content-type: text/html"));
+        assert!(index.contains("<div id=\"root\"></div>"));
+        assert!(index.contains("/ws"));
+        assert_eq!(index.matches("<script data-rux-reload>").count(), 1);
+        assert!(missing.starts_with("HTTP/1.1 404"));
+    }
+
+    #[test]
+    fn test_reload_client_is_injected_once() {
+        let page = "<html><body><div id=\"root\"></div></body></html>";
+        let injected = inject_reload_client(page);
+        assert!(injected.contains("new WebSocket(protocol + location.host + '/ws')"));
+        assert!(injected.ends_with("</script>\n</body></html>"));
+        assert_eq!(inject_reload_client(&injected), injected);
+
+        // Fragments without a body get it appended
+        assert!(inject_reload_client("<p>Hi</p>").starts_with("<p>Hi</p>\n<script data-rux-reload>"));
+    }
+
+    #[tokio::test]
+    async fn test_failed_compile_shows_error_overlay() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let src = root.join("src");
+        let out_dir = root.join("dist");
+        std::fs::create_dir_all(&src).unwrap();
+        std::fs::create_dir_all(&out_dir).unwrap();
+        std::fs::write(out_dir.join("index.html"), "<body><div id=\"app\"></div></body>").unwrap();
+        let file = src.join("app.rsx");
+        std::fs::write(&file, "fn total() -> f64 { 1 + missing }").unwrap();
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = DevServer::new(addr.port()).with_out_dir(&out_dir);
+        let state = server.state();
+        let mut updates = state.subscribe();
+        tokio::spawn(async move { server.serve(listener).await });
+
+        let mut compiler = IncrementalCompiler::with_root(&src);
+        let error = compiler.compile_incremental(std::slice::from_ref(&file)).unwrap_err();
+        state.set_error(&error);
+        assert_eq!(updates.recv().await.unwrap(), "reload");
+        let overlay = get(addr, "/").await;
+
+        std::fs::write(&file, "fn total() -> f64 { 1 + 2 }").unwrap();
+        compiler.compile_incremental(std::slice::from_ref(&file)).unwrap();
+        state.clear_error();
+        let page = get(addr, "/").await;
+
+        assert!(overlay.starts_with("HTTP/1.1 500"));
+        assert!(overlay.contains("rux-error-overlay"));
+        assert!(overlay.contains("Undefined variable: missing"));
+        // The report quotes the offending source
+        assert!(overlay.contains("fn total() -&gt; f64 { 1 + missing }"));
+        assert!(overlay.contains("data-rux-reload"));
+
+        assert!(page.starts_with("HTTP/1.1 200"));
+        assert!(page.contains("<div id=\"app\"></div>"));
+        assert!(!page.contains("rux-error-overlay"));
+    }
+
+    #[tokio::test]
+    async fn test_binds_to_requested_host() {
+        let server = DevServer::new(0).with_host("0.0.0.0".parse().unwrap());
+        let listener = server.bind().await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        assert!(addr.ip().is_unspecified(), "{}", addr);
+        assert_ne!(addr.port(), 0);
+        drop(listener);
+
+        let Commands::Dev(args) = Cli::try_parse_from(["rux", "dev", "--host", "0.0.0.0"]).unwrap().command else {
+            panic!("expected the dev command");
+        };
+        assert_eq!(args.host.to_string(), "0.0.0.0");
+        let Commands::Dev(args) = Cli::try_parse_from(["rux", "dev"]).unwrap().command else {
+            panic!("expected the dev command");
+        };
+        assert!(args.host.is_loopback());
+    }
+
+    #[tokio::test]
+    async fn test_dev_loop_exits_on_shutdown() {
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path();
+        let mut watcher = FileWatcher::new().unwrap();
+        watcher.watch_directory(src).unwrap();
+        let mut compiler = IncrementalCompiler::with_root(src);
+        let state = DevState::new();
+
+        let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+        let shutdown = async {
+            let _ = stopped.await;
+        };
+        stop.send(()).unwrap();
+        let finished = tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            dev_loop(&watcher, &mut compiler, &state, shutdown),
+        )
+        .await;
+
+        assert!(finished.is_ok(), "the loop kept running after shutdown");
+        assert!(state.error().is_none());
+    }
+}
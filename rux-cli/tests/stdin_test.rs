@@ -0,0 +1,45 @@
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::process::{Command, Output, Stdio};
+
+    fn rux_with_stdin(args: &[&str], source: &str) -> Output {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_rux"))
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child.stdin.take().unwrap().write_all(source.as_bytes()).unwrap();
+        child.wait_with_output().unwrap()
+    }
+
+    #[test]
+    fn test_check_valid_stdin() {
+        let output = rux_with_stdin(&["check", "-"], "fn double(x: i32) -> i32 { x * 2 }");
+        assert!(output.status.success());
+        assert!(String::from_utf8_lossy(&output.stdout).contains("Checking \"<stdin>\"..."));
+    }
+
+    #[test]
+    fn test_check_invalid_stdin() {
+        let output = rux_with_stdin(&["check", "--stdin"], "fn label() -> String { \"on: \" + true }");
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("String concatenation"));
+    }
+
+    #[test]
+    fn test_check_stdin_json() {
+        let output = rux_with_stdin(
+            &["check", "--format", "json", "-"],
+            "fn label() -> String { \"on: \" + true }",
+        );
+        assert!(!output.status.success());
+
+        let diagnostics: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(diagnostics[0]["file"], "<stdin>");
+        assert_eq!(diagnostics[0]["code"], "rux::type_check");
+        assert_eq!(diagnostics[0]["line"], 1);
+    }
+}
@@ -1,11 +1,16 @@
 use crate::ast::*;
+use crate::diagnostics::WarningCollector;
 use crate::errors::Result;
+use crate::lexer::Span;
 use std::collections::{HashMap, HashSet};
 
 pub struct DependencyAnalyzer {
     dependencies: HashMap<String, HashSet<String>>,
+    #[allow(dead_code)]
     signals: HashSet<String>,
     components: HashSet<String>,
+    prop_types: HashSet<String>,
+    warnings: WarningCollector,
 }
 
 impl DependencyAnalyzer {
@@ -14,12 +19,65 @@ impl DependencyAnalyzer {
             dependencies: HashMap::new(),
             signals: HashSet::new(),
             components: HashSet::new(),
+            prop_types: HashSet::new(),
+            warnings: WarningCollector::new(),
         }
     }
     
+    /// Warnings reported by the last analysis, leaving the collector empty
+    pub fn take_warnings(&mut self) -> WarningCollector {
+        std::mem::take(&mut self.warnings)
+    }
+    
     pub fn analyze(&mut self, ast: &AST) -> Result<()> {
         // Build dependency graph
-        for item in &ast.items {
+        self.analyze_items(&ast.items)?;
+        self.check_unused_imports(&ast.items);
+        Ok(())
+    }
+    
+    /// Warns about every `use` whose name, or alias, is never referenced
+    /// in the file. Glob imports are left alone, since what they bring in
+    /// isn't known here.
+    fn check_unused_imports(&mut self, items: &[Item]) {
+        let mut referenced = HashSet::new();
+        names_in_items(items, &mut referenced);
+        self.warn_unused_imports(items, &referenced);
+    }
+    
+    fn warn_unused_imports(&mut self, items: &[Item], referenced: &HashSet<String>) {
+        for item in items {
+            match item {
+                Item::Use(use_stmt) if !use_stmt.glob => {
+                    let Some(name) = use_stmt.alias.as_ref().or(use_stmt.path.last()) else {
+                        continue;
+                    };
+                    if !referenced.contains(name) {
+                        self.warnings.warn(format!("Unused import '{}'", name), use_stmt.span);
+                    }
+                }
+                Item::Mod(module) => self.warn_unused_imports(&module.items, referenced),
+                _ => {}
+            }
+        }
+    }
+    
+    /// Warns about every `let` in `body` whose name is never referenced in
+    /// it. Names starting with `_` are meant to be unused.
+    fn check_unused_variables(&mut self, body: &Block) {
+        let mut referenced = HashSet::new();
+        names_in_block(body, &mut referenced);
+        let mut bindings = Vec::new();
+        lets_in_block(body, &mut bindings);
+        for (name, span) in bindings {
+            if !name.starts_with('_') && !referenced.contains(name) {
+                self.warnings.warn(format!("Unused variable '{}'", name), span);
+            }
+        }
+    }
+    
+    fn analyze_items(&mut self, items: &[Item]) -> Result<()> {
+        for item in items {
             match item {
                 Item::Component(c) => {
                     self.components.insert(c.name.clone());
@@ -28,6 +86,9 @@ impl DependencyAnalyzer {
                 Item::Function(f) => {
                     self.analyze_function(f)?;
                 }
+                Item::Mod(m) => {
+                    self.analyze_items(&m.items)?;
+                }
                 _ => {}
             }
         }
@@ -35,6 +96,23 @@ impl DependencyAnalyzer {
     }
     
     fn analyze_component(&mut self, component: &Component) -> Result<()> {
+        if !component.name.starts_with(|c: char| c.is_ascii_uppercase()) {
+            self.warnings.warn(
+                format!("Component '{}' should have a PascalCase name", component.name),
+                component.span,
+            );
+        }
+        
+        for prop in &component.props {
+            if let Some(name) = Self::named_type(&prop.param_type) {
+                self.prop_types.insert(name.to_string());
+            }
+        }
+        
+        if let Expr::Block(body, _) = &component.body {
+            self.check_unused_variables(body);
+        }
+        
         let mut deps = HashSet::new();
         self.collect_dependencies_from_expr(&component.body, &mut deps);
         self.dependencies.insert(component.name.clone(), deps);
@@ -42,6 +120,8 @@ impl DependencyAnalyzer {
     }
     
     fn analyze_function(&mut self, function: &Function) -> Result<()> {
+        self.check_unused_variables(&function.body);
+        
         let mut deps = HashSet::new();
         self.collect_dependencies_from_block(&function.body, &mut deps);
         self.dependencies.insert(function.name.clone(), deps);
@@ -102,15 +182,18 @@ impl DependencyAnalyzer {
             Stmt::Let { value, .. } => {
                 self.collect_dependencies_from_expr(value, deps);
             }
-            Stmt::Expr(expr) => {
+            Stmt::Expr(expr) | Stmt::Tail(expr) => {
                 self.collect_dependencies_from_expr(expr, deps);
             }
             Stmt::Return(Some(expr), _) => {
                 self.collect_dependencies_from_expr(expr, deps);
             }
-            Stmt::Return(None, _) => {
+            Stmt::Return(None, _) | Stmt::Break(None, _) => {
                 // No dependencies for return without value
             }
+            Stmt::Break(Some(expr), _) => {
+                self.collect_dependencies_from_expr(expr, deps);
+            }
             Stmt::If { condition, then, else_, .. } => {
                 self.collect_dependencies_from_expr(condition, deps);
                 self.collect_dependencies_from_stmt(then, deps);
@@ -163,18 +246,44 @@ impl DependencyAnalyzer {
     }
     
     fn collect_dependencies_from_jsx_prop(&self, prop: &JSXProp, deps: &mut HashSet<String>) {
-        match &prop.value {
-            JSXPropValue::Expr(expr) => {
-                self.collect_dependencies_from_expr(expr, deps);
-            }
-            _ => {}
+        if let JSXPropValue::Expr(expr) = &prop.value {
+            self.collect_dependencies_from_expr(expr, deps);
+        }
+    }
+    
+    /// Name of a plain or referenced nominal type such as `Props` or `&Props`
+    fn named_type(ty: &Type) -> Option<&str> {
+        match &ty.kind {
+            TypeKind::Ident(name) => Some(name),
+            TypeKind::Path(path) => path.last().map(String::as_str),
+            TypeKind::Reference { inner, .. } => Self::named_type(inner),
+            _ => None,
         }
     }
     
+    /// Names of every component seen during analysis
+    pub fn components(&self) -> &HashSet<String> {
+        &self.components
+    }
+    
+    /// Types used as component props, e.g. `CardProps` in `fn Card(props: CardProps)`
+    pub fn prop_types(&self) -> &HashSet<String> {
+        &self.prop_types
+    }
+    
     pub fn get_dependencies(&self, name: &str) -> Option<&HashSet<String>> {
         self.dependencies.get(name)
     }
     
+    /// Full graph mapping each component/function to the names it references
+    pub fn dependencies(&self) -> &HashMap<String, HashSet<String>> {
+        &self.dependencies
+    }
+    
+    pub fn into_dependencies(self) -> HashMap<String, HashSet<String>> {
+        self.dependencies
+    }
+    
     pub fn track_reactive_dependencies(&self, expr: &Expr) -> HashSet<String> {
         let mut signals = HashSet::new();
         self.collect_reactive_dependencies(expr, &mut signals);
@@ -229,3 +338,318 @@ impl DependencyAnalyzer {
         }
     }
 }
+
+impl Default for DependencyAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Names referenced by items, for spotting unused imports. Paths such as
+/// `Shape::Circle` count as a use of their first segment.
+fn names_in_items(items: &[Item], names: &mut HashSet<String>) {
+    for item in items {
+        match item {
+            Item::Component(c) => {
+                names_in_params(&c.props, names);
+                names_in_type(&c.return_type, names);
+                names_in_where(&c.where_clause, names);
+                names_in_expr(&c.body, names);
+            }
+            Item::Function(f) => names_in_function(f, names),
+            Item::Struct(s) => {
+                for field in &s.fields {
+                    names_in_type(&field.field_type, names);
+                }
+            }
+            Item::Enum(e) => {
+                for variant in &e.variants {
+                    match &variant.data {
+                        Some(EnumVariantData::Tuple(types)) => types.iter().for_each(|ty| names_in_type(ty, names)),
+                        Some(EnumVariantData::Struct(fields)) => {
+                            fields.iter().for_each(|field| names_in_type(&field.field_type, names))
+                        }
+                        None => {}
+                    }
+                }
+            }
+            Item::Trait(t) => {
+                names_in_where(&t.where_clause, names);
+                for trait_item in &t.items {
+                    match trait_item {
+                        TraitItem::Method(method) => names_in_function(method, names),
+                        TraitItem::Type(_, Some(ty)) => names_in_type(ty, names),
+                        TraitItem::Type(_, None) => {}
+                    }
+                }
+            }
+            Item::Impl(i) => {
+                if let Some(trait_name) = &i.trait_name {
+                    insert_path(trait_name, names);
+                }
+                insert_path(&i.type_name, names);
+                names_in_where(&i.where_clause, names);
+                for method in &i.items {
+                    names_in_function(method, names);
+                }
+            }
+            Item::TypeAlias(t) => names_in_type(&t.aliased_type, names),
+            Item::Mod(m) => names_in_items(&m.items, names),
+            Item::Use(_) => {}
+        }
+    }
+}
+
+fn names_in_function(function: &Function, names: &mut HashSet<String>) {
+    names_in_params(&function.params, names);
+    if let Some(ty) = &function.return_type {
+        names_in_type(ty, names);
+    }
+    names_in_where(&function.where_clause, names);
+    names_in_block(&function.body, names);
+}
+
+fn names_in_params(params: &[Param], names: &mut HashSet<String>) {
+    for param in params {
+        names_in_type(&param.param_type, names);
+        if let Some(default) = &param.default {
+            names_in_expr(default, names);
+        }
+    }
+}
+
+fn names_in_where(predicates: &[WherePredicate], names: &mut HashSet<String>) {
+    for predicate in predicates {
+        names_in_type(&predicate.ty, names);
+        predicate.bounds.iter().for_each(|bound| names_in_type(bound, names));
+    }
+}
+
+fn names_in_type(ty: &Type, names: &mut HashSet<String>) {
+    match &ty.kind {
+        TypeKind::Ident(name) => insert_path(name, names),
+        TypeKind::Path(path) => {
+            if let Some(first) = path.first() {
+                names.insert(first.clone());
+            }
+        }
+        TypeKind::Tuple(types) => types.iter().for_each(|ty| names_in_type(ty, names)),
+        TypeKind::Array(inner)
+        | TypeKind::Slice(inner)
+        | TypeKind::Reference { inner, .. }
+        | TypeKind::Option(inner)
+        | TypeKind::Dyn(inner)
+        | TypeKind::Impl(inner)
+        | TypeKind::Range(inner) => names_in_type(inner, names),
+        TypeKind::Function { params, return_type } => {
+            params.iter().for_each(|ty| names_in_type(ty, names));
+            names_in_type(return_type, names);
+        }
+        TypeKind::Generic { name, args } => {
+            insert_path(name, names);
+            args.iter().for_each(|ty| names_in_type(ty, names));
+        }
+        TypeKind::FnTrait { name, params, return_type } => {
+            insert_path(name, names);
+            params.iter().for_each(|ty| names_in_type(ty, names));
+            if let Some(ty) = return_type {
+                names_in_type(ty, names);
+            }
+        }
+        TypeKind::Result { ok, err } => {
+            names_in_type(ok, names);
+            names_in_type(err, names);
+        }
+        TypeKind::Unit => {}
+    }
+}
+
+fn names_in_block(block: &Block, names: &mut HashSet<String>) {
+    for stmt in &block.statements {
+        names_in_stmt(stmt, names);
+    }
+}
+
+fn names_in_stmt(stmt: &Stmt, names: &mut HashSet<String>) {
+    match stmt {
+        Stmt::Let { value, .. }
+        | Stmt::Expr(value)
+        | Stmt::Tail(value)
+        | Stmt::Return(Some(value), _)
+        | Stmt::Break(Some(value), _) => names_in_expr(value, names),
+        Stmt::Return(None, _) | Stmt::Break(None, _) => {}
+        Stmt::If { condition, then, else_, .. } => {
+            names_in_expr(condition, names);
+            names_in_stmt(then, names);
+            if let Some(else_stmt) = else_ {
+                names_in_stmt(else_stmt, names);
+            }
+        }
+        Stmt::For { iter, body, .. } => {
+            names_in_expr(iter, names);
+            names_in_stmt(body, names);
+        }
+        Stmt::While { condition, body, .. } => {
+            names_in_expr(condition, names);
+            names_in_stmt(body, names);
+        }
+        Stmt::Match { expr, arms, .. } => {
+            names_in_expr(expr, names);
+            names_in_arms(arms, names);
+        }
+        Stmt::Block(block) => names_in_block(block, names),
+    }
+}
+
+fn names_in_arms(arms: &[MatchArm], names: &mut HashSet<String>) {
+    for arm in arms {
+        names_in_pattern(&arm.pattern, names);
+        if let Some(guard) = &arm.guard {
+            names_in_expr(guard, names);
+        }
+        names_in_expr(&arm.body, names);
+    }
+}
+
+fn names_in_pattern(pattern: &Pattern, names: &mut HashSet<String>) {
+    match pattern {
+        // A bare identifier may be a unit variant or constant brought in by
+        // an import as well as a new binding
+        Pattern::Ident(name, _) => insert_path(name, names),
+        Pattern::Path(path, _) => {
+            if let Some(first) = path.first() {
+                names.insert(first.clone());
+            }
+        }
+        Pattern::Tuple(elems, _) => elems.iter().for_each(|p| names_in_pattern(p, names)),
+        Pattern::Struct { name, fields, .. } => {
+            insert_path(name, names);
+            fields.iter().for_each(|(_, p)| names_in_pattern(p, names));
+        }
+        Pattern::TupleStruct { name, elems, .. } => {
+            insert_path(name, names);
+            elems.iter().for_each(|p| names_in_pattern(p, names));
+        }
+        Pattern::Literal(..) | Pattern::Wildcard(_) => {}
+    }
+}
+
+fn names_in_expr(expr: &Expr, names: &mut HashSet<String>) {
+    match expr {
+        Expr::Literal(..) => {}
+        Expr::Variable(name, _) => insert_path(name, names),
+        Expr::Binary { left, right, .. } => {
+            names_in_expr(left, names);
+            names_in_expr(right, names);
+        }
+        Expr::Unary { expr, .. } => names_in_expr(expr, names),
+        Expr::Call { callee, args, .. } => {
+            names_in_expr(callee, names);
+            args.iter().for_each(|arg| names_in_expr(arg, names));
+        }
+        Expr::MethodCall { receiver, args, .. } => {
+            names_in_expr(receiver, names);
+            args.iter().for_each(|arg| names_in_expr(arg, names));
+        }
+        Expr::FieldAccess { object, .. } => names_in_expr(object, names),
+        Expr::Index { object, index, .. } => {
+            names_in_expr(object, names);
+            names_in_expr(index, names);
+        }
+        Expr::JSXElement(jsx, _) => names_in_jsx(jsx, names),
+        Expr::Block(block, _) | Expr::Loop(block, _) => names_in_block(block, names),
+        Expr::If { condition, then, else_, .. } => {
+            names_in_expr(condition, names);
+            names_in_expr(then, names);
+            if let Some(else_expr) = else_ {
+                names_in_expr(else_expr, names);
+            }
+        }
+        Expr::Match { expr, arms, .. } => {
+            names_in_expr(expr, names);
+            names_in_arms(arms, names);
+        }
+        Expr::Lambda { params, body, .. } => {
+            names_in_params(params, names);
+            names_in_expr(body, names);
+        }
+        Expr::Tuple(elems, _) | Expr::Array(elems, _) => elems.iter().for_each(|e| names_in_expr(e, names)),
+        Expr::Range { start, end, .. } => {
+            names_in_expr(start, names);
+            names_in_expr(end, names);
+        }
+        Expr::Struct { name, fields, .. } => {
+            insert_path(name, names);
+            fields.iter().for_each(|(_, value)| names_in_expr(value, names));
+        }
+    }
+}
+
+fn names_in_jsx(jsx: &JSXElement, names: &mut HashSet<String>) {
+    let (JSXElement::SelfClosing { tag, props, .. } | JSXElement::WithChildren { tag, props, .. }) = jsx;
+    names.insert(tag.clone());
+    for prop in props {
+        if let JSXPropValue::Expr(expr) = &prop.value {
+            names_in_expr(expr, names);
+        }
+    }
+    if let JSXElement::WithChildren { children, .. } = jsx {
+        for child in children {
+            match child {
+                JSXChild::Element(element) => names_in_jsx(element, names),
+                JSXChild::Expr(expr) => names_in_expr(expr, names),
+                JSXChild::Text(..) => {}
+            }
+        }
+    }
+}
+
+/// Every `let` binding in `block` and the blocks nested in it
+fn lets_in_block<'a>(block: &'a Block, bindings: &mut Vec<(&'a str, Span)>) {
+    for stmt in &block.statements {
+        lets_in_stmt(stmt, bindings);
+    }
+}
+
+fn lets_in_stmt<'a>(stmt: &'a Stmt, bindings: &mut Vec<(&'a str, Span)>) {
+    match stmt {
+        Stmt::Let { name, value, span, .. } => {
+            bindings.push((name, *span));
+            lets_in_expr(value, bindings);
+        }
+        Stmt::Expr(value) | Stmt::Tail(value) | Stmt::Return(Some(value), _) | Stmt::Break(Some(value), _) => {
+            lets_in_expr(value, bindings)
+        }
+        Stmt::Return(None, _) | Stmt::Break(None, _) => {}
+        Stmt::If { then, else_, .. } => {
+            lets_in_stmt(then, bindings);
+            if let Some(else_stmt) = else_ {
+                lets_in_stmt(else_stmt, bindings);
+            }
+        }
+        Stmt::For { body, .. } | Stmt::While { body, .. } => lets_in_stmt(body, bindings),
+        Stmt::Match { arms, .. } => arms.iter().for_each(|arm| lets_in_expr(&arm.body, bindings)),
+        Stmt::Block(block) => lets_in_block(block, bindings),
+    }
+}
+
+/// Lets inside blocks nested in an expression. Lambda bodies are left out
+/// along with the rest of the lambda's scope.
+fn lets_in_expr<'a>(expr: &'a Expr, bindings: &mut Vec<(&'a str, Span)>) {
+    match expr {
+        Expr::Block(block, _) | Expr::Loop(block, _) => lets_in_block(block, bindings),
+        Expr::If { then, else_, .. } => {
+            lets_in_expr(then, bindings);
+            if let Some(else_expr) = else_ {
+                lets_in_expr(else_expr, bindings);
+            }
+        }
+        Expr::Match { arms, .. } => arms.iter().for_each(|arm| lets_in_expr(&arm.body, bindings)),
+        _ => {}
+    }
+}
+
+/// Records `name`, or the first segment of a path like `Shape::Circle`
+fn insert_path(name: &str, names: &mut HashSet<String>) {
+    names.insert(name.split("::").next().unwrap_or(name).to_string());
+}
@@ -1,11 +1,16 @@
 use crate::lexer::Span;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::mem;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Hash)]
 pub struct AST {
     pub items: Vec<Item>,
+    /// Inner doc comments (`//!`) documenting the file itself
+    pub docs: Vec<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Hash)]
 pub enum Item {
     Component(Component),
     Function(Function),
@@ -18,38 +23,67 @@ pub enum Item {
     TypeAlias(TypeAlias),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Hash)]
 pub struct Component {
+    pub attributes: Vec<Attribute>,
+    /// Doc comment lines above the item, without the `///`
+    pub docs: Vec<String>,
     pub name: String,
+    pub generics: Vec<String>,
     pub props: Vec<Param>,
     pub return_type: Type,
+    pub where_clause: Vec<WherePredicate>,
     pub body: Expr,
     pub span: Span,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Hash)]
 pub struct Function {
+    pub attributes: Vec<Attribute>,
+    pub docs: Vec<String>,
     pub name: String,
+    pub generics: Vec<String>,
     pub params: Vec<Param>,
     pub return_type: Option<Type>,
+    pub where_clause: Vec<WherePredicate>,
     pub body: Block,
     pub span: Span,
 }
 
-#[derive(Debug, Clone)]
+/// `#[name(args)]` annotation on an item. `args` keeps the source text
+/// between the parentheses so it can be emitted unchanged.
+#[derive(Debug, Clone, Hash)]
+pub struct Attribute {
+    pub name: String,
+    pub args: Option<String>,
+    pub span: Span,
+}
+
+/// A `T: Bound + Other` constraint. Bounds written inline in a generic
+/// parameter list are stored here too, alongside the `where` clause.
+#[derive(Debug, Clone, Hash)]
+pub struct WherePredicate {
+    pub ty: Type,
+    pub bounds: Vec<Type>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, Hash)]
 pub struct Param {
     pub name: String,
     pub param_type: Type,
+    /// Value used when the argument or prop is omitted
+    pub default: Option<Expr>,
     pub span: Span,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Hash)]
 pub struct Block {
     pub statements: Vec<Stmt>,
     pub span: Span,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Hash)]
 pub enum Stmt {
     Let {
         name: String,
@@ -58,7 +92,11 @@ pub enum Stmt {
         span: Span,
     },
     Expr(Expr),
+    /// Trailing expression without a semicolon; its value is the block's value
+    Tail(Expr),
     Return(Option<Expr>, Span),
+    /// Both branches are `Stmt::Block`s, except that an `else if` chain
+    /// has the next `Stmt::If` as `else_`
     If {
         condition: Expr,
         then: Box<Stmt>,
@@ -76,6 +114,8 @@ pub enum Stmt {
         body: Box<Stmt>,
         span: Span,
     },
+    /// `break;` or, inside `loop`, `break value;`
+    Break(Option<Expr>, Span),
     Match {
         expr: Expr,
         arms: Vec<MatchArm>,
@@ -84,7 +124,7 @@ pub enum Stmt {
     Block(Block),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Hash)]
 pub struct MatchArm {
     pub pattern: Pattern,
     pub guard: Option<Expr>,
@@ -92,7 +132,7 @@ pub struct MatchArm {
     pub span: Span,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Hash)]
 pub enum Pattern {
     Ident(String, Span),
     Literal(Literal, Span),
@@ -102,10 +142,19 @@ pub enum Pattern {
         fields: Vec<(String, Pattern)>,
         span: Span,
     },
+    /// Qualified path such as `Color::Red`
+    Path(Vec<String>, Span),
+    /// Constructor with positional fields, such as `Some(x)` or
+    /// `Shape::Circle(r)`
+    TupleStruct {
+        name: String,
+        elems: Vec<Pattern>,
+        span: Span,
+    },
     Wildcard(Span),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Hash)]
 pub enum Expr {
     Literal(Literal, Span),
     Variable(String, Span),
@@ -149,6 +198,8 @@ pub enum Expr {
         else_: Option<Box<Expr>>,
         span: Span,
     },
+    /// `loop { .. }`, whose value is the value its `break`s carry
+    Loop(Block, Span),
     Match {
         expr: Box<Expr>,
         arms: Vec<MatchArm>,
@@ -161,6 +212,13 @@ pub enum Expr {
     },
     Tuple(Vec<Expr>, Span),
     Array(Vec<Expr>, Span),
+    /// `start..end`, or `start..=end` when `inclusive`
+    Range {
+        start: Box<Expr>,
+        end: Box<Expr>,
+        inclusive: bool,
+        span: Span,
+    },
     Struct {
         name: String,
         fields: Vec<(String, Expr)>,
@@ -168,7 +226,7 @@ pub enum Expr {
     },
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Hash)]
 pub enum JSXElement {
     SelfClosing {
         tag: String,
@@ -183,28 +241,31 @@ pub enum JSXElement {
     },
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Hash)]
 pub enum JSXChild {
     Element(JSXElement),
     Text(String, Span),
     Expr(Expr),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Hash)]
 pub struct JSXProp {
     pub name: String,
     pub value: JSXPropValue,
+    /// Event modifiers after the name, e.g. `prevent` and `stop` in
+    /// `onClick:prevent:stop`
+    pub modifiers: Vec<String>,
     pub span: Span,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Hash)]
 pub enum JSXPropValue {
     Literal(Literal),
     Expr(Expr),
     Bool(bool),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Hash)]
 pub enum BinaryOp {
     Add,
     Sub,
@@ -226,7 +287,7 @@ pub enum BinaryOp {
     Shr,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Hash)]
 pub enum UnaryOp {
     Not,
     Neg,
@@ -237,19 +298,37 @@ pub enum UnaryOp {
 #[derive(Debug, Clone)]
 pub enum Literal {
     String(String),
+    /// A number written without a fraction or exponent, e.g. `5`. Its type
+    /// comes from where it is used.
+    Integer(i64),
     Number(f64),
     Boolean(bool),
     Char(char),
     Unit,
 }
 
-#[derive(Debug, Clone)]
+impl Hash for Literal {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        mem::discriminant(self).hash(state);
+        match self {
+            Literal::String(s) => s.hash(state),
+            Literal::Integer(n) => n.hash(state),
+            // `f64` isn't `Hash`, so its bits are hashed instead
+            Literal::Number(n) => n.to_bits().hash(state),
+            Literal::Boolean(b) => b.hash(state),
+            Literal::Char(c) => c.hash(state),
+            Literal::Unit => {}
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash)]
 pub struct Type {
     pub kind: TypeKind,
     pub span: Span,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Hash)]
 pub enum TypeKind {
     Ident(String),
     Path(Vec<String>),
@@ -265,6 +344,25 @@ pub enum TypeKind {
         return_type: Box<Type>,
     },
     Option(Box<Type>),
+    /// Generic type other than `Option`/`Result`, e.g. `Vec<String>` or
+    /// `Rc<State>`
+    Generic {
+        name: String,
+        args: Vec<Type>,
+    },
+    /// `dyn Trait`
+    Dyn(Box<Type>),
+    /// `impl Trait`
+    Impl(Box<Type>),
+    /// Closure trait sugar such as `Fn(i32) -> bool`. The return type is
+    /// kept optional so `Fn()` isn't rewritten as `Fn() -> ()`.
+    FnTrait {
+        name: String,
+        params: Vec<Type>,
+        return_type: Option<Box<Type>>,
+    },
+    /// A range over the given element type
+    Range(Box<Type>),
     Result {
         ok: Box<Type>,
         err: Box<Type>,
@@ -272,78 +370,139 @@ pub enum TypeKind {
     Unit,
 }
 
-#[derive(Debug, Clone)]
+impl fmt::Display for Type {
+    /// Source-like form without spans, e.g. `fn(&str) -> Option<i32>`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            TypeKind::Ident(name) => write!(f, "{}", name),
+            TypeKind::Path(path) => write!(f, "{}", path.join("::")),
+            TypeKind::Tuple(types) => write!(f, "({})", type_list(types)),
+            TypeKind::Array(inner) => write!(f, "[{}]", inner),
+            TypeKind::Slice(inner) => write!(f, "&[{}]", inner),
+            TypeKind::Reference { mutable, inner } => {
+                write!(f, "&{}{}", if *mutable { "mut " } else { "" }, inner)
+            }
+            TypeKind::Function { params, return_type } => write!(f, "fn({}) -> {}", type_list(params), return_type),
+            TypeKind::Option(inner) => write!(f, "Option<{}>", inner),
+            TypeKind::Range(inner) => write!(f, "Range<{}>", inner),
+            TypeKind::Result { ok, err } => write!(f, "Result<{}, {}>", ok, err),
+            TypeKind::Generic { name, args } => write!(f, "{}<{}>", name, type_list(args)),
+            TypeKind::Dyn(inner) => write!(f, "dyn {}", inner),
+            TypeKind::Impl(inner) => write!(f, "impl {}", inner),
+            TypeKind::FnTrait { name, params, return_type } => {
+                write!(f, "{}({})", name, type_list(params))?;
+                match return_type {
+                    Some(return_type) => write!(f, " -> {}", return_type),
+                    None => Ok(()),
+                }
+            }
+            TypeKind::Unit => write!(f, "()"),
+        }
+    }
+}
+
+fn type_list(types: &[Type]) -> String {
+    types.iter().map(Type::to_string).collect::<Vec<_>>().join(", ")
+}
+
+#[derive(Debug, Clone, Hash)]
 pub struct Struct {
+    pub attributes: Vec<Attribute>,
+    pub docs: Vec<String>,
     pub name: String,
     pub fields: Vec<StructField>,
     pub span: Span,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Hash)]
 pub struct StructField {
     pub name: String,
     pub field_type: Type,
     pub span: Span,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Hash)]
 pub struct Enum {
+    pub attributes: Vec<Attribute>,
+    pub docs: Vec<String>,
     pub name: String,
     pub variants: Vec<EnumVariant>,
     pub span: Span,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Hash)]
 pub struct EnumVariant {
     pub name: String,
     pub data: Option<EnumVariantData>,
     pub span: Span,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Hash)]
 pub enum EnumVariantData {
     Tuple(Vec<Type>),
     Struct(Vec<StructField>),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Hash)]
 pub struct Trait {
+    pub attributes: Vec<Attribute>,
+    pub docs: Vec<String>,
     pub name: String,
+    pub generics: Vec<String>,
+    pub where_clause: Vec<WherePredicate>,
     pub items: Vec<TraitItem>,
     pub span: Span,
 }
 
-#[derive(Debug, Clone)]
+// Unboxed to match `Item::Function`; traits hold few items, so the size
+// difference costs little
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone, Hash)]
 pub enum TraitItem {
     Method(Function),
     Type(String, Option<Type>),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Hash)]
 pub struct Impl {
+    pub attributes: Vec<Attribute>,
+    pub docs: Vec<String>,
+    pub generics: Vec<String>,
     pub trait_name: Option<String>,
     pub type_name: String,
+    pub where_clause: Vec<WherePredicate>,
     pub items: Vec<Function>,
     pub span: Span,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Hash)]
 pub struct Use {
     pub path: Vec<String>,
     pub alias: Option<String>,
+    /// `use path::*`, bringing in everything `path` contains
+    pub glob: bool,
     pub span: Span,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Hash)]
 pub struct Mod {
     pub name: String,
     pub items: Vec<Item>,
     pub span: Span,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Hash)]
 pub struct TypeAlias {
     pub name: String,
     pub aliased_type: Type,
     pub span: Span,
 }
+
+/// Modifiers an event prop can take, as in `onClick:prevent:stop`
+pub const EVENT_MODIFIERS: &[&str] = &["prevent", "stop"];
+
+/// Event handler props are `on` followed by an uppercase letter, e.g.
+/// `onClick` or `onKeyDown`
+pub fn is_event_prop(name: &str) -> bool {
+    name.strip_prefix("on").and_then(|rest| rest.chars().next()).is_some_and(char::is_uppercase)
+}
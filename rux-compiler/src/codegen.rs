@@ -1,9 +1,80 @@
 use crate::ast::*;
 use crate::errors::Result;
+use crate::analyzer::DependencyAnalyzer;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+
+/// JSX prop names that differ from the DOM attribute they set. Anything
+/// not listed, including `data-*`/`aria-*` and event handlers, is passed
+/// through unchanged.
+const DOM_ATTRIBUTE_NAMES: &[(&str, &str)] = &[
+    ("className", "class"),
+    ("htmlFor", "for"),
+    ("tabIndex", "tabindex"),
+    ("readOnly", "readonly"),
+    ("maxLength", "maxlength"),
+    ("minLength", "minlength"),
+    ("autoComplete", "autocomplete"),
+    ("autoFocus", "autofocus"),
+    ("autoPlay", "autoplay"),
+    ("contentEditable", "contenteditable"),
+    ("crossOrigin", "crossorigin"),
+    ("spellCheck", "spellcheck"),
+    ("colSpan", "colspan"),
+    ("rowSpan", "rowspan"),
+    ("accessKey", "accesskey"),
+    ("encType", "enctype"),
+    ("formAction", "formaction"),
+    ("noValidate", "novalidate"),
+    ("srcSet", "srcset"),
+    ("useMap", "usemap"),
+    ("dateTime", "datetime"),
+    ("httpEquiv", "http-equiv"),
+    ("acceptCharset", "accept-charset"),
+    ("strokeWidth", "stroke-width"),
+    ("strokeLinecap", "stroke-linecap"),
+    ("strokeLinejoin", "stroke-linejoin"),
+    ("strokeDasharray", "stroke-dasharray"),
+    ("strokeOpacity", "stroke-opacity"),
+    ("fillOpacity", "fill-opacity"),
+    ("fillRule", "fill-rule"),
+    ("clipPath", "clip-path"),
+    ("clipRule", "clip-rule"),
+    ("fontFamily", "font-family"),
+    ("fontSize", "font-size"),
+    ("fontWeight", "font-weight"),
+    ("textAnchor", "text-anchor"),
+    ("stopColor", "stop-color"),
+    ("stopOpacity", "stop-opacity"),
+];
+
+/// The DOM attribute a JSX prop name sets
+pub fn dom_attribute_name(name: &str) -> &str {
+    DOM_ATTRIBUTE_NAMES
+        .iter()
+        .find(|(jsx, _)| *jsx == name)
+        .map_or(name, |(_, dom)| dom)
+}
+
+/// Generated Rust kept between builds by `generate_rust_code_cached`.
+/// Keys hash an item together with everything else its code depends on,
+/// so a hit can be reused as is.
+pub trait CodegenCache {
+    fn get(&mut self, key: u64) -> Option<String>;
+    fn put(&mut self, key: u64, code: &str);
+}
 
 pub struct CodeGenerator {
     output: String,
     indent_level: usize,
+    prop_structs: HashSet<String>,
+    components: HashSet<String>,
+    /// Locals of the function being generated that hold a `String` or
+    /// `&str`, so `+` on them lowers to `format!`
+    string_locals: HashSet<String>,
+    /// Hash of the project-wide state above, folded into cache keys
+    context_hash: u64,
 }
 
 impl CodeGenerator {
@@ -11,33 +82,61 @@ impl CodeGenerator {
         Self {
             output: String::new(),
             indent_level: 0,
+            prop_structs: HashSet::new(),
+            components: HashSet::new(),
+            string_locals: HashSet::new(),
+            context_hash: 0,
         }
     }
 
     pub fn generate_rust_code(&mut self, ast: &AST) -> Result<String> {
+        self.generate_partial_rust_code(ast, &HashMap::new())
+    }
+
+    /// Like `generate_rust_code`, but emits a `compile_error!` with the
+    /// given message in place of each item whose index is in `failed`
+    pub fn generate_partial_rust_code(&mut self, ast: &AST, failed: &HashMap<usize, String>) -> Result<String> {
+        self.generate(ast, failed, None)
+    }
+
+    /// Like `generate_rust_code`, but reuses the code `cache` holds for
+    /// items that haven't changed and stores the code of those that have.
+    /// Modules aren't cached themselves, only the items inside them.
+    pub fn generate_rust_code_cached(&mut self, ast: &AST, cache: &mut dyn CodegenCache) -> Result<String> {
+        self.generate(ast, &HashMap::new(), Some(cache))
+    }
+
+    fn generate<'c>(
+        &mut self,
+        ast: &AST,
+        failed: &HashMap<usize, String>,
+        mut cache: Option<&mut (dyn CodegenCache + 'c)>,
+    ) -> Result<String> {
         self.output.clear();
         self.indent_level = 0;
 
+        // Prop structs get derives so components can compare props, and
+        // imports of components follow their snake_case function names
+        let mut analyzer = DependencyAnalyzer::new();
+        analyzer.analyze(ast)?;
+        self.prop_structs = analyzer.prop_types().clone();
+        self.components = analyzer.components().clone();
+        self.context_hash = Self::hash_context(&self.prop_structs, &self.components);
+
+        for line in &ast.docs {
+            self.writeln(&format!("//!{}", line));
+        }
+
         // Add necessary imports
         self.writeln("use rux_core::virtual_tree::{VirtualNode, NodeType, PropValue};");
         self.writeln("use std::collections::HashMap;");
         self.writeln("");
 
         // Generate code for each item
-        for item in &ast.items {
-            match item {
-                Item::Component(component) => self.generate_component(component)?,
-                Item::Function(function) => self.generate_function(function)?,
-                Item::Struct(struct_def) => self.generate_struct(struct_def)?,
-                Item::Enum(enum_def) => self.generate_enum(enum_def)?,
-                Item::Trait(trait_def) => self.generate_trait(trait_def)?,
-                Item::TypeAlias(alias) => self.generate_type_alias(alias)?,
-                Item::Use(use_stmt) => self.generate_use(use_stmt)?,
-                Item::Mod(mod_def) => self.generate_mod(mod_def)?,
-                Item::Impl(impl_block) => {
-                    // Impl blocks would go here
-                    self.writeln("// TODO: impl block");
-                }
+        for (i, item) in ast.items.iter().enumerate() {
+            match failed.get(&i) {
+                Some(message) => self.writeln(&format!("compile_error!({:?});", message)),
+                None => self.generate_item(item, cache.as_deref_mut())?,
             }
             self.writeln("");
         }
@@ -45,24 +144,75 @@ impl CodeGenerator {
         Ok(self.output.clone())
     }
 
+    fn generate_item<'c>(&mut self, item: &Item, cache: Option<&mut (dyn CodegenCache + 'c)>) -> Result<()> {
+        match (item, cache) {
+            (Item::Mod(mod_def), cache) => self.generate_mod(mod_def, cache),
+            (_, Some(cache)) => {
+                let key = self.cache_key(item);
+                if let Some(code) = cache.get(key) {
+                    self.output.push_str(&code);
+                    return Ok(());
+                }
+                let start = self.output.len();
+                self.generate_uncached_item(item)?;
+                cache.put(key, &self.output[start..]);
+                Ok(())
+            }
+            (_, None) => self.generate_uncached_item(item),
+        }
+    }
+
+    fn generate_uncached_item(&mut self, item: &Item) -> Result<()> {
+        match item {
+            Item::Component(component) => self.generate_component(component),
+            Item::Function(function) => self.generate_function(function),
+            Item::Struct(struct_def) => self.generate_struct(struct_def),
+            Item::Enum(enum_def) => self.generate_enum(enum_def),
+            Item::Trait(trait_def) => self.generate_trait(trait_def),
+            Item::TypeAlias(alias) => self.generate_type_alias(alias),
+            Item::Use(use_stmt) => self.generate_use(use_stmt),
+            Item::Mod(mod_def) => self.generate_mod(mod_def, None),
+            Item::Impl(impl_block) => self.generate_impl(impl_block),
+        }
+    }
+
     fn generate_component(&mut self, component: &Component) -> Result<()> {
         // Generate function signature
+        self.generate_docs(&component.docs);
+        self.generate_attributes(&component.attributes);
         let fn_name = self.snake_case(&component.name);
-        self.write(&format!("pub fn {}() -> VirtualNode {{\n", fn_name));
+        self.indent();
+        self.write(&format!("pub fn {}", fn_name));
+        self.generate_generics(&component.generics);
+        self.write("() -> VirtualNode");
+        self.generate_where_clause(&component.where_clause)?;
+        self.writeln(" {");
         self.indent_level += 1;
 
         // Generate component body (JSX expression)
+        self.string_locals.clear();
         self.generate_expression(&component.body)?;
 
         self.indent_level -= 1;
+        self.indent();
         self.writeln("}");
         Ok(())
     }
 
     fn generate_function(&mut self, function: &Function) -> Result<()> {
+        self.generate_fn(function, "pub ")
+    }
+
+    fn generate_fn(&mut self, function: &Function, visibility: &str) -> Result<()> {
+        self.generate_docs(&function.docs);
+        self.generate_attributes(&function.attributes);
+
         // Generate function signature
-        self.write("pub fn ");
+        self.indent();
+        self.write(visibility);
+        self.write("fn ");
         self.write(&self.snake_case(&function.name));
+        self.generate_generics(&function.generics);
         self.write("(");
 
         // Generate parameters
@@ -82,19 +232,112 @@ impl CodeGenerator {
             self.write(" -> ");
             self.generate_type(return_type)?;
         }
+        self.generate_where_clause(&function.where_clause)?;
 
         self.writeln(" {");
         self.indent_level += 1;
 
         // Generate function body
+        self.string_locals = function
+            .params
+            .iter()
+            .filter(|param| is_string_type(&param.param_type))
+            .map(|param| param.name.clone())
+            .collect();
         self.generate_block(&function.body)?;
 
         self.indent_level -= 1;
+        self.indent();
         self.writeln("}");
         Ok(())
     }
 
+    /// Derives `Clone`, `PartialEq` and `Debug` on a prop struct, skipping
+    /// traits it already derives. `#[rux(no_derive)]` opts out.
+    fn generate_prop_derives(&mut self, attributes: &[Attribute]) {
+        let opted_out = attributes
+            .iter()
+            .any(|attr| attr.name == "rux" && attr.args.as_deref() == Some("no_derive"));
+        if opted_out {
+            return;
+        }
+
+        let existing: Vec<&str> = attributes
+            .iter()
+            .filter(|attr| attr.name == "derive")
+            .filter_map(|attr| attr.args.as_deref())
+            .flat_map(|args| args.split(',').map(str::trim))
+            .collect();
+        let missing: Vec<&str> = ["Clone", "PartialEq", "Debug"]
+            .into_iter()
+            .filter(|name| !existing.contains(name))
+            .collect();
+
+        if !missing.is_empty() {
+            self.indent();
+            self.writeln(&format!("#[derive({})]", missing.join(", ")));
+        }
+    }
+
+    fn generate_docs(&mut self, docs: &[String]) {
+        for line in docs {
+            self.indent();
+            self.writeln(&format!("///{}", line));
+        }
+    }
+
+    fn generate_attributes(&mut self, attributes: &[Attribute]) {
+        // `#[rux(...)]` attributes are compiler directives, not Rust
+        for attribute in attributes.iter().filter(|attr| attr.name != "rux") {
+            self.indent();
+            self.write("#[");
+            self.write(&attribute.name);
+            if let Some(ref args) = attribute.args {
+                self.write("(");
+                self.write(args);
+                self.write(")");
+            }
+            self.writeln("]");
+        }
+    }
+
+    fn generate_generics(&mut self, generics: &[String]) {
+        if !generics.is_empty() {
+            self.write("<");
+            self.write(&generics.join(", "));
+            self.write(">");
+        }
+    }
+
+    fn generate_where_clause(&mut self, predicates: &[WherePredicate]) -> Result<()> {
+        if predicates.is_empty() {
+            return Ok(());
+        }
+
+        self.write(" where ");
+        for (i, predicate) in predicates.iter().enumerate() {
+            if i > 0 {
+                self.write(", ");
+            }
+            self.generate_type(&predicate.ty)?;
+            self.write(": ");
+            for (j, bound) in predicate.bounds.iter().enumerate() {
+                if j > 0 {
+                    self.write(" + ");
+                }
+                self.generate_type(bound)?;
+            }
+        }
+        Ok(())
+    }
+
     fn generate_struct(&mut self, struct_def: &Struct) -> Result<()> {
+        self.generate_docs(&struct_def.docs);
+        self.generate_attributes(&struct_def.attributes);
+        if self.prop_structs.contains(&struct_def.name) {
+            self.generate_prop_derives(&struct_def.attributes);
+        }
+        self.indent();
         self.write("pub struct ");
         self.write(&struct_def.name);
         self.writeln(" {");
@@ -109,11 +352,15 @@ impl CodeGenerator {
         }
         self.indent_level -= 1;
 
+        self.indent();
         self.writeln("}");
         Ok(())
     }
 
     fn generate_enum(&mut self, enum_def: &Enum) -> Result<()> {
+        self.generate_docs(&enum_def.docs);
+        self.generate_attributes(&enum_def.attributes);
+        self.indent();
         self.write("pub enum ");
         self.write(&enum_def.name);
         self.writeln(" {");
@@ -154,20 +401,56 @@ impl CodeGenerator {
         }
         self.indent_level -= 1;
 
+        self.indent();
         self.writeln("}");
         Ok(())
     }
 
     fn generate_trait(&mut self, trait_def: &Trait) -> Result<()> {
+        self.generate_docs(&trait_def.docs);
+        self.generate_attributes(&trait_def.attributes);
+        self.indent();
         self.write("pub trait ");
         self.write(&trait_def.name);
+        self.generate_generics(&trait_def.generics);
+        self.generate_where_clause(&trait_def.where_clause)?;
         self.writeln(" {");
         // Trait methods would go here
+        self.indent();
+        self.writeln("}");
+        Ok(())
+    }
+
+    fn generate_impl(&mut self, impl_block: &Impl) -> Result<()> {
+        self.generate_docs(&impl_block.docs);
+        self.generate_attributes(&impl_block.attributes);
+        self.indent();
+        self.write("impl");
+        self.generate_generics(&impl_block.generics);
+        self.write(" ");
+        if let Some(ref trait_name) = impl_block.trait_name {
+            self.write(trait_name);
+            self.write(" for ");
+        }
+        self.write(&impl_block.type_name);
+        self.generate_where_clause(&impl_block.where_clause)?;
+        self.writeln(" {");
+
+        // Trait methods inherit the trait's visibility
+        let visibility = if impl_block.trait_name.is_some() { "" } else { "pub " };
+        self.indent_level += 1;
+        for method in &impl_block.items {
+            self.generate_fn(method, visibility)?;
+        }
+        self.indent_level -= 1;
+
+        self.indent();
         self.writeln("}");
         Ok(())
     }
 
     fn generate_type_alias(&mut self, alias: &TypeAlias) -> Result<()> {
+        self.indent();
         self.write("pub type ");
         self.write(&alias.name);
         self.write(" = ");
@@ -177,8 +460,24 @@ impl CodeGenerator {
     }
 
     fn generate_use(&mut self, use_stmt: &Use) -> Result<()> {
+        self.indent();
         self.write("use ");
-        self.write(&use_stmt.path.join("::"));
+        let path: Vec<String> = use_stmt
+            .path
+            .iter()
+            .enumerate()
+            .map(|(i, segment)| {
+                if i + 1 == use_stmt.path.len() && self.components.contains(segment) {
+                    self.snake_case(segment)
+                } else {
+                    segment.clone()
+                }
+            })
+            .collect();
+        self.write(&path.join("::"));
+        if use_stmt.glob {
+            self.write("::*");
+        }
         if let Some(ref alias) = use_stmt.alias {
             self.write(" as ");
             self.write(alias);
@@ -187,11 +486,23 @@ impl CodeGenerator {
         Ok(())
     }
 
-    fn generate_mod(&mut self, mod_def: &Mod) -> Result<()> {
+    fn generate_mod<'c>(&mut self, mod_def: &Mod, mut cache: Option<&mut (dyn CodegenCache + 'c)>) -> Result<()> {
+        self.indent();
         self.write("pub mod ");
         self.write(&mod_def.name);
         self.writeln(" {");
-        // Module contents would go here
+
+        // Modules share the runtime imports declared at the top of the file
+        self.indent_level += 1;
+        self.indent();
+        self.writeln("use super::*;");
+        for item in &mod_def.items {
+            self.writeln("");
+            self.generate_item(item, cache.as_deref_mut())?;
+        }
+        self.indent_level -= 1;
+
+        self.indent();
         self.writeln("}");
         Ok(())
     }
@@ -203,6 +514,17 @@ impl CodeGenerator {
             Expr::Variable(name, _) => {
                 self.write(&self.snake_case(name));
             }
+            Expr::Binary { left, op: BinaryOp::Add, right, .. }
+                if self.is_string_expr(left) || self.is_string_expr(right) =>
+            {
+                // Rust has no `&str + &str` or `String + String`, so build
+                // an owned String instead of relying on `+`
+                self.write("format!(\"{}{}\", ");
+                self.generate_expression(left)?;
+                self.write(", ");
+                self.generate_expression(right)?;
+                self.write(")");
+            }
             Expr::Binary { left, op, right, .. } => {
                 self.write("(");
                 self.generate_expression(left)?;
@@ -264,22 +586,30 @@ impl CodeGenerator {
                 self.indent();
                 self.write("}");
             }
-            Expr::If { condition, then, else_, .. } => {
-                self.write("if ");
-                self.generate_expression(condition)?;
-                self.write(" {\n");
+            Expr::Loop(block, _) => {
+                self.writeln("loop {");
                 self.indent_level += 1;
-                self.generate_expression(then)?;
+                self.generate_block(block)?;
                 self.indent_level -= 1;
                 self.indent();
                 self.write("}");
-                if let Some(ref else_expr) = else_ {
-                    self.write(" else {\n");
-                    self.indent_level += 1;
-                    self.generate_expression(else_expr)?;
-                    self.indent_level -= 1;
-                    self.indent();
-                    self.write("}");
+            }
+            Expr::If { condition, then, else_, .. } => {
+                self.write("if ");
+                self.generate_expression(condition)?;
+                self.write(" ");
+                self.generate_branch_expression(then)?;
+                match else_.as_deref() {
+                    // Keep `else if` chains flat
+                    Some(else_if @ Expr::If { .. }) => {
+                        self.write(" else ");
+                        self.generate_expression(else_if)?;
+                    }
+                    Some(else_expr) => {
+                        self.write(" else ");
+                        self.generate_branch_expression(else_expr)?;
+                    }
+                    None => {}
                 }
             }
             Expr::Match { expr, arms, .. } => {
@@ -288,11 +618,7 @@ impl CodeGenerator {
                 self.write(" {\n");
                 self.indent_level += 1;
                 for arm in arms {
-                    self.indent();
-                    // Pattern matching would go here (simplified)
-                    self.write("_ => ");
-                    self.generate_expression(&arm.body)?;
-                    self.writeln(",");
+                    self.generate_match_arm(arm)?;
                 }
                 self.indent_level -= 1;
                 self.indent();
@@ -322,6 +648,13 @@ impl CodeGenerator {
                 }
                 self.write(")");
             }
+            Expr::Range { start, end, inclusive, .. } => {
+                self.write("(");
+                self.generate_expression(start)?;
+                self.write(if *inclusive { "..=" } else { ".." });
+                self.generate_expression(end)?;
+                self.write(")");
+            }
             Expr::Array(exprs, _) => {
                 self.write("vec![");
                 for (i, expr) in exprs.iter().enumerate() {
@@ -375,9 +708,18 @@ impl CodeGenerator {
                             child_nodes.push("VirtualNode { id: rux_core::virtual_tree::NodeId(0), node_type: NodeType::Text(\"TODO\".to_string()), props: HashMap::new(), children: vec![], key: None }".to_string());
                         }
                         JSXChild::Expr(expr) => {
-                            // For expressions, we'd need to generate the expression and convert to VirtualNode
-                            // For now, use a placeholder
-                            child_nodes.push("VirtualNode { id: rux_core::virtual_tree::NodeId(0), node_type: NodeType::Text(\"TODO\".to_string()), props: HashMap::new(), children: vec![], key: None }".to_string());
+                            let mut child_gen = CodeGenerator::new();
+                            child_gen.generate_expression(expr)?;
+                            if matches!(expr, Expr::JSXElement(..)) {
+                                child_nodes.push(child_gen.output);
+                            } else {
+                                // Non-element values render as text through
+                                // their Display impl
+                                child_nodes.push(format!(
+                                    "VirtualNode {{ id: rux_core::virtual_tree::NodeId(0), node_type: NodeType::Text(({}).to_string()), props: HashMap::new(), children: vec![], key: None }}",
+                                    child_gen.output
+                                ));
+                            }
                         }
                     }
                 }
@@ -415,11 +757,29 @@ impl CodeGenerator {
         self.indent();
         self.writeln("let mut props = HashMap::new();");
         for prop in props {
+            if let (Some(bound), JSXPropValue::Expr(signal)) = (prop.name.strip_prefix("bind:"), &prop.value) {
+                self.generate_binding(bound, signal)?;
+                continue;
+            }
+            let name = dom_attribute_name(&prop.name);
             self.indent();
             self.write("props.insert(\"");
-            self.write(&prop.name);
+            self.write(name);
+            // Modifiers stay in the key for the renderer, `onClick:prevent`
+            for modifier in &prop.modifiers {
+                self.write(":");
+                self.write(modifier);
+            }
             self.write("\".to_string(), ");
-            self.generate_prop_value(&prop.value)?;
+            match &prop.value {
+                JSXPropValue::Expr(expr) if name == "class" => self.generate_class_value(expr)?,
+                JSXPropValue::Expr(Expr::Variable(handler, _)) if is_event_prop(name) => {
+                    self.write("PropValue::Function(\"");
+                    self.write(handler);
+                    self.write("\".to_string())");
+                }
+                value => self.generate_prop_value(value)?,
+            }
             self.writeln(");");
         }
         self.indent();
@@ -454,6 +814,29 @@ impl CodeGenerator {
         Ok(())
     }
 
+    /// Expands `bind:value={signal}` into a `value` prop read from the
+    /// signal and an `onInput` handler writing back to it. `bind:checked`
+    /// does the same with `checked` and `onChange`. The handlers come from
+    /// `rux_core::bindings`, which renderers run without registering them.
+    fn generate_binding(&mut self, bound: &str, signal: &Expr) -> Result<()> {
+        let mut signal_gen = CodeGenerator::new();
+        signal_gen.generate_expression(signal)?;
+        let signal = signal_gen.output;
+        
+        let (value, event, handler) = match bound {
+            "checked" => (format!("PropValue::Boolean({}.get())", signal), "onChange", "bind_checked"),
+            _ => (format!("PropValue::String(({}.get()).to_string())", signal), "onInput", "bind_value"),
+        };
+        self.indent();
+        self.writeln(&format!("props.insert(\"{}\".to_string(), {});", bound, value));
+        self.indent();
+        self.writeln(&format!(
+            "props.insert(\"{}\".to_string(), rux_core::bindings::{}(&{}));",
+            event, handler, signal
+        ));
+        Ok(())
+    }
+
     fn generate_prop_value(&mut self, value: &JSXPropValue) -> Result<()> {
         match value {
             JSXPropValue::Literal(lit) => {
@@ -463,6 +846,11 @@ impl CodeGenerator {
                         self.write(&self.escape_string(s));
                         self.write("\".to_string())");
                     }
+                    Literal::Integer(n) => {
+                        self.write("PropValue::Number(");
+                        self.write(&n.to_string());
+                        self.write(".0)");
+                    }
                     Literal::Number(n) => {
                         self.write("PropValue::Number(");
                         self.write(&n.to_string());
@@ -483,7 +871,7 @@ impl CodeGenerator {
                 self.write(if *b { "true" } else { "false" });
                 self.write(")");
             }
-            JSXPropValue::Expr(expr) => {
+            JSXPropValue::Expr(_expr) => {
                 // For expressions in props, we'd need to generate the expression
                 // For now, use a placeholder
                 self.write("PropValue::String(\"TODO\".to_string())");
@@ -492,6 +880,123 @@ impl CodeGenerator {
         Ok(())
     }
 
+    /// Emits an expression-valued `class` prop as a space-joined string.
+    /// An array may mix plain class names with `(name, condition)` pairs;
+    /// when every entry is a literal the string is built at compile time.
+    fn generate_class_value(&mut self, expr: &Expr) -> Result<()> {
+        let Expr::Array(items, _) = expr else {
+            self.write("PropValue::String((");
+            self.generate_expression(expr)?;
+            self.write(").to_string())");
+            return Ok(());
+        };
+
+        if let Some(classes) = Self::static_class_list(items) {
+            self.write("PropValue::String(\"");
+            self.write(&self.escape_string(&classes.join(" ")));
+            self.write("\".to_string())");
+            return Ok(());
+        }
+
+        self.write("PropValue::String({ let mut classes: Vec<String> = Vec::new(); ");
+        for item in items {
+            match item {
+                Expr::Tuple(pair, _) if pair.len() == 2 => {
+                    self.write("if ");
+                    self.generate_expression(&pair[1])?;
+                    self.write(" { classes.push((");
+                    self.generate_expression(&pair[0])?;
+                    self.write(").to_string()); } ");
+                }
+                _ => {
+                    self.write("classes.push((");
+                    self.generate_expression(item)?;
+                    self.write(").to_string()); ");
+                }
+            }
+        }
+        self.write("classes.join(\" \") })");
+        Ok(())
+    }
+
+    /// The enabled class names if every entry of a class array is a
+    /// literal, `None` if any of them needs evaluating at runtime
+    fn static_class_list(items: &[Expr]) -> Option<Vec<String>> {
+        let mut classes = Vec::new();
+        for item in items {
+            match item {
+                Expr::Literal(Literal::String(name), _) => classes.push(name.clone()),
+                Expr::Tuple(pair, _) if pair.len() == 2 => match (&pair[0], &pair[1]) {
+                    (Expr::Literal(Literal::String(name), _), Expr::Literal(Literal::Boolean(on), _)) => {
+                        if *on {
+                            classes.push(name.clone());
+                        }
+                    }
+                    _ => return None,
+                },
+                _ => return None,
+            }
+        }
+        Some(classes)
+    }
+
+    fn generate_match_arm(&mut self, arm: &MatchArm) -> Result<()> {
+        self.indent();
+        self.generate_pattern(&arm.pattern)?;
+        if let Some(guard) = &arm.guard {
+            self.write(" if ");
+            self.generate_expression(guard)?;
+        }
+        self.write(" => ");
+        self.generate_expression(&arm.body)?;
+        self.writeln(",");
+        Ok(())
+    }
+
+    fn generate_pattern(&mut self, pattern: &Pattern) -> Result<()> {
+        match pattern {
+            Pattern::Ident(name, _) => self.write(name),
+            Pattern::Literal(lit, _) => self.generate_literal(lit)?,
+            Pattern::Tuple(patterns, _) => {
+                self.write("(");
+                for (i, pattern) in patterns.iter().enumerate() {
+                    if i > 0 {
+                        self.write(", ");
+                    }
+                    self.generate_pattern(pattern)?;
+                }
+                self.write(")");
+            }
+            Pattern::Struct { name, fields, .. } => {
+                self.write(name);
+                self.write(" { ");
+                for (i, (field, pattern)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        self.write(", ");
+                    }
+                    self.write(field);
+                    self.write(": ");
+                    self.generate_pattern(pattern)?;
+                }
+                self.write(" }");
+            }
+            Pattern::Path(path, _) => self.write(&path.join("::")),
+            Pattern::TupleStruct { name, elems, .. } => {
+                self.write(name);
+                self.write("(");
+                for (i, elem) in elems.iter().enumerate() {
+                    if i > 0 {
+                        self.write(", ");
+                    }
+                    self.generate_pattern(elem)?;
+                }
+                self.write(")");
+            }
+            Pattern::Wildcard(_) => self.write("_"),
+        }
+        Ok(())
+    }
+
     fn generate_literal(&mut self, lit: &Literal) -> Result<()> {
         match lit {
             Literal::String(s) => {
@@ -499,6 +1004,9 @@ impl CodeGenerator {
                 self.write(&self.escape_string(s));
                 self.write("\"");
             }
+            Literal::Integer(n) => {
+                self.write(&n.to_string());
+            }
             Literal::Number(n) => {
                 self.write(&n.to_string());
             }
@@ -524,6 +1032,53 @@ impl CodeGenerator {
         Ok(())
     }
 
+    /// `if .. { .. } else if .. { .. } else { .. }` from the current
+    /// position, without a trailing newline
+    fn generate_if_statement(&mut self, stmt: &Stmt) -> Result<()> {
+        let Stmt::If { condition, then, else_, .. } = stmt else {
+            return self.generate_branch_statement(stmt);
+        };
+        self.write("if ");
+        self.generate_expression(condition)?;
+        self.write(" ");
+        self.generate_branch_statement(then)?;
+        if let Some(else_stmt) = else_ {
+            self.write(" else ");
+            self.generate_if_statement(else_stmt)?;
+        }
+        Ok(())
+    }
+    
+    /// A branch body in braces, from the current position
+    fn generate_branch_statement(&mut self, stmt: &Stmt) -> Result<()> {
+        self.writeln("{");
+        self.indent_level += 1;
+        match stmt {
+            Stmt::Block(block) => self.generate_block(block)?,
+            other => self.generate_statement(other)?,
+        }
+        self.indent_level -= 1;
+        self.indent();
+        self.write("}");
+        Ok(())
+    }
+    
+    /// A branch of an `if` expression in braces, from the current position
+    fn generate_branch_expression(&mut self, expr: &Expr) -> Result<()> {
+        if let Expr::Block(..) = expr {
+            return self.generate_expression(expr);
+        }
+        self.writeln("{");
+        self.indent_level += 1;
+        self.indent();
+        self.generate_expression(expr)?;
+        self.writeln("");
+        self.indent_level -= 1;
+        self.indent();
+        self.write("}");
+        Ok(())
+    }
+    
     fn generate_statement(&mut self, stmt: &Stmt) -> Result<()> {
         match stmt {
             Stmt::Let { name, value, mutable, .. } => {
@@ -537,12 +1092,22 @@ impl CodeGenerator {
                 self.write(" = ");
                 self.generate_expression(value)?;
                 self.writeln(";");
+                if self.is_string_expr(value) {
+                    self.string_locals.insert(name.clone());
+                } else {
+                    self.string_locals.remove(name);
+                }
             }
             Stmt::Expr(expr) => {
                 self.indent();
                 self.generate_expression(expr)?;
                 self.writeln(";");
             }
+            Stmt::Tail(expr) => {
+                self.indent();
+                self.generate_expression(expr)?;
+                self.writeln("");
+            }
             Stmt::Return(Some(expr), _) => {
                 self.indent();
                 self.write("return ");
@@ -553,24 +1118,19 @@ impl CodeGenerator {
                 self.indent();
                 self.writeln("return;");
             }
-            Stmt::If { condition, then, else_, .. } => {
+            Stmt::Break(Some(expr), _) => {
                 self.indent();
-                self.write("if ");
-                self.generate_expression(condition)?;
-                self.write(" {\n");
-                self.indent_level += 1;
-                self.generate_statement(then)?;
-                self.indent_level -= 1;
+                self.write("break ");
+                self.generate_expression(expr)?;
+                self.writeln(";");
+            }
+            Stmt::Break(None, _) => {
                 self.indent();
-                self.write("}");
-                if let Some(ref else_stmt) = else_ {
-                    self.write(" else {\n");
-                    self.indent_level += 1;
-                    self.generate_statement(else_stmt)?;
-                    self.indent_level -= 1;
-                    self.indent();
-                    self.write("}");
-                }
+                self.writeln("break;");
+            }
+            Stmt::If { .. } => {
+                self.indent();
+                self.generate_if_statement(stmt)?;
                 self.writeln("");
             }
             Stmt::For { var, iter, body, .. } => {
@@ -604,11 +1164,7 @@ impl CodeGenerator {
                 self.write(" {\n");
                 self.indent_level += 1;
                 for arm in arms {
-                    self.indent();
-                    // Pattern matching would go here
-                    self.write("_ => ");
-                    self.generate_expression(&arm.body)?;
-                    self.writeln(",");
+                    self.generate_match_arm(arm)?;
                 }
                 self.indent_level -= 1;
                 self.indent();
@@ -660,6 +1216,11 @@ impl CodeGenerator {
                 self.generate_type(elem_type)?;
                 self.write("]");
             }
+            TypeKind::Range(elem_type) => {
+                self.write("std::ops::Range<");
+                self.generate_type(elem_type)?;
+                self.write(">");
+            }
             TypeKind::Function { params, return_type } => {
                 self.write("fn(");
                 for (i, param) in params.iter().enumerate() {
@@ -690,6 +1251,40 @@ impl CodeGenerator {
                 self.generate_type(err)?;
                 self.write(">");
             }
+            TypeKind::Generic { name, args } => {
+                self.write(name);
+                self.write("<");
+                for (i, arg) in args.iter().enumerate() {
+                    if i > 0 {
+                        self.write(", ");
+                    }
+                    self.generate_type(arg)?;
+                }
+                self.write(">");
+            }
+            TypeKind::Dyn(inner) => {
+                self.write("dyn ");
+                self.generate_type(inner)?;
+            }
+            TypeKind::Impl(inner) => {
+                self.write("impl ");
+                self.generate_type(inner)?;
+            }
+            TypeKind::FnTrait { name, params, return_type } => {
+                self.write(name);
+                self.write("(");
+                for (i, param) in params.iter().enumerate() {
+                    if i > 0 {
+                        self.write(", ");
+                    }
+                    self.generate_type(param)?;
+                }
+                self.write(")");
+                if let Some(return_type) = return_type {
+                    self.write(" -> ");
+                    self.generate_type(return_type)?;
+                }
+            }
         }
         Ok(())
     }
@@ -729,6 +1324,18 @@ impl CodeGenerator {
     }
 
     // Helper methods
+    fn is_string_expr(&self, expr: &Expr) -> bool {
+        match expr {
+            Expr::Literal(Literal::String(_), _) => true,
+            Expr::Variable(name, _) => self.string_locals.contains(name),
+            Expr::MethodCall { method, .. } => method == "to_string",
+            Expr::Binary { left, op: BinaryOp::Add, right, .. } => {
+                self.is_string_expr(left) || self.is_string_expr(right)
+            }
+            _ => false,
+        }
+    }
+
     fn write(&mut self, s: &str) {
         self.output.push_str(s);
     }
@@ -738,6 +1345,28 @@ impl CodeGenerator {
         self.output.push('\n');
     }
 
+    /// Items generate the same code as long as they, their nesting depth
+    /// and the project's components and prop structs stay the same. Spans
+    /// aren't part of the key: editing one item moves every item after it
+    /// in the file, but doesn't change their code.
+    fn cache_key(&self, item: &Item) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        item.hash(&mut hasher);
+        self.indent_level.hash(&mut hasher);
+        self.context_hash.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn hash_context(prop_structs: &HashSet<String>, components: &HashSet<String>) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        for names in [prop_structs, components] {
+            let mut names: Vec<&String> = names.iter().collect();
+            names.sort();
+            names.hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+
     fn indent(&mut self) {
         for _ in 0..self.indent_level {
             self.output.push_str("    ");
@@ -747,8 +1376,8 @@ impl CodeGenerator {
     fn snake_case(&self, s: &str) -> String {
         // Simple conversion - in production, use a proper library
         let mut result = String::new();
-        let mut chars = s.chars().peekable();
-        while let Some(ch) = chars.next() {
+        let chars = s.chars().peekable();
+        for ch in chars {
             if ch.is_uppercase() && !result.is_empty() {
                 result.push('_');
             }
@@ -782,3 +1411,12 @@ impl Default for CodeGenerator {
         Self::new()
     }
 }
+
+/// `String`, `&str` or `&String`
+fn is_string_type(ty: &Type) -> bool {
+    match &ty.kind {
+        TypeKind::Ident(name) => name == "String" || name == "str",
+        TypeKind::Reference { inner, .. } => is_string_type(inner),
+        _ => false,
+    }
+}
@@ -0,0 +1,116 @@
+use crate::lexer::Span;
+use serde_json::{json, Value};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        }
+    }
+}
+
+/// A non-fatal message produced by a compiler pass
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    pub span: Span,
+    /// How to fix it, e.g. `add ';'`
+    pub help: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn warning(message: impl Into<String>, span: Span) -> Self {
+        Self {
+            severity: Severity::Warning,
+            message: message.into(),
+            span,
+            help: None,
+        }
+    }
+    
+    /// An error a pass recovered from so it could keep going. Compilation
+    /// still fails.
+    pub fn error(message: impl Into<String>, span: Span) -> Self {
+        Self {
+            severity: Severity::Error,
+            ..Self::warning(message, span)
+        }
+    }
+    
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help = Some(help.into());
+        self
+    }
+    
+    /// Machine-readable form with the same fields as `Error::to_json`
+    pub fn to_json(&self) -> Value {
+        json!({
+            "severity": self.severity.as_str(),
+            "code": format!("rux::{}", self.severity.as_str()),
+            "message": self.message,
+            "file": Value::Null,
+            "span": {
+                "start": self.span.start,
+                "end": self.span.end,
+            },
+            "line": self.span.line,
+            "column": self.span.column,
+            "help": self.help,
+        })
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} (line {}, column {})", self.severity.as_str(), self.message, self.span.line, self.span.column)?;
+        if let Some(help) = &self.help {
+            write!(f, "\n  help: {}", help)?;
+        }
+        Ok(())
+    }
+}
+
+/// Accumulates diagnostics that should be reported without stopping compilation
+#[derive(Debug, Clone, Default)]
+pub struct WarningCollector {
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl WarningCollector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn warn(&mut self, message: impl Into<String>, span: Span) {
+        self.diagnostics.push(Diagnostic::warning(message, span));
+    }
+
+    pub fn push(&mut self, diagnostic: Diagnostic) {
+        self.diagnostics.push(diagnostic);
+    }
+
+    pub fn extend(&mut self, other: WarningCollector) {
+        self.diagnostics.extend(other.diagnostics);
+    }
+
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.diagnostics.len()
+    }
+}
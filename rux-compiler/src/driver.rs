@@ -1,148 +1,903 @@
-use crate::ast::AST;
+use crate::ast::{EnumVariant, EnumVariantData, Item, Type, AST};
 use crate::errors::{Error, Result};
-use crate::lexer::{Lexer, TokenWithSpan};
+use crate::lexer::{Lexer, Span};
 use crate::parser::Parser;
 use crate::type_checker::TypeChecker;
 use crate::optimizer::Optimizer;
 use crate::analyzer::DependencyAnalyzer;
-use crate::codegen::CodeGenerator;
+use crate::codegen::{CodeGenerator, CodegenCache};
+use crate::diagnostics::{Diagnostic, Severity, WarningCollector};
+use crate::resolver::ModuleResolver;
 use std::path::{Path, PathBuf};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// Controls which optimizer passes the driver runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompilerOptions {
+    /// Remove items that are never referenced
+    pub dead_code: bool,
+    /// Evaluate constant expressions at compile time
+    pub constant_folding: bool,
+    /// Compute repeated pure expressions once
+    pub common_subexpressions: bool,
+    /// Expand components used exactly once at their call site
+    pub inline_components: bool,
+    /// Master switch; when off no optimizer pass runs
+    pub optimize: bool,
+    /// Record how long each pass takes, see `Compiler::timings`
+    pub profile: bool,
+    /// Threads `compile_project` parses files on; 0 uses every core
+    pub jobs: usize,
+    /// Run generated Rust through rustfmt. Output rustfmt rejects is kept
+    /// as generated, with a warning.
+    pub format: bool,
+}
+
+impl CompilerOptions {
+    /// Every pass enabled, used for release builds
+    pub fn release() -> Self {
+        Self {
+            dead_code: true,
+            constant_folding: true,
+            common_subexpressions: true,
+            inline_components: true,
+            optimize: true,
+            profile: false,
+            jobs: 0,
+            format: false,
+        }
+    }
+    
+    /// No optimizer passes, so generated code mirrors the source
+    pub fn debug() -> Self {
+        Self {
+            dead_code: false,
+            constant_folding: false,
+            common_subexpressions: false,
+            inline_components: false,
+            optimize: false,
+            profile: false,
+            jobs: 0,
+            format: false,
+        }
+    }
+}
+
+impl Default for CompilerOptions {
+    fn default() -> Self {
+        Self::release()
+    }
+}
+
+/// Time spent in each pass over one file, in the order the passes ran.
+/// Project builds check all files together, so their type checking and
+/// later passes are recorded under `Compiler::PROJECT`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PassTimings {
+    pub file: String,
+    pub passes: Vec<(&'static str, Duration)>,
+}
+
+impl PassTimings {
+    pub fn new(file: impl Into<String>) -> Self {
+        Self {
+            file: file.into(),
+            passes: Vec::new(),
+        }
+    }
+    
+    pub fn get(&self, pass: &str) -> Option<Duration> {
+        self.passes.iter().find(|(name, _)| *name == pass).map(|(_, duration)| *duration)
+    }
+    
+    pub fn total(&self) -> Duration {
+        self.passes.iter().map(|(_, duration)| *duration).sum()
+    }
+}
+
+impl fmt::Display for PassTimings {
+    /// `app.rsx: lex 0.10ms, parse 0.25ms, total 0.35ms`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:", self.file)?;
+        for (name, duration) in &self.passes {
+            write!(f, " {} {:.2}ms,", name, millis(*duration))?;
+        }
+        write!(f, " total {:.2}ms", millis(self.total()))
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// A transformation over a type-checked AST, e.g. injecting imports or
+/// expanding custom elements. Register one with `Compiler::add_pass`.
+pub trait AstPass: Send {
+    fn run(&mut self, ast: &mut AST) -> Result<()>;
+    
+    /// Name the pass is timed and traced under
+    fn name(&self) -> &'static str {
+        "custom"
+    }
+    
+    /// Warnings reported by previous runs, leaving none behind
+    fn take_warnings(&mut self) -> WarningCollector {
+        WarningCollector::new()
+    }
+}
 
 pub struct Compiler {
     source_map: HashMap<PathBuf, String>,
+    options: CompilerOptions,
+    dependencies: HashMap<String, HashSet<String>>,
+    warnings: WarningCollector,
+    timings: Vec<PassTimings>,
+    error_file: Option<PathBuf>,
+    /// Passes run after analysis, in order. The optimizer is registered
+    /// last and custom passes are inserted ahead of it.
+    passes: Vec<Box<dyn AstPass>>,
+    /// How many of the trailing `passes` the compiler registered itself
+    builtin_passes: usize,
 }
 
 impl Compiler {
+    /// File name the project-wide passes of `compile_project` are timed under
+    pub const PROJECT: &'static str = "<project>";
+    
     pub fn new() -> Self {
+        Self::new_with_options(CompilerOptions::default())
+    }
+    
+    pub fn new_with_options(options: CompilerOptions) -> Self {
+        let mut passes: Vec<Box<dyn AstPass>> = Vec::new();
+        if options.optimize {
+            passes.push(Box::new(Optimizer::with_options(options)));
+        }
         Self {
             source_map: HashMap::new(),
+            options,
+            dependencies: HashMap::new(),
+            warnings: WarningCollector::new(),
+            timings: Vec::new(),
+            error_file: None,
+            builtin_passes: passes.len(),
+            passes,
         }
     }
     
+    /// Registers a pass to run on every compiled AST after type checking,
+    /// before the optimizer. Passes run in the order they were added.
+    pub fn add_pass(&mut self, pass: Box<dyn AstPass>) {
+        let at = self.passes.len() - self.builtin_passes;
+        self.passes.insert(at, pass);
+    }
+    
     pub fn compile_file(&mut self, path: &Path) -> Result<AST> {
-        let source = std::fs::read_to_string(path)
-            .map_err(|e| Error::parser(
-                format!("Failed to read file: {}", e),
-                String::new(),
-                (0, 0).into(),
-            ))?;
-        
+        self.compile_file_with_checker(path, TypeChecker::new())
+    }
+    
+    /// Compiles a file with a type checker the caller has prepared, e.g.
+    /// with items from other files defined as externals
+    pub fn compile_file_with_checker(&mut self, path: &Path, type_checker: TypeChecker) -> Result<AST> {
+        let _span = tracing::debug_span!("compile", file = %path.display()).entered();
+        let source = Self::read_source(path)?;
         self.source_map.insert(path.to_path_buf(), source.clone());
+        self.start_timing(&path.display().to_string());
+        self.compile_source(&source, type_checker).map_err(|e| e.with_source(&source))
+    }
+    
+    /// `compile_file_with_checker` for a file the caller has already read
+    /// and parsed, recording `timings` as that parse's
+    fn compile_parsed(&mut self, path: &Path, source: &str, mut ast: AST, timings: PassTimings, type_checker: TypeChecker) -> Result<AST> {
+        let _span = tracing::debug_span!("compile", file = %path.display()).entered();
+        self.source_map.insert(path.to_path_buf(), source.to_string());
+        if self.options.profile {
+            self.timings.push(timings);
+        }
+        self.run_passes(&mut ast, type_checker).map_err(|e| e.with_source(source))?;
+        Ok(ast)
+    }
+    
+    pub fn compile_string(&mut self, source: &str, filename: &str) -> Result<AST> {
+        let _span = tracing::debug_span!("compile", file = filename).entered();
+        self.start_timing(filename);
+        self.compile_source(source, TypeChecker::new()).map_err(|e| e.with_source(source))
+    }
+    
+    /// `compile_string` that also returns the warnings reported while
+    /// compiling `source`, whether or not it compiled
+    pub fn compile_string_checked(&mut self, source: &str, filename: &str) -> (Result<AST>, Vec<Diagnostic>) {
+        let before = self.warnings.len();
+        let result = self.compile_string(source, filename);
+        (result, self.warnings.diagnostics()[before..].to_vec())
+    }
+    
+    /// Lexes and parses `source` without type checking or optimizing it,
+    /// so editor tooling gets an AST for code that doesn't check yet
+    pub fn parse_only(&mut self, source: &str, filename: &str) -> Result<AST> {
+        let _span = tracing::debug_span!("compile", file = filename).entered();
+        self.start_timing(filename);
+        self.parse_source(source).map_err(|e| e.with_source(source))
+    }
+    
+    fn compile_source(&mut self, source: &str, type_checker: TypeChecker) -> Result<AST> {
+        let mut ast = self.parse_source(source)?;
+        self.run_passes(&mut ast, type_checker)?;
+        Ok(ast)
+    }
+    
+    fn parse_source(&mut self, source: &str) -> Result<AST> {
+        let mut timings = PassTimings::new("");
+        let result = lex_and_parse(source, &mut timings);
+        if let Some(current) = self.timings.last_mut() {
+            current.passes.append(&mut timings.passes);
+        }
+        result
+    }
+    
+    /// Pass timings of every file compiled so far, when
+    /// `CompilerOptions::profile` is set
+    pub fn timings(&self) -> &[PassTimings] {
+        &self.timings
+    }
+    
+    fn start_timing(&mut self, file: &str) {
+        if self.options.profile {
+            self.timings.push(PassTimings::new(file));
+        }
+    }
+    
+    fn record_timing(&mut self, pass: &'static str, start: Instant) {
+        if let Some(timings) = self.timings.last_mut() {
+            timings.passes.push((pass, start.elapsed()));
+        }
+    }
+    
+    /// Dependency graph of every component and function compiled so far,
+    /// keyed by name
+    pub fn dependencies(&self) -> &HashMap<String, HashSet<String>> {
+        &self.dependencies
+    }
+    
+    /// Warnings reported by every pass over the files compiled so far
+    pub fn warnings(&self) -> &WarningCollector {
+        &self.warnings
+    }
+    
+    /// Compiles every `.rsx` file under `root` into one AST. Each file
+    /// becomes a module mirroring its path, `use` declarations are resolved
+    /// across files, and the combined tree is checked as a whole.
+    /// Files are lexed and parsed in parallel, then merged in path order,
+    /// so the result doesn't depend on `CompilerOptions::jobs`.
+    pub fn compile_project(&mut self, root: &Path) -> Result<AST> {
+        let _span = tracing::debug_span!("compile_project", root = %root.display()).entered();
+        self.error_file = None;
+        let mut files = Vec::new();
+        Self::find_source_files(root, &mut files)?;
+        files.sort();
+        tracing::debug!(files = files.len(), "found source files");
         
-        // Lex
-        let mut lexer = Lexer::new(&source);
-        let tokens = lexer.tokenize()?;
-        
-        // Parse
-        let mut parser = Parser::new(tokens, source);
-        let mut ast = parser.parse()?;
-        
-        // Type check
-        let mut type_checker = TypeChecker::new();
-        type_checker.check(&ast)?;
-        
-        // Analyze dependencies
-        let mut analyzer = DependencyAnalyzer::new();
-        analyzer.analyze(&ast)?;
+        let resolver = ModuleResolver::new();
+        let mut ast = AST { items: Vec::new(), docs: Vec::new() };
         
-        // Optimize
-        let optimizer = Optimizer::new();
-        optimizer.optimize(&mut ast)?;
+        for (path, parsed) in files.iter().zip(self.parse_files(&files)) {
+            if self.options.profile {
+                self.timings.push(parsed.timings);
+            }
+            if let Some(source) = parsed.source {
+                self.source_map.insert(path.clone(), source);
+            }
+            let file_ast = match parsed.ast {
+                Ok(file_ast) => file_ast,
+                Err(e) => {
+                    self.error_file = Some(path.clone());
+                    return Err(e);
+                }
+            };
+            
+            let module_path = ModuleResolver::module_path(root, path);
+            resolver.insert_module(&mut ast.items, &module_path, file_ast.items);
+        }
         
+        resolver.resolve_imports(&mut ast.items)?;
+        self.start_timing(Self::PROJECT);
+        self.run_passes(&mut ast, TypeChecker::new_for_project())?;
         Ok(ast)
     }
     
-    pub fn compile_string(&mut self, source: &str, filename: &str) -> Result<AST> {
-        // Lex
-        let mut lexer = Lexer::new(source);
-        let tokens = lexer.tokenize()?;
+    /// Reads and parses `files` across `CompilerOptions::jobs` threads,
+    /// returning the results in the same order as `files`
+    fn parse_files(&self, files: &[PathBuf]) -> Vec<ParsedFile> {
+        let jobs = match self.options.jobs {
+            0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+            jobs => jobs,
+        };
+        let chunk_size = files.len().div_ceil(jobs).max(1);
+        // Workers don't inherit the calling thread's span, so their spans
+        // are parented to it explicitly
+        let span = tracing::Span::current();
         
-        // Parse
-        let mut parser = Parser::new(tokens, source.to_string());
-        let mut ast = parser.parse()?;
+        std::thread::scope(|scope| {
+            let workers: Vec<_> = files
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    let span = span.clone();
+                    scope.spawn(move || {
+                        let _entered = span.enter();
+                        chunk.iter().map(|path| ParsedFile::parse(path)).collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().expect("parser thread panicked"))
+                .collect()
+        })
+    }
+    
+    /// The file the last `compile_project` error was reported in, when it
+    /// came from parsing a single file rather than the project-wide passes
+    pub fn error_file(&self) -> Option<&Path> {
+        self.error_file.as_deref()
+    }
+    
+    fn find_source_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+        let entries = std::fs::read_dir(dir).map_err(|e| Error::parser(
+            format!("Failed to read directory {:?}: {}", dir, e),
+            String::new(),
+            (0, 0).into(),
+        ))?;
         
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                Self::find_source_files(&path, files)?;
+            } else if path.extension().and_then(|s| s.to_str()) == Some("rsx") {
+                files.push(path);
+            }
+        }
+        Ok(())
+    }
+    
+    fn read_source(path: &Path) -> Result<String> {
+        std::fs::read_to_string(path)
+            .map_err(|e| Error::parser(
+                format!("Failed to read file: {}", e),
+                String::new(),
+                (0, 0).into(),
+            ))
+    }
+    
+    /// Type checks and analyzes a parsed AST, then runs the registered
+    /// passes over it, the optimizer last.
+    fn run_passes(&mut self, ast: &mut AST, mut type_checker: TypeChecker) -> Result<()> {
         // Type check
-        let mut type_checker = TypeChecker::new();
-        type_checker.check(&ast)?;
+        let start = Instant::now();
+        tracing::debug_span!("typecheck").in_scope(|| type_checker.check(ast))?;
+        self.warnings.extend(type_checker.take_warnings());
+        self.record_timing("typecheck", start);
         
         // Analyze dependencies
+        let start = Instant::now();
         let mut analyzer = DependencyAnalyzer::new();
-        analyzer.analyze(&ast)?;
+        tracing::debug_span!("analyze").in_scope(|| analyzer.analyze(ast))?;
+        self.warnings.extend(analyzer.take_warnings());
+        self.dependencies.extend(analyzer.into_dependencies());
+        self.record_timing("analyze", start);
         
-        // Optimize
-        let optimizer = Optimizer::new();
-        optimizer.optimize(&mut ast)?;
-        
-        Ok(ast)
+        // Custom passes, then the optimizer
+        self.run_registered_passes(ast, self.passes.len())
+    }
+    
+    /// Runs the first `count` registered passes over `ast`, timing and
+    /// tracing each under its name
+    fn run_registered_passes(&mut self, ast: &mut AST, count: usize) -> Result<()> {
+        for i in 0..count {
+            let name = self.passes[i].name();
+            let start = Instant::now();
+            let result = tracing::debug_span!("pass", name).in_scope(|| self.passes[i].run(ast));
+            self.warnings.extend(self.passes[i].take_warnings());
+            result?;
+            self.record_timing(name, start);
+        }
+        Ok(())
+    }
+    
+    /// Generates Rust for a compiled AST, timed as the last compiled
+    /// file's codegen pass. Output that doesn't parse as Rust is a codegen
+    /// bug and is returned as `Error::Internal` instead.
+    pub fn generate_rust(&mut self, ast: &AST) -> Result<String> {
+        let start = Instant::now();
+        let mut codegen = CodeGenerator::new();
+        let code = tracing::debug_span!("codegen").in_scope(|| codegen.generate_rust_code(ast))?;
+        self.record_timing("codegen", start);
+        validate_rust(&code)?;
+        Ok(self.format_rust(code))
+    }
+    
+    /// `generate_rust`, reusing code from `cache` for unchanged items
+    pub fn generate_rust_cached(&mut self, ast: &AST, cache: &mut dyn CodegenCache) -> Result<String> {
+        let start = Instant::now();
+        let mut codegen = CodeGenerator::new();
+        let code = tracing::debug_span!("codegen").in_scope(|| codegen.generate_rust_code_cached(ast, cache))?;
+        self.record_timing("codegen", start);
+        validate_rust(&code)?;
+        Ok(self.format_rust(code))
+    }
+    
+    /// Formats generated code when `CompilerOptions::format` is set. Code
+    /// rustfmt can't parse points to a codegen bug, so it is reported as a
+    /// warning rather than hidden.
+    fn format_rust(&mut self, code: String) -> String {
+        if !self.options.format {
+            return code;
+        }
+        let start = Instant::now();
+        let formatted = match tracing::debug_span!("format").in_scope(|| rustfmt(&code)) {
+            Ok(formatted) => formatted,
+            Err(message) => {
+                self.warnings.warn(
+                    format!("Generated Rust was left unformatted: {}", message),
+                    Span::new(0, 0, 1, 1),
+                );
+                code
+            }
+        };
+        self.record_timing("format", start);
+        formatted
     }
     
     pub fn compile_string_to_rust(&mut self, source: &str, filename: &str) -> Result<String> {
         let ast = self.compile_string(source, filename)?;
+        self.generate_rust(&ast)
+    }
+    
+    /// Best-effort version of `compile_string_to_rust`: items that fail to
+    /// type check become `compile_error!` stubs carrying the diagnostic,
+    /// and everything else is still generated. Only a parse error fails the
+    /// whole file. Custom passes run over the items that checked, but the
+    /// output is not optimized, since dead code elimination can't see uses
+    /// inside the failed items.
+    pub fn compile_string_to_rust_partial(&mut self, source: &str, filename: &str) -> Result<String> {
+        let _span = tracing::debug_span!("compile", file = filename).entered();
+        self.start_timing(filename);
+        let mut ast = self.parse_source(source).map_err(|e| e.with_source(source))?;
+        
+        let mut type_checker = TypeChecker::new();
+        let mut failures: HashMap<usize, String> = tracing::debug_span!("typecheck")
+            .in_scope(|| type_checker.check_each(&ast))
+            .into_iter()
+            .map(|(i, e)| (i, e.to_string()))
+            .collect();
+        self.warnings.extend(type_checker.take_warnings());
+        
+        // Failed items sit out the custom passes, then go back where they
+        // were, or at the end if the passes removed items before them
+        let mut failed_items = Vec::new();
+        for (i, item) in std::mem::take(&mut ast.items).into_iter().enumerate() {
+            match failures.remove(&i) {
+                Some(message) => failed_items.push((i, item, message)),
+                None => ast.items.push(item),
+            }
+        }
+        let custom_passes = self.passes.len() - self.builtin_passes;
+        self.run_registered_passes(&mut ast, custom_passes).map_err(|e| e.with_source(source))?;
+        let mut failed = HashMap::new();
+        for (i, item, message) in failed_items {
+            let at = i.min(ast.items.len());
+            ast.items.insert(at, item);
+            failed.insert(at, message);
+        }
+        
         let mut codegen = CodeGenerator::new();
-        codegen.generate_rust_code(&ast)
+        tracing::debug_span!("codegen").in_scope(|| codegen.generate_partial_rust_code(&ast, &failed))
+    }
+}
+
+impl Default for Compiler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// One project file after lexing and parsing, produced on a worker thread
+struct ParsedFile {
+    source: Option<String>,
+    ast: Result<AST>,
+    timings: PassTimings,
+}
+
+impl ParsedFile {
+    fn parse(path: &Path) -> Self {
+        let mut timings = PassTimings::new(path.display().to_string());
+        match Compiler::read_source(path) {
+            Ok(source) => {
+                let ast = lex_and_parse(&source, &mut timings).map_err(|e| e.with_source(&source));
+                Self { source: Some(source), ast, timings }
+            }
+            Err(e) => Self { source: None, ast: Err(e), timings },
+        }
+    }
+}
+
+/// Parses generated code, so codegen bugs are reported against the
+/// generated line rather than surfacing later as `rustc` errors
+fn validate_rust(code: &str) -> Result<()> {
+    let Err(e) = syn::parse_file(code) else {
+        return Ok(());
+    };
+    let start = e.span().start();
+    let range = e.span().byte_range();
+    Err(Error::internal(
+        format!(
+            "generated Rust does not parse at line {}, column {}: {}",
+            start.line,
+            start.column + 1,
+            e
+        ),
+        code,
+        (range.start, range.len()).into(),
+    ))
+}
+
+/// Formats Rust source with the `rustfmt` on `PATH`
+fn rustfmt(code: &str) -> std::result::Result<String, String> {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+    
+    let mut child = Command::new("rustfmt")
+        .args(["--edition", "2021"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("could not run rustfmt: {}", e))?;
+    
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let input = code.to_string();
+    // Written from another thread so a full stdout pipe can't deadlock us
+    let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()));
+    let output = child.wait_with_output().map_err(|e| format!("rustfmt failed: {}", e))?;
+    let _ = writer.join();
+    
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+fn lex_and_parse(source: &str, timings: &mut PassTimings) -> Result<AST> {
+    // Lex
+    let start = Instant::now();
+    let mut lexer = Lexer::new(source);
+    let tokens = tracing::debug_span!("lex").in_scope(|| lexer.tokenize())?;
+    timings.passes.push(("lex", start.elapsed()));
+    
+    // Parse
+    let start = Instant::now();
+    let mut parser = Parser::new(tokens, source.to_string());
+    let ast = tracing::debug_span!("parse").in_scope(|| parser.parse())?;
+    timings.passes.push(("parse", start.elapsed()));
+    // The parser keeps going past the errors it can recover from, but the
+    // source still doesn't compile
+    if let Some(error) = parser.diagnostics().iter().find(|d| d.severity == Severity::Error) {
+        let err = Error::parser(error.message.clone(), source.to_string(), error.span.to_source_span());
+        return Err(match &error.help {
+            Some(help) => err.with_help(help.clone()),
+            None => err,
+        });
     }
+    Ok(ast)
 }
 
 pub struct IncrementalCompiler {
     compiler: Compiler,
-    file_hashes: HashMap<PathBuf, u64>,
-    dependency_graph: HashMap<PathBuf, Vec<PathBuf>>,
+    root: Option<PathBuf>,
+    files: HashMap<PathBuf, FileCache>,
+    checked: Vec<PathBuf>,
+}
+
+/// A file as read by one `compile_incremental` call
+struct Revision {
+    hash: u64,
+    source: String,
+    /// The parse and its timings, or `None` when the file is unchanged
+    /// since its last check and the cached AST still holds
+    parsed: Option<(Result<AST>, PassTimings)>,
+}
+
+/// What the last successful check of a file recorded about it
+struct FileCache {
+    hash: u64,
+    /// The file as parsed, before any pass ran, so an unchanged file that
+    /// is re-checked for its imports isn't parsed again
+    ast: AST,
+    module_path: Vec<String>,
+    /// Value types of exported functions and components, keyed by name
+    exports: HashMap<String, Option<Type>>,
+    /// Public signature of every exported item, compared between checks to
+    /// decide whether dependents need re-checking
+    signatures: BTreeMap<String, String>,
+    /// `use` paths, relative to the project root
+    imports: Vec<Vec<String>>,
 }
 
 impl IncrementalCompiler {
     pub fn new() -> Self {
         Self {
             compiler: Compiler::new(),
-            file_hashes: HashMap::new(),
-            dependency_graph: HashMap::new(),
+            root: None,
+            files: HashMap::new(),
+            checked: Vec::new(),
+        }
+    }
+    
+    /// Compiler for a project under `root`, whose files are imported by
+    /// their path from there. Without a root, each file is a module named
+    /// after itself.
+    pub fn with_root(root: &Path) -> Self {
+        Self {
+            root: Some(root.to_path_buf()),
+            ..Self::new()
         }
     }
     
+    /// Checks with `options` instead of the defaults. Dev builds want
+    /// `CompilerOptions::debug()`, since the optimizer would also drop
+    /// functions that only other files call.
+    pub fn with_options(mut self, options: CompilerOptions) -> Self {
+        self.compiler = Compiler::new_with_options(options);
+        self
+    }
+    
+    /// Re-checks the changed files whose contents differ from the last
+    /// check. Dependents are only re-checked when a file's exported
+    /// signatures changed, not when just its bodies did.
     pub fn compile_incremental(&mut self, changed_files: &[PathBuf]) -> Result<()> {
-        let affected = self.find_affected_files(changed_files);
+        self.checked.clear();
+        // A file that can't be read is reported when it's checked
+        let mut revisions: HashMap<PathBuf, Revision> = changed_files
+            .iter()
+            .filter_map(|file| Some((file.clone(), self.read_revision(file).ok()?)))
+            .collect();
+        let mut queue: VecDeque<PathBuf> = self.dependency_order(changed_files, &revisions).into();
+        let mut forced = HashSet::new();
         
-        for file in affected {
-            self.compiler.compile_file(&file)?;
-            // Update hash
-            let hash = self.compute_file_hash(&file)?;
-            self.file_hashes.insert(file, hash);
+        while let Some(file) = queue.pop_front() {
+            let revision = match revisions.remove(&file) {
+                Some(revision) => revision,
+                None => self.read_revision(&file)?,
+            };
+            let previous = self.files.get(&file);
+            if revision.parsed.is_none() && !forced.contains(&file) {
+                tracing::debug!(file = %file.display(), "skipped unchanged file");
+                continue;
+            }
+            let previous_signatures = previous.map(|cache| cache.signatures.clone());
+            let (ast, timings) = match revision.parsed {
+                Some((ast, timings)) => (ast?, timings),
+                // Unchanged, but forced by a change to one of its imports
+                None => (previous.expect("unchanged files are cached").ast.clone(), PassTimings::new(file.display().to_string())),
+            };
+            
+            let cache = self.check_file(&file, revision.hash, &revision.source, ast, timings)?;
+            let signatures_changed = previous_signatures.as_ref() != Some(&cache.signatures);
+            self.checked.push(file.clone());
+            self.files.insert(file.clone(), cache);
+            
+            if signatures_changed {
+                tracing::debug!(file = %file.display(), "exported signatures changed, re-checking dependents");
+                for dependent in self.dependents_of(&file) {
+                    if forced.insert(dependent.clone()) {
+                        queue.push_back(dependent);
+                    }
+                }
+            }
         }
         
         Ok(())
     }
     
-    fn find_affected_files(&self, changed: &[PathBuf]) -> Vec<PathBuf> {
-        let mut affected = changed.to_vec();
-        let mut to_check = changed.to_vec();
+    /// Files type-checked by the last `compile_incremental` call
+    pub fn checked_files(&self) -> &[PathBuf] {
+        &self.checked
+    }
+    
+    /// Pass timings of every check so far, when `CompilerOptions::profile`
+    /// is set. A file re-checked only for its imports has no lex or parse
+    /// pass, since its cached AST is reused.
+    pub fn timings(&self) -> &[PassTimings] {
+        self.compiler.timings()
+    }
+    
+    /// Reads `file`, parsing it unless it's unchanged since its last check
+    fn read_revision(&self, file: &Path) -> Result<Revision> {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
         
-        while let Some(file) = to_check.pop() {
-            if let Some(dependents) = self.dependency_graph.get(&file) {
-                for dependent in dependents {
-                    if !affected.contains(dependent) {
-                        affected.push(dependent.clone());
-                        to_check.push(dependent.clone());
-                    }
+        let source = Compiler::read_source(file)?;
+        let mut hasher = DefaultHasher::new();
+        source.hash(&mut hasher);
+        let hash = hasher.finish();
+        
+        let parsed = match self.files.get(file) {
+            Some(cache) if cache.hash == hash => None,
+            _ => {
+                let mut timings = PassTimings::new(file.display().to_string());
+                let ast = lex_and_parse(&source, &mut timings).map_err(|e| e.with_source(&source));
+                Some((ast, timings))
+            }
+        };
+        Ok(Revision { hash, source, parsed })
+    }
+    
+    fn check_file(&mut self, file: &Path, hash: u64, source: &str, ast: AST, timings: PassTimings) -> Result<FileCache> {
+        let mut type_checker = TypeChecker::new();
+        for (path, cache) in &self.files {
+            if path == file {
+                continue;
+            }
+            for (name, value_type) in &cache.exports {
+                let mut item_path = cache.module_path.clone();
+                item_path.push(name.clone());
+                type_checker.define_external(item_path, value_type.clone());
+            }
+        }
+        
+        self.compiler.compile_parsed(file, source, ast.clone(), timings, type_checker)?;
+        // Exports are read from the file as written, since the optimizer
+        // drops items that only other files use
+        let mut cache = FileCache {
+            hash,
+            module_path: self.module_path(file),
+            exports: HashMap::new(),
+            signatures: BTreeMap::new(),
+            imports: Self::imports(&ast),
+            ast,
+        };
+        
+        for item in &cache.ast.items {
+            match item {
+                Item::Function(f) => {
+                    let ty = TypeChecker::function_type(f);
+                    cache.signatures.insert(f.name.clone(), Self::generic_signature(&f.generics, &ty));
+                    cache.exports.insert(f.name.clone(), Some(ty));
+                }
+                Item::Component(c) => {
+                    let ty = TypeChecker::component_type(c);
+                    cache.signatures.insert(c.name.clone(), Self::generic_signature(&c.generics, &ty));
+                    cache.exports.insert(c.name.clone(), Some(ty));
                 }
+                Item::Struct(s) => {
+                    let fields: Vec<String> = s.fields.iter().map(|f| format!("{}: {}", f.name, f.field_type)).collect();
+                    cache.signatures.insert(s.name.clone(), format!("struct {{ {} }}", fields.join(", ")));
+                    cache.exports.insert(s.name.clone(), None);
+                }
+                Item::Enum(e) => {
+                    let variants: Vec<String> = e.variants.iter().map(Self::variant_signature).collect();
+                    cache.signatures.insert(e.name.clone(), format!("enum {{ {} }}", variants.join(", ")));
+                    cache.exports.insert(e.name.clone(), None);
+                }
+                _ => {}
             }
         }
         
-        affected
+        Ok(cache)
     }
     
-    fn compute_file_hash(&self, path: &Path) -> Result<u64> {
-        use std::collections::hash_map::DefaultHasher;
-        use std::hash::{Hash, Hasher};
+    /// Every `use` path in `ast`, relative to the project root
+    fn imports(ast: &AST) -> Vec<Vec<String>> {
+        ast.items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Use(use_stmt) => Some(Self::import_path(&use_stmt.path)),
+                _ => None,
+            })
+            .collect()
+    }
+    
+    fn variant_signature(variant: &EnumVariant) -> String {
+        match &variant.data {
+            None => variant.name.clone(),
+            Some(EnumVariantData::Tuple(types)) => {
+                let types: Vec<String> = types.iter().map(Type::to_string).collect();
+                format!("{}({})", variant.name, types.join(", "))
+            }
+            Some(EnumVariantData::Struct(fields)) => {
+                let fields: Vec<String> = fields.iter().map(|f| format!("{}: {}", f.name, f.field_type)).collect();
+                format!("{} {{ {} }}", variant.name, fields.join(", "))
+            }
+        }
+    }
+    
+    /// A `use` path relative to the project root
+    fn import_path(path: &[String]) -> Vec<String> {
+        match path.first().map(String::as_str) {
+            Some("crate") => path[1..].to_vec(),
+            _ => path.to_vec(),
+        }
+    }
+    
+    /// `files` with each one after the files among them that it imports,
+    /// so a first check of a project sees the exports its imports need.
+    /// Files in an import cycle keep their relative order.
+    fn dependency_order(&self, files: &[PathBuf], revisions: &HashMap<PathBuf, Revision>) -> Vec<PathBuf> {
+        let modules: Vec<Vec<String>> = files.iter().map(|file| self.module_path(file)).collect();
+        // A file that doesn't read or parse is reported when it's checked
+        let imports: Vec<Vec<Vec<String>>> = files
+            .iter()
+            .map(|file| match revisions.get(file).map(|revision| &revision.parsed) {
+                Some(Some((Ok(ast), _))) => Self::imports(ast),
+                Some(None) => self.files[file].imports.clone(),
+                _ => Vec::new(),
+            })
+            .collect();
         
-        let content = std::fs::read_to_string(path)
-            .map_err(|e| Error::parser(
-                format!("Failed to read file: {}", e),
-                String::new(),
-                (0, 0).into(),
-            ))?;
+        fn visit(i: usize, modules: &[Vec<String>], imports: &[Vec<Vec<String>>], visited: &mut [bool], order: &mut Vec<usize>) {
+            if visited[i] {
+                return;
+            }
+            visited[i] = true;
+            for (j, module) in modules.iter().enumerate() {
+                let imported = imports[i]
+                    .iter()
+                    .any(|import| import.len() > module.len() && import.starts_with(module));
+                if j != i && imported {
+                    visit(j, modules, imports, visited, order);
+                }
+            }
+            order.push(i);
+        }
         
-        let mut hasher = DefaultHasher::new();
-        content.hash(&mut hasher);
-        Ok(hasher.finish())
+        let mut visited = vec![false; files.len()];
+        let mut order = Vec::new();
+        for i in 0..files.len() {
+            visit(i, &modules, &imports, &mut visited, &mut order);
+        }
+        order.into_iter().map(|i| files[i].clone()).collect()
+    }
+    
+    fn generic_signature(generics: &[String], ty: &Type) -> String {
+        if generics.is_empty() {
+            ty.to_string()
+        } else {
+            format!("<{}> {}", generics.join(", "), ty)
+        }
+    }
+    
+    fn module_path(&self, file: &Path) -> Vec<String> {
+        let root = self.root.as_deref().or_else(|| file.parent()).unwrap_or(Path::new(""));
+        ModuleResolver::module_path(root, file)
+    }
+    
+    /// Files with a `use` of something inside `file`'s module
+    fn dependents_of(&self, file: &Path) -> Vec<PathBuf> {
+        let Some(module_path) = self.files.get(file).map(|cache| &cache.module_path) else {
+            return Vec::new();
+        };
+        
+        let mut dependents: Vec<PathBuf> = self
+            .files
+            .iter()
+            .filter(|(path, cache)| {
+                path.as_path() != file
+                    && cache
+                        .imports
+                        .iter()
+                        .any(|import| import.len() > module_path.len() && import.starts_with(module_path))
+            })
+            .map(|(path, _)| path.clone())
+            .collect();
+        dependents.sort();
+        dependents
+    }
+}
+
+impl Default for IncrementalCompiler {
+    fn default() -> Self {
+        Self::new()
     }
 }
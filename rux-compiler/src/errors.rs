@@ -1,4 +1,5 @@
-use miette::{Diagnostic, NamedSource, SourceSpan};
+use miette::{Diagnostic, SourceSpan};
+use serde_json::{json, Value};
 use thiserror::Error;
 
 #[derive(Debug, Error, Diagnostic)]
@@ -8,6 +9,7 @@ pub enum Error {
     #[diagnostic(code(rux::lexer))]
     Lexer {
         message: String,
+        #[source_code]
         source_code: String,
         #[label("here")]
         span: SourceSpan,
@@ -17,15 +19,56 @@ pub enum Error {
     #[diagnostic(code(rux::parser))]
     Parser {
         message: String,
+        #[source_code]
         source_code: String,
         #[label("here")]
         span: SourceSpan,
+        #[help]
+        help: Option<String>,
     },
     
     #[error("Type error: {message}")]
     #[diagnostic(code(rux::type_check))]
     Type {
         message: String,
+        #[source_code]
+        source_code: String,
+        #[label("here")]
+        span: SourceSpan,
+    },
+    
+    #[error("Resolution error: {message}")]
+    #[diagnostic(code(rux::resolve))]
+    Resolve {
+        message: String,
+        #[source_code]
+        source_code: String,
+        #[label("here")]
+        span: SourceSpan,
+    },
+    
+    /// A closing tag naming a different element than the one it closes
+    #[error("Parser error: {message}")]
+    #[diagnostic(code(rux::parser))]
+    MismatchedTag {
+        message: String,
+        #[source_code]
+        source_code: String,
+        #[label("closed here")]
+        span: SourceSpan,
+        #[label("opened here")]
+        open_span: SourceSpan,
+        #[help]
+        help: String,
+    },
+    
+    /// A bug in the compiler itself, e.g. generated Rust that doesn't
+    /// parse. The source is the generated code, not the user's.
+    #[error("Internal compiler error: {message}")]
+    #[diagnostic(code(rux::internal))]
+    Internal {
+        message: String,
+        #[source_code]
         source_code: String,
         #[label("here")]
         span: SourceSpan,
@@ -48,7 +91,16 @@ impl Error {
             message: message.into(),
             source_code: source.into(),
             span,
+            help: None,
+        }
+    }
+    
+    /// Adds a suggestion to a parser error; other errors are unchanged
+    pub fn with_help(mut self, suggestion: impl Into<String>) -> Self {
+        if let Error::Parser { help, .. } = &mut self {
+            *help = Some(suggestion.into());
         }
+        self
     }
     
     pub fn type_error(message: impl Into<String>, source: impl Into<String>, span: SourceSpan) -> Self {
@@ -58,4 +110,133 @@ impl Error {
             span,
         }
     }
+    
+    pub fn resolve(message: impl Into<String>, source: impl Into<String>, span: SourceSpan) -> Self {
+        Error::Resolve {
+            message: message.into(),
+            source_code: source.into(),
+            span,
+        }
+    }
+    
+    /// `</closing>` at `span` ends the element opened as `<tag>` at `open_span`
+    pub fn mismatched_tag(
+        tag: &str,
+        closing: &str,
+        source: impl Into<String>,
+        open_span: SourceSpan,
+        span: SourceSpan,
+    ) -> Self {
+        Error::MismatchedTag {
+            message: format!("Mismatched closing tag </{}>", closing),
+            source_code: source.into(),
+            span,
+            open_span,
+            help: format!("<{}> is closed with </{}>", tag, tag),
+        }
+    }
+    
+    pub fn internal(message: impl Into<String>, source: impl Into<String>, span: SourceSpan) -> Self {
+        Error::Internal {
+            message: message.into(),
+            source_code: source.into(),
+            span,
+        }
+    }
+    
+    /// Diagnostic code, matching the `code(...)` reported through miette
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::Lexer { .. } => "rux::lexer",
+            Error::Parser { .. } | Error::MismatchedTag { .. } => "rux::parser",
+            Error::Type { .. } => "rux::type_check",
+            Error::Resolve { .. } => "rux::resolve",
+            Error::Internal { .. } => "rux::internal",
+        }
+    }
+    
+    pub fn message(&self) -> &str {
+        match self {
+            Error::Lexer { message, .. }
+            | Error::Parser { message, .. }
+            | Error::MismatchedTag { message, .. }
+            | Error::Type { message, .. }
+            | Error::Resolve { message, .. }
+            | Error::Internal { message, .. } => message,
+        }
+    }
+    
+    pub fn span(&self) -> SourceSpan {
+        match self {
+            Error::Lexer { span, .. }
+            | Error::Parser { span, .. }
+            | Error::MismatchedTag { span, .. }
+            | Error::Type { span, .. }
+            | Error::Resolve { span, .. }
+            | Error::Internal { span, .. } => *span,
+        }
+    }
+    
+    pub fn source_code(&self) -> &str {
+        match self {
+            Error::Lexer { source_code, .. }
+            | Error::Parser { source_code, .. }
+            | Error::MismatchedTag { source_code, .. }
+            | Error::Type { source_code, .. }
+            | Error::Resolve { source_code, .. }
+            | Error::Internal { source_code, .. } => source_code,
+        }
+    }
+    
+    /// Attaches the source the error was reported against, unless the pass
+    /// that raised it already did
+    pub fn with_source(mut self, source: &str) -> Self {
+        match &mut self {
+            Error::Lexer { source_code, .. }
+            | Error::Parser { source_code, .. }
+            | Error::MismatchedTag { source_code, .. }
+            | Error::Type { source_code, .. }
+            | Error::Resolve { source_code, .. }
+            | Error::Internal { source_code, .. } => {
+                if source_code.is_empty() {
+                    *source_code = source.to_string();
+                }
+            }
+        }
+        self
+    }
+    
+    /// Machine-readable form of the error. `line` and `column` are derived
+    /// from the attached source and are `null` when there is none; `file` is
+    /// left for the caller to fill in.
+    pub fn to_json(&self) -> Value {
+        let span = self.span();
+        let position = line_column(self.source_code(), span.offset());
+        json!({
+            "severity": "error",
+            "code": self.code(),
+            "message": self.message(),
+            "file": Value::Null,
+            "span": {
+                "start": span.offset(),
+                "end": span.offset() + span.len(),
+            },
+            "line": position.map(|(line, _)| line),
+            "column": position.map(|(_, column)| column),
+        })
+    }
+}
+
+/// 1-based line and column of a byte offset, counting columns in characters
+/// like the lexer does
+fn line_column(source: &str, offset: usize) -> Option<(usize, usize)> {
+    if source.is_empty() || offset > source.len() {
+        return None;
+    }
+    
+    let before = source.get(..offset)?;
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let column = before[line_start..].chars().count() + 1;
+    Some((line, column))
 }
@@ -32,6 +32,8 @@ pub enum Token {
     Await,
     As,
     While,
+    Loop,
+    Break,
     
     // Literals
     String(String),
@@ -69,6 +71,7 @@ pub enum Token {
     FatArrow,    // =>
     Dot,         // .
     DotDot,      // ..
+    DotDotEq,    // ..=
     DotDotDot,   // ...
     Colon,       // :
     ColonColon,  // ::
@@ -95,8 +98,16 @@ pub enum Token {
     JSXOpenTag(String),
     JSXCloseTag(String),
     JSXSelfClose, // />
+    JSXText(String),
     
     // Special
+    /// Source the lexer couldn't read, from `tokenize_recovering`
+    Error,
+    /// `///` or `/** */` comment with the text after the marker. Block
+    /// comments keep one line per source line, leading `*`s removed.
+    DocComment(String),
+    /// `//!` or `/*! */` comment documenting the enclosing item
+    InnerDocComment(String),
     Eof,
     Newline,
     Whitespace,
@@ -120,17 +131,54 @@ impl Span {
         }
     }
     
+    /// Smallest span covering both `self` and `other`. Line and column are
+    /// taken from whichever span starts first.
+    pub fn join(self, other: Span) -> Span {
+        let first = if other.start < self.start { other } else { self };
+        Span {
+            start: first.start,
+            end: self.end.max(other.end),
+            line: first.line,
+            column: first.column,
+        }
+    }
+    
     pub fn to_source_span(&self) -> miette::SourceSpan {
         (self.start, self.end - self.start).into()
     }
 }
 
+/// Hashes nothing, so hashing an AST node covers its content but not where
+/// it is in the file: code that only moved keeps its hash
+impl std::hash::Hash for Span {
+    fn hash<H: std::hash::Hasher>(&self, _state: &mut H) {}
+}
+
 #[derive(Debug, Clone)]
 pub struct TokenWithSpan {
     pub token: Token,
     pub span: Span,
 }
 
+/// Lexing context. JSX needs different rules depending on where we are
+/// inside an element, so the lexer keeps a stack of these.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum LexMode {
+    /// Ordinary Rust-like code. `depth` counts open braces so we know when a
+    /// `{...}` embedded in JSX is finished.
+    Code { depth: usize },
+    /// Right after `<` of an opening tag, expecting the tag name.
+    OpenTagName,
+    /// Right after `<` of a closing tag, expecting `/name`.
+    CloseTagName,
+    /// After the name of a closing tag, expecting `>`.
+    CloseTagEnd,
+    /// Inside an opening tag, lexing attributes.
+    Tag,
+    /// Between an opening and closing tag, lexing text and children.
+    Children,
+}
+
 pub struct Lexer<'a> {
     source: &'a str,
     chars: Peekable<Chars<'a>>,
@@ -140,6 +188,8 @@ pub struct Lexer<'a> {
     start: usize,
     start_line: usize,
     start_column: usize,
+    modes: Vec<LexMode>,
+    last_token: Option<Token>,
 }
 
 impl<'a> Lexer<'a> {
@@ -153,14 +203,42 @@ impl<'a> Lexer<'a> {
             start: 0,
             start_line: 1,
             start_column: 1,
+            modes: vec![LexMode::Code { depth: 0 }],
+            last_token: None,
         }
     }
     
     pub fn tokenize(&mut self) -> Result<Vec<TokenWithSpan>> {
+        let (tokens, mut errors) = self.tokenize_recovering();
+        if errors.is_empty() {
+            Ok(tokens)
+        } else {
+            Err(errors.remove(0))
+        }
+    }
+    
+    /// Tokenizes the whole source even when parts of it are invalid. Each
+    /// bad stretch becomes a `Token::Error` in the stream and its error is
+    /// returned alongside, so tooling still sees every later token.
+    pub fn tokenize_recovering(&mut self) -> (Vec<TokenWithSpan>, Vec<Error>) {
         let mut tokens = Vec::new();
+        let mut errors = Vec::new();
         
         loop {
-            let token = self.next_token()?;
+            let before = self.current;
+            let token = match self.next_token() {
+                Ok(token) => token,
+                Err(e) => {
+                    errors.push(e);
+                    // An error that consumed nothing would repeat forever
+                    if self.current == before && self.advance().is_none() {
+                        let span = Span::new(self.current, self.current, self.line, self.column);
+                        tokens.push(TokenWithSpan { token: Token::Eof, span });
+                        break;
+                    }
+                    Token::Error
+                }
+            };
             let span = Span::new(
                 self.start,
                 self.current,
@@ -177,16 +255,46 @@ impl<'a> Lexer<'a> {
                     // Skip whitespace and newlines in token stream
                     continue;
                 }
+                // Comments don't change how a following `<` reads
+                Token::DocComment(_) | Token::InnerDocComment(_) => {
+                    tokens.push(TokenWithSpan { token, span });
+                }
                 _ => {
+                    self.last_token = Some(token.clone());
                     tokens.push(TokenWithSpan { token, span });
                 }
             }
         }
         
-        Ok(tokens)
+        (tokens, errors)
+    }
+    
+    fn mode(&self) -> LexMode {
+        *self.modes.last().expect("lexer mode stack is never empty")
+    }
+    
+    fn set_mode(&mut self, mode: LexMode) {
+        *self.modes.last_mut().expect("lexer mode stack is never empty") = mode;
     }
     
     fn next_token(&mut self) -> Result<Token> {
+        match self.mode() {
+            LexMode::Code { .. } => self.next_code_token(),
+            LexMode::OpenTagName => self.jsx_open_tag_name(),
+            LexMode::CloseTagName => self.jsx_close_tag_name(),
+            LexMode::CloseTagEnd => self.jsx_close_tag_end(),
+            LexMode::Tag => self.next_tag_token(),
+            LexMode::Children => self.next_children_token(),
+        }
+    }
+    
+    fn begin_token(&mut self) {
+        self.start = self.current;
+        self.start_line = self.line;
+        self.start_column = self.column;
+    }
+    
+    fn next_code_token(&mut self) -> Result<Token> {
         self.skip_whitespace();
         self.start = self.current;
         self.start_line = self.line;
@@ -209,8 +317,24 @@ impl<'a> Lexer<'a> {
             // Single character tokens
             '(' => Ok(Token::LParen),
             ')' => Ok(Token::RParen),
-            '{' => Ok(Token::LBrace),
-            '}' => Ok(Token::RBrace),
+            '{' => {
+                if let LexMode::Code { depth } = self.mode() {
+                    self.set_mode(LexMode::Code { depth: depth + 1 });
+                }
+                Ok(Token::LBrace)
+            }
+            '}' => {
+                if let LexMode::Code { depth } = self.mode() {
+                    let depth = depth.saturating_sub(1);
+                    if depth == 0 && self.modes.len() > 1 {
+                        // End of a `{...}` embedded in JSX
+                        self.modes.pop();
+                    } else {
+                        self.set_mode(LexMode::Code { depth });
+                    }
+                }
+                Ok(Token::RBrace)
+            }
             '[' => Ok(Token::LBracket),
             ']' => Ok(Token::RBracket),
             ',' => Ok(Token::Comma),
@@ -218,7 +342,9 @@ impl<'a> Lexer<'a> {
             '@' => Ok(Token::At),
             '#' => Ok(Token::Hash),
             '$' => Ok(Token::Dollar),
-            '_' => Ok(Token::Underscore),
+            '_' if !matches!(self.peek(), Some(c) if c.is_alphanumeric() || c == '_') => {
+                Ok(Token::Underscore)
+            }
             
             // Operators that might be multi-character
             '+' => {
@@ -248,13 +374,15 @@ impl<'a> Lexer<'a> {
                 if self.match_char('=') {
                     Ok(Token::SlashEq)
                 } else if self.match_char('/') {
-                    // Line comment
-                    self.skip_line_comment();
-                    self.next_token()
+                    match self.line_comment() {
+                        Some(doc) => Ok(doc),
+                        None => self.next_token(),
+                    }
                 } else if self.match_char('*') {
-                    // Block comment
-                    self.skip_block_comment()?;
-                    self.next_token()
+                    match self.block_comment()? {
+                        Some(doc) => Ok(doc),
+                        None => self.next_token(),
+                    }
                 } else {
                     Ok(Token::Slash)
                 }
@@ -285,7 +413,8 @@ impl<'a> Lexer<'a> {
             '<' => {
                 // Check if this is JSX
                 if self.is_jsx_context() {
-                    self.jsx_element()
+                    self.modes.push(LexMode::OpenTagName);
+                    Ok(Token::JSXOpen)
                 } else if self.match_char('=') {
                     Ok(Token::Le)
                 } else if self.match_char('<') {
@@ -322,6 +451,8 @@ impl<'a> Lexer<'a> {
                 if self.match_char('.') {
                     if self.match_char('.') {
                         Ok(Token::DotDotDot)
+                    } else if self.match_char('=') {
+                        Ok(Token::DotDotEq)
                     } else {
                         Ok(Token::DotDot)
                     }
@@ -349,11 +480,7 @@ impl<'a> Lexer<'a> {
             }
             
             // Identifiers and keywords
-            'a'..='z' | 'A'..='Z' | '_' => {
-                self.current -= 1;
-                self.column -= 1;
-                self.identifier_or_keyword()
-            }
+            ch @ ('a'..='z' | 'A'..='Z' | '_') => self.identifier_or_keyword(ch),
             
             _ => Err(Error::lexer(
                 format!("Unexpected character: {}", ch),
@@ -399,6 +526,63 @@ impl<'a> Lexer<'a> {
         }
     }
     
+    /// Lexes the rest of a comment after `//`, returning a token if it's a
+    /// doc comment. `////` is an ordinary comment, as in Rust.
+    fn line_comment(&mut self) -> Option<Token> {
+        let inner = match self.peek() {
+            Some('/') if self.chars.clone().nth(1) != Some('/') => Some(false),
+            Some('!') => Some(true),
+            _ => None,
+        };
+        if inner.is_some() {
+            self.advance();
+        }
+        
+        let start = self.current;
+        self.skip_line_comment();
+        let text = self.source[start..self.current].trim_end().to_string();
+        inner.map(|inner| if inner { Token::InnerDocComment(text) } else { Token::DocComment(text) })
+    }
+    
+    /// Lexes the rest of a comment after `/*`, returning a token if it's a
+    /// doc comment. `/**/` and `/***` are ordinary comments.
+    fn block_comment(&mut self) -> Result<Option<Token>> {
+        let mut ahead = self.chars.clone();
+        let inner = match (ahead.next(), ahead.next()) {
+            (Some('*'), Some(next)) if next != '*' && next != '/' => Some(false),
+            (Some('!'), _) => Some(true),
+            _ => None,
+        };
+        if inner.is_some() {
+            self.advance();
+        }
+        
+        let start = self.current;
+        self.skip_block_comment()?;
+        let Some(inner) = inner else {
+            return Ok(None);
+        };
+        
+        // `skip_block_comment` stops after the closing `*/`
+        let body = &self.source[start..self.current - 2];
+        let lines: Vec<&str> = body
+            .lines()
+            .enumerate()
+            .map(|(i, line)| {
+                if i == 0 {
+                    line.trim_end()
+                } else {
+                    let line = line.trim_start();
+                    line.strip_prefix('*').unwrap_or(line).trim_end()
+                }
+            })
+            .collect();
+        let first = lines.iter().position(|line| !line.is_empty()).unwrap_or(lines.len());
+        let last = lines.iter().rposition(|line| !line.is_empty()).map_or(first, |i| i + 1);
+        let text = lines[first..last].join("\n");
+        Ok(Some(if inner { Token::InnerDocComment(text) } else { Token::DocComment(text) }))
+    }
+    
     fn skip_line_comment(&mut self) {
         while let Some(ch) = self.peek() {
             if ch == '\n' {
@@ -589,8 +773,9 @@ impl<'a> Lexer<'a> {
         Ok(Token::Number(num))
     }
     
-    fn identifier_or_keyword(&mut self) -> Result<Token> {
+    fn identifier_or_keyword(&mut self, first: char) -> Result<Token> {
         let mut ident = String::new();
+        ident.push(first);
         
         while let Some(ch) = self.peek() {
             if ch.is_alphanumeric() || ch == '_' {
@@ -627,6 +812,8 @@ impl<'a> Lexer<'a> {
             "await" => Token::Await,
             "as" => Token::As,
             "while" => Token::While,
+            "loop" => Token::Loop,
+            "break" => Token::Break,
             "true" => Token::Boolean(true),
             "false" => Token::Boolean(false),
             _ => Token::Ident(ident),
@@ -634,53 +821,192 @@ impl<'a> Lexer<'a> {
     }
     
     fn is_jsx_context(&mut self) -> bool {
-        // Simple heuristic: if next char is a letter or /, it's likely JSX
-        matches!(self.peek(), Some('a'..='z' | 'A'..='Z' | '/'))
+        // `<` starts JSX when followed by a tag name and not preceded by
+        // something that could be the left operand of a comparison or the
+        // start of a generic parameter list
+        let after_operand = matches!(
+            self.last_token,
+            Some(
+                Token::Impl
+                    | Token::Ident(_)
+                    | Token::Number(_)
+                    | Token::String(_)
+                    | Token::Char(_)
+                    | Token::Boolean(_)
+                    | Token::RParen
+                    | Token::RBracket
+            )
+        );
+        !after_operand && matches!(self.peek(), Some('a'..='z' | 'A'..='Z'))
     }
     
-    fn jsx_element(&mut self) -> Result<Token> {
-        // This is a simplified JSX lexer
-        // Full implementation would handle JSX attributes, children, etc.
-        if self.match_char('/') {
-            // Closing tag: </tag>
-            let mut tag = String::new();
-            while let Some(ch) = self.peek() {
-                if ch.is_alphanumeric() || ch == '_' || ch == '-' {
-                    tag.push(ch);
-                    self.advance();
-                } else if ch == '>' {
+    fn jsx_name(&mut self) -> String {
+        let mut name = String::new();
+        while let Some(ch) = self.peek() {
+            if ch.is_alphanumeric() || ch == '_' || ch == '-' {
+                name.push(ch);
+                self.advance();
+            } else {
+                break;
+            }
+        }
+        name
+    }
+    
+    fn jsx_error(&self, message: &str) -> Error {
+        Error::lexer(
+            message,
+            self.source,
+            Span::new(self.start, self.current, self.start_line, self.start_column)
+                .to_source_span(),
+        )
+    }
+    
+    fn jsx_open_tag_name(&mut self) -> Result<Token> {
+        self.begin_token();
+        let tag = self.jsx_name();
+        if tag.is_empty() {
+            return Err(self.jsx_error("Expected JSX tag name"));
+        }
+        self.set_mode(LexMode::Tag);
+        Ok(Token::JSXOpenTag(tag))
+    }
+    
+    fn jsx_close_tag_name(&mut self) -> Result<Token> {
+        self.begin_token();
+        if !self.match_char('/') {
+            return Err(self.jsx_error("Invalid JSX closing tag"));
+        }
+        let tag = self.jsx_name();
+        self.set_mode(LexMode::CloseTagEnd);
+        Ok(Token::JSXCloseTag(tag))
+    }
+    
+    fn jsx_close_tag_end(&mut self) -> Result<Token> {
+        self.skip_whitespace();
+        self.begin_token();
+        if !self.match_char('>') {
+            return Err(self.jsx_error("Invalid JSX closing tag"));
+        }
+        // The whole element is finished
+        self.modes.pop();
+        Ok(Token::JSXClose)
+    }
+    
+    fn next_tag_token(&mut self) -> Result<Token> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('>') => {
+                self.begin_token();
+                self.advance();
+                self.set_mode(LexMode::Children);
+                Ok(Token::JSXClose)
+            }
+            Some('/') if self.chars.clone().nth(1) == Some('>') => {
+                self.begin_token();
+                self.advance();
+                self.advance();
+                self.modes.pop();
+                Ok(Token::JSXSelfClose)
+            }
+            Some('{') => {
+                self.begin_token();
+                self.advance();
+                self.modes.push(LexMode::Code { depth: 1 });
+                Ok(Token::LBrace)
+            }
+            Some(ch) if ch.is_alphabetic() || ch == '_' => {
+                // Attribute names may contain dashes (`data-id`, `aria-label`)
+                // and colon-separated parts (`bind:value`, `onClick:prevent:stop`)
+                self.begin_token();
+                let mut name = self.jsx_name();
+                while self.peek() == Some(':') && self.chars.clone().nth(1).is_some_and(char::is_alphabetic) {
                     self.advance();
-                    return Ok(Token::JSXCloseTag(tag));
+                    name.push(':');
+                    name.push_str(&self.jsx_name());
+                }
+                Ok(Token::Ident(name))
+            }
+            _ => self.next_code_token(),
+        }
+    }
+    
+    fn next_children_token(&mut self) -> Result<Token> {
+        self.begin_token();
+        match self.peek() {
+            None => Ok(Token::Eof),
+            Some('<') => {
+                self.advance();
+                if self.peek() == Some('/') {
+                    self.set_mode(LexMode::CloseTagName);
                 } else {
-                    break;
+                    self.modes.push(LexMode::OpenTagName);
                 }
+                Ok(Token::JSXOpen)
             }
-            return Err(Error::lexer(
-                "Invalid JSX closing tag",
-                self.source,
-                Span::new(self.start, self.current, self.start_line, self.start_column)
-                    .to_source_span(),
-            ));
-        } else {
-            // Opening tag: <tag or <tag/>
-            let mut tag = String::new();
-            while let Some(ch) = self.peek() {
-                if ch.is_alphanumeric() || ch == '_' || ch == '-' {
-                    tag.push(ch);
-                    self.advance();
-                } else if ch == '/' {
-                    self.advance();
-                    if self.match_char('>') {
-                        return Ok(Token::JSXSelfClose);
+            Some('{') => {
+                self.advance();
+                self.modes.push(LexMode::Code { depth: 1 });
+                Ok(Token::LBrace)
+            }
+            Some(_) => {
+                let mut text = String::new();
+                while let Some(ch) = self.peek() {
+                    if ch == '<' || ch == '{' {
+                        break;
                     }
-                } else if ch == '>' {
+                    text.push(ch);
                     self.advance();
-                    return Ok(Token::JSXOpenTag(tag));
-                } else {
-                    break;
+                }
+                match normalize_jsx_text(&text) {
+                    Some(text) => Ok(Token::JSXText(text)),
+                    None => Ok(Token::Whitespace),
                 }
             }
-            Ok(Token::JSXOpen)
         }
     }
 }
+
+/// Applies JSX whitespace rules to a run of raw child text. Whitespace next
+/// to a line break is insignificant, so indentation and blank lines vanish
+/// and the remaining lines join with a single space. Whitespace on the same
+/// line as text or an interpolation is kept, so `Count: {n} times` keeps
+/// both spaces, and any other whitespace run collapses to one space.
+/// Returns `None` when nothing significant is left.
+fn normalize_jsx_text(raw: &str) -> Option<String> {
+    let lines: Vec<&str> = raw.split('\n').collect();
+    let last = lines.len() - 1;
+    
+    let mut parts = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        let mut line = *line;
+        if i > 0 {
+            line = line.trim_start();
+        }
+        if i < last {
+            line = line.trim_end();
+        }
+        if !line.is_empty() {
+            parts.push(line);
+        }
+    }
+    
+    if parts.is_empty() {
+        return None;
+    }
+    
+    let mut text = String::new();
+    let mut in_whitespace = false;
+    for ch in parts.join(" ").chars() {
+        if ch.is_whitespace() {
+            if !in_whitespace {
+                text.push(' ');
+            }
+            in_whitespace = true;
+        } else {
+            text.push(ch);
+            in_whitespace = false;
+        }
+    }
+    Some(text)
+}
@@ -1,19 +1,27 @@
 pub mod lexer;
 pub mod parser;
 pub mod ast;
+mod pretty;
 pub mod errors;
+pub mod diagnostics;
 pub mod type_checker;
 pub mod optimizer;
 pub mod analyzer;
 pub mod driver;
+pub mod resolver;
 pub mod codegen;
 
 pub use errors::{Error, Result};
+pub use diagnostics::{Diagnostic, Severity, WarningCollector};
 pub use lexer::{Lexer, Token, TokenWithSpan};
 pub use parser::Parser;
 pub use ast::AST;
 pub use type_checker::TypeChecker;
 pub use optimizer::Optimizer;
 pub use analyzer::DependencyAnalyzer;
-pub use driver::{Compiler, IncrementalCompiler};
-pub use codegen::CodeGenerator;
+pub use driver::{AstPass, Compiler, CompilerOptions, IncrementalCompiler, PassTimings};
+pub use resolver::ModuleResolver;
+pub use codegen::{CodeGenerator, CodegenCache};
+
+/// Version of the compiler, which build caches are tied to
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -1,36 +1,81 @@
 use crate::ast::*;
+use crate::diagnostics::WarningCollector;
+use crate::driver::{AstPass, CompilerOptions};
 use crate::errors::Result;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
-pub struct Optimizer;
+pub struct Optimizer {
+    /// Which passes `optimize` applies
+    options: CompilerOptions,
+    warnings: WarningCollector,
+    /// Immutable `let` bindings in scope during constant folding, innermost
+    /// last. `None` marks a name bound to something that isn't constant,
+    /// which hides any outer constant of the same name.
+    constants: Vec<HashMap<String, Option<Literal>>>,
+    /// Bindings introduced by common subexpression elimination so far
+    hoisted: usize,
+}
 
 impl Optimizer {
     pub fn new() -> Self {
-        Self
+        Self::with_options(CompilerOptions::release())
     }
     
-    pub fn optimize(&self, ast: &mut AST) -> Result<()> {
+    /// An optimizer applying only the passes `options` enables
+    pub fn with_options(options: CompilerOptions) -> Self {
+        Self {
+            options,
+            warnings: WarningCollector::new(),
+            constants: Vec::new(),
+            hoisted: 0,
+        }
+    }
+    
+    /// Warnings reported by previous passes, leaving the collector empty
+    pub fn take_warnings(&mut self) -> WarningCollector {
+        std::mem::take(&mut self.warnings)
+    }
+    
+    pub fn optimize(&mut self, ast: &mut AST) -> Result<()> {
+        if !self.options.optimize {
+            return Ok(());
+        }
+        
         // Apply optimization passes
-        self.dead_code_elimination(ast)?;
-        self.constant_folding(ast)?;
-        // Component inlining would go here
+        if self.options.dead_code {
+            self.dead_code_elimination(ast)?;
+        }
+        if self.options.inline_components {
+            self.inline_components(ast)?;
+        }
+        if self.options.constant_folding {
+            self.constant_folding(ast)?;
+        }
+        if self.options.common_subexpressions {
+            self.common_subexpression_elimination(ast)?;
+        }
         Ok(())
     }
     
-    fn dead_code_elimination(&self, ast: &mut AST) -> Result<()> {
+    pub fn dead_code_elimination(&self, ast: &mut AST) -> Result<()> {
         // Build symbol usage graph
         let mut used_symbols = HashSet::new();
         self.collect_used_symbols(ast, &mut used_symbols);
         
         // Remove unused items
         ast.items.retain(|item| {
-            match item {
-                Item::Function(f) => used_symbols.contains(&f.name),
-                Item::Component(c) => used_symbols.contains(&c.name),
-                Item::Struct(s) => used_symbols.contains(&s.name),
-                Item::Enum(e) => used_symbols.contains(&e.name),
-                _ => true, // Keep other items
+            let name = match item {
+                Item::Function(f) => &f.name,
+                Item::Component(c) => &c.name,
+                Item::Struct(s) => &s.name,
+                Item::Enum(e) => &e.name,
+                _ => return true, // Keep other items
+            };
+            let used = used_symbols.contains(name);
+            if !used {
+                tracing::debug!(item = %name, "removed unused item");
             }
+            used
         });
         
         Ok(())
@@ -41,17 +86,92 @@ impl Optimizer {
             match item {
                 Item::Component(c) => {
                     used.insert(c.name.clone());
+                    for prop in &c.props {
+                        self.collect_symbols_from_type(&prop.param_type, used);
+                    }
                     self.collect_symbols_from_expr(&c.body, used);
                 }
                 Item::Function(f) => {
                     used.insert(f.name.clone());
+                    self.collect_symbols_from_function_signature(f, used);
                     self.collect_symbols_from_block(&f.body, used);
                 }
+                Item::Impl(i) => {
+                    used.insert(i.type_name.clone());
+                    for method in &i.items {
+                        self.collect_symbols_from_function_signature(method, used);
+                        self.collect_symbols_from_block(&method.body, used);
+                    }
+                }
                 _ => {}
             }
         }
     }
     
+    fn collect_symbols_from_function_signature(&self, function: &Function, used: &mut HashSet<String>) {
+        for param in &function.params {
+            self.collect_symbols_from_type(&param.param_type, used);
+        }
+        if let Some(ref return_type) = function.return_type {
+            self.collect_symbols_from_type(return_type, used);
+        }
+    }
+    
+    /// Types referenced by signatures keep their struct/enum definitions alive
+    fn collect_symbols_from_type(&self, ty: &Type, used: &mut HashSet<String>) {
+        match &ty.kind {
+            TypeKind::Ident(name) => {
+                used.insert(name.clone());
+            }
+            TypeKind::Path(path) => {
+                if let Some(name) = path.last() {
+                    used.insert(name.clone());
+                }
+            }
+            TypeKind::Tuple(types) => {
+                for ty in types {
+                    self.collect_symbols_from_type(ty, used);
+                }
+            }
+            TypeKind::Array(inner)
+            | TypeKind::Slice(inner)
+            | TypeKind::Option(inner)
+            | TypeKind::Range(inner)
+            | TypeKind::Dyn(inner)
+            | TypeKind::Impl(inner)
+            | TypeKind::Reference { inner, .. } => {
+                self.collect_symbols_from_type(inner, used);
+            }
+            TypeKind::Function { params, return_type } => {
+                for ty in params {
+                    self.collect_symbols_from_type(ty, used);
+                }
+                self.collect_symbols_from_type(return_type, used);
+            }
+            TypeKind::Result { ok, err } => {
+                self.collect_symbols_from_type(ok, used);
+                self.collect_symbols_from_type(err, used);
+            }
+            TypeKind::Generic { name, args } => {
+                if let Some(name) = name.rsplit("::").next() {
+                    used.insert(name.to_string());
+                }
+                for ty in args {
+                    self.collect_symbols_from_type(ty, used);
+                }
+            }
+            TypeKind::FnTrait { params, return_type, .. } => {
+                for ty in params {
+                    self.collect_symbols_from_type(ty, used);
+                }
+                if let Some(return_type) = return_type {
+                    self.collect_symbols_from_type(return_type, used);
+                }
+            }
+            TypeKind::Unit => {}
+        }
+    }
+    
     fn collect_symbols_from_expr(&self, expr: &Expr, used: &mut HashSet<String>) {
         match expr {
             Expr::Variable(name, _) => {
@@ -98,13 +218,13 @@ impl Optimizer {
             Stmt::Let { value, .. } => {
                 self.collect_symbols_from_expr(value, used);
             }
-            Stmt::Expr(expr) => {
+            Stmt::Expr(expr) | Stmt::Tail(expr) => {
                 self.collect_symbols_from_expr(expr, used);
             }
-            Stmt::Return(Some(expr), _) => {
+            Stmt::Return(Some(expr), _) | Stmt::Break(Some(expr), _) => {
                 self.collect_symbols_from_expr(expr, used);
             }
-            Stmt::Return(None, _) => {
+            Stmt::Return(None, _) | Stmt::Break(None, _) => {
                 // No symbols for return without value
             }
             Stmt::If { condition, then, else_, .. } => {
@@ -157,16 +277,16 @@ impl Optimizer {
     }
     
     fn collect_symbols_from_jsx_prop(&self, prop: &JSXProp, used: &mut HashSet<String>) {
-        match &prop.value {
-            JSXPropValue::Expr(expr) => {
-                self.collect_symbols_from_expr(expr, used);
-            }
-            _ => {}
+        if let JSXPropValue::Expr(expr) = &prop.value {
+            self.collect_symbols_from_expr(expr, used);
         }
     }
     
-    fn constant_folding(&self, ast: &mut AST) -> Result<()> {
+    /// Folds constant expressions. Immutable `let` bindings of a literal
+    /// are propagated to their uses first, so `let x = 2; x + 3` folds too.
+    pub fn constant_folding(&mut self, ast: &mut AST) -> Result<()> {
         for item in &mut ast.items {
+            self.constants.clear();
             match item {
                 Item::Component(c) => {
                     self.fold_constants_in_expr(&mut c.body)?;
@@ -180,27 +300,38 @@ impl Optimizer {
         Ok(())
     }
     
-    fn fold_constants_in_expr(&self, expr: &mut Expr) -> Result<()> {
+    fn fold_constants_in_expr(&mut self, expr: &mut Expr) -> Result<()> {
         match expr {
-            Expr::Binary { left, op, right, .. } => {
+            Expr::Variable(name, span) => {
+                if let Some(lit) = self.constant(name) {
+                    *expr = Expr::Literal(lit, *span);
+                }
+            }
+            Expr::Binary { left, op, right, span } => {
                 self.fold_constants_in_expr(left)?;
                 self.fold_constants_in_expr(right)?;
                 
                 if let (Expr::Literal(lit1, _), Expr::Literal(lit2, _)) = (&**left, &**right) {
                     if let Some(result) = self.evaluate_binary(lit1, op, lit2) {
+                        tracing::debug!(line = span.line, column = span.column, "folded constant expression");
                         *expr = result;
+                    } else if matches!(op, BinaryOp::Div | BinaryOp::Rem)
+                        && (matches!(lit2, Literal::Integer(0))
+                            || matches!(lit2, Literal::Number(n) if *n == 0.0))
+                    {
+                        self.warnings.warn("Constant division by zero", *span);
                     }
                 }
             }
             Expr::Unary { expr: inner, op, .. } => {
                 self.fold_constants_in_expr(inner)?;
-                if let Expr::Literal(lit, span) = &**inner {
+                if let Expr::Literal(lit, _span) = &**inner {
                     if let Some(result) = self.evaluate_unary(lit, op) {
                         *expr = result;
                     }
                 }
             }
-            Expr::Block(block, _) => {
+            Expr::Block(block, _) | Expr::Loop(block, _) => {
                 self.fold_constants_in_block(block)?;
             }
             Expr::If { condition, then, else_, .. } => {
@@ -215,25 +346,70 @@ impl Optimizer {
         Ok(())
     }
     
-    fn fold_constants_in_block(&self, block: &mut Block) -> Result<()> {
-        for stmt in &mut block.statements {
-            self.fold_constants_in_stmt(stmt)?;
+    fn fold_constants_in_block(&mut self, block: &mut Block) -> Result<()> {
+        self.constants.push(HashMap::new());
+        let result = block
+            .statements
+            .iter_mut()
+            .try_for_each(|stmt| self.fold_constants_in_stmt(stmt));
+        self.constants.pop();
+        result
+    }
+    
+    /// The literal `name` is bound to, if it is a propagatable constant
+    fn constant(&self, name: &str) -> Option<Literal> {
+        self.constants
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name))
+            .cloned()
+            .flatten()
+    }
+    
+    fn bind_constant(&mut self, name: &str, value: Option<Literal>) {
+        if self.constants.is_empty() {
+            self.constants.push(HashMap::new());
+        }
+        if let Some(scope) = self.constants.last_mut() {
+            scope.insert(name.to_string(), value);
+        }
+    }
+    
+    /// Hides outer constants behind the names a pattern binds
+    fn shadow_pattern(&mut self, pattern: &Pattern) {
+        match pattern {
+            Pattern::Ident(name, _) => self.bind_constant(name, None),
+            Pattern::Tuple(patterns, _) | Pattern::TupleStruct { elems: patterns, .. } => {
+                for pattern in patterns {
+                    self.shadow_pattern(pattern);
+                }
+            }
+            Pattern::Struct { fields, .. } => {
+                for (_, pattern) in fields {
+                    self.shadow_pattern(pattern);
+                }
+            }
+            Pattern::Literal(..) | Pattern::Path(..) | Pattern::Wildcard(_) => {}
         }
-        Ok(())
     }
     
-    fn fold_constants_in_stmt(&self, stmt: &mut Stmt) -> Result<()> {
+    fn fold_constants_in_stmt(&mut self, stmt: &mut Stmt) -> Result<()> {
         match stmt {
-            Stmt::Let { value, .. } => {
+            Stmt::Let { name, value, mutable, .. } => {
                 self.fold_constants_in_expr(value)?;
+                let constant = match value {
+                    Expr::Literal(lit, _) if !*mutable => Some(lit.clone()),
+                    _ => None,
+                };
+                self.bind_constant(name, constant);
             }
-            Stmt::Expr(expr) => {
+            Stmt::Expr(expr) | Stmt::Tail(expr) => {
                 self.fold_constants_in_expr(expr)?;
             }
-            Stmt::Return(Some(expr), _) => {
+            Stmt::Return(Some(expr), _) | Stmt::Break(Some(expr), _) => {
                 self.fold_constants_in_expr(expr)?;
             }
-            Stmt::Return(None, _) => {
+            Stmt::Return(None, _) | Stmt::Break(None, _) => {
                 // No constants to fold for return without value
             }
             Stmt::If { condition, then, else_, .. } => {
@@ -243,9 +419,13 @@ impl Optimizer {
                     self.fold_constants_in_stmt(else_stmt)?;
                 }
             }
-            Stmt::For { iter, body, .. } => {
+            Stmt::For { var, iter, body, .. } => {
                 self.fold_constants_in_expr(iter)?;
-                self.fold_constants_in_stmt(body)?;
+                self.constants.push(HashMap::new());
+                self.bind_constant(var, None);
+                let result = self.fold_constants_in_stmt(body);
+                self.constants.pop();
+                result?;
             }
             Stmt::While { condition, body, .. } => {
                 self.fold_constants_in_expr(condition)?;
@@ -254,7 +434,11 @@ impl Optimizer {
             Stmt::Match { expr, arms, .. } => {
                 self.fold_constants_in_expr(expr)?;
                 for arm in arms {
-                    self.fold_constants_in_expr(&mut arm.body)?;
+                    self.constants.push(HashMap::new());
+                    self.shadow_pattern(&arm.pattern);
+                    let result = self.fold_constants_in_expr(&mut arm.body);
+                    self.constants.pop();
+                    result?;
                 }
             }
             Stmt::Block(block) => {
@@ -266,6 +450,20 @@ impl Optimizer {
     
     fn evaluate_binary(&self, left: &Literal, op: &BinaryOp, right: &Literal) -> Option<Expr> {
         match (left, op, right) {
+            // Integer arithmetic that would overflow is left for the
+            // compiler to report
+            (Literal::Integer(l), BinaryOp::Add, Literal::Integer(r)) => {
+                l.checked_add(*r).map(|n| Expr::Literal(Literal::Integer(n), crate::lexer::Span::new(0, 0, 0, 0)))
+            }
+            (Literal::Integer(l), BinaryOp::Sub, Literal::Integer(r)) => {
+                l.checked_sub(*r).map(|n| Expr::Literal(Literal::Integer(n), crate::lexer::Span::new(0, 0, 0, 0)))
+            }
+            (Literal::Integer(l), BinaryOp::Mul, Literal::Integer(r)) => {
+                l.checked_mul(*r).map(|n| Expr::Literal(Literal::Integer(n), crate::lexer::Span::new(0, 0, 0, 0)))
+            }
+            (Literal::Integer(l), BinaryOp::Div, Literal::Integer(r)) => {
+                l.checked_div(*r).map(|n| Expr::Literal(Literal::Integer(n), crate::lexer::Span::new(0, 0, 0, 0)))
+            }
             (Literal::Number(l), BinaryOp::Add, Literal::Number(r)) => {
                 Some(Expr::Literal(Literal::Number(l + r), crate::lexer::Span::new(0, 0, 0, 0)))
             }
@@ -294,6 +492,9 @@ impl Optimizer {
     
     fn evaluate_unary(&self, lit: &Literal, op: &UnaryOp) -> Option<Expr> {
         match (lit, op) {
+            (Literal::Integer(n), UnaryOp::Neg) => {
+                n.checked_neg().map(|n| Expr::Literal(Literal::Integer(n), crate::lexer::Span::new(0, 0, 0, 0)))
+            }
             (Literal::Number(n), UnaryOp::Neg) => {
                 Some(Expr::Literal(Literal::Number(-n), crate::lexer::Span::new(0, 0, 0, 0)))
             }
@@ -303,4 +504,621 @@ impl Optimizer {
             _ => None,
         }
     }
+    
+    /// Hoists pure expressions a block computes more than once into a
+    /// `let`, so two uses of `a * b` become `let __cse_0 = (a * b);` and two
+    /// reads of `__cse_0`. Only operators over variables and literals count
+    /// as pure; calls, JSX and conditionally evaluated code are left alone.
+    pub fn common_subexpression_elimination(&mut self, ast: &mut AST) -> Result<()> {
+        for item in &mut ast.items {
+            match item {
+                Item::Component(c) => self.cse_in_expr(&mut c.body),
+                Item::Function(f) => self.cse_in_block(&mut f.body),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+    
+    fn cse_in_expr(&mut self, expr: &mut Expr) {
+        match expr {
+            Expr::Block(block, _) | Expr::Loop(block, _) => self.cse_in_block(block),
+            Expr::If { condition, then, else_, .. } => {
+                self.cse_in_expr(condition);
+                self.cse_in_expr(then);
+                if let Some(else_expr) = else_ {
+                    self.cse_in_expr(else_expr);
+                }
+            }
+            Expr::Match { expr, arms, .. } => {
+                self.cse_in_expr(expr);
+                for arm in arms {
+                    self.cse_in_expr(&mut arm.body);
+                }
+            }
+            Expr::Lambda { body, .. } => self.cse_in_expr(body),
+            _ => {}
+        }
+    }
+    
+    fn cse_in_stmt(&mut self, stmt: &mut Stmt) {
+        match stmt {
+            Stmt::Let { value: expr, .. }
+            | Stmt::Expr(expr)
+            | Stmt::Tail(expr)
+            | Stmt::Return(Some(expr), _)
+            | Stmt::Break(Some(expr), _) => {
+                self.cse_in_expr(expr);
+            }
+            Stmt::Return(None, _) | Stmt::Break(None, _) => {}
+            Stmt::If { then, else_, .. } => {
+                self.cse_in_stmt(then);
+                if let Some(else_stmt) = else_ {
+                    self.cse_in_stmt(else_stmt);
+                }
+            }
+            Stmt::For { body, .. } | Stmt::While { body, .. } => self.cse_in_stmt(body),
+            Stmt::Match { arms, .. } => {
+                for arm in arms {
+                    self.cse_in_expr(&mut arm.body);
+                }
+            }
+            Stmt::Block(block) => self.cse_in_block(block),
+        }
+    }
+    
+    fn cse_in_block(&mut self, block: &mut Block) {
+        // Nested blocks are their own scopes, so they're handled first
+        for stmt in &mut block.statements {
+            self.cse_in_stmt(stmt);
+        }
+        
+        while let Some((key, first)) = Self::repeated_subexpression(block) {
+            let name = format!("__cse_{}", self.hoisted);
+            self.hoisted += 1;
+            tracing::debug!(binding = %name, "hoisted repeated expression");
+            
+            let mut value = None;
+            for stmt in &mut block.statements[first..] {
+                for root in stmt_roots(stmt) {
+                    visit_unconditional(root, &mut |expr| {
+                        if pure_key(expr).as_deref() != Some(key.as_str()) {
+                            return true;
+                        }
+                        let span = expr_span(expr);
+                        let original = std::mem::replace(expr, Expr::Variable(name.clone(), span));
+                        value.get_or_insert(original);
+                        false
+                    });
+                }
+            }
+            
+            let Some(value) = value else { break };
+            let span = expr_span(&value);
+            block.statements.insert(first, Stmt::Let {
+                name,
+                value,
+                mutable: false,
+                span,
+            });
+        }
+    }
+    
+    /// The largest pure compound expression that occurs more than once in
+    /// the block's statements, with the index of the first statement using
+    /// it. Candidates whose variables are rebound by a `let` between their
+    /// uses would change meaning when hoisted, so they're skipped.
+    fn repeated_subexpression(block: &mut Block) -> Option<(String, usize)> {
+        // key -> (uses, first statement, last statement, variables)
+        let mut occurrences: HashMap<String, (usize, usize, usize, Vec<String>)> = HashMap::new();
+        for (i, stmt) in block.statements.iter_mut().enumerate() {
+            for root in stmt_roots(stmt) {
+                visit_unconditional(root, &mut |expr| {
+                    if matches!(expr, Expr::Binary { .. } | Expr::Unary { .. }) {
+                        if let Some(key) = pure_key(expr) {
+                            let entry = occurrences.entry(key).or_insert_with(|| {
+                                let mut vars = Vec::new();
+                                collect_variables(expr, &mut vars);
+                                (0, i, i, vars)
+                            });
+                            entry.0 += 1;
+                            entry.2 = i;
+                        }
+                    }
+                    true
+                });
+            }
+        }
+        
+        occurrences
+            .into_iter()
+            .filter(|(_, (uses, first, last, vars))| {
+                *uses > 1
+                    && !block.statements[*first..=*last]
+                        .iter()
+                        .any(|stmt| matches!(stmt, Stmt::Let { name, .. } if vars.contains(name)))
+            })
+            .max_by(|(a, (_, a_first, ..)), (b, (_, b_first, ..))| {
+                a.len().cmp(&b.len()).then(b_first.cmp(a_first)).then(b.cmp(a))
+            })
+            .map(|(key, (_, first, ..))| (key, first))
+    }
+    
+    /// Replaces each component rendered in exactly one place with its body,
+    /// props substituted, and drops its definition. For now only components
+    /// whose body is a single element built from simple expressions qualify;
+    /// generic, recursive and prop-defaulted components are left alone, and
+    /// so are usages that pass children or unknown props.
+    pub fn inline_components(&mut self, ast: &mut AST) -> Result<()> {
+        let mut skipped = HashSet::new();
+        loop {
+            let candidate = ast.items.iter().enumerate().find_map(|(i, item)| match item {
+                Item::Component(c) if !skipped.contains(&c.name) => Some((i, c.clone())),
+                _ => None,
+            });
+            let Some((index, component)) = candidate else { break };
+            skipped.insert(component.name.clone());
+            let Some(mut inliner) = ComponentInliner::new(&component) else { continue };
+            
+            // A component that renders itself is never inlined
+            inliner.visit_expr(&mut component.body.clone());
+            if inliner.uses > 0 {
+                continue;
+            }
+            
+            // Count first, so nothing is expanded unless the single use can be
+            inliner.visit_items_except(&mut ast.items, index);
+            if inliner.uses != 1 || inliner.inlined != 1 {
+                continue;
+            }
+            
+            inliner.expand = true;
+            inliner.visit_items_except(&mut ast.items, index);
+            ast.items.remove(index);
+            tracing::debug!(component = %component.name, "inlined component");
+        }
+        Ok(())
+    }
+}
+
+impl AstPass for Optimizer {
+    fn run(&mut self, ast: &mut AST) -> Result<()> {
+        self.optimize(ast)
+    }
+    
+    fn name(&self) -> &'static str {
+        "optimize"
+    }
+    
+    fn take_warnings(&mut self) -> WarningCollector {
+        Optimizer::take_warnings(self)
+    }
+}
+
+/// Finds the usages of one component and counts those that can be
+/// inlined, expanding them once `expand` is set
+struct ComponentInliner<'a> {
+    component: &'a Component,
+    body: &'a JSXElement,
+    expand: bool,
+    /// Every reference to the component, inlinable or not
+    uses: usize,
+    inlined: usize,
+}
+
+impl<'a> ComponentInliner<'a> {
+    /// None if the component's body can't be inlined anywhere
+    fn new(component: &'a Component) -> Option<Self> {
+        let body = inlinable_body(component)?;
+        Some(Self { component, body, expand: false, uses: 0, inlined: 0 })
+    }
+    
+    /// Visits every item but the component's own, at `index`
+    fn visit_items_except(&mut self, items: &mut [Item], index: usize) {
+        for (i, item) in items.iter_mut().enumerate() {
+            if i != index {
+                self.visit_item(item);
+            }
+        }
+    }
+    
+    fn visit_item(&mut self, item: &mut Item) {
+        match item {
+            Item::Component(c) => self.visit_expr(&mut c.body),
+            Item::Function(f) => self.visit_block(&mut f.body),
+            Item::Impl(i) => {
+                for function in &mut i.items {
+                    self.visit_block(&mut function.body);
+                }
+            }
+            Item::Mod(m) => {
+                for item in &mut m.items {
+                    self.visit_item(item);
+                }
+            }
+            Item::Use(u) if u.path.last() == Some(&self.component.name) => self.uses += 1,
+            _ => {}
+        }
+    }
+    
+    fn visit_block(&mut self, block: &mut Block) {
+        for stmt in &mut block.statements {
+            self.visit_stmt(stmt);
+        }
+    }
+    
+    fn visit_stmt(&mut self, stmt: &mut Stmt) {
+        match stmt {
+            Stmt::Let { value: expr, .. }
+            | Stmt::Expr(expr)
+            | Stmt::Tail(expr)
+            | Stmt::Return(Some(expr), _)
+            | Stmt::Break(Some(expr), _) => {
+                self.visit_expr(expr);
+            }
+            Stmt::Return(None, _) | Stmt::Break(None, _) => {}
+            Stmt::If { condition, then, else_, .. } => {
+                self.visit_expr(condition);
+                self.visit_stmt(then);
+                if let Some(else_stmt) = else_ {
+                    self.visit_stmt(else_stmt);
+                }
+            }
+            Stmt::For { iter: expr, body, .. } | Stmt::While { condition: expr, body, .. } => {
+                self.visit_expr(expr);
+                self.visit_stmt(body);
+            }
+            Stmt::Match { expr, arms, .. } => {
+                self.visit_expr(expr);
+                self.visit_arms(arms);
+            }
+            Stmt::Block(block) => self.visit_block(block),
+        }
+    }
+    
+    fn visit_arms(&mut self, arms: &mut [MatchArm]) {
+        for arm in arms {
+            if let Some(guard) = &mut arm.guard {
+                self.visit_expr(guard);
+            }
+            self.visit_expr(&mut arm.body);
+        }
+    }
+    
+    fn visit_expr(&mut self, expr: &mut Expr) {
+        match expr {
+            Expr::Variable(name, _) if *name == self.component.name => self.uses += 1,
+            Expr::Literal(..) | Expr::Variable(..) => {}
+            Expr::Binary { left, right, .. } => {
+                self.visit_expr(left);
+                self.visit_expr(right);
+            }
+            Expr::Unary { expr, .. } => self.visit_expr(expr),
+            Expr::Call { callee: receiver, args, .. } | Expr::MethodCall { receiver, args, .. } => {
+                self.visit_expr(receiver);
+                for arg in args {
+                    self.visit_expr(arg);
+                }
+            }
+            Expr::FieldAccess { object, .. } => self.visit_expr(object),
+            Expr::Index { object, index, .. } => {
+                self.visit_expr(object);
+                self.visit_expr(index);
+            }
+            Expr::JSXElement(jsx, _) => self.visit_jsx(jsx),
+            Expr::Block(block, _) | Expr::Loop(block, _) => self.visit_block(block),
+            Expr::If { condition, then, else_, .. } => {
+                self.visit_expr(condition);
+                self.visit_expr(then);
+                if let Some(else_expr) = else_ {
+                    self.visit_expr(else_expr);
+                }
+            }
+            Expr::Match { expr, arms, .. } => {
+                self.visit_expr(expr);
+                self.visit_arms(arms);
+            }
+            Expr::Lambda { body, .. } => self.visit_expr(body),
+            Expr::Tuple(items, _) | Expr::Array(items, _) => {
+                for item in items {
+                    self.visit_expr(item);
+                }
+            }
+            Expr::Range { start, end, .. } => {
+                self.visit_expr(start);
+                self.visit_expr(end);
+            }
+            Expr::Struct { fields, .. } => {
+                for (_, value) in fields {
+                    self.visit_expr(value);
+                }
+            }
+        }
+    }
+    
+    fn visit_jsx(&mut self, jsx: &mut JSXElement) {
+        let (tag, props, children) = match jsx {
+            JSXElement::SelfClosing { tag, props, .. } => (tag, props, None),
+            JSXElement::WithChildren { tag, props, children, .. } => (tag, props, Some(children)),
+        };
+        for prop in props.iter_mut() {
+            if let JSXPropValue::Expr(expr) = &mut prop.value {
+                self.visit_expr(expr);
+            }
+        }
+        for child in children.into_iter().flatten() {
+            match child {
+                JSXChild::Element(elem) => self.visit_jsx(elem),
+                JSXChild::Expr(expr) => self.visit_expr(expr),
+                JSXChild::Text(..) => {}
+            }
+        }
+        
+        if *tag == self.component.name {
+            self.uses += 1;
+            if let Some(expanded) = self.instantiate(jsx) {
+                if self.expand {
+                    *jsx = expanded;
+                }
+                self.inlined += 1;
+            }
+        }
+    }
+    
+    /// The component's body with the props passed by `usage` substituted,
+    /// or None if the usage passes children, doesn't match the props, or
+    /// passes an expression the body doesn't read exactly once, which
+    /// substituting would evaluate a different number of times
+    fn instantiate(&self, usage: &JSXElement) -> Option<JSXElement> {
+        let props = match usage {
+            JSXElement::SelfClosing { props, .. } => props,
+            JSXElement::WithChildren { props, children, .. } if children.is_empty() => props,
+            JSXElement::WithChildren { .. } => return None,
+        };
+        
+        let mut args = HashMap::new();
+        for prop in props {
+            if !self.component.props.iter().any(|param| param.name == prop.name) {
+                return None;
+            }
+            let value = match &prop.value {
+                JSXPropValue::Literal(lit) => Expr::Literal(lit.clone(), prop.span),
+                JSXPropValue::Expr(expr) => expr.clone(),
+                JSXPropValue::Bool(b) => Expr::Literal(Literal::Boolean(*b), prop.span),
+            };
+            args.insert(prop.name.as_str(), value);
+        }
+        if args.len() != self.component.props.len() {
+            return None;
+        }
+        
+        let mut body = self.body.clone();
+        let mut reads = HashMap::new();
+        substitute_in_jsx(&mut body, &args, &mut reads);
+        let evaluated_once = args.iter().all(|(name, value)| {
+            matches!(value, Expr::Literal(..) | Expr::Variable(..)) || reads.get(name) == Some(&1)
+        });
+        evaluated_once.then_some(body)
+    }
+}
+
+/// The element a component renders, if the component can be inlined
+fn inlinable_body(component: &Component) -> Option<&JSXElement> {
+    if !component.generics.is_empty() || component.props.iter().any(|param| param.default.is_some()) {
+        return None;
+    }
+    let jsx = match &component.body {
+        Expr::JSXElement(jsx, _) => jsx,
+        Expr::Block(block, _) => match block.statements.as_slice() {
+            [Stmt::Tail(Expr::JSXElement(jsx, _))] => jsx,
+            _ => return None,
+        },
+        _ => return None,
+    };
+    is_simple_jsx(jsx, &component.props).then_some(jsx)
+}
+
+/// Whether substituting props into `jsx` is safe: nothing in it introduces
+/// bindings that could capture a prop, and it doesn't use `children`
+/// unless that's a prop
+fn is_simple_jsx(jsx: &JSXElement, props: &[Param]) -> bool {
+    let (jsx_props, children) = match jsx {
+        JSXElement::SelfClosing { props, .. } => (props, &[][..]),
+        JSXElement::WithChildren { props, children, .. } => (props, children.as_slice()),
+    };
+    jsx_props.iter().all(|prop| match &prop.value {
+        JSXPropValue::Expr(expr) => is_simple_expr(expr, props),
+        _ => true,
+    }) && children.iter().all(|child| match child {
+        JSXChild::Element(elem) => is_simple_jsx(elem, props),
+        JSXChild::Expr(expr) => is_simple_expr(expr, props),
+        JSXChild::Text(..) => true,
+    })
+}
+
+fn is_simple_expr(expr: &Expr, props: &[Param]) -> bool {
+    let all = |exprs: &[Expr]| exprs.iter().all(|expr| is_simple_expr(expr, props));
+    match expr {
+        Expr::Literal(..) => true,
+        Expr::Variable(name, _) => name != "children" || props.iter().any(|param| param.name == "children"),
+        Expr::Binary { left, right, .. } => is_simple_expr(left, props) && is_simple_expr(right, props),
+        Expr::Unary { expr, .. } | Expr::FieldAccess { object: expr, .. } => is_simple_expr(expr, props),
+        Expr::Call { callee: receiver, args, .. } | Expr::MethodCall { receiver, args, .. } => {
+            is_simple_expr(receiver, props) && all(args)
+        }
+        Expr::Index { object, index, .. } => is_simple_expr(object, props) && is_simple_expr(index, props),
+        Expr::JSXElement(jsx, _) => is_simple_jsx(jsx, props),
+        Expr::Tuple(items, _) | Expr::Array(items, _) => all(items),
+        Expr::Range { start, end, .. } => is_simple_expr(start, props) && is_simple_expr(end, props),
+        Expr::Struct { fields, .. } => fields.iter().all(|(_, value)| is_simple_expr(value, props)),
+        Expr::Block(..) | Expr::Loop(..) | Expr::If { .. } | Expr::Match { .. } | Expr::Lambda { .. } => false,
+    }
+}
+
+/// Replaces reads of the props in `args` with the values passed for them,
+/// counting the reads of each in `reads`. Only called on bodies
+/// `is_simple_jsx` accepted.
+fn substitute_in_jsx<'a>(jsx: &mut JSXElement, args: &HashMap<&'a str, Expr>, reads: &mut HashMap<&'a str, usize>) {
+    let (props, children) = match jsx {
+        JSXElement::SelfClosing { props, .. } => (props, None),
+        JSXElement::WithChildren { props, children, .. } => (props, Some(children)),
+    };
+    for prop in props.iter_mut() {
+        if let JSXPropValue::Expr(expr) = &mut prop.value {
+            substitute_in_expr(expr, args, reads);
+        }
+    }
+    for child in children.into_iter().flatten() {
+        match child {
+            JSXChild::Element(elem) => substitute_in_jsx(elem, args, reads),
+            JSXChild::Expr(expr) => substitute_in_expr(expr, args, reads),
+            JSXChild::Text(..) => {}
+        }
+    }
+}
+
+fn substitute_in_expr<'a>(expr: &mut Expr, args: &HashMap<&'a str, Expr>, reads: &mut HashMap<&'a str, usize>) {
+    match expr {
+        Expr::Variable(name, _) => {
+            if let Some((&name, value)) = args.get_key_value(name.as_str()) {
+                *reads.entry(name).or_insert(0) += 1;
+                *expr = value.clone();
+            }
+        }
+        Expr::Binary { left, right, .. } => {
+            substitute_in_expr(left, args, reads);
+            substitute_in_expr(right, args, reads);
+        }
+        Expr::Unary { expr, .. } | Expr::FieldAccess { object: expr, .. } => substitute_in_expr(expr, args, reads),
+        Expr::Call { callee: receiver, args: call_args, .. } | Expr::MethodCall { receiver, args: call_args, .. } => {
+            substitute_in_expr(receiver, args, reads);
+            for arg in call_args {
+                substitute_in_expr(arg, args, reads);
+            }
+        }
+        Expr::Index { object, index, .. } => {
+            substitute_in_expr(object, args, reads);
+            substitute_in_expr(index, args, reads);
+        }
+        Expr::JSXElement(jsx, _) => substitute_in_jsx(jsx, args, reads),
+        Expr::Tuple(items, _) | Expr::Array(items, _) => {
+            for item in items {
+                substitute_in_expr(item, args, reads);
+            }
+        }
+        Expr::Range { start, end, .. } => {
+            substitute_in_expr(start, args, reads);
+            substitute_in_expr(end, args, reads);
+        }
+        Expr::Struct { fields, .. } => {
+            for (_, value) in fields {
+                substitute_in_expr(value, args, reads);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Expressions a statement evaluates directly, outside any nested scope
+fn stmt_roots(stmt: &mut Stmt) -> Vec<&mut Expr> {
+    match stmt {
+        Stmt::Let { value: expr, .. }
+        | Stmt::Expr(expr)
+        | Stmt::Tail(expr)
+        | Stmt::Return(Some(expr), _)
+        | Stmt::Break(Some(expr), _) => vec![expr],
+        Stmt::If { condition: expr, .. } | Stmt::For { iter: expr, .. } | Stmt::Match { expr, .. } => vec![expr],
+        // A loop condition runs once per iteration, so it stays put
+        Stmt::Return(None, _) | Stmt::Break(None, _) | Stmt::While { .. } | Stmt::Block(_) => Vec::new(),
+    }
+}
+
+/// Calls `f` on `expr` and on each subexpression that is always evaluated
+/// along with it. Returning false from `f` skips that expression's children.
+fn visit_unconditional(expr: &mut Expr, f: &mut impl FnMut(&mut Expr) -> bool) {
+    if !f(expr) {
+        return;
+    }
+    match expr {
+        Expr::Binary { left, op, right, .. } => {
+            visit_unconditional(left, f);
+            // The right side of `&&`/`||` may never run
+            if !matches!(op, BinaryOp::And | BinaryOp::Or) {
+                visit_unconditional(right, f);
+            }
+        }
+        Expr::Unary { expr, .. } => visit_unconditional(expr, f),
+        Expr::Call { callee, args, .. } => {
+            visit_unconditional(callee, f);
+            for arg in args {
+                visit_unconditional(arg, f);
+            }
+        }
+        Expr::MethodCall { receiver, args, .. } => {
+            visit_unconditional(receiver, f);
+            for arg in args {
+                visit_unconditional(arg, f);
+            }
+        }
+        Expr::FieldAccess { object, .. } => visit_unconditional(object, f),
+        Expr::Index { object, index, .. } => {
+            visit_unconditional(object, f);
+            visit_unconditional(index, f);
+        }
+        Expr::Tuple(items, _) | Expr::Array(items, _) => {
+            for item in items {
+                visit_unconditional(item, f);
+            }
+        }
+        Expr::Range { start, end, .. } => {
+            visit_unconditional(start, f);
+            visit_unconditional(end, f);
+        }
+        Expr::Struct { fields, .. } => {
+            for (_, value) in fields {
+                visit_unconditional(value, f);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// A canonical form of a side-effect-free expression, equal for any two
+/// expressions that always compute the same value. None if `expr` isn't
+/// pure.
+fn pure_key(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Literal(lit, _) => Some(format!("{:?}", lit)),
+        Expr::Variable(name, _) => Some(name.clone()),
+        Expr::Binary { left, op, right, .. } => Some(format!("({} {:?} {})", pure_key(left)?, op, pure_key(right)?)),
+        Expr::Unary { op: op @ (UnaryOp::Neg | UnaryOp::Not), expr, .. } => {
+            Some(format!("({:?} {})", op, pure_key(expr)?))
+        }
+        _ => None,
+    }
+}
+
+fn collect_variables(expr: &Expr, vars: &mut Vec<String>) {
+    match expr {
+        Expr::Variable(name, _) => vars.push(name.clone()),
+        Expr::Binary { left, right, .. } => {
+            collect_variables(left, vars);
+            collect_variables(right, vars);
+        }
+        Expr::Unary { expr, .. } => collect_variables(expr, vars),
+        _ => {}
+    }
+}
+
+fn expr_span(expr: &Expr) -> crate::lexer::Span {
+    match expr {
+        Expr::Binary { span, .. } | Expr::Unary { span, .. } | Expr::Variable(_, span) | Expr::Literal(_, span) => *span,
+        _ => crate::lexer::Span::new(0, 0, 0, 0),
+    }
+}
+
+impl Default for Optimizer {
+    fn default() -> Self {
+        Self::new()
+    }
 }
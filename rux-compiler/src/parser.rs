@@ -1,33 +1,167 @@
 use crate::ast::*;
+use crate::diagnostics::Diagnostic;
 use crate::errors::{Error, Result};
-use crate::lexer::{Token, TokenWithSpan};
+use crate::lexer::{Span, Token, TokenWithSpan};
+use std::collections::HashMap;
 
 pub struct Parser {
     tokens: Vec<TokenWithSpan>,
     current: usize,
     source: String,
+    /// Doc comment tokens taken out of the stream, keyed by the index of
+    /// the token that follows them
+    docs: HashMap<usize, Vec<Token>>,
+    /// Tags of the JSX elements whose children are being parsed, innermost
+    /// last
+    open_tags: Vec<String>,
+    /// Errors the parser recovered from, e.g. missing semicolons
+    diagnostics: Vec<Diagnostic>,
 }
 
 impl Parser {
     pub fn new(tokens: Vec<TokenWithSpan>, source: String) -> Self {
+        // Doc comments may appear anywhere, so they're set aside here and
+        // only picked up by the items that follow them
+        let mut docs: HashMap<usize, Vec<Token>> = HashMap::new();
+        let mut kept = Vec::with_capacity(tokens.len());
+        for token in tokens {
+            match token.token {
+                Token::DocComment(_) | Token::InnerDocComment(_) => {
+                    docs.entry(kept.len()).or_default().push(token.token);
+                }
+                _ => kept.push(token),
+            }
+        }
+        
         Self {
-            tokens,
+            tokens: kept,
             current: 0,
             source,
+            docs,
+            open_tags: Vec::new(),
+            diagnostics: Vec::new(),
         }
     }
     
+    /// Errors recovered from while parsing. `parse` can succeed with
+    /// these present, but the source is still invalid.
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+    
     pub fn parse(&mut self) -> Result<AST> {
         let mut items = Vec::new();
+        let mut docs = self.take_docs(true);
         
         while !self.is_at_end() {
             items.push(self.parse_item()?);
+            docs.extend(self.take_docs(true));
         }
         
-        Ok(AST { items })
+        Ok(AST { items, docs })
+    }
+    
+    /// Removes the doc comments right before the current token, outer
+    /// (`///`) or inner (`//!`), returning their lines
+    fn take_docs(&mut self, inner: bool) -> Vec<String> {
+        let Some(tokens) = self.docs.get_mut(&self.current) else {
+            return Vec::new();
+        };
+        let mut lines = Vec::new();
+        tokens.retain(|token| match token {
+            Token::DocComment(text) if !inner => {
+                lines.extend(text.split('\n').map(str::to_string));
+                false
+            }
+            Token::InnerDocComment(text) if inner => {
+                lines.extend(text.split('\n').map(str::to_string));
+                false
+            }
+            _ => true,
+        });
+        lines
     }
     
     fn parse_item(&mut self) -> Result<Item> {
+        let mut docs = self.take_docs(false);
+        let attr_span = self.peek().span;
+        let attributes = self.parse_attributes()?;
+        docs.extend(self.take_docs(false));
+        let mut item = self.parse_item_inner()?;
+        
+        match &mut item {
+            Item::Component(c) => c.docs = docs,
+            Item::Function(f) => f.docs = docs,
+            Item::Struct(s) => s.docs = docs,
+            Item::Enum(e) => e.docs = docs,
+            Item::Trait(t) => t.docs = docs,
+            Item::Impl(i) => i.docs = docs,
+            // Nothing to attach them to in the generated Rust
+            Item::Use(_) | Item::Mod(_) | Item::TypeAlias(_) => {}
+        }
+        
+        if !attributes.is_empty() {
+            match &mut item {
+                Item::Component(c) => c.attributes = attributes,
+                Item::Function(f) => f.attributes = attributes,
+                Item::Struct(s) => s.attributes = attributes,
+                Item::Enum(e) => e.attributes = attributes,
+                Item::Trait(t) => t.attributes = attributes,
+                Item::Impl(i) => i.attributes = attributes,
+                Item::Use(_) | Item::Mod(_) | Item::TypeAlias(_) => {
+                    return Err(Error::parser(
+                        "Attributes are not supported on this item",
+                        self.source.clone(),
+                        attr_span.to_source_span(),
+                    ));
+                }
+            }
+        }
+        
+        Ok(item)
+    }
+    
+    /// Parses any `#[name]` / `#[name(args)]` attributes before an item.
+    fn parse_attributes(&mut self) -> Result<Vec<Attribute>> {
+        let mut attributes = Vec::new();
+        
+        while self.check(&Token::Hash) {
+            let span = self.advance().span;
+            self.expect(&Token::LBracket)?;
+            
+            let mut name = self.parse_identifier()?;
+            while self.match_token(&Token::ColonColon) {
+                name.push_str("::");
+                name.push_str(&self.parse_identifier()?);
+            }
+            
+            let args = if self.check(&Token::LParen) {
+                let open = self.advance().span;
+                let mut depth = 1;
+                while depth > 0 {
+                    if self.is_at_end() {
+                        return Err(self.error("Unterminated attribute arguments"));
+                    }
+                    match self.advance().token {
+                        Token::LParen => depth += 1,
+                        Token::RParen => depth -= 1,
+                        _ => {}
+                    }
+                }
+                let close = self.previous().span;
+                Some(self.source[open.end..close.start].trim().to_string())
+            } else {
+                None
+            };
+            
+            self.expect(&Token::RBracket)?;
+            attributes.push(Attribute { name, args, span });
+        }
+        
+        Ok(attributes)
+    }
+    
+    fn parse_item_inner(&mut self) -> Result<Item> {
         if self.check(&Token::Fn) {
             self.parse_function_or_component()
         } else if self.check(&Token::Struct) {
@@ -50,10 +184,10 @@ impl Parser {
     }
     
     fn parse_function_or_component(&mut self) -> Result<Item> {
-        let start_span = self.previous().span;
-        self.advance(); // consume 'fn'
+        let start_span = self.advance().span; // consume 'fn'
         
         let name = self.parse_identifier()?;
+        let (generics, mut where_clause) = self.parse_generic_params()?;
         let params = self.parse_params()?;
         
         // Check if this is a component (returns Element) or regular function
@@ -63,28 +197,38 @@ impl Parser {
         } else {
             None
         };
+        where_clause.extend(self.parse_where_clause()?);
         
         if self.check(&Token::LBrace) {
             // Block body
             let block = self.parse_block()?;
+            let span = start_span.join(self.previous().span);
             if let Some(ref ret_type) = return_type {
                 // Check if return type is Element (component)
                 if self.is_element_type(ret_type) {
                     return Ok(Item::Component(Component {
+                        attributes: Vec::new(),
+                        docs: Vec::new(),
                         name,
+                        generics,
                         props: params,
                         return_type: ret_type.clone(),
-                        body: Expr::Block(block, start_span),
-                        span: start_span,
+                        where_clause,
+                        body: Expr::Block(block, span),
+                        span,
                     }));
                 }
             }
             Ok(Item::Function(Function {
+                attributes: Vec::new(),
+                docs: Vec::new(),
                 name,
+                generics,
                 params,
                 return_type,
+                where_clause,
                 body: block,
-                span: start_span,
+                span,
             }))
         } else {
             // Expression body (for components)
@@ -92,11 +236,15 @@ impl Parser {
                 if self.is_element_type(ret_type) {
                     let expr = self.parse_expression()?;
                     return Ok(Item::Component(Component {
+                        attributes: Vec::new(),
+                        docs: Vec::new(),
                         name,
+                        generics,
                         props: params,
                         return_type: ret_type.clone(),
+                        where_clause,
                         body: expr,
-                        span: start_span,
+                        span: start_span.join(self.previous().span),
                     }));
                 }
             }
@@ -104,6 +252,72 @@ impl Parser {
         }
     }
     
+    /// Parses an optional `<T, U: Bound>` list. Inline bounds are returned
+    /// as predicates so they can be merged with the `where` clause.
+    fn parse_generic_params(&mut self) -> Result<(Vec<String>, Vec<WherePredicate>)> {
+        let mut generics = Vec::new();
+        let mut predicates = Vec::new();
+        
+        if !self.match_token(&Token::Lt) {
+            return Ok((generics, predicates));
+        }
+        
+        while !self.check(&Token::Gt) {
+            let span = self.peek().span;
+            let name = self.parse_identifier()?;
+            if self.match_token(&Token::Colon) {
+                predicates.push(WherePredicate {
+                    ty: Type {
+                        kind: TypeKind::Ident(name.clone()),
+                        span,
+                    },
+                    bounds: self.parse_bounds()?,
+                    span,
+                });
+            }
+            generics.push(name);
+            
+            if !self.match_token(&Token::Comma) {
+                break;
+            }
+        }
+        
+        self.expect(&Token::Gt)?;
+        Ok((generics, predicates))
+    }
+    
+    /// Parses an optional `where T: Bound, U: A + B` clause, stopping at the
+    /// opening brace of the body.
+    fn parse_where_clause(&mut self) -> Result<Vec<WherePredicate>> {
+        let mut predicates = Vec::new();
+        
+        if !self.match_token(&Token::Where) {
+            return Ok(predicates);
+        }
+        
+        while !self.check(&Token::LBrace) && !self.is_at_end() {
+            let span = self.peek().span;
+            let ty = self.parse_type()?;
+            self.expect(&Token::Colon)?;
+            let bounds = self.parse_bounds()?;
+            predicates.push(WherePredicate { ty, bounds, span });
+            
+            if !self.match_token(&Token::Comma) {
+                break;
+            }
+        }
+        
+        Ok(predicates)
+    }
+    
+    fn parse_bounds(&mut self) -> Result<Vec<Type>> {
+        let mut bounds = vec![self.parse_type()?];
+        while self.match_token(&Token::Plus) {
+            bounds.push(self.parse_type()?);
+        }
+        Ok(bounds)
+    }
+    
     fn is_element_type(&self, ty: &Type) -> bool {
         matches!(&ty.kind, TypeKind::Ident(name) if name == "Element")
     }
@@ -118,10 +332,16 @@ impl Parser {
                 self.expect(&Token::Colon)?;
                 let param_type = self.parse_type()?;
                 let span = self.previous().span;
+                let default = if self.match_token(&Token::Eq) {
+                    Some(self.parse_expression()?)
+                } else {
+                    None
+                };
                 
                 params.push(Param {
                     name,
                     param_type,
+                    default,
                     span,
                 });
                 
@@ -158,7 +378,7 @@ impl Parser {
             } else {
                 let inner = self.parse_type()?;
                 self.expect(&Token::Semicolon)?;
-                let size = self.parse_expression()?;
+                let _size = self.parse_expression()?;
                 self.expect(&Token::RBracket)?;
                 TypeKind::Array(Box::new(inner))
             }
@@ -170,6 +390,11 @@ impl Parser {
                 mutable,
                 inner: Box::new(inner),
             }
+        } else if self.match_token(&Token::Impl) {
+            TypeKind::Impl(Box::new(self.parse_type()?))
+        } else if matches!(&self.peek().token, Token::Ident(name) if name == "dyn") {
+            self.advance();
+            TypeKind::Dyn(Box::new(self.parse_type()?))
         } else if matches!(self.peek().token, Token::Ident(_)) {
             let name = self.parse_identifier()?;
             if self.match_token(&Token::ColonColon) {
@@ -181,7 +406,48 @@ impl Parser {
                         break;
                     }
                 }
-                TypeKind::Path(path)
+                if self.match_token(&Token::Lt) {
+                    TypeKind::Generic {
+                        name: path.join("::"),
+                        args: self.parse_generic_args()?,
+                    }
+                } else {
+                    TypeKind::Path(path)
+                }
+            } else if matches!(name.as_str(), "Fn" | "FnMut" | "FnOnce") && self.match_token(&Token::LParen) {
+                // Closure trait sugar, `Fn(i32) -> bool`
+                let mut params = Vec::new();
+                while !self.check(&Token::RParen) {
+                    params.push(self.parse_type()?);
+                    if !self.match_token(&Token::Comma) {
+                        break;
+                    }
+                }
+                self.expect(&Token::RParen)?;
+                let return_type = if self.match_token(&Token::Arrow) {
+                    Some(Box::new(self.parse_type()?))
+                } else {
+                    None
+                };
+                TypeKind::FnTrait {
+                    name,
+                    params,
+                    return_type,
+                }
+            } else if self.match_token(&Token::Lt) {
+                let mut args = self.parse_generic_args()?;
+                match (name.as_str(), args.len()) {
+                    ("Option", 1) => TypeKind::Option(Box::new(args.remove(0))),
+                    ("Result", 2) => {
+                        let err = args.pop().unwrap();
+                        let ok = args.pop().unwrap();
+                        TypeKind::Result {
+                            ok: Box::new(ok),
+                            err: Box::new(err),
+                        }
+                    }
+                    _ => TypeKind::Generic { name, args },
+                }
             } else {
                 TypeKind::Ident(name)
             }
@@ -192,6 +458,29 @@ impl Parser {
         Ok(Type { kind, span })
     }
     
+    /// Type arguments after the opening `<`, through the closing `>`
+    fn parse_generic_args(&mut self) -> Result<Vec<Type>> {
+        let mut args = Vec::new();
+        loop {
+            args.push(self.parse_type()?);
+            if !self.match_token(&Token::Comma) {
+                break;
+            }
+        }
+        
+        // `Option<Vec<T>>` lexes its closing brackets as one `>>`, so split
+        // it and leave the second `>` for the outer list
+        if self.check(&Token::Shr) {
+            let token = &mut self.tokens[self.current];
+            token.token = Token::Gt;
+            token.span.start += 1;
+            token.span.column += 1;
+        } else {
+            self.expect(&Token::Gt)?;
+        }
+        Ok(args)
+    }
+    
     fn parse_block(&mut self) -> Result<Block> {
         let span = self.expect(&Token::LBrace)?.span;
         let mut statements = Vec::new();
@@ -211,7 +500,7 @@ impl Parser {
             self.expect(&Token::Eq)?;
             let value = self.parse_expression()?;
             let span = self.previous().span;
-            self.expect(&Token::Semicolon)?;
+            self.expect_semicolon()?;
             Ok(Stmt::Let {
                 name,
                 value,
@@ -225,7 +514,7 @@ impl Parser {
             } else {
                 None
             };
-            self.expect(&Token::Semicolon)?;
+            self.expect_semicolon()?;
             Ok(Stmt::Return(value, span))
         } else if self.match_token(&Token::If) {
             self.parse_if_statement()
@@ -235,11 +524,29 @@ impl Parser {
             self.parse_while_statement()
         } else if self.match_token(&Token::Match) {
             self.parse_match_statement()
+        } else if self.match_token(&Token::Break) {
+            let span = self.previous().span;
+            let value = if !self.check(&Token::Semicolon) && !self.check(&Token::RBrace) {
+                Some(self.parse_expression()?)
+            } else {
+                None
+            };
+            self.expect_semicolon()?;
+            Ok(Stmt::Break(value, span))
         } else if self.check(&Token::LBrace) {
             Ok(Stmt::Block(self.parse_block()?))
         } else {
             let expr = self.parse_expression()?;
-            self.expect(&Token::Semicolon)?;
+            if self.check(&Token::RBrace) {
+                // Trailing expression gives the block its value
+                return Ok(Stmt::Tail(expr));
+            }
+            // Like a block, a `loop` statement needs no semicolon
+            if matches!(expr, Expr::Loop(..)) {
+                self.match_token(&Token::Semicolon);
+                return Ok(Stmt::Expr(expr));
+            }
+            self.expect_semicolon()?;
             Ok(Stmt::Expr(expr))
         }
     }
@@ -249,11 +556,31 @@ impl Parser {
     }
     
     fn parse_assignment(&mut self) -> Result<Expr> {
-        let expr = self.parse_or()?;
+        let expr = self.parse_range()?;
         // Assignment parsing would go here
         Ok(expr)
     }
     
+    fn parse_range(&mut self) -> Result<Expr> {
+        let start = self.parse_or()?;
+        
+        let inclusive = if self.match_token(&Token::DotDotEq) {
+            true
+        } else if self.match_token(&Token::DotDot) {
+            false
+        } else {
+            return Ok(start);
+        };
+        let span = self.previous().span;
+        let end = self.parse_or()?;
+        Ok(Expr::Range {
+            start: Box::new(start),
+            end: Box::new(end),
+            inclusive,
+            span,
+        })
+    }
+    
     fn parse_or(&mut self) -> Result<Expr> {
         let mut expr = self.parse_and()?;
         
@@ -408,28 +735,55 @@ impl Parser {
         }
     }
     
+    /// Arguments after an opening `(`, with the span of the closing `)`
+    fn parse_args(&mut self) -> Result<(Vec<Expr>, Span)> {
+        let mut args = Vec::new();
+        if !self.check(&Token::RParen) {
+            loop {
+                args.push(self.parse_expression()?);
+                if !self.match_token(&Token::Comma) {
+                    break;
+                }
+            }
+        }
+        let span = self.expect(&Token::RParen)?.span;
+        Ok((args, span))
+    }
+    
     fn parse_call(&mut self) -> Result<Expr> {
         let mut expr = self.parse_primary()?;
         
         loop {
             if self.match_token(&Token::LParen) {
-                let mut args = Vec::new();
-                if !self.check(&Token::RParen) {
-                    loop {
-                        args.push(self.parse_expression()?);
-                        if !self.match_token(&Token::Comma) {
-                            break;
-                        }
-                    }
-                }
-                let span = self.expect(&Token::RParen)?.span;
+                let (args, span) = self.parse_args()?;
                 expr = Expr::Call {
                     callee: Box::new(expr),
                     args,
                     span,
                 };
             } else if self.match_token(&Token::Dot) {
+                if let Token::Number(_) = self.peek().token {
+                    let span = self.advance().span;
+                    for (field, span) in self.tuple_indices(span)? {
+                        expr = Expr::FieldAccess {
+                            object: Box::new(expr),
+                            field,
+                            span,
+                        };
+                    }
+                    continue;
+                }
                 let field = self.parse_identifier()?;
+                if self.match_token(&Token::LParen) {
+                    let (args, span) = self.parse_args()?;
+                    expr = Expr::MethodCall {
+                        receiver: Box::new(expr),
+                        method: field,
+                        args,
+                        span,
+                    };
+                    continue;
+                }
                 let span = self.previous().span;
                 expr = Expr::FieldAccess {
                     object: Box::new(expr),
@@ -455,10 +809,52 @@ impl Parser {
     fn parse_primary(&mut self) -> Result<Expr> {
         if self.match_token(&Token::JSXOpen) {
             self.parse_jsx_element()
+        } else if self.match_token(&Token::Match) {
+            let span = self.previous().span;
+            let expr = self.parse_expression()?;
+            let arms = self.parse_match_arms()?;
+            Ok(Expr::Match {
+                expr: Box::new(expr),
+                arms,
+                span,
+            })
+        } else if self.match_token(&Token::If) {
+            self.parse_if_expression()
+        } else if self.match_token(&Token::Loop) {
+            let span = self.previous().span;
+            Ok(Expr::Loop(self.parse_block()?, span))
         } else if self.match_token(&Token::LParen) {
+            let span = self.previous().span;
+            if self.match_token(&Token::RParen) {
+                return Ok(Expr::Literal(Literal::Unit, span));
+            }
             let expr = self.parse_expression()?;
-            self.expect(&Token::RParen)?;
-            Ok(expr)
+            if !self.match_token(&Token::Comma) {
+                self.expect(&Token::RParen)?;
+                return Ok(expr);
+            }
+            
+            // A trailing comma makes a one-element tuple
+            let mut items = vec![expr];
+            while !self.check(&Token::RParen) && !self.is_at_end() {
+                items.push(self.parse_expression()?);
+                if !self.match_token(&Token::Comma) {
+                    break;
+                }
+            }
+            let end = self.expect(&Token::RParen)?.span;
+            Ok(Expr::Tuple(items, span.join(end)))
+        } else if self.match_token(&Token::LBracket) {
+            let span = self.previous().span;
+            let mut items = Vec::new();
+            while !self.check(&Token::RBracket) && !self.is_at_end() {
+                items.push(self.parse_expression()?);
+                if !self.match_token(&Token::Comma) {
+                    break;
+                }
+            }
+            let end = self.expect(&Token::RBracket)?.span;
+            Ok(Expr::Array(items, span.join(end)))
         } else if let Token::Ident(name) = &self.peek().token {
             let name = name.clone();
             let span = self.advance().span;
@@ -470,7 +866,11 @@ impl Parser {
         } else if let Token::Number(n) = &self.peek().token {
             let n = *n;
             let span = self.advance().span;
-            Ok(Expr::Literal(Literal::Number(n), span))
+            let integer = self.source
+                .get(span.start..span.end)
+                .filter(|text| text.bytes().all(|b| b.is_ascii_digit()))
+                .and_then(|text| text.parse::<i64>().ok());
+            Ok(Expr::Literal(integer.map_or(Literal::Number(n), Literal::Integer), span))
         } else if let Token::Boolean(b) = &self.peek().token {
             let b = *b;
             let span = self.advance().span;
@@ -484,17 +884,93 @@ impl Parser {
         }
     }
     
+    fn parse_if_expression(&mut self) -> Result<Expr> {
+        let span = self.previous().span;
+        let condition = self.parse_expression()?;
+        let then_block = self.parse_block()?;
+        let then_span = then_block.span;
+        let else_ = if self.match_token(&Token::Else) {
+            let else_expr = if self.match_token(&Token::If) {
+                self.parse_if_expression()?
+            } else {
+                let block = self.parse_block()?;
+                let block_span = block.span;
+                Expr::Block(block, block_span)
+            };
+            Some(Box::new(else_expr))
+        } else {
+            None
+        };
+        Ok(Expr::If {
+            condition: Box::new(condition),
+            then: Box::new(Expr::Block(then_block, then_span)),
+            else_,
+            span,
+        })
+    }
+    
+    /// `bind:value={signal}` or `bind:checked={signal}`, after the name
+    fn parse_jsx_binding(&mut self, bound: &str, name: &str, span: Span) -> Result<JSXProp> {
+        if !matches!(bound, "value" | "checked") {
+            return Err(Error::parser(
+                format!("Cannot bind '{}', only bind:value and bind:checked are supported", bound),
+                self.source.clone(),
+                span.to_source_span(),
+            ));
+        }
+        if !(self.match_token(&Token::Eq) && self.match_token(&Token::LBrace)) {
+            return Err(self.error(&format!("Expected a signal to bind, e.g. {}={{text}}", name)));
+        }
+        let signal = self.parse_expression()?;
+        self.expect(&Token::RBrace)?;
+        Ok(JSXProp {
+            name: name.to_string(),
+            value: JSXPropValue::Expr(signal),
+            modifiers: Vec::new(),
+            span,
+        })
+    }
+    
+    /// Splits `onClick:prevent:stop` into the event prop and its modifiers
+    fn parse_jsx_modifiers(&self, name: &str, span: Span) -> Result<(String, Vec<String>)> {
+        let mut parts = name.split(':');
+        let prop = parts.next().unwrap_or_default().to_string();
+        let modifiers: Vec<String> = parts.map(str::to_string).collect();
+        if modifiers.is_empty() {
+            return Ok((prop, modifiers));
+        }
+        
+        let error = |message: String| Err(Error::parser(message, self.source.clone(), span.to_source_span()));
+        if !is_event_prop(&prop) {
+            return error(format!("Modifiers only apply to event props, not '{}'", prop));
+        }
+        if let Some(unknown) = modifiers.iter().find(|m| !EVENT_MODIFIERS.contains(&m.as_str())) {
+            return error(format!(
+                "Unknown event modifier '{}', expected one of: {}",
+                unknown,
+                EVENT_MODIFIERS.join(", ")
+            ));
+        }
+        Ok((prop, modifiers))
+    }
+    
     fn parse_jsx_element(&mut self) -> Result<Expr> {
         let start_span = self.previous().span;
         
         if let Token::JSXOpenTag(tag) = &self.peek().token {
             let tag = tag.clone();
-            self.advance();
+            // `<tag`, where an unclosed element is reported
+            let open_tag_span = start_span.join(self.advance().span);
             
             let mut props = Vec::new();
-            while !self.check(&Token::JSXSlash) && !self.check(&Token::JSXClose) {
+            while !self.check(&Token::JSXSelfClose) && !self.check(&Token::JSXClose) && !self.is_at_end() {
                 let name = self.parse_identifier()?;
                 let prop_span = self.previous().span;
+                if let Some(bound) = name.strip_prefix("bind:") {
+                    props.push(self.parse_jsx_binding(bound, &name, prop_span)?);
+                    continue;
+                }
+                let (name, modifiers) = self.parse_jsx_modifiers(&name, prop_span)?;
                 
                 if self.match_token(&Token::Eq) {
                     let value = if self.check(&Token::LBrace) {
@@ -517,6 +993,7 @@ impl Parser {
                     props.push(JSXProp {
                         name,
                         value,
+                        modifiers,
                         span: prop_span,
                     });
                 } else {
@@ -524,79 +1001,120 @@ impl Parser {
                     props.push(JSXProp {
                         name,
                         value: JSXPropValue::Bool(true),
+                        modifiers,
                         span: prop_span,
                     });
                 }
             }
             
             if self.match_token(&Token::JSXSelfClose) {
+                let span = start_span.join(self.previous().span);
                 return Ok(Expr::JSXElement(
                 JSXElement::SelfClosing {
                     tag,
                     props,
-                    span: start_span,
+                    span,
                 },
-                    start_span,
+                    span,
                 ));
             }
             
             self.expect(&Token::JSXClose)?;
             
-            let mut children = Vec::new();
-            loop {
-                if self.is_at_end() {
-                    break;
-                }
-                if self.check(&Token::JSXOpen) {
-                    // Check if this is a closing tag
-                    if self.current + 1 < self.tokens.len() {
-                        if let Token::JSXCloseTag(_) = &self.tokens[self.current + 1].token {
-                            break;
-                        }
-                    }
-                    children.push(JSXChild::Element(self.parse_jsx_element_inner()?));
-                } else if let Token::String(s) = &self.peek().token {
-                    let s = s.clone();
-                    let span = self.advance().span;
-                    children.push(JSXChild::Text(s, span));
-                } else if self.check(&Token::JSXClose) {
-                    // Check if next is closing tag
-                    if self.current + 1 < self.tokens.len() {
-                        if let Token::JSXCloseTag(_) = &self.tokens[self.current + 1].token {
-                            break;
-                        }
-                    }
-                    children.push(JSXChild::Expr(self.parse_expression()?));
-                } else {
-                    children.push(JSXChild::Expr(self.parse_expression()?));
-                }
-            }
-            
-            if let Token::JSXCloseTag(expected_tag) = &self.peek().token {
-                if expected_tag == &tag {
+            self.open_tags.push(tag.clone());
+            let children = self.parse_jsx_children();
+            self.open_tags.pop();
+            let children = children?;
+            self.match_token(&Token::JSXOpen);
+            match &self.peek().token {
+                Token::JSXCloseTag(closing) if closing == &tag => {
                     self.advance();
                     self.expect(&Token::JSXClose)?;
-                } else {
-                    return Err(self.error(&format!("Expected closing tag </{}>", tag)));
                 }
-            } else {
-                return Err(self.error("Expected closing tag"));
+                // A typo rather than a missing close, since no enclosing
+                // element has that tag either
+                Token::JSXCloseTag(closing) if !self.open_tags.contains(closing) => {
+                    let close_span = self.previous().span.join(self.peek().span);
+                    return Err(Error::mismatched_tag(
+                        &tag,
+                        closing,
+                        self.source.clone(),
+                        open_tag_span.to_source_span(),
+                        close_span.to_source_span(),
+                    ));
+                }
+                other => {
+                    let found = match other {
+                        Token::JSXCloseTag(closing) => format!("found </{}>", closing),
+                        _ => "reached the end of the file".to_string(),
+                    };
+                    return Err(Error::parser(
+                        format!("Unclosed <{}>: expected </{}> but {}", tag, tag, found),
+                        self.source.clone(),
+                        open_tag_span.to_source_span(),
+                    ));
+                }
             }
             
+            let span = start_span.join(self.previous().span);
             Ok(Expr::JSXElement(
                 JSXElement::WithChildren {
                     tag,
                     props,
                     children,
-                    span: start_span,
+                    span,
                 },
-                start_span,
+                span,
             ))
         } else {
             Err(self.error("Expected JSX tag"))
         }
     }
     
+    /// Children up to the closing tag, which is left unconsumed
+    fn parse_jsx_children(&mut self) -> Result<Vec<JSXChild>> {
+        let mut children = Vec::new();
+        loop {
+            if self.is_at_end() {
+                break;
+            }
+            if self.check(&Token::JSXOpen) {
+                // Check if this is a closing tag
+                if self.current + 1 < self.tokens.len() {
+                    if let Token::JSXCloseTag(_) = &self.tokens[self.current + 1].token {
+                        break;
+                    }
+                }
+                self.advance(); // consume '<'
+                children.push(JSXChild::Element(self.parse_jsx_element_inner()?));
+            } else if let Token::JSXText(text) = &self.peek().token {
+                let text = text.clone();
+                let span = self.advance().span;
+                children.push(JSXChild::Text(text, span));
+            } else if self.match_token(&Token::LBrace) {
+                // The lexer drops comments, so `{/* note */}` arrives as
+                // an empty container and renders nothing
+                if self.match_token(&Token::RBrace) {
+                    continue;
+                }
+                let expr = if self.check(&Token::For) {
+                    // `{for x in xs { <li /> }}` renders the body once
+                    // per item
+                    let span = self.peek().span;
+                    let stmt = self.parse_statement()?;
+                    Expr::Block(Block { statements: vec![stmt], span }, span)
+                } else {
+                    self.parse_expression()?
+                };
+                self.expect(&Token::RBrace)?;
+                children.push(JSXChild::Expr(expr));
+            } else {
+                children.push(JSXChild::Expr(self.parse_expression()?));
+            }
+        }
+        Ok(children)
+    }
+    
     fn parse_jsx_element_inner(&mut self) -> Result<JSXElement> {
         // Simplified - would need full implementation
         self.parse_jsx_element().map(|e| {
@@ -608,12 +1126,20 @@ impl Parser {
         })
     }
     
+    /// `if cond { .. }`, with an optional `else { .. }` or `else if ..`.
+    /// Branches are always blocks, and an `else if` chain nests each
+    /// following `if` as the previous one's `else`.
     fn parse_if_statement(&mut self) -> Result<Stmt> {
         let span = self.previous().span;
         let condition = self.parse_expression()?;
-        let then = Box::new(self.parse_statement()?);
+        let then = Box::new(Stmt::Block(self.parse_block()?));
         let else_ = if self.match_token(&Token::Else) {
-            Some(Box::new(self.parse_statement()?))
+            let else_stmt = if self.match_token(&Token::If) {
+                self.parse_if_statement()?
+            } else {
+                Stmt::Block(self.parse_block()?)
+            };
+            Some(Box::new(else_stmt))
         } else {
             None
         };
@@ -653,19 +1179,24 @@ impl Parser {
     fn parse_match_statement(&mut self) -> Result<Stmt> {
         let span = self.previous().span;
         let expr = self.parse_expression()?;
+        let arms = self.parse_match_arms()?;
+        Ok(Stmt::Match {
+            expr,
+            arms,
+            span,
+        })
+    }
+    
+    fn parse_match_arms(&mut self) -> Result<Vec<MatchArm>> {
         self.expect(&Token::LBrace)?;
         let mut arms = Vec::new();
         
-        while !self.check(&Token::RBrace) {
+        while !self.check(&Token::RBrace) && !self.is_at_end() {
             arms.push(self.parse_match_arm()?);
         }
         
         self.expect(&Token::RBrace)?;
-        Ok(Stmt::Match {
-            expr,
-            arms,
-            span,
-        })
+        Ok(arms)
     }
     
     fn parse_match_arm(&mut self) -> Result<MatchArm> {
@@ -694,7 +1225,31 @@ impl Parser {
         if let Token::Ident(name) = &self.peek().token {
             let name = name.clone();
             self.advance();
-            Ok(Pattern::Ident(name, span))
+            let mut path = vec![name];
+            while self.match_token(&Token::ColonColon) {
+                path.push(self.parse_identifier()?);
+            }
+            
+            if self.match_token(&Token::LParen) {
+                let mut elems = Vec::new();
+                while !self.check(&Token::RParen) {
+                    elems.push(self.parse_pattern()?);
+                    if !self.match_token(&Token::Comma) {
+                        break;
+                    }
+                }
+                self.expect(&Token::RParen)?;
+                return Ok(Pattern::TupleStruct {
+                    name: path.join("::"),
+                    elems,
+                    span,
+                });
+            }
+            
+            if path.len() > 1 {
+                return Ok(Pattern::Path(path, span));
+            }
+            Ok(Pattern::Ident(path.remove(0), span))
         } else if let Token::Underscore = &self.peek().token {
             self.advance();
             Ok(Pattern::Wildcard(span))
@@ -725,7 +1280,13 @@ impl Parser {
         }
         
         self.expect(&Token::RBrace)?;
-        Ok(Struct { name, fields, span })
+        Ok(Struct {
+            attributes: Vec::new(),
+            docs: Vec::new(),
+            name,
+            fields,
+            span,
+        })
     }
     
     fn parse_enum(&mut self) -> Result<Enum> {
@@ -786,6 +1347,8 @@ impl Parser {
         
         self.expect(&Token::RBrace)?;
         Ok(Enum {
+            attributes: Vec::new(),
+            docs: Vec::new(),
             name,
             variants,
             span,
@@ -795,6 +1358,8 @@ impl Parser {
     fn parse_trait(&mut self) -> Result<Trait> {
         let span = self.expect(&Token::Trait)?.span;
         let name = self.parse_identifier()?;
+        let (generics, mut where_clause) = self.parse_generic_params()?;
+        where_clause.extend(self.parse_where_clause()?);
         self.expect(&Token::LBrace)?;
         let mut items = Vec::new();
         
@@ -816,11 +1381,20 @@ impl Parser {
         }
         
         self.expect(&Token::RBrace)?;
-        Ok(Trait { name, items, span })
+        Ok(Trait {
+            attributes: Vec::new(),
+            docs: Vec::new(),
+            name,
+            generics,
+            where_clause,
+            items,
+            span,
+        })
     }
     
     fn parse_impl(&mut self) -> Result<Impl> {
         let span = self.expect(&Token::Impl)?.span;
+        let (generics, mut where_clause) = self.parse_generic_params()?;
         let trait_name = if matches!(self.peek().token, Token::Ident(_)) {
             let name = self.parse_identifier()?;
             if self.match_token(&Token::For) {
@@ -834,6 +1408,7 @@ impl Parser {
             None
         };
         let type_name = self.parse_identifier()?;
+        where_clause.extend(self.parse_where_clause()?);
         self.expect(&Token::LBrace)?;
         let mut items = Vec::new();
         
@@ -843,27 +1418,38 @@ impl Parser {
         
         self.expect(&Token::RBrace)?;
         Ok(Impl {
+            attributes: Vec::new(),
+            docs: Vec::new(),
+            generics,
             trait_name,
             type_name,
+            where_clause,
             items,
             span,
         })
     }
     
     fn parse_function_inner(&mut self) -> Result<Function> {
+        let docs = self.take_docs(false);
         let span = self.expect(&Token::Fn)?.span;
         let name = self.parse_identifier()?;
+        let (generics, mut where_clause) = self.parse_generic_params()?;
         let params = self.parse_params()?;
         let return_type = if self.match_token(&Token::Arrow) {
             Some(self.parse_type()?)
         } else {
             None
         };
+        where_clause.extend(self.parse_where_clause()?);
         let body = self.parse_block()?;
         Ok(Function {
+            attributes: Vec::new(),
+            docs,
             name,
+            generics,
             params,
             return_type,
+            where_clause,
             body,
             span,
         })
@@ -874,18 +1460,23 @@ impl Parser {
         let mut path = Vec::new();
         path.push(self.parse_identifier()?);
         
+        let mut glob = false;
         while self.match_token(&Token::ColonColon) {
+            if self.match_token(&Token::Star) {
+                glob = true;
+                break;
+            }
             path.push(self.parse_identifier()?);
         }
         
-        let alias = if self.match_token(&Token::As) {
+        let alias = if !glob && self.match_token(&Token::As) {
             Some(self.parse_identifier()?)
         } else {
             None
         };
         
-        self.expect(&Token::Semicolon)?;
-        Ok(Use { path, alias, span })
+        self.expect_semicolon()?;
+        Ok(Use { path, alias, glob, span })
     }
     
     fn parse_mod(&mut self) -> Result<Mod> {
@@ -907,7 +1498,7 @@ impl Parser {
         let name = self.parse_identifier()?;
         self.expect(&Token::Eq)?;
         let aliased_type = self.parse_type()?;
-        self.expect(&Token::Semicolon)?;
+        self.expect_semicolon()?;
         Ok(TypeAlias {
             name,
             aliased_type,
@@ -915,6 +1506,30 @@ impl Parser {
         })
     }
     
+    /// Tuple indices in the number token at `span`, each with its own span.
+    /// `pair.0.1` lexes as `pair`, `.`, `0.1`, so one token can hold two.
+    fn tuple_indices(&self, span: crate::lexer::Span) -> Result<Vec<(String, crate::lexer::Span)>> {
+        let text = self.source.get(span.start..span.end).unwrap_or_default();
+        let mut indices = Vec::new();
+        let mut offset = 0;
+        for index in text.split('.') {
+            if index.is_empty() || !index.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(Error::parser(
+                    format!("Invalid tuple index: {}", text),
+                    self.source.clone(),
+                    span.to_source_span(),
+                ));
+            }
+            let start = span.start + offset;
+            indices.push((
+                index.to_string(),
+                crate::lexer::Span::new(start, start + index.len(), span.line, span.column + offset),
+            ));
+            offset += index.len() + 1;
+        }
+        Ok(indices)
+    }
+    
     fn parse_identifier(&mut self) -> Result<String> {
         if let Token::Ident(name) = &self.peek().token {
             let name = name.clone();
@@ -973,6 +1588,29 @@ impl Parser {
         }
     }
     
+    /// Expects the `;` ending a statement. One missing before a line
+    /// break or a closing `}` is recorded as a diagnostic and parsing goes
+    /// on as if it were there; anywhere else it is an error.
+    fn expect_semicolon(&mut self) -> Result<()> {
+        if self.match_token(&Token::Semicolon) {
+            return Ok(());
+        }
+        let previous = self.previous().span;
+        let next = self.peek();
+        if !(self.is_at_end() || next.token == Token::RBrace || next.span.line > previous.line) {
+            return Err(self.error("Expected ';'"));
+        }
+        
+        let insert_at = Span::new(
+            previous.end,
+            previous.end,
+            previous.line,
+            previous.column + self.source[previous.start..previous.end].chars().count(),
+        );
+        self.diagnostics.push(Diagnostic::error("Missing ';'", insert_at).with_help("add ';'"));
+        Ok(())
+    }
+    
     fn error(&self, message: &str) -> Error {
         let span = if self.current < self.tokens.len() {
             self.tokens[self.current].span
@@ -0,0 +1,395 @@
+use crate::ast::*;
+
+impl AST {
+    /// Compact, indented dump of the tree for tooling output. Nodes are shown
+    /// by kind and name; spans are reduced to `@line:column` on items.
+    pub fn pretty(&self) -> String {
+        let mut printer = PrettyPrinter::default();
+        for item in &self.items {
+            printer.item(item);
+        }
+        printer.out
+    }
+}
+
+#[derive(Default)]
+struct PrettyPrinter {
+    out: String,
+    depth: usize,
+}
+
+impl PrettyPrinter {
+    fn line(&mut self, text: impl AsRef<str>) {
+        for _ in 0..self.depth {
+            self.out.push_str("  ");
+        }
+        self.out.push_str(text.as_ref());
+        self.out.push('\n');
+    }
+
+    fn nested(&mut self, f: impl FnOnce(&mut Self)) {
+        self.depth += 1;
+        f(self);
+        self.depth -= 1;
+    }
+
+    fn item(&mut self, item: &Item) {
+        match item {
+            Item::Component(c) => {
+                self.line(format!("Component({}){} @{}:{}", c.name, generics(&c.generics), c.span.line, c.span.column));
+                self.nested(|p| {
+                    p.params("Props", &c.props);
+                    p.line(format!("Returns {}", c.return_type));
+                    p.expr(&c.body);
+                });
+            }
+            Item::Function(f) => self.function(f),
+            Item::Struct(s) => {
+                self.line(format!("Struct({}) @{}:{}", s.name, s.span.line, s.span.column));
+                self.nested(|p| {
+                    for field in &s.fields {
+                        p.line(format!("{}: {}", field.name, field.field_type));
+                    }
+                });
+            }
+            Item::Enum(e) => {
+                self.line(format!("Enum({}) @{}:{}", e.name, e.span.line, e.span.column));
+                self.nested(|p| {
+                    for variant in &e.variants {
+                        p.line(match &variant.data {
+                            None => variant.name.clone(),
+                            Some(EnumVariantData::Tuple(types)) => {
+                                format!("{}({})", variant.name, join(types))
+                            }
+                            Some(EnumVariantData::Struct(fields)) => format!(
+                                "{} {{ {} }}",
+                                variant.name,
+                                fields
+                                    .iter()
+                                    .map(|f| format!("{}: {}", f.name, f.field_type))
+                                    .collect::<Vec<_>>()
+                                    .join(", ")
+                            ),
+                        });
+                    }
+                });
+            }
+            Item::Trait(t) => {
+                self.line(format!("Trait({}){} @{}:{}", t.name, generics(&t.generics), t.span.line, t.span.column));
+                self.nested(|p| {
+                    for trait_item in &t.items {
+                        match trait_item {
+                            TraitItem::Method(f) => p.function(f),
+                            TraitItem::Type(name, _) => p.line(format!("Type({})", name)),
+                        }
+                    }
+                });
+            }
+            Item::Impl(i) => {
+                let header = match &i.trait_name {
+                    Some(trait_name) => format!("Impl({} for {})", trait_name, i.type_name),
+                    None => format!("Impl({})", i.type_name),
+                };
+                self.line(format!("{} @{}:{}", header, i.span.line, i.span.column));
+                self.nested(|p| {
+                    for function in &i.items {
+                        p.function(function);
+                    }
+                });
+            }
+            Item::Use(u) => {
+                let alias = u.alias.as_ref().map(|a| format!(" as {}", a)).unwrap_or_default();
+                let glob = if u.glob { "::*" } else { "" };
+                self.line(format!("Use({}{}{}) @{}:{}", u.path.join("::"), glob, alias, u.span.line, u.span.column));
+            }
+            Item::Mod(m) => {
+                self.line(format!("Mod({}) @{}:{}", m.name, m.span.line, m.span.column));
+                self.nested(|p| {
+                    for item in &m.items {
+                        p.item(item);
+                    }
+                });
+            }
+            Item::TypeAlias(t) => {
+                self.line(format!("TypeAlias({} = {}) @{}:{}", t.name, t.aliased_type, t.span.line, t.span.column));
+            }
+        }
+    }
+
+    fn function(&mut self, f: &Function) {
+        self.line(format!("Function({}){} @{}:{}", f.name, generics(&f.generics), f.span.line, f.span.column));
+        self.nested(|p| {
+            p.params("Params", &f.params);
+            if let Some(return_type) = &f.return_type {
+                p.line(format!("Returns {}", return_type));
+            }
+            p.block(&f.body);
+        });
+    }
+
+    fn params(&mut self, label: &str, params: &[Param]) {
+        if params.is_empty() {
+            return;
+        }
+        self.line(label);
+        self.nested(|p| {
+            for param in params {
+                p.line(format!("{}: {}", param.name, param.param_type));
+                if let Some(default) = &param.default {
+                    p.nested(|p| {
+                        p.line("Default");
+                        p.nested(|p| p.expr(default));
+                    });
+                }
+            }
+        });
+    }
+
+    fn block(&mut self, block: &Block) {
+        self.line("Block");
+        self.nested(|p| {
+            for stmt in &block.statements {
+                p.stmt(stmt);
+            }
+        });
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::Let { name, value, mutable, .. } => {
+                self.line(format!("Let({}{})", if *mutable { "mut " } else { "" }, name));
+                self.nested(|p| p.expr(value));
+            }
+            Stmt::Expr(expr) => {
+                self.line("Expr");
+                self.nested(|p| p.expr(expr));
+            }
+            Stmt::Tail(expr) => {
+                self.line("Tail");
+                self.nested(|p| p.expr(expr));
+            }
+            Stmt::Return(value, _) => {
+                self.line("Return");
+                if let Some(value) = value {
+                    self.nested(|p| p.expr(value));
+                }
+            }
+            Stmt::If { condition, then, else_, .. } => {
+                self.line("If");
+                self.nested(|p| {
+                    p.expr(condition);
+                    p.stmt(then);
+                    if let Some(else_) = else_ {
+                        p.line("Else");
+                        p.nested(|p| p.stmt(else_));
+                    }
+                });
+            }
+            Stmt::For { var, iter, body, .. } => {
+                self.line(format!("For({})", var));
+                self.nested(|p| {
+                    p.expr(iter);
+                    p.stmt(body);
+                });
+            }
+            Stmt::While { condition, body, .. } => {
+                self.line("While");
+                self.nested(|p| {
+                    p.expr(condition);
+                    p.stmt(body);
+                });
+            }
+            Stmt::Match { expr, arms, .. } => self.match_(expr, arms),
+            Stmt::Break(value, _) => {
+                self.line("Break");
+                if let Some(value) = value {
+                    self.nested(|p| p.expr(value));
+                }
+            }
+            Stmt::Block(block) => self.block(block),
+        }
+    }
+
+    fn match_(&mut self, expr: &Expr, arms: &[MatchArm]) {
+        self.line("Match");
+        self.nested(|p| {
+            p.expr(expr);
+            for arm in arms {
+                p.line(format!("Arm({})", pattern(&arm.pattern)));
+                p.nested(|p| {
+                    if let Some(guard) = &arm.guard {
+                        p.line("Guard");
+                        p.nested(|p| p.expr(guard));
+                    }
+                    p.expr(&arm.body);
+                });
+            }
+        });
+    }
+
+    fn expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Literal(lit, _) => self.line(literal(lit)),
+            Expr::Variable(name, _) => self.line(format!("Variable({})", name)),
+            Expr::Binary { left, op, right, .. } => {
+                self.line(format!("Binary({:?})", op));
+                self.nested(|p| {
+                    p.expr(left);
+                    p.expr(right);
+                });
+            }
+            Expr::Unary { op, expr, .. } => {
+                self.line(format!("Unary({:?})", op));
+                self.nested(|p| p.expr(expr));
+            }
+            Expr::Call { callee, args, .. } => {
+                self.line("Call");
+                self.nested(|p| {
+                    p.expr(callee);
+                    for arg in args {
+                        p.expr(arg);
+                    }
+                });
+            }
+            Expr::MethodCall { receiver, method, args, .. } => {
+                self.line(format!("MethodCall({})", method));
+                self.nested(|p| {
+                    p.expr(receiver);
+                    for arg in args {
+                        p.expr(arg);
+                    }
+                });
+            }
+            Expr::FieldAccess { object, field, .. } => {
+                self.line(format!("Field({})", field));
+                self.nested(|p| p.expr(object));
+            }
+            Expr::Index { object, index, .. } => {
+                self.line("Index");
+                self.nested(|p| {
+                    p.expr(object);
+                    p.expr(index);
+                });
+            }
+            Expr::JSXElement(element, _) => self.jsx(element),
+            Expr::Block(block, _) => self.block(block),
+            Expr::If { condition, then, else_, .. } => {
+                self.line("If");
+                self.nested(|p| {
+                    p.expr(condition);
+                    p.expr(then);
+                    if let Some(else_) = else_ {
+                        p.line("Else");
+                        p.nested(|p| p.expr(else_));
+                    }
+                });
+            }
+            Expr::Loop(body, _) => {
+                self.line("Loop");
+                self.nested(|p| p.block(body));
+            }
+            Expr::Match { expr, arms, .. } => self.match_(expr, arms),
+            Expr::Lambda { params, body, .. } => {
+                let names: Vec<&str> = params.iter().map(|p| p.name.as_str()).collect();
+                self.line(format!("Lambda({})", names.join(", ")));
+                self.nested(|p| p.expr(body));
+            }
+            Expr::Tuple(items, _) => {
+                self.line("Tuple");
+                self.nested(|p| items.iter().for_each(|item| p.expr(item)));
+            }
+            Expr::Array(items, _) => {
+                self.line("Array");
+                self.nested(|p| items.iter().for_each(|item| p.expr(item)));
+            }
+            Expr::Range { start, end, inclusive, .. } => {
+                self.line(if *inclusive { "Range(..=)" } else { "Range(..)" });
+                self.nested(|p| {
+                    p.expr(start);
+                    p.expr(end);
+                });
+            }
+            Expr::Struct { name, fields, .. } => {
+                self.line(format!("Struct({})", name));
+                self.nested(|p| {
+                    for (field, value) in fields {
+                        p.line(format!("{}:", field));
+                        p.nested(|p| p.expr(value));
+                    }
+                });
+            }
+        }
+    }
+
+    fn jsx(&mut self, element: &JSXElement) {
+        let (tag, props, children) = match element {
+            JSXElement::SelfClosing { tag, props, .. } => (tag, props, &[][..]),
+            JSXElement::WithChildren { tag, props, children, .. } => (tag, props, &children[..]),
+        };
+        self.line(format!("JSX({})", tag));
+        self.nested(|p| {
+            for prop in props {
+                match &prop.value {
+                    JSXPropValue::Literal(lit) => p.line(format!("{}={}", prop.name, literal(lit))),
+                    JSXPropValue::Bool(b) => p.line(format!("{}={}", prop.name, b)),
+                    JSXPropValue::Expr(expr) => {
+                        p.line(format!("{}=", prop.name));
+                        p.nested(|p| p.expr(expr));
+                    }
+                }
+            }
+            for child in children {
+                match child {
+                    JSXChild::Element(element) => p.jsx(element),
+                    JSXChild::Text(text, _) => p.line(format!("Text({:?})", text)),
+                    JSXChild::Expr(expr) => p.expr(expr),
+                }
+            }
+        });
+    }
+}
+
+fn generics(params: &[String]) -> String {
+    if params.is_empty() {
+        String::new()
+    } else {
+        format!("<{}>", params.join(", "))
+    }
+}
+
+fn literal(lit: &Literal) -> String {
+    match lit {
+        Literal::String(s) => format!("String({:?})", s),
+        Literal::Integer(n) => format!("Integer({})", n),
+        Literal::Number(n) => format!("Number({})", n),
+        Literal::Boolean(b) => format!("Boolean({})", b),
+        Literal::Char(c) => format!("Char({:?})", c),
+        Literal::Unit => "Unit".to_string(),
+    }
+}
+
+fn pattern(pattern: &Pattern) -> String {
+    match pattern {
+        Pattern::Ident(name, _) => name.clone(),
+        Pattern::Literal(lit, _) => literal(lit),
+        Pattern::Tuple(items, _) => format!("({})", items.iter().map(self::pattern).collect::<Vec<_>>().join(", ")),
+        Pattern::Struct { name, fields, .. } => format!(
+            "{} {{ {} }}",
+            name,
+            fields
+                .iter()
+                .map(|(field, p)| format!("{}: {}", field, self::pattern(p)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Pattern::Path(path, _) => path.join("::"),
+        Pattern::TupleStruct { name, elems, .. } => {
+            format!("{}({})", name, elems.iter().map(self::pattern).collect::<Vec<_>>().join(", "))
+        }
+        Pattern::Wildcard(_) => "_".to_string(),
+    }
+}
+
+fn join(types: &[Type]) -> String {
+    types.iter().map(Type::to_string).collect::<Vec<_>>().join(", ")
+}
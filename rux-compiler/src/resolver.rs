@@ -0,0 +1,135 @@
+use crate::ast::*;
+use crate::errors::{Error, Result};
+use crate::lexer::Span;
+use std::path::Path;
+
+/// Links the files of a project into one module tree and resolves `use`
+/// declarations between them.
+pub struct ModuleResolver;
+
+impl ModuleResolver {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Module path for a source file relative to the project root, e.g.
+    /// `components/button.rsx` becomes `["components", "button"]`. A
+    /// `mod.rsx` file holds the items of its directory's module.
+    pub fn module_path(root: &Path, file: &Path) -> Vec<String> {
+        let relative = file.strip_prefix(root).unwrap_or(file).with_extension("");
+        let mut path: Vec<String> = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().replace('-', "_"))
+            .collect();
+        if path.last().map(String::as_str) == Some("mod") {
+            path.pop();
+        }
+        path
+    }
+
+    /// Adds a file's items to the module at `path`, creating parent modules
+    /// as needed.
+    pub fn insert_module(&self, items: &mut Vec<Item>, path: &[String], file_items: Vec<Item>) {
+        let Some((name, rest)) = path.split_first() else {
+            items.extend(file_items);
+            return;
+        };
+
+        let index = items
+            .iter()
+            .position(|item| matches!(item, Item::Mod(m) if &m.name == name))
+            .unwrap_or_else(|| {
+                items.push(Item::Mod(Mod {
+                    name: name.clone(),
+                    items: Vec::new(),
+                    span: Span::new(0, 0, 1, 1),
+                }));
+                items.len() - 1
+            });
+
+        if let Item::Mod(module) = &mut items[index] {
+            self.insert_module(&mut module.items, rest, file_items);
+        }
+    }
+
+    /// Resolves every `use` in the tree and rewrites it to an absolute
+    /// `crate::` path. Relative paths are looked up from the current module
+    /// first and then from the project root.
+    pub fn resolve_imports(&self, items: &mut [Item]) -> Result<()> {
+        let root = items.to_vec();
+        self.resolve_in_module(&root, items, &mut Vec::new())
+    }
+
+    fn resolve_in_module(&self, root: &[Item], items: &mut [Item], current: &mut Vec<String>) -> Result<()> {
+        for item in items.iter_mut() {
+            match item {
+                Item::Use(use_stmt) => {
+                    let resolved = self.resolve_path(root, current, &use_stmt.path).ok_or_else(|| {
+                        Error::resolve(
+                            format!("Unresolved import '{}'", use_stmt.path.join("::")),
+                            String::new(),
+                            use_stmt.span.to_source_span(),
+                        )
+                    })?;
+                    use_stmt.path = std::iter::once("crate".to_string()).chain(resolved).collect();
+                }
+                Item::Mod(module) => {
+                    current.push(module.name.clone());
+                    self.resolve_in_module(root, &mut module.items, current)?;
+                    current.pop();
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn resolve_path(&self, root: &[Item], current: &[String], path: &[String]) -> Option<Vec<String>> {
+        let candidates: Vec<Vec<String>> = match path.first().map(String::as_str) {
+            Some("crate") => vec![path[1..].to_vec()],
+            Some("self") => vec![[current, &path[1..]].concat()],
+            Some("super") => {
+                let supers = path.iter().take_while(|s| *s == "super").count();
+                let base = current.get(..current.len().checked_sub(supers)?)?;
+                vec![[base, &path[supers..]].concat()]
+            }
+            _ => vec![[current, path].concat(), path.to_vec()],
+        };
+
+        candidates
+            .into_iter()
+            .find(|candidate| Self::item_exists(root, candidate))
+    }
+
+    fn item_exists(items: &[Item], path: &[String]) -> bool {
+        let Some((name, rest)) = path.split_first() else {
+            return false;
+        };
+
+        items.iter().any(|item| match item {
+            Item::Mod(module) if &module.name == name => {
+                rest.is_empty() || Self::item_exists(&module.items, rest)
+            }
+            _ => rest.is_empty() && Self::item_name(item) == Some(name.as_str()),
+        })
+    }
+
+    fn item_name(item: &Item) -> Option<&str> {
+        match item {
+            Item::Component(c) => Some(&c.name),
+            Item::Function(f) => Some(&f.name),
+            Item::Struct(s) => Some(&s.name),
+            Item::Enum(e) => Some(&e.name),
+            Item::Trait(t) => Some(&t.name),
+            Item::TypeAlias(t) => Some(&t.name),
+            Item::Mod(m) => Some(&m.name),
+            Item::Use(_) | Item::Impl(_) => None,
+        }
+    }
+}
+
+impl Default for ModuleResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,4 +1,5 @@
 use crate::ast::*;
+use crate::diagnostics::WarningCollector;
 use crate::errors::{Error, Result};
 use crate::lexer::Span;
 use std::collections::HashMap;
@@ -39,24 +40,282 @@ impl TypeEnvironment {
     }
 }
 
+impl Default for TypeEnvironment {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 pub struct TypeChecker {
     env: TypeEnvironment,
+    enums: HashMap<String, Enum>,
+    /// Struct definitions by name, for checking the fields of struct literals
+    structs: HashMap<String, Struct>,
+    /// Every item by absolute module path, with its value type for
+    /// functions and components
+    items: HashMap<Vec<String>, Option<Type>>,
+    /// Methods from `impl` blocks, keyed by the implementing type's name
+    methods: HashMap<String, Vec<Function>>,
+    /// Methods declared by each trait, with or without a default body
+    trait_methods: HashMap<String, Vec<Function>>,
+    /// Traits each type implements, keyed by type name
+    trait_impls: HashMap<String, Vec<String>>,
+    module_path: Vec<String>,
+    strict_imports: bool,
+    /// Enclosing loops, innermost last: the types of a `loop`'s `break`
+    /// values so far, or None for `for` and `while`
+    loop_breaks: Vec<Option<Vec<Type>>>,
+    /// Declared return type of the function being checked, which integer
+    /// literals in `return` and tail position must fit
+    return_type: Option<Type>,
+    warnings: WarningCollector,
 }
 
 impl TypeChecker {
     pub fn new() -> Self {
         Self {
             env: TypeEnvironment::new(),
+            enums: HashMap::new(),
+            structs: HashMap::new(),
+            items: HashMap::new(),
+            methods: HashMap::new(),
+            trait_methods: HashMap::new(),
+            trait_impls: HashMap::new(),
+            module_path: Vec::new(),
+            strict_imports: false,
+            loop_breaks: Vec::new(),
+            return_type: None,
+            warnings: WarningCollector::new(),
+        }
+    }
+    
+    /// Checker for a whole project, where every `use` must resolve to an
+    /// item in the AST. Single-file checks leave unknown imports alone since
+    /// they may name external crates.
+    pub fn new_for_project() -> Self {
+        Self {
+            strict_imports: true,
+            ..Self::new()
         }
     }
     
+    /// Makes an item from outside the checked AST, such as one defined in
+    /// another file, importable under its absolute module path
+    pub fn define_external(&mut self, path: Vec<String>, value_type: Option<Type>) {
+        self.items.insert(path, value_type);
+    }
+    
+    /// Warnings reported by the last check, leaving the collector empty
+    pub fn take_warnings(&mut self) -> WarningCollector {
+        std::mem::take(&mut self.warnings)
+    }
+    
     pub fn check(&mut self, ast: &AST) -> Result<()> {
-        for item in &ast.items {
+        // First pass: collect type definitions so items can refer to
+        // types declared later in the file
+        self.collect_definitions(&ast.items, &mut Vec::new());
+        self.bind_module_items();
+        self.check_items(&ast.items)
+    }
+    
+    /// Checks every top-level item even after one fails, returning the
+    /// index and error of each item that didn't check
+    pub fn check_each(&mut self, ast: &AST) -> Vec<(usize, Error)> {
+        self.collect_definitions(&ast.items, &mut Vec::new());
+        self.bind_module_items();
+        
+        let mut failures = Vec::new();
+        for (i, item) in ast.items.iter().enumerate() {
+            if let Item::Use(use_stmt) = item {
+                if let Err(e) = self.check_use(use_stmt) {
+                    failures.push((i, e));
+                }
+            }
+        }
+        
+        for (i, item) in ast.items.iter().enumerate() {
+            // A failed item can leave its scope behind
+            let env = self.env.clone();
+            if let Err(e) = self.check_item(item) {
+                self.env = env;
+                failures.push((i, e));
+            }
+        }
+        failures
+    }
+    
+    fn check_items(&mut self, items: &[Item]) -> Result<()> {
+        // Imports apply to the whole module, so bind them before checking
+        // any item that might use them
+        for item in items {
+            if let Item::Use(use_stmt) = item {
+                self.check_use(use_stmt)?;
+            }
+        }
+        
+        for item in items {
             self.check_item(item)?;
         }
         Ok(())
     }
     
+    fn collect_definitions(&mut self, items: &[Item], path: &mut Vec<String>) {
+        for item in items {
+            let (name, value_type) = match item {
+                Item::Enum(enum_def) => {
+                    self.enums.insert(enum_def.name.clone(), enum_def.clone());
+                    (&enum_def.name, None)
+                }
+                Item::Function(function) => (&function.name, Some(Self::function_type(function))),
+                Item::Component(component) => (&component.name, Some(Self::component_type(component))),
+                Item::Struct(struct_def) => {
+                    self.structs.insert(struct_def.name.clone(), struct_def.clone());
+                    (&struct_def.name, None)
+                }
+                Item::Trait(trait_def) => {
+                    let methods = trait_def.items.iter().filter_map(|item| match item {
+                        TraitItem::Method(method) => Some(method.clone()),
+                        TraitItem::Type(..) => None,
+                    });
+                    self.trait_methods.insert(trait_def.name.clone(), methods.collect());
+                    (&trait_def.name, None)
+                }
+                Item::TypeAlias(alias) => (&alias.name, None),
+                Item::Mod(mod_def) => {
+                    path.push(mod_def.name.clone());
+                    self.collect_definitions(&mod_def.items, path);
+                    path.pop();
+                    (&mod_def.name, None)
+                }
+                Item::Impl(impl_def) => {
+                    self.methods
+                        .entry(impl_def.type_name.clone())
+                        .or_default()
+                        .extend(impl_def.items.iter().cloned());
+                    if let Some(trait_name) = &impl_def.trait_name {
+                        self.trait_impls
+                            .entry(impl_def.type_name.clone())
+                            .or_default()
+                            .push(trait_name.clone());
+                    }
+                    continue;
+                }
+                Item::Use(_) => continue,
+            };
+            
+            let mut item_path = path.clone();
+            item_path.push(name.clone());
+            self.items.insert(item_path, value_type);
+        }
+    }
+    
+    /// Value type of a function, as seen by callers
+    pub fn function_type(function: &Function) -> Type {
+        let return_type = function.return_type.clone().unwrap_or(Type {
+            kind: TypeKind::Unit,
+            span: function.span,
+        });
+        Type {
+            kind: TypeKind::Function {
+                params: function.params.iter().map(|p| p.param_type.clone()).collect(),
+                return_type: Box::new(return_type),
+            },
+            span: function.span,
+        }
+    }
+    
+    pub fn component_type(component: &Component) -> Type {
+        Type {
+            kind: TypeKind::Function {
+                params: component.props.iter().map(|p| p.param_type.clone()).collect(),
+                return_type: Box::new(component.return_type.clone()),
+            },
+            span: component.span,
+        }
+    }
+    
+    /// Binds the functions and components declared directly in the current
+    /// module so they can be called by name.
+    fn bind_module_items(&mut self) {
+        let depth = self.module_path.len();
+        let bindings: Vec<(String, Type)> = self
+            .items
+            .iter()
+            .filter(|(path, _)| path.len() == depth + 1 && path.starts_with(&self.module_path))
+            .filter_map(|(path, ty)| Some((path[depth].clone(), ty.clone()?)))
+            .collect();
+        
+        for (name, ty) in bindings {
+            self.env.bind(name, ty);
+        }
+    }
+    
+    /// Binds the name introduced by a `use`. Paths may be absolute
+    /// (`crate::`), relative to `self`/`super`, or relative to the current
+    /// module and then the root.
+    fn check_use(&mut self, use_stmt: &Use) -> Result<()> {
+        let path = &use_stmt.path;
+        let candidates: Vec<Vec<String>> = match path.first().map(String::as_str) {
+            Some("crate") => vec![path[1..].to_vec()],
+            Some("self") => vec![[&self.module_path[..], &path[1..]].concat()],
+            Some("super") => {
+                let supers = path.iter().take_while(|s| *s == "super").count();
+                let base_len = self.module_path.len().saturating_sub(supers);
+                vec![[&self.module_path[..base_len], &path[supers..]].concat()]
+            }
+            _ => vec![[&self.module_path[..], &path[..]].concat(), path.clone()],
+        };
+        
+        if use_stmt.glob {
+            return self.check_glob_use(use_stmt, &candidates);
+        }
+        
+        match candidates.iter().find_map(|candidate| self.items.get(candidate)) {
+            Some(value_type) => {
+                if let (Some(ty), Some(last)) = (value_type, path.last()) {
+                    let name = use_stmt.alias.clone().unwrap_or_else(|| last.clone());
+                    self.env.bind(name, ty.clone());
+                }
+                Ok(())
+            }
+            None if self.strict_imports => Err(Error::type_error(
+                format!("Unresolved import '{}'", path.join("::")),
+                String::new(),
+                use_stmt.span.to_source_span(),
+            )),
+            None => Ok(()),
+        }
+    }
+    
+    /// Binds every item directly inside the first candidate module that
+    /// has any
+    fn check_glob_use(&mut self, use_stmt: &Use, candidates: &[Vec<String>]) -> Result<()> {
+        for candidate in candidates {
+            let members: Vec<(String, Type)> = self
+                .items
+                .iter()
+                .filter(|(path, _)| path.len() == candidate.len() + 1 && path.starts_with(candidate))
+                .filter_map(|(path, ty)| Some((path.last()?.clone(), ty.clone()?)))
+                .collect();
+            let is_module = self.items.keys().any(|path| path.len() > candidate.len() && path.starts_with(candidate));
+            if is_module {
+                for (name, ty) in members {
+                    self.env.bind(name, ty);
+                }
+                return Ok(());
+            }
+        }
+        
+        if self.strict_imports {
+            return Err(Error::type_error(
+                format!("Unresolved import '{}::*'", use_stmt.path.join("::")),
+                String::new(),
+                use_stmt.span.to_source_span(),
+            ));
+        }
+        Ok(())
+    }
+    
     fn check_item(&mut self, item: &Item) -> Result<()> {
         match item {
             Item::Component(component) => self.check_component(component),
@@ -65,11 +324,14 @@ impl TypeChecker {
             Item::Enum(enum_def) => self.check_enum(enum_def),
             Item::Trait(trait_def) => self.check_trait(trait_def),
             Item::Impl(impl_def) => self.check_impl(impl_def),
-            Item::Use(_) => Ok(()), // Use statements don't need type checking
+            Item::Use(_) => Ok(()), // Bound up front by check_items
             Item::Mod(mod_def) => {
-                let mut new_env = TypeEnvironment::with_parent(self.env.clone());
+                let new_env = TypeEnvironment::with_parent(self.env.clone());
                 let old_env = std::mem::replace(&mut self.env, new_env);
+                self.module_path.push(mod_def.name.clone());
+                self.bind_module_items();
                 let result = self.check_mod(mod_def);
+                self.module_path.pop();
                 self.env = old_env;
                 result
             }
@@ -78,9 +340,20 @@ impl TypeChecker {
     }
     
     fn check_component(&mut self, component: &Component) -> Result<()> {
+        self.check_param_defaults(&component.props)?;
+        
         // Create new scope for component parameters
         let mut param_env = TypeEnvironment::with_parent(self.env.clone());
         
+        // JSX children passed at the usage site, unless a prop takes the name
+        param_env.bind(
+            "children".to_string(),
+            Type {
+                kind: TypeKind::Ident("Element".to_string()),
+                span: component.span,
+            },
+        );
+        
         // Add parameters to environment
         for param in &component.props {
             param_env.bind(param.name.clone(), param.param_type.clone());
@@ -103,7 +376,8 @@ impl TypeChecker {
         let body_type = self.check_expression(&component.body)?;
         self.env = old_env;
         
-        // Verify body type matches return type
+        // Verify body type matches return type. A block body is blamed on
+        // the expression it ends in, since that is what has the wrong type.
         if !self.types_match(&body_type, &component.return_type) {
             return Err(Error::type_error(
                 format!(
@@ -111,14 +385,28 @@ impl TypeChecker {
                     component.name, body_type.kind
                 ),
                 String::new(),
-                component.body.span().to_source_span(),
+                Self::result_span(&component.body).to_source_span(),
             ));
         }
         
         Ok(())
     }
     
+    /// Span of the expression that produces `expr`'s value: the tail of a
+    /// block, or the block itself when it has no tail
+    fn result_span(expr: &Expr) -> Span {
+        match expr {
+            Expr::Block(block, _) => match block.statements.last() {
+                Some(Stmt::Tail(tail)) => Self::result_span(tail),
+                _ => expr.span(),
+            },
+            _ => expr.span(),
+        }
+    }
+    
     fn check_function(&mut self, function: &Function) -> Result<()> {
+        self.check_param_defaults(&function.params)?;
+        
         // Create new scope for function parameters
         let mut param_env = TypeEnvironment::with_parent(self.env.clone());
         
@@ -128,23 +416,51 @@ impl TypeChecker {
         
         // Check function body
         let old_env = std::mem::replace(&mut self.env, param_env);
-        self.check_block(&function.body)?;
+        let old_return = std::mem::replace(&mut self.return_type, function.return_type.clone());
+        let result = match &function.return_type {
+            Some(return_type) => self.check_block_against(&function.body, return_type),
+            None => self.check_block(&function.body),
+        };
+        self.return_type = old_return;
         self.env = old_env;
+        result?;
         
         Ok(())
     }
     
-    fn check_struct(&mut self, struct_def: &Struct) -> Result<()> {
+    /// Default values are checked in the enclosing scope, so they can't
+    /// refer to other parameters
+    fn check_param_defaults(&mut self, params: &[Param]) -> Result<()> {
+        for param in params {
+            let Some(default) = &param.default else {
+                continue;
+            };
+            let default_type = self.check_literal_against(default, &param.param_type)?;
+            if !self.types_match(&param.param_type, &default_type) {
+                return Err(Error::type_error(
+                    format!(
+                        "Default value for '{}' must be {}, found {}",
+                        param.name, param.param_type, default_type
+                    ),
+                    String::new(),
+                    default.span().to_source_span(),
+                ));
+            }
+        }
+        Ok(())
+    }
+    
+    fn check_struct(&mut self, _struct_def: &Struct) -> Result<()> {
         // Struct definitions don't need type checking beyond syntax
         Ok(())
     }
     
-    fn check_enum(&mut self, enum_def: &Enum) -> Result<()> {
+    fn check_enum(&mut self, _enum_def: &Enum) -> Result<()> {
         // Enum definitions don't need type checking beyond syntax
         Ok(())
     }
     
-    fn check_trait(&mut self, trait_def: &Trait) -> Result<()> {
+    fn check_trait(&mut self, _trait_def: &Trait) -> Result<()> {
         // Trait definitions don't need type checking beyond syntax
         Ok(())
     }
@@ -158,22 +474,43 @@ impl TypeChecker {
     }
     
     fn check_mod(&mut self, mod_def: &Mod) -> Result<()> {
-        for item in &mod_def.items {
-            self.check_item(item)?;
-        }
-        Ok(())
+        self.check_items(&mod_def.items)
     }
     
-    fn check_type_alias(&mut self, alias: &TypeAlias) -> Result<()> {
+    fn check_type_alias(&mut self, _alias: &TypeAlias) -> Result<()> {
         // Type aliases don't need type checking beyond syntax
         Ok(())
     }
     
-    fn check_block(&mut self, block: &Block) -> Result<()> {
+    fn check_block(&mut self, block: &Block) -> Result<Type> {
+        let mut block_type = Type {
+            kind: TypeKind::Unit,
+            span: block.span,
+        };
         for stmt in &block.statements {
-            self.check_statement(stmt)?;
+            if let Stmt::Tail(expr) = stmt {
+                block_type = self.check_expression(expr)?;
+            } else {
+                self.check_statement(stmt)?;
+            }
         }
-        Ok(())
+        Ok(block_type)
+    }
+    
+    /// Like `check_block`, but an integer literal tail must fit `expected`
+    fn check_block_against(&mut self, block: &Block, expected: &Type) -> Result<Type> {
+        let mut block_type = Type {
+            kind: TypeKind::Unit,
+            span: block.span,
+        };
+        for stmt in &block.statements {
+            if let Stmt::Tail(expr) = stmt {
+                block_type = self.check_literal_against(expr, expected)?;
+            } else {
+                self.check_statement(stmt)?;
+            }
+        }
+        Ok(block_type)
     }
     
     fn check_statement(&mut self, stmt: &Stmt) -> Result<()> {
@@ -183,15 +520,44 @@ impl TypeChecker {
                 self.env.bind(name.clone(), value_type);
                 Ok(())
             }
-            Stmt::Expr(expr) => {
+            Stmt::Expr(expr) | Stmt::Tail(expr) => {
                 self.check_expression(expr)?;
                 Ok(())
             }
             Stmt::Return(Some(expr), _) => {
-                self.check_expression(expr)?;
+                match self.return_type.clone() {
+                    Some(return_type) => self.check_literal_against(expr, &return_type)?,
+                    None => self.check_expression(expr)?,
+                };
                 Ok(())
             }
             Stmt::Return(None, _) => Ok(()),
+            Stmt::Break(value, span) => {
+                let value_type = match value {
+                    Some(expr) => self.check_expression(expr)?,
+                    None => Type {
+                        kind: TypeKind::Unit,
+                        span: *span,
+                    },
+                };
+                match self.loop_breaks.last_mut() {
+                    Some(Some(breaks)) => {
+                        breaks.push(value_type);
+                        Ok(())
+                    }
+                    Some(None) if value.is_some() => Err(Error::type_error(
+                        "break with a value only works inside loop",
+                        String::new(),
+                        span.to_source_span(),
+                    )),
+                    Some(None) => Ok(()),
+                    None => Err(Error::type_error(
+                        "break outside of a loop",
+                        String::new(),
+                        span.to_source_span(),
+                    )),
+                }
+            }
             Stmt::If { condition, then, else_, .. } => {
                 let cond_type = self.check_expression(condition)?;
                 if !self.is_bool_type(&cond_type) {
@@ -207,11 +573,28 @@ impl TypeChecker {
                 }
                 Ok(())
             }
-            Stmt::For { iter, body, .. } => {
+            Stmt::For { var, iter, body, .. } => {
                 let iter_type = self.check_expression(iter)?;
-                // Check that iter is iterable (simplified)
-                self.check_statement(body)?;
-                Ok(())
+                if self.is_numeric_type(&iter_type) || self.is_bool_type(&iter_type) {
+                    return Err(Error::type_error(
+                        format!("Cannot iterate over {}", iter_type),
+                        String::new(),
+                        iter.span().to_source_span(),
+                    ));
+                }
+                
+                // The loop variable is scoped to the body. Its type is only
+                // known for built-in iterables.
+                let mut loop_env = TypeEnvironment::with_parent(self.env.clone());
+                if let Some(item_type) = self.item_type(&iter_type) {
+                    loop_env.bind(var.clone(), item_type);
+                }
+                let old_env = std::mem::replace(&mut self.env, loop_env);
+                self.loop_breaks.push(None);
+                let result = self.check_statement(body);
+                self.loop_breaks.pop();
+                self.env = old_env;
+                result
             }
             Stmt::While { condition, body, .. } => {
                 let cond_type = self.check_expression(condition)?;
@@ -222,17 +605,23 @@ impl TypeChecker {
                         condition.span().to_source_span(),
                     ));
                 }
-                self.check_statement(body)?;
-                Ok(())
+                self.loop_breaks.push(None);
+                let result = self.check_statement(body);
+                self.loop_breaks.pop();
+                result
             }
-            Stmt::Match { expr, arms, .. } => {
+            Stmt::Match { expr, arms, span } => {
                 let expr_type = self.check_expression(expr)?;
                 for arm in arms {
                     self.check_match_arm(arm, &expr_type)?;
                 }
+                self.warn_unreachable_arms(arms, &expr_type);
+                self.check_exhaustive(arms, &expr_type, *span)
+            }
+            Stmt::Block(block) => {
+                self.check_block(block)?;
                 Ok(())
             }
-            Stmt::Block(block) => self.check_block(block),
         }
     }
     
@@ -257,14 +646,27 @@ impl TypeChecker {
             }
             Expr::Call { callee, args, span } => {
                 let callee_type = self.check_expression(callee)?;
-                let arg_types: Result<Vec<Type>> = args.iter().map(|a| self.check_expression(a)).collect();
-                let arg_types = arg_types?;
+                let arg_types = match &callee_type.kind {
+                    TypeKind::Function { params, .. } if params.len() == args.len() => args
+                        .iter()
+                        .zip(params)
+                        .map(|(arg, param)| self.check_literal_against(arg, param))
+                        .collect::<Result<Vec<Type>>>()?,
+                    _ => args.iter().map(|a| self.check_expression(a)).collect::<Result<Vec<Type>>>()?,
+                };
                 self.type_of_call(&callee_type, &arg_types, *span)
             }
             Expr::MethodCall { receiver, method, args, span } => {
                 let receiver_type = self.check_expression(receiver)?;
-                let arg_types: Result<Vec<Type>> = args.iter().map(|a| self.check_expression(a)).collect();
-                let arg_types = arg_types?;
+                let param_types = self.method_param_types(&receiver_type, method);
+                let arg_types = match param_types {
+                    Some(params) if params.len() == args.len() => args
+                        .iter()
+                        .zip(&params)
+                        .map(|(arg, param)| self.check_literal_against(arg, param))
+                        .collect::<Result<Vec<Type>>>()?,
+                    _ => args.iter().map(|a| self.check_expression(a)).collect::<Result<Vec<Type>>>()?,
+                };
                 self.type_of_method_call(&receiver_type, method, &arg_types, *span)
             }
             Expr::FieldAccess { object, field, span } => {
@@ -276,20 +678,40 @@ impl TypeChecker {
                 let index_type = self.check_expression(index)?;
                 self.type_of_index(&object_type, &index_type, *span)
             }
-            Expr::JSXElement(jsx, span) => {
+            Expr::JSXElement(_jsx, span) => {
                 // JSX elements always return Element type
                 Ok(Type {
                     kind: TypeKind::Ident("Element".to_string()),
                     span: *span,
                 })
             }
-            Expr::Block(block, span) => {
-                self.check_block(block)?;
-                // Block returns unit type unless last expression
-                Ok(Type {
+            Expr::Block(block, _) => {
+                // Block returns unit type unless it ends in a tail expression
+                self.check_block(block)
+            }
+            Expr::Loop(block, span) => {
+                self.loop_breaks.push(Some(Vec::new()));
+                let result = self.check_block(block);
+                let breaks = self.loop_breaks.pop().flatten().unwrap_or_default();
+                result?;
+                // Every `break` must carry the same type, which is the
+                // loop's value
+                let mut loop_type = Type {
                     kind: TypeKind::Unit,
                     span: *span,
-                })
+                };
+                for (i, break_type) in breaks.into_iter().enumerate() {
+                    if i == 0 || Self::is_integer_literal_type(&loop_type) && self.types_match(&loop_type, &break_type) {
+                        loop_type = break_type;
+                    } else if !self.types_match(&loop_type, &break_type) {
+                        return Err(Error::type_error(
+                            format!("Loop breaks with mismatched types: expected {}, found {}", loop_type, break_type),
+                            String::new(),
+                            break_type.span.to_source_span(),
+                        ));
+                    }
+                }
+                Ok(loop_type)
             }
             Expr::If { condition, then, else_, span } => {
                 let cond_type = self.check_expression(condition)?;
@@ -320,6 +742,8 @@ impl TypeChecker {
                     let arm_type = self.check_match_arm_expr(arm, &expr_type)?;
                     arm_types.push(arm_type);
                 }
+                self.warn_unreachable_arms(arms, &expr_type);
+                self.check_exhaustive(arms, &expr_type, *span)?;
                 // All arms must have the same type
                 if let Some(first_type) = arm_types.first() {
                     for arm_type in &arm_types[1..] {
@@ -346,8 +770,15 @@ impl TypeChecker {
                     lambda_env.bind(param.name.clone(), param.param_type.clone());
                 }
                 let old_env = std::mem::replace(&mut self.env, lambda_env);
-                let body_type = self.check_expression(body)?;
+                // A lambda body can't break out of the loops around it, and
+                // its `return`s leave the lambda, not the function
+                let outer_loops = std::mem::take(&mut self.loop_breaks);
+                let outer_return = self.return_type.take();
+                let body_type = self.check_expression(body);
+                self.return_type = outer_return;
+                self.loop_breaks = outer_loops;
                 self.env = old_env;
+                let body_type = body_type?;
                 
                 let param_types: Vec<Type> = params.iter().map(|p| p.param_type.clone()).collect();
                 Ok(Type {
@@ -365,6 +796,22 @@ impl TypeChecker {
                     span: *span,
                 })
             }
+            Expr::Range { start, end, span, .. } => {
+                let start_type = self.check_expression(start)?;
+                let end_type = self.check_expression(end)?;
+                if !self.is_numeric_type(&start_type) || !self.types_match(&start_type, &end_type) {
+                    return Err(Error::type_error(
+                        format!("Range bounds must be numbers of the same type, found {} and {}", start_type, end_type),
+                        String::new(),
+                        span.to_source_span(),
+                    ));
+                }
+                let item_type = if Self::is_integer_literal_type(&start_type) { end_type } else { start_type };
+                Ok(Type {
+                    kind: TypeKind::Range(Box::new(item_type)),
+                    span: *span,
+                })
+            }
             Expr::Array(exprs, span) => {
                 if exprs.is_empty() {
                     return Err(Error::type_error(
@@ -390,9 +837,16 @@ impl TypeChecker {
                 })
             }
             Expr::Struct { name, fields, span } => {
-                // Check struct fields
                 for (field_name, field_expr) in fields {
-                    self.check_expression(field_expr)?;
+                    let field_type = self
+                        .structs
+                        .get(name)
+                        .and_then(|s| s.fields.iter().find(|f| &f.name == field_name))
+                        .map(|f| f.field_type.clone());
+                    match field_type {
+                        Some(field_type) => self.check_literal_against(field_expr, &field_type)?,
+                        None => self.check_expression(field_expr)?,
+                    };
                 }
                 Ok(Type {
                     kind: TypeKind::Ident(name.clone()),
@@ -403,18 +857,275 @@ impl TypeChecker {
     }
     
     fn check_match_arm(&mut self, arm: &MatchArm, expr_type: &Type) -> Result<()> {
-        // Simplified pattern matching type checking
-        self.check_expression(&arm.body)?;
+        self.check_match_arm_expr(arm, expr_type)?;
         Ok(())
     }
     
-    fn check_match_arm_expr(&mut self, arm: &MatchArm, _expr_type: &Type) -> Result<Type> {
-        self.check_expression(&arm.body)
+    fn check_match_arm_expr(&mut self, arm: &MatchArm, expr_type: &Type) -> Result<Type> {
+        // Names bound by the pattern are only visible inside the arm
+        let mut arm_env = TypeEnvironment::with_parent(self.env.clone());
+        self.bind_pattern(&arm.pattern, expr_type, &mut arm_env)?;
+        let old_env = std::mem::replace(&mut self.env, arm_env);
+        let result = match &arm.guard {
+            Some(guard) => self.check_expression(guard).and_then(|_| self.check_expression(&arm.body)),
+            None => self.check_expression(&arm.body),
+        };
+        self.env = old_env;
+        result
+    }
+    
+    fn bind_pattern(&self, pattern: &Pattern, expr_type: &Type, env: &mut TypeEnvironment) -> Result<()> {
+        match pattern {
+            Pattern::Ident(name, _) => {
+                if !self.is_variant_name(expr_type, name) {
+                    env.bind(name.clone(), expr_type.clone());
+                }
+            }
+            Pattern::Tuple(patterns, _) => {
+                if let TypeKind::Tuple(types) = &expr_type.kind {
+                    for (pattern, ty) in patterns.iter().zip(types) {
+                        self.bind_pattern(pattern, ty, env)?;
+                    }
+                }
+            }
+            Pattern::TupleStruct { name, elems, span } => {
+                let fields = self.constructor_fields(name, expr_type).ok_or_else(|| {
+                    Error::type_error(
+                        format!("Pattern '{}(..)' does not match type {}", name, expr_type),
+                        String::new(),
+                        span.to_source_span(),
+                    )
+                })?;
+                if fields.len() != elems.len() {
+                    return Err(Error::type_error(
+                        format!("Pattern '{}' expects {} field(s), found {}", name, fields.len(), elems.len()),
+                        String::new(),
+                        span.to_source_span(),
+                    ));
+                }
+                for (elem, ty) in elems.iter().zip(&fields) {
+                    self.bind_pattern(elem, ty, env)?;
+                }
+            }
+            Pattern::Struct { .. } | Pattern::Literal(..) | Pattern::Path(..) | Pattern::Wildcard(_) => {}
+        }
+        Ok(())
+    }
+    
+    /// Field types of the constructor `name` when destructuring a value of
+    /// `ty`, e.g. `[T]` for `Some` on an `Option<T>`. None if `ty` has no
+    /// such tuple variant.
+    fn constructor_fields(&self, name: &str, ty: &Type) -> Option<Vec<Type>> {
+        let variant = name.rsplit("::").next().unwrap_or(name);
+        match &ty.kind {
+            TypeKind::Reference { inner, .. } => self.constructor_fields(name, inner),
+            TypeKind::Option(inner) => (variant == "Some").then(|| vec![(**inner).clone()]),
+            TypeKind::Result { ok, err } => match variant {
+                "Ok" => Some(vec![(**ok).clone()]),
+                "Err" => Some(vec![(**err).clone()]),
+                _ => None,
+            },
+            _ => match &self.enum_variant_named(ty, variant)?.data {
+                Some(EnumVariantData::Tuple(types)) => Some(types.clone()),
+                _ => None,
+            },
+        }
+    }
+    
+    /// Whether a bare identifier pattern names a variant of `ty`, like
+    /// `None` for an `Option`, rather than binding a new name
+    fn is_variant_name(&self, ty: &Type, name: &str) -> bool {
+        self.enum_variant_named(ty, name).is_some()
+            || (name == "None" && builtin_variants(ty).is_some_and(|(type_name, _)| type_name == "Option"))
+    }
+    
+    /// Whether `pattern` matches every value of `ty`
+    fn is_irrefutable(&self, pattern: &Pattern, ty: &Type) -> bool {
+        match pattern {
+            Pattern::Wildcard(_) => true,
+            Pattern::Ident(name, _) => !self.is_variant_name(ty, name),
+            Pattern::Tuple(patterns, _) => match &ty.kind {
+                TypeKind::Tuple(types) => patterns.iter().zip(types).all(|(p, t)| self.is_irrefutable(p, t)),
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+    
+    fn enum_of_type(&self, ty: &Type) -> Option<&Enum> {
+        let name = match &ty.kind {
+            TypeKind::Ident(name) => name,
+            TypeKind::Path(path) => path.last()?,
+            TypeKind::Reference { inner, .. } => return self.enum_of_type(inner),
+            _ => return None,
+        };
+        self.enums.get(name)
+    }
+    
+    fn enum_variant_named(&self, ty: &Type, name: &str) -> Option<&EnumVariant> {
+        self.enum_of_type(ty)?.variants.iter().find(|v| v.name == name)
+    }
+    
+    /// Warns about arms that follow an unguarded catch-all arm.
+    fn warn_unreachable_arms(&mut self, arms: &[MatchArm], expr_type: &Type) {
+        let catch_all = arms.iter().position(|arm| {
+            arm.guard.is_none()
+                && match &arm.pattern {
+                    Pattern::Wildcard(_) => true,
+                    Pattern::Ident(name, _) => !self.is_variant_name(expr_type, name),
+                    _ => false,
+                }
+        });
+        
+        if let Some(index) = catch_all {
+            for arm in &arms[index + 1..] {
+                self.warnings.warn("Unreachable match arm", arm.span);
+            }
+        }
+    }
+    
+    /// Verifies that a match over an enum value covers every variant.
+    fn check_exhaustive(&self, arms: &[MatchArm], expr_type: &Type, span: Span) -> Result<()> {
+        let (type_name, variants): (&str, Vec<&str>) = match self.enum_of_type(expr_type) {
+            Some(enum_def) => (&enum_def.name, enum_def.variants.iter().map(|v| v.name.as_str()).collect()),
+            None => match builtin_variants(expr_type) {
+                Some((type_name, variants)) => (type_name, variants.to_vec()),
+                None => return Ok(()),
+            },
+        };
+        
+        let mut covered = Vec::new();
+        for arm in arms {
+            // Guarded arms may not match, so they never count as coverage
+            if arm.guard.is_some() {
+                continue;
+            }
+            match &arm.pattern {
+                Pattern::Wildcard(_) => return Ok(()),
+                Pattern::Ident(name, _) => {
+                    if self.is_variant_name(expr_type, name) {
+                        covered.push(name.as_str());
+                    } else {
+                        // A plain binding matches anything
+                        return Ok(());
+                    }
+                }
+                Pattern::Path(path, _) => {
+                    if let Some((variant, prefix)) = path.split_last() {
+                        if prefix.last().is_none_or(|enum_name| enum_name == type_name) {
+                            covered.push(variant.as_str());
+                        }
+                    }
+                }
+                Pattern::Struct { name, .. } => {
+                    if let Some(variant) = variant_of(name, type_name) {
+                        covered.push(variant);
+                    }
+                }
+                Pattern::TupleStruct { name, elems, .. } => {
+                    // `Some(x)` covers `Some`, but `Some(Some(x))` doesn't
+                    let fields = self.constructor_fields(name, expr_type).unwrap_or_default();
+                    if elems.iter().zip(&fields).all(|(elem, ty)| self.is_irrefutable(elem, ty)) {
+                        if let Some(variant) = variant_of(name, type_name) {
+                            covered.push(variant);
+                        }
+                    }
+                }
+                Pattern::Literal(..) | Pattern::Tuple(..) => {}
+            }
+        }
+        
+        let missing: Vec<&str> = variants
+            .into_iter()
+            .filter(|name| !covered.contains(name))
+            .collect();
+        
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::type_error(
+                format!(
+                    "Non-exhaustive match on '{}', missing {}",
+                    type_name,
+                    missing
+                        .iter()
+                        .map(|name| format!("{}::{}", type_name, name))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+                String::new(),
+                span.to_source_span(),
+            ))
+        }
+    }
+    
+    /// Checks `expr` where a value of type `expected` is wanted. An integer
+    /// literal takes on `expected` if it is a number type the value fits in.
+    fn check_literal_against(&mut self, expr: &Expr, expected: &Type) -> Result<Type> {
+        let Some(value) = Self::integer_literal(expr) else {
+            return self.check_expression(expr);
+        };
+        let span = expr.span();
+        let TypeKind::Ident(name) = &expected.kind else {
+            return Err(Error::type_error(
+                format!("Expected {}, found integer literal {}", expected, value),
+                String::new(),
+                span.to_source_span(),
+            ));
+        };
+        match name.as_str() {
+            "f32" | "f64" => {}
+            _ => match integer_range(name) {
+                Some((min, max)) if (min..=max).contains(&(value as i128)) => {}
+                Some(_) => {
+                    return Err(Error::type_error(
+                        format!("Literal {} is out of range for {}", value, name),
+                        String::new(),
+                        span.to_source_span(),
+                    ));
+                }
+                None if self.is_known_type(name) => {
+                    return Err(Error::type_error(
+                        format!("Expected {}, found integer literal {}", expected, value),
+                        String::new(),
+                        span.to_source_span(),
+                    ));
+                }
+                // Generic parameters and aliases would need resolving first
+                None => return self.check_expression(expr),
+            },
+        }
+        Ok(Type {
+            kind: expected.kind.clone(),
+            span,
+        })
+    }
+    
+    /// Whether `name` is a concrete type rather than a generic parameter
+    fn is_known_type(&self, name: &str) -> bool {
+        matches!(name, "bool" | "char" | "str" | "String" | "Element")
+            || self.structs.contains_key(name)
+            || self.enums.contains_key(name)
+    }
+    
+    /// Value of an integer literal, possibly negated
+    fn integer_literal(expr: &Expr) -> Option<i64> {
+        match expr {
+            Expr::Literal(Literal::Integer(n), _) => Some(*n),
+            Expr::Unary { op: UnaryOp::Neg, expr, .. } => Self::integer_literal(expr).and_then(i64::checked_neg),
+            _ => None,
+        }
+    }
+    
+    /// Type of an integer literal no context has fixed yet
+    fn is_integer_literal_type(ty: &Type) -> bool {
+        matches!(&ty.kind, TypeKind::Ident(name) if name == INTEGER_LITERAL)
     }
     
     fn type_of_literal(&self, lit: &Literal, span: Span) -> Type {
         let kind = match lit {
             Literal::String(_) => TypeKind::Ident("String".to_string()),
+            Literal::Integer(_) => TypeKind::Ident(INTEGER_LITERAL.to_string()),
             Literal::Number(_) => TypeKind::Ident("f64".to_string()),
             Literal::Boolean(_) => TypeKind::Ident("bool".to_string()),
             Literal::Char(_) => TypeKind::Ident("char".to_string()),
@@ -431,10 +1142,32 @@ impl TypeChecker {
         span: Span,
     ) -> Result<Type> {
         match op {
+            BinaryOp::Add if self.is_string_type(left) || self.is_string_type(right) => {
+                // String concatenation. Both sides must already be strings:
+                // `String + number` is rejected rather than implicitly
+                // converted, so the conversion stays visible in the source.
+                if self.is_string_type(left) && self.is_string_type(right) {
+                    Ok(Type {
+                        kind: TypeKind::Ident("String".to_string()),
+                        span,
+                    })
+                } else {
+                    Err(Error::type_error(
+                        "String concatenation requires both operands to be String",
+                        String::new(),
+                        span.to_source_span(),
+                    ))
+                }
+            }
             BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div | BinaryOp::Rem => {
                 // Numeric operations
                 if self.is_numeric_type(left) && self.is_numeric_type(right) {
-                    Ok(left.clone()) // Return left type (simplified)
+                    // `x + 1` has the type of `x`
+                    if Self::is_integer_literal_type(left) {
+                        Ok(right.clone())
+                    } else {
+                        Ok(left.clone())
+                    }
                 } else {
                     Err(Error::type_error(
                         format!("Binary operator {:?} requires numeric types", op),
@@ -519,28 +1252,151 @@ impl TypeChecker {
         }
     }
     
+    /// Resolves `receiver.method(args)` against the `impl` blocks of the
+    /// receiver's type, falling back to the methods of the traits it
+    /// implements. A leading `self` parameter is the receiver, not an
+    /// argument.
     fn type_of_method_call(
         &self,
-        _receiver_type: &Type,
-        _method: &str,
-        _arg_types: &[Type],
+        receiver_type: &Type,
+        method: &str,
+        arg_types: &[Type],
         span: Span,
     ) -> Result<Type> {
-        // Simplified - would need method resolution
-        Err(Error::type_error(
-            "Method calls not yet fully implemented",
-            String::new(),
-            span.to_source_span(),
-        ))
+        let Some(function) = self.find_method(receiver_type, method) else {
+            return Err(Error::type_error(
+                format!("No method '{}' found for type {}", method, receiver_type),
+                String::new(),
+                span.to_source_span(),
+            ));
+        };
+        
+        let params = match function.params.first() {
+            Some(first) if first.name == "self" => &function.params[1..],
+            _ => &function.params[..],
+        };
+        if params.len() != arg_types.len() {
+            return Err(Error::type_error(
+                format!(
+                    "Method '{}' takes {} argument(s), found {}",
+                    method,
+                    params.len(),
+                    arg_types.len()
+                ),
+                String::new(),
+                span.to_source_span(),
+            ));
+        }
+        // Generic parameters would need inference to compare
+        if function.generics.is_empty() {
+            for (param, arg_type) in params.iter().zip(arg_types) {
+                let param_type = self.resolve_self_type(&param.param_type, receiver_type);
+                if !self.types_match(&param_type, arg_type) {
+                    return Err(Error::type_error(
+                        format!(
+                            "Argument '{}' of method '{}' must be {}, found {}",
+                            param.name, method, param_type, arg_type
+                        ),
+                        String::new(),
+                        span.to_source_span(),
+                    ));
+                }
+            }
+        }
+        
+        Ok(match &function.return_type {
+            Some(return_type) => self.resolve_self_type(return_type, receiver_type),
+            None => Type { kind: TypeKind::Unit, span },
+        })
     }
     
-    fn type_of_field_access(&self, object_type: &Type, _field: &str, span: Span) -> Result<Type> {
-        // Simplified - would need struct field lookup
-        Err(Error::type_error(
-            "Field access not yet fully implemented",
-            String::new(),
-            span.to_source_span(),
-        ))
+    /// Parameter types of a non-generic method, without the receiver
+    fn method_param_types(&self, receiver_type: &Type, method: &str) -> Option<Vec<Type>> {
+        let function = self.find_method(receiver_type, method)?;
+        if !function.generics.is_empty() {
+            return None;
+        }
+        Some(
+            function
+                .params
+                .iter()
+                .filter(|param| param.name != "self")
+                .map(|param| self.resolve_self_type(&param.param_type, receiver_type))
+                .collect(),
+        )
+    }
+    
+    fn find_method(&self, receiver_type: &Type, method: &str) -> Option<&Function> {
+        let type_name = match &receiver_type.kind {
+            TypeKind::Ident(name) | TypeKind::Generic { name, .. } => name,
+            TypeKind::Reference { inner, .. } => return self.find_method(inner, method),
+            _ => return None,
+        };
+        let own = self.methods.get(type_name).into_iter().flatten();
+        let inherited = self
+            .trait_impls
+            .get(type_name)
+            .into_iter()
+            .flatten()
+            .filter_map(|trait_name| self.trait_methods.get(trait_name))
+            .flatten();
+        own.chain(inherited).find(|function| function.name == method)
+    }
+    
+    /// `Self` in a method signature stands for the receiver's type
+    fn resolve_self_type(&self, ty: &Type, receiver_type: &Type) -> Type {
+        let receiver_type = match &receiver_type.kind {
+            TypeKind::Reference { inner, .. } => inner,
+            _ => receiver_type,
+        };
+        match &ty.kind {
+            TypeKind::Ident(name) if name == "Self" => receiver_type.clone(),
+            _ => ty.clone(),
+        }
+    }
+    
+    /// Type of `field` on a struct value, or of index `field` on a tuple.
+    /// The object is checked first, so a chain like `a.b.c` resolves one
+    /// struct at a time and fails at the first field that doesn't exist;
+    /// `span` is that field's name.
+    fn type_of_field_access(&self, object_type: &Type, field: &str, span: Span) -> Result<Type> {
+        let object_type = match &object_type.kind {
+            TypeKind::Reference { inner, .. } => inner,
+            _ => object_type,
+        };
+        if let TypeKind::Tuple(items) = &object_type.kind {
+            return field
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| items.get(index))
+                .cloned()
+                .ok_or_else(|| Error::type_error(
+                    format!("No field '{}' on type {}", field, object_type),
+                    String::new(),
+                    span.to_source_span(),
+                ));
+        }
+        let struct_def = match &object_type.kind {
+            TypeKind::Ident(name) => self.structs.get(name),
+            _ => None,
+        };
+        let Some(struct_def) = struct_def else {
+            return Err(Error::type_error(
+                format!("No field '{}' on type {}", field, object_type),
+                String::new(),
+                span.to_source_span(),
+            ));
+        };
+        struct_def
+            .fields
+            .iter()
+            .find(|f| f.name == field)
+            .map(|f| f.field_type.clone())
+            .ok_or_else(|| Error::type_error(
+                format!("Unknown field '{}' on struct {}", field, struct_def.name),
+                String::new(),
+                span.to_source_span(),
+            ))
     }
     
     fn type_of_index(&self, object_type: &Type, _index_type: &Type, span: Span) -> Result<Type> {
@@ -558,8 +1414,21 @@ impl TypeChecker {
         matches!(&ty.kind, TypeKind::Ident(name) if name == "bool")
     }
     
+    fn is_string_type(&self, ty: &Type) -> bool {
+        matches!(&ty.kind, TypeKind::Ident(name) if name == "String")
+    }
+    
     fn is_numeric_type(&self, ty: &Type) -> bool {
-        matches!(&ty.kind, TypeKind::Ident(name) if matches!(name.as_str(), "i32" | "i64" | "f32" | "f64" | "u32" | "u64"))
+        matches!(&ty.kind, TypeKind::Ident(name) if matches!(name.as_str(), "f32" | "f64" | INTEGER_LITERAL) || integer_range(name).is_some())
+    }
+    
+    /// Element type of a built-in iterable
+    fn item_type(&self, ty: &Type) -> Option<Type> {
+        match &ty.kind {
+            TypeKind::Range(item) | TypeKind::Array(item) | TypeKind::Slice(item) => Some((**item).clone()),
+            TypeKind::Reference { inner, .. } => self.item_type(inner),
+            _ => None,
+        }
     }
     
     fn is_element_type(&self, ty: &Type) -> bool {
@@ -568,18 +1437,78 @@ impl TypeChecker {
     
     fn types_match(&self, t1: &Type, t2: &Type) -> bool {
         match (&t1.kind, &t2.kind) {
-            (TypeKind::Ident(n1), TypeKind::Ident(n2)) => n1 == n2,
+            (TypeKind::Ident(n1), TypeKind::Ident(n2)) if n1 == n2 => true,
+            // An integer literal fits any number type
+            (TypeKind::Ident(n), _) if n == INTEGER_LITERAL => self.is_numeric_type(t2),
+            (_, TypeKind::Ident(n)) if n == INTEGER_LITERAL => self.is_numeric_type(t1),
             (TypeKind::Unit, TypeKind::Unit) => true,
             (TypeKind::Tuple(t1), TypeKind::Tuple(t2)) => {
                 t1.len() == t2.len() && t1.iter().zip(t2.iter()).all(|(a, b)| self.types_match(a, b))
             }
             (TypeKind::Array(a1), TypeKind::Array(a2)) => self.types_match(a1, a2),
             (TypeKind::Slice(s1), TypeKind::Slice(s2)) => self.types_match(s1, s2),
+            (TypeKind::Range(r1), TypeKind::Range(r2)) => self.types_match(r1, r2),
+            (TypeKind::Option(o1), TypeKind::Option(o2)) => self.types_match(o1, o2),
+            (TypeKind::Result { ok: ok1, err: err1 }, TypeKind::Result { ok: ok2, err: err2 }) => {
+                self.types_match(ok1, ok2) && self.types_match(err1, err2)
+            }
+            (TypeKind::Generic { name: n1, args: a1 }, TypeKind::Generic { name: n2, args: a2 }) => {
+                n1 == n2 && a1.len() == a2.len() && a1.iter().zip(a2.iter()).all(|(a, b)| self.types_match(a, b))
+            }
             _ => false,
         }
     }
 }
 
+/// Type name given to integer literals until their context decides
+const INTEGER_LITERAL: &str = "{integer}";
+
+/// Smallest and largest value of a built-in integer type
+fn integer_range(name: &str) -> Option<(i128, i128)> {
+    Some(match name {
+        "i8" => (i8::MIN as i128, i8::MAX as i128),
+        "i16" => (i16::MIN as i128, i16::MAX as i128),
+        "i32" => (i32::MIN as i128, i32::MAX as i128),
+        "i64" | "isize" => (i64::MIN as i128, i64::MAX as i128),
+        "i128" => (i128::MIN, i128::MAX),
+        "u8" => (0, u8::MAX as i128),
+        "u16" => (0, u16::MAX as i128),
+        "u32" => (0, u32::MAX as i128),
+        "u64" | "usize" => (0, u64::MAX as i128),
+        "u128" => (0, i128::MAX),
+        _ => return None,
+    })
+}
+
+/// The variant a pattern's `Enum::Variant` name covers on `type_name`:
+/// its last segment, unless the segment before names another type
+fn variant_of<'a>(name: &'a str, type_name: &str) -> Option<&'a str> {
+    match name.rsplit_once("::") {
+        Some((prefix, variant)) => {
+            let enum_name = prefix.rsplit("::").next().unwrap_or(prefix);
+            (enum_name == type_name).then_some(variant)
+        }
+        None => Some(name),
+    }
+}
+
+/// Variants of the prelude enums `Option` and `Result`, which have no
+/// declaration to look them up in
+fn builtin_variants(ty: &Type) -> Option<(&'static str, [&'static str; 2])> {
+    match &ty.kind {
+        TypeKind::Option(_) => Some(("Option", ["Some", "None"])),
+        TypeKind::Result { .. } => Some(("Result", ["Ok", "Err"])),
+        TypeKind::Reference { inner, .. } => builtin_variants(inner),
+        _ => None,
+    }
+}
+
+impl Default for TypeChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 // Helper trait for getting span from expressions
 trait HasSpan {
     fn span(&self) -> Span;
@@ -598,11 +1527,13 @@ impl HasSpan for Expr {
             Expr::Index { span, .. } => *span,
             Expr::JSXElement(_, span) => *span,
             Expr::Block(_, span) => *span,
+            Expr::Loop(_, span) => *span,
             Expr::If { span, .. } => *span,
             Expr::Match { span, .. } => *span,
             Expr::Lambda { span, .. } => *span,
             Expr::Tuple(_, span) => *span,
             Expr::Array(_, span) => *span,
+            Expr::Range { span, .. } => *span,
             Expr::Struct { span, .. } => *span,
         }
     }
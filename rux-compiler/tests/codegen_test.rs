@@ -0,0 +1,430 @@
+#[cfg(test)]
+mod tests {
+    use rux_compiler::codegen::dom_attribute_name;
+    use rux_compiler::{CodeGenerator, CodegenCache, Compiler, CompilerOptions, Lexer, Parser};
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_string_concatenation_codegen() {
+        let source = r#"
+            fn greet(name: String) -> String {
+                "Hello, " + name
+            }
+        "#;
+
+        let mut compiler = Compiler::new();
+        let code = compiler.compile_string_to_rust(source, "greet.rsx").unwrap();
+        assert!(code.contains(r#"format!("{}{}", "Hello, ", name)"#));
+    }
+
+    #[test]
+    fn test_expression_child_codegen() {
+        let source = "fn App() -> Element { <p>{count}</p> }";
+
+        let mut compiler = Compiler::new();
+        let code = compiler.compile_string_to_rust(source, "app.rsx").unwrap();
+        assert!(code.contains("NodeType::Text((count).to_string())"));
+    }
+
+    #[test]
+    fn test_constant_folding_can_be_disabled() {
+        let source = "fn three() -> f64 { 1 + 2 }";
+
+        let options = CompilerOptions {
+            constant_folding: false,
+            ..CompilerOptions::default()
+        };
+        let mut compiler = Compiler::new_with_options(options);
+        let code = compiler.compile_string_to_rust(source, "three.rsx").unwrap();
+        assert!(code.contains("(1 + 2)"));
+
+        let mut compiler = Compiler::new();
+        let code = compiler.compile_string_to_rust(source, "three.rsx").unwrap();
+        assert!(!code.contains("(1 + 2)"));
+    }
+
+    #[test]
+    fn test_where_clause_codegen() {
+        let source = "fn first<T: Clone>(x: T) -> T where T: Debug { x }";
+
+        let mut compiler = Compiler::new();
+        let code = compiler.compile_string_to_rust(source, "first.rsx").unwrap();
+        assert!(code.contains("pub fn first<T>(x: T) -> T where T: Clone, T: Debug {"));
+    }
+
+    #[test]
+    fn test_derive_attribute_codegen() {
+        let source = r#"
+            #[derive(Clone, Debug)]
+            struct Point { x: f64 }
+        "#;
+
+        let mut compiler = Compiler::new_with_options(CompilerOptions::debug());
+        let code = compiler.compile_string_to_rust(source, "point.rsx").unwrap();
+        assert!(code.contains("#[derive(Clone, Debug)]\npub struct Point {"));
+    }
+
+    #[test]
+    fn test_prop_struct_derives() {
+        let source = r#"
+            struct CardProps { title: String }
+            struct Point { x: f64 }
+            #[rux(no_derive)]
+            struct BadgeProps { label: String }
+
+            fn Card(props: CardProps) -> Element { <div></div> }
+            fn Badge(props: BadgeProps) -> Element { <span></span> }
+        "#;
+
+        let mut compiler = Compiler::new_with_options(CompilerOptions::debug());
+        let code = compiler.compile_string_to_rust(source, "card.rsx").unwrap();
+        assert!(code.contains("#[derive(Clone, PartialEq, Debug)]\npub struct CardProps {"));
+        assert!(!code.contains("]\npub struct Point {"));
+        assert!(!code.contains("]\npub struct BadgeProps {"));
+        assert!(!code.contains("#[rux"));
+    }
+
+    #[test]
+    fn test_class_list_codegen() {
+        let source = r#"fn App() -> Element { <div className={["btn", ("active", true), ("hidden", false), "wide"]}>Hi</div> }"#;
+
+        let mut compiler = Compiler::new();
+        let code = compiler.compile_string_to_rust(source, "app.rsx").unwrap();
+        assert!(code.contains(r#"props.insert("class".to_string(), PropValue::String("btn active wide".to_string()));"#));
+        assert!(!code.contains("className"));
+    }
+
+    #[test]
+    fn test_conditional_class_codegen() {
+        let source = r#"fn App(active: bool) -> Element { <div class={["btn", ("on", active)]}>Hi</div> }"#;
+
+        let mut compiler = Compiler::new();
+        let code = compiler.compile_string_to_rust(source, "app.rsx").unwrap();
+        assert!(code.contains(r#"classes.push(("btn").to_string());"#));
+        assert!(code.contains(r#"if active { classes.push(("on").to_string()); }"#));
+        assert!(code.contains(r#"classes.join(" ")"#));
+        assert!(!code.contains("TODO"));
+    }
+
+    #[test]
+    fn test_dom_attribute_names() {
+        let source = r#"fn App() -> Element { <svg tabIndex="0" strokeWidth="2" viewBox="0 0 10 10" data-id="x" aria-label="Icon" /> }"#;
+
+        let mut compiler = Compiler::new();
+        let code = compiler.compile_string_to_rust(source, "app.rsx").unwrap();
+        assert!(code.contains(r#"props.insert("tabindex".to_string()"#));
+        assert!(code.contains(r#"props.insert("stroke-width".to_string()"#));
+        assert!(code.contains(r#"props.insert("viewBox".to_string()"#));
+        assert!(code.contains(r#"props.insert("data-id".to_string()"#));
+        assert!(code.contains(r#"props.insert("aria-label".to_string()"#));
+        assert!(!code.contains("tabIndex"));
+
+        assert_eq!(dom_attribute_name("htmlFor"), "for");
+        assert_eq!(dom_attribute_name("onClick"), "onClick");
+    }
+
+    #[test]
+    fn test_option_match_arms_codegen() {
+        let source = r#"
+            fn unwrap_or_zero(value: Option<f64>) -> f64 {
+                match value {
+                    Some(n) => n,
+                    None => 0,
+                }
+            }
+        "#;
+
+        let mut compiler = Compiler::new();
+        let code = compiler.compile_string_to_rust(source, "option.rsx").unwrap();
+        assert!(code.contains("Some(n) => n,"));
+        assert!(code.contains("None => 0,"));
+    }
+
+    #[test]
+    fn test_generic_and_trait_object_types_pass_through() {
+        let source = r#"
+            fn apply(callback: Box<dyn Fn(f64) -> bool>, items: Option<Vec<String>>, state: Arc<Mutex<f64>>) {}
+        "#;
+
+        let mut compiler = Compiler::new();
+        let code = compiler.compile_string_to_rust(source, "apply.rsx").unwrap();
+        assert!(code.contains("callback: Box<dyn Fn(f64) -> bool>"));
+        assert!(code.contains("items: Option<Vec<String>>"));
+        assert!(code.contains("state: Arc<Mutex<f64>>"));
+    }
+
+    #[test]
+    fn test_let_constants_are_propagated() {
+        let mut compiler = Compiler::new();
+        let code = compiler
+            .compile_string_to_rust("fn five() -> f64 { let x = 2; let y = x + 3; y }", "five.rsx")
+            .unwrap();
+        assert!(code.contains("let y = 5;"), "{}", code);
+
+        let mut compiler = Compiler::new();
+        let code = compiler
+            .compile_string_to_rust("fn five() -> f64 { let mut x = 2; let y = x + 3; y }", "five.rsx")
+            .unwrap();
+        assert!(code.contains("let y = (x + 3);"), "{}", code);
+    }
+
+    #[test]
+    fn test_loop_variable_shadows_constant() {
+        let source = "fn sum() { let i = 10; for i in 0..3 { let next = i + 1; } let last = i + 1; }";
+
+        let mut compiler = Compiler::new();
+        let code = compiler.compile_string_to_rust(source, "sum.rsx").unwrap();
+        assert!(code.contains("let next = (i + 1);"), "{}", code);
+        assert!(code.contains("let last = 11;"), "{}", code);
+    }
+
+    #[test]
+    fn test_repeated_subexpression_is_hoisted_once() {
+        let source = "fn area(a: f64, b: f64) -> f64 { let double = a * b + a * b; let half = a * b / 2; double + half }";
+
+        let mut compiler = Compiler::new();
+        let code = compiler.compile_string_to_rust(source, "area.rsx").unwrap();
+        assert_eq!(code.matches("(a * b)").count(), 1, "{}", code);
+        assert!(code.contains("let __cse_0 = (a * b);"), "{}", code);
+        assert!(code.contains("let double = (__cse_0 + __cse_0);"), "{}", code);
+        assert!(code.contains("let half = (__cse_0 / 2);"), "{}", code);
+    }
+
+    #[test]
+    fn test_subexpression_not_hoisted_past_rebinding() {
+        let source = "fn f(a: f64, b: f64) -> f64 { let x = a * b; let a = b + 1; let y = a * b; x + y }";
+
+        let mut compiler = Compiler::new();
+        let code = compiler.compile_string_to_rust(source, "f.rsx").unwrap();
+        assert!(!code.contains("__cse"), "{}", code);
+    }
+
+    #[test]
+    fn test_single_use_component_is_inlined() {
+        let source = r#"
+            fn Header(title: String) -> Element { <h1 class="header">{title}</h1> }
+
+            fn App() -> Element { <Header title="Welcome" /> }
+        "#;
+
+        let mut compiler = Compiler::new();
+        let code = compiler.compile_string_to_rust(source, "app.rsx").unwrap();
+        assert!(!code.contains("pub fn header("), "{}", code);
+        assert!(!code.contains("NodeType::Component"), "{}", code);
+        assert!(code.contains(r#"NodeType::Element("h1".to_string())"#), "{}", code);
+        assert!(code.contains(r#"NodeType::Text(("Welcome").to_string())"#), "{}", code);
+    }
+
+    #[test]
+    fn test_component_used_twice_is_not_inlined() {
+        let source = r#"
+            fn Header(title: String) -> Element { <h1>{title}</h1> }
+
+            fn App() -> Element { <div><Header title="One" /><Header title="Two" /></div> }
+        "#;
+
+        let mut compiler = Compiler::new();
+        let code = compiler.compile_string_to_rust(source, "app.rsx").unwrap();
+        assert!(code.contains("pub fn header("), "{}", code);
+    }
+
+    #[test]
+    fn test_prop_expression_read_twice_is_not_inlined() {
+        let source = r#"
+            fn Header(title: String) -> Element { <h1 title={title}>{title}</h1> }
+
+            fn App(name: String) -> Element { <Header title={name.to_uppercase()} /> }
+        "#;
+
+        let mut compiler = Compiler::new();
+        let code = compiler.compile_string_to_rust(source, "app.rsx").unwrap();
+        assert!(code.contains("pub fn header("), "{}", code);
+        assert!(code.contains(r#"NodeType::Element("Header".to_string())"#), "{}", code);
+    }
+
+    #[test]
+    fn test_prop_variable_read_twice_is_inlined() {
+        let source = r#"
+            fn Header(title: String) -> Element { <h1 title={title}>{title}</h1> }
+
+            fn App(name: String) -> Element { <Header title={name} /> }
+        "#;
+
+        let mut compiler = Compiler::new();
+        let code = compiler.compile_string_to_rust(source, "app.rsx").unwrap();
+        assert!(!code.contains("pub fn header("), "{}", code);
+    }
+
+    #[test]
+    fn test_doc_comments_are_emitted() {
+        let source = r#"
+            //! The app
+
+            /// greeting
+            fn App() -> Element { <p>Hello</p> }
+
+            fn main() {
+                /// not attached to anything
+                let x = 1;
+            }
+        "#;
+
+        let mut compiler = Compiler::new_with_options(CompilerOptions::debug());
+        let code = compiler.compile_string_to_rust(source, "app.rsx").unwrap();
+        assert!(code.starts_with("//! The app\n"), "{}", code);
+        assert!(code.contains("/// greeting\npub fn app() -> VirtualNode"), "{}", code);
+        assert!(!code.contains("not attached"), "{}", code);
+    }
+
+    #[test]
+    fn test_bind_expands_to_prop_and_handler() {
+        let source = r#"
+            fn NameField(name: Signal<String>) -> Element {
+                <input bind:value={name} />
+            }
+
+            fn AgreeBox(agreed: Signal<bool>) -> Element {
+                <input type="checkbox" bind:checked={agreed} />
+            }
+        "#;
+
+        let mut compiler = Compiler::new_with_options(CompilerOptions::debug());
+        let code = compiler.compile_string_to_rust(source, "form.rsx").unwrap();
+        assert!(code.contains(r#"props.insert("value".to_string(), PropValue::String((name.get()).to_string()));"#), "{}", code);
+        assert!(code.contains(r#"props.insert("onInput".to_string(), rux_core::bindings::bind_value(&name));"#), "{}", code);
+        assert!(code.contains(r#"props.insert("checked".to_string(), PropValue::Boolean(agreed.get()));"#), "{}", code);
+        assert!(code.contains(r#"props.insert("onChange".to_string(), rux_core::bindings::bind_checked(&agreed));"#), "{}", code);
+        assert!(!code.contains("bind:"), "{}", code);
+    }
+
+    #[test]
+    fn test_event_modifiers_stay_in_prop_key() {
+        let source = r#"
+            fn SaveForm() -> Element {
+                <form onSubmit:prevent={save} />
+            }
+        "#;
+
+        let mut compiler = Compiler::new_with_options(CompilerOptions::debug());
+        let code = compiler.compile_string_to_rust(source, "form.rsx").unwrap();
+        assert!(code.contains(r#"props.insert("onSubmit:prevent".to_string(), PropValue::Function("save".to_string()));"#), "{}", code);
+    }
+
+    #[test]
+    fn test_else_if_chains_stay_flat() {
+        let source = r#"
+            fn print(message: String) {}
+
+            fn log_size(n: f64) {
+                if n < 10 {
+                    print("small");
+                } else if n < 100 {
+                    print("medium");
+                } else {
+                    print("large");
+                }
+            }
+
+            fn size(n: f64) -> String {
+                if n < 10 { "small" } else if n < 100 { "medium" } else { "large" }
+            }
+        "#;
+
+        let mut compiler = Compiler::new();
+        let code = compiler.compile_string_to_rust(source, "size.rsx").unwrap();
+        assert_eq!(code.matches("} else if (n < 100) {").count(), 2, "{}", code);
+        assert!(!code.contains("else {\n        if"), "{}", code);
+        assert!(code.contains("    } else {\n        \"large\"\n    }"), "{}", code);
+    }
+
+    #[test]
+    fn test_tuple_index_codegen() {
+        let source = r#"
+            fn sum(pair: (f64, f64)) -> f64 {
+                pair.0 + pair.1
+            }
+        "#;
+
+        let mut compiler = Compiler::new();
+        let code = compiler.compile_string_to_rust(source, "sum.rsx").unwrap();
+        assert!(code.contains("(pair.0 + pair.1)"), "{}", code);
+    }
+
+    /// In-memory cache counting what codegen reused and regenerated
+    #[derive(Default)]
+    struct CountingCache {
+        entries: HashMap<u64, String>,
+        reused: usize,
+        generated: usize,
+    }
+
+    impl CodegenCache for CountingCache {
+        fn get(&mut self, key: u64) -> Option<String> {
+            let code = self.entries.get(&key).cloned()?;
+            self.reused += 1;
+            Some(code)
+        }
+
+        fn put(&mut self, key: u64, code: &str) {
+            self.generated += 1;
+            self.entries.insert(key, code.to_string());
+        }
+    }
+
+    #[test]
+    fn test_cached_codegen_regenerates_only_edited_item() {
+        let generate = |source: &str, cache: &mut CountingCache| {
+            let mut lexer = Lexer::new(source);
+            let tokens = lexer.tokenize().unwrap();
+            let ast = Parser::new(tokens, source.to_string()).parse().unwrap();
+            CodeGenerator::new().generate_rust_code_cached(&ast, cache).unwrap()
+        };
+        let before = r#"
+            fn Header() -> Element { <h1>Title</h1> }
+            fn Body() -> Element { <p>Body</p> }
+            fn Footer() -> Element { <p>Footer</p> }
+        "#;
+        // The edit makes the first item longer, moving the other two
+        let after = r#"
+            fn Header() -> Element { <h1>A much longer title</h1> }
+            fn Body() -> Element { <p>Body</p> }
+            fn Footer() -> Element { <p>Footer</p> }
+        "#;
+
+        let mut cache = CountingCache::default();
+        generate(before, &mut cache);
+        assert_eq!((cache.reused, cache.generated), (0, 3));
+
+        cache.reused = 0;
+        cache.generated = 0;
+        let code = generate(after, &mut cache);
+        assert_eq!((cache.reused, cache.generated), (2, 1));
+        assert!(code.contains("A much longer title"));
+        assert!(code.contains("Footer"));
+    }
+
+    #[test]
+    fn test_folding_negation_overflow_is_left_alone() {
+        let source = "fn g() -> i64 { -(0 - 9223372036854775807 - 1) }";
+
+        let mut compiler = Compiler::new();
+        let code = compiler.compile_string_to_rust(source, "g.rsx").unwrap();
+        // The inner subtraction folds to i64::MIN, whose negation doesn't fit
+        assert!(code.contains("-(-9223372036854775808)"), "{}", code);
+    }
+
+    #[test]
+    fn test_string_variable_concatenation_codegen() {
+        let source = r#"
+            fn greet(a: String, b: String) -> String {
+                let both = a + b;
+                both + "!"
+            }
+        "#;
+
+        let mut compiler = Compiler::new();
+        let code = compiler.compile_string_to_rust(source, "greet.rsx").unwrap();
+        assert!(code.contains(r#"format!("{}{}", a, b)"#), "{}", code);
+        assert!(code.contains(r#"format!("{}{}", both, "!")"#), "{}", code);
+    }
+}
@@ -0,0 +1,37 @@
+#[cfg(test)]
+mod tests {
+    use rux_compiler::Compiler;
+
+    #[test]
+    fn test_type_error_to_json() {
+        let source = "fn label() -> String {\n    \"enabled: \" + true\n}\n";
+        let mut compiler = Compiler::new();
+        let err = compiler.compile_string(source, "label.rsx").unwrap_err();
+
+        let json = err.to_json();
+        assert_eq!(json["severity"], "error");
+        assert_eq!(json["code"], "rux::type_check");
+        assert!(json["message"].as_str().unwrap().contains("String concatenation"));
+        assert!(json["file"].is_null());
+
+        let start = json["span"]["start"].as_u64().unwrap() as usize;
+        let end = json["span"]["end"].as_u64().unwrap() as usize;
+        assert_eq!(&source[start..end], "true");
+        assert_eq!(json["line"], 2);
+        assert_eq!(json["column"], 19);
+    }
+
+    #[test]
+    fn test_warning_to_json() {
+        let source = "fn ratio() -> i32 { 1 / 0 }";
+        let mut compiler = Compiler::new();
+        compiler.compile_string(source, "ratio.rsx").unwrap();
+
+        let warning = &compiler.warnings().diagnostics()[0];
+        let json = warning.to_json();
+        assert_eq!(json["severity"], "warning");
+        assert_eq!(json["code"], "rux::warning");
+        assert_eq!(json["span"]["start"], warning.span.start);
+        assert_eq!(json["line"], 1);
+    }
+}
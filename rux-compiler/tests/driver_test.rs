@@ -0,0 +1,562 @@
+#[cfg(test)]
+mod tests {
+    use rux_compiler::ast::Item;
+    use miette::Diagnostic;
+    use rux_compiler::{AstPass, Compiler, CompilerOptions, IncrementalCompiler, PassTimings, Severity, AST};
+    use std::time::Duration;
+
+    #[test]
+    fn test_dependency_graph_is_exposed() {
+        let source = r#"
+            fn format_title() -> String {
+                "Hello"
+            }
+
+            fn App() -> Element {
+                <h1>{format_title()}</h1>
+            }
+        "#;
+
+        let mut compiler = Compiler::new();
+        compiler.compile_string(source, "app.rsx").unwrap();
+
+        let app_deps = compiler.dependencies().get("App").expect("App should be in the graph");
+        assert!(app_deps.contains("format_title"));
+        assert!(compiler.dependencies().contains_key("format_title"));
+    }
+
+    #[test]
+    fn test_warning_does_not_fail_compilation() {
+        let source = r#"
+            enum Light { On, Off }
+
+            fn describe(light: Light) {
+                match light {
+                    _ => 0,
+                    Light::On => 1,
+                }
+            }
+        "#;
+
+        let mut compiler = Compiler::new();
+        compiler.compile_string(source, "light.rsx").unwrap();
+
+        let warnings = compiler.warnings().diagnostics();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].severity, Severity::Warning);
+        assert!(warnings[0].message.contains("Unreachable match arm"));
+    }
+
+    #[test]
+    fn test_dead_code_keeps_types_used_in_signatures() {
+        let source = r#"
+            struct CardProps { title: String }
+            struct Unused { x: f64 }
+
+            fn Card(props: CardProps) -> Element { <div></div> }
+        "#;
+
+        let mut compiler = Compiler::new();
+        let ast = compiler.compile_string(source, "card.rsx").unwrap();
+
+        let structs: Vec<&str> = ast
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Struct(s) => Some(s.name.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(structs, vec!["CardProps"]);
+    }
+
+    /// Writes `files` into a directory that is removed when the returned guard drops
+    fn write_project(files: &[(&str, &str)]) -> tempfile::TempDir {
+        let root = tempfile::tempdir().unwrap();
+        for (path, source) in files {
+            let path = root.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, source).unwrap();
+        }
+        root
+    }
+
+    #[test]
+    fn test_compile_project_resolves_imports() {
+        let project = write_project(
+            &[
+                ("components/button.rsx", "fn Button() -> Element { <button>Click</button> }"),
+                (
+                    "app.rsx",
+                    "use components::button::Button;\nfn App() -> Element { <div><Button /></div> }",
+                ),
+            ],
+        );
+        let root = project.path();
+
+        let mut compiler = Compiler::new();
+        let ast = compiler.compile_project(root).unwrap();
+
+        let app = ast
+            .items
+            .iter()
+            .find_map(|item| match item {
+                Item::Mod(m) if m.name == "app" => Some(m),
+                _ => None,
+            })
+            .expect("app module");
+        let import = app
+            .items
+            .iter()
+            .find_map(|item| match item {
+                Item::Use(u) => Some(u),
+                _ => None,
+            })
+            .expect("use declaration");
+        assert_eq!(import.path, vec!["crate", "components", "button", "Button"]);
+    }
+
+    #[test]
+    fn test_compile_project_rejects_unknown_import() {
+        let project = write_project(
+            &[("app.rsx", "use components::Missing;\nfn App() -> Element { <div></div> }")],
+        );
+        let root = project.path();
+
+        let mut compiler = Compiler::new();
+        let err = compiler.compile_project(root).unwrap_err();
+
+        assert!(err.to_string().contains("Unresolved import 'components::Missing'"));
+    }
+
+    #[test]
+    fn test_compile_project_calls_imported_function() {
+        let project = write_project(
+            &[
+                ("utils/format.rsx", "fn title() -> String { \"Home\" }"),
+                ("app.rsx", "use utils::format::title;\nfn heading() -> String { title() }"),
+            ],
+        );
+        let root = project.path();
+
+        let mut compiler = Compiler::new();
+        let result = compiler.compile_project(root);
+
+        result.unwrap();
+    }
+
+    #[test]
+    fn test_parallel_project_build_matches_sequential() {
+        let files: Vec<(String, String)> = (0..6)
+            .map(|i| {
+                (
+                    format!("pages/page{}.rsx", i),
+                    format!("fn Page{}() -> Element {{ <h1>\"Page {}\"</h1> }}", i, i),
+                )
+            })
+            .chain([("app.rsx".to_string(), "use pages::page3::Page3;\nfn App() -> Element { <Page3 /> }".to_string())])
+            .collect();
+        let files: Vec<(&str, &str)> = files.iter().map(|(path, source)| (path.as_str(), source.as_str())).collect();
+        let project = write_project(&files);
+        let root = project.path();
+
+        let generate = |jobs| {
+            let options = CompilerOptions { jobs, ..CompilerOptions::debug() };
+            let mut compiler = Compiler::new_with_options(options);
+            let ast = compiler.compile_project(root).unwrap();
+            compiler.generate_rust(&ast).unwrap()
+        };
+        let sequential = generate(1);
+        let outputs: Vec<String> = [2, 3, 16].into_iter().map(generate).collect();
+
+        assert!(sequential.contains("pub fn page5()"));
+        for output in outputs {
+            assert_eq!(output, sequential);
+        }
+    }
+
+    #[test]
+    fn test_project_parse_error_reports_file() {
+        let project = write_project(
+            &[
+                ("a.rsx", "fn a() -> f64 { 1 }"),
+                ("b.rsx", "fn b( -> f64 { 2 }"),
+                ("c.rsx", "fn c() -> f64 { 3 }"),
+            ],
+        );
+        let root = project.path();
+
+        let options = CompilerOptions { jobs: 3, ..CompilerOptions::debug() };
+        let mut compiler = Compiler::new_with_options(options);
+        let result = compiler.compile_project(root);
+        let error_file = compiler.error_file().map(|path| path.to_path_buf());
+
+        assert!(result.is_err());
+        assert_eq!(error_file, Some(root.join("b.rsx")));
+    }
+
+    #[test]
+    fn test_incremental_skips_dependents_when_signatures_unchanged() {
+        let project = write_project(
+            &[
+                ("math.rsx", "fn double(x: f64) -> f64 { x * 2 }"),
+                ("app.rsx", "use math::double;\nfn four() -> f64 { double(2) }"),
+            ],
+        );
+        let root = project.path();
+        let math = root.join("math.rsx");
+        let app = root.join("app.rsx");
+
+        let mut compiler = IncrementalCompiler::with_root(root);
+        compiler.compile_incremental(&[math.clone(), app.clone()]).unwrap();
+        assert_eq!(compiler.checked_files(), &[math.clone(), app.clone()]);
+
+        // Nothing changed on disk, so nothing is checked
+        compiler.compile_incremental(std::slice::from_ref(&math)).unwrap();
+        assert!(compiler.checked_files().is_empty());
+
+        // A body-only edit re-checks the file but not its dependents
+        std::fs::write(&math, "fn double(x: f64) -> f64 { x + x }").unwrap();
+        compiler.compile_incremental(std::slice::from_ref(&math)).unwrap();
+        assert_eq!(compiler.checked_files(), std::slice::from_ref(&math));
+
+        // Changing what the file exports re-checks the files importing it
+        std::fs::write(&math, "fn double(x: f64) -> f64 { x + x }\nfn triple(x: f64) -> f64 { x * 3 }").unwrap();
+        compiler.compile_incremental(std::slice::from_ref(&math)).unwrap();
+        assert_eq!(compiler.checked_files(), &[math, app]);
+    }
+
+    #[test]
+    fn test_incremental_parses_each_changed_file_once() {
+        let project = write_project(
+            &[
+                ("math.rsx", "fn double(x: f64) -> f64 { x * 2 }"),
+                ("app.rsx", "use math::double;\nfn four() -> f64 { double(2) }"),
+            ],
+        );
+        let root = project.path();
+        let math = root.join("math.rsx");
+        let app = root.join("app.rsx");
+        let options = CompilerOptions { profile: true, ..CompilerOptions::debug() };
+        let lexes = |timings: &[PassTimings]| -> Vec<usize> {
+            timings.iter().map(|t| t.passes.iter().filter(|(pass, _)| *pass == "lex").count()).collect()
+        };
+
+        let mut compiler = IncrementalCompiler::with_root(root).with_options(options);
+        compiler.compile_incremental(&[math.clone(), app.clone()]).unwrap();
+        assert_eq!(lexes(compiler.timings()), vec![1, 1]);
+
+        // The dependent is re-checked from its cached AST
+        std::fs::write(&math, "fn double(x: f64) -> f64 { x * 2 }\nfn triple(x: f64) -> f64 { x * 3 }").unwrap();
+        compiler.compile_incremental(std::slice::from_ref(&math)).unwrap();
+        assert_eq!(compiler.checked_files(), &[math, app]);
+        assert_eq!(lexes(&compiler.timings()[2..]), vec![1, 0]);
+        assert!(compiler.timings()[3].get("typecheck").is_some());
+    }
+
+    #[test]
+    fn test_parse_only_skips_type_checking() {
+        let source = r#"
+            fn label() -> String {
+                "enabled: " + true
+            }
+        "#;
+
+        let mut compiler = Compiler::new();
+        assert!(compiler.compile_string(source, "label.rsx").is_err());
+
+        let ast = compiler.parse_only(source, "label.rsx").unwrap();
+        assert!(matches!(&ast.items[..], [Item::Function(f)] if f.name == "label"));
+    }
+
+    #[test]
+    fn test_parse_only_reports_syntax_errors() {
+        let mut compiler = Compiler::new();
+        assert!(compiler.parse_only("fn broken( {", "broken.rsx").is_err());
+    }
+
+    #[test]
+    fn test_partial_codegen_stubs_failed_items() {
+        let source = r#"
+            fn Valid() -> Element {
+                <p>"ok"</p>
+            }
+
+            fn Invalid() -> Element {
+                let label = "enabled: " + true;
+                <p>{label}</p>
+            }
+        "#;
+
+        let mut compiler = Compiler::new();
+        assert!(compiler.compile_string_to_rust(source, "app.rsx").is_err());
+
+        let code = compiler.compile_string_to_rust_partial(source, "app.rsx").unwrap();
+        assert!(code.contains("pub fn valid("));
+        assert!(!code.contains("pub fn invalid("));
+        assert!(code.contains("compile_error!(\""));
+        assert!(code.contains("String concatenation"));
+    }
+
+    #[test]
+    fn test_profile_records_each_pass() {
+        let options = CompilerOptions {
+            profile: true,
+            ..CompilerOptions::default()
+        };
+        let mut compiler = Compiler::new_with_options(options);
+        compiler
+            .compile_string_to_rust("fn App() -> Element { <h1>\"Hi\"</h1> }", "app.rsx")
+            .unwrap();
+
+        let timings = compiler.timings();
+        assert_eq!(timings.len(), 1);
+        assert_eq!(timings[0].file, "app.rsx");
+        for pass in ["lex", "parse", "typecheck", "analyze", "optimize", "codegen"] {
+            let duration = timings[0].get(pass).unwrap_or_else(|| panic!("missing {} timing", pass));
+            assert!(duration > Duration::ZERO, "{} took no time", pass);
+        }
+        assert!(timings[0].to_string().starts_with("app.rsx: lex "));
+    }
+
+    #[test]
+    fn test_timings_are_off_by_default() {
+        let mut compiler = Compiler::new();
+        compiler.compile_string("fn one() -> f64 { 1 }", "one.rsx").unwrap();
+        assert!(compiler.timings().is_empty());
+    }
+
+    #[test]
+    fn test_format_produces_valid_rust() {
+        let source = r#"
+            struct Props { title: String }
+
+            fn double(x: f64) -> f64 { let y = x * 2; y }
+
+            fn App() -> Element { <div class="app"><h1>Hi</h1></div> }
+        "#;
+
+        let raw = Compiler::new().compile_string_to_rust(source, "app.rsx").unwrap();
+        let options = CompilerOptions { format: true, ..CompilerOptions::default() };
+        let mut compiler = Compiler::new_with_options(options);
+        let formatted = compiler.compile_string_to_rust(source, "app.rsx").unwrap();
+
+        // Without rustfmt the code is kept as generated, with a warning
+        let diagnostics = compiler.warnings().diagnostics();
+        if std::process::Command::new("rustfmt").arg("--version").output().is_err() {
+            assert_eq!(formatted, raw);
+            assert_eq!(diagnostics.len(), 1, "{:?}", diagnostics);
+            assert!(diagnostics[0].to_string().contains("Generated Rust was left unformatted"), "{}", diagnostics[0]);
+            return;
+        }
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+        assert_ne!(formatted, raw);
+        syn::parse_file(&formatted).expect("formatted output should be valid Rust");
+        assert!(formatted.contains("pub fn double(x: f64) -> f64 {\n"));
+        assert!(formatted.lines().all(|line| line == line.trim_end()));
+    }
+
+    #[test]
+    fn test_invalid_generated_rust_is_an_internal_error() {
+        // `move` is an identifier in RSX but a keyword in Rust, and codegen
+        // doesn't escape it yet
+        let source = "fn count() -> f64 { let move = 1; move }";
+
+        let mut compiler = Compiler::new_with_options(CompilerOptions::debug());
+        let err = compiler.compile_string_to_rust(source, "count.rsx").unwrap_err();
+
+        assert_eq!(err.code(), "rux::internal");
+        assert!(err.message().contains("line 5"), "{}", err.message());
+        let offset = err.span().offset();
+        assert!(err.source_code()[offset..].starts_with("move = 1;"), "{}", err.source_code());
+    }
+
+    /// Records the name of every span created, in order
+    #[derive(Default)]
+    struct SpanRecorder {
+        names: std::sync::Mutex<Vec<&'static str>>,
+    }
+
+    impl tracing::Subscriber for SpanRecorder {
+        fn enabled(&self, _metadata: &tracing::Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+            let mut names = self.names.lock().unwrap();
+            names.push(span.metadata().name());
+            tracing::span::Id::from_u64(names.len() as u64)
+        }
+
+        fn record(&self, _span: &tracing::span::Id, _values: &tracing::span::Record<'_>) {}
+        fn record_follows_from(&self, _span: &tracing::span::Id, _follows: &tracing::span::Id) {}
+        fn event(&self, _event: &tracing::Event<'_>) {}
+        fn enter(&self, _span: &tracing::span::Id) {}
+        fn exit(&self, _span: &tracing::span::Id) {}
+    }
+
+    #[test]
+    fn test_compile_emits_pass_spans() {
+        let recorder = std::sync::Arc::new(SpanRecorder::default());
+        tracing::subscriber::with_default(recorder.clone(), || {
+            let mut compiler = Compiler::new();
+            compiler.compile_string_to_rust("fn one() -> f64 { 1 + 2 }", "one.rsx").unwrap();
+        });
+
+        assert_eq!(
+            *recorder.names.lock().unwrap(),
+            ["compile", "lex", "parse", "typecheck", "analyze", "pass", "codegen"]
+        );
+    }
+
+    struct RenameComponent {
+        from: &'static str,
+        to: &'static str,
+    }
+
+    impl AstPass for RenameComponent {
+        fn run(&mut self, ast: &mut AST) -> rux_compiler::Result<()> {
+            for item in &mut ast.items {
+                if let Item::Component(component) = item {
+                    if component.name == self.from {
+                        component.name = self.to.to_string();
+                    }
+                }
+            }
+            Ok(())
+        }
+
+        fn name(&self) -> &'static str {
+            "rename"
+        }
+    }
+
+    #[test]
+    fn test_custom_pass_runs_before_codegen() {
+        let options = CompilerOptions { profile: true, ..CompilerOptions::default() };
+        let mut compiler = Compiler::new_with_options(options);
+        compiler.add_pass(Box::new(RenameComponent { from: "App", to: "MainView" }));
+
+        let rust = compiler.compile_string_to_rust("fn App() -> Element { <div>Hi</div> }", "app.rsx").unwrap();
+
+        assert!(rust.contains("pub fn main_view("), "{}", rust);
+        assert!(!rust.contains("pub fn app("));
+        assert!(compiler.timings()[0].get("rename").is_some());
+    }
+
+    #[test]
+    fn test_custom_pass_runs_on_partial_compile() {
+        let source = r#"
+            fn App() -> Element {
+                <p>"ok"</p>
+            }
+
+            fn Invalid() -> Element {
+                let label = "enabled: " + true;
+                <p>{label}</p>
+            }
+        "#;
+        let mut compiler = Compiler::new();
+        compiler.add_pass(Box::new(RenameComponent { from: "App", to: "MainView" }));
+
+        let code = compiler.compile_string_to_rust_partial(source, "app.rsx").unwrap();
+
+        assert!(code.contains("pub fn main_view("), "{}", code);
+        assert!(code.contains("String concatenation"));
+        let stub = code.find("compile_error!(").unwrap();
+        assert!(code.find("pub fn main_view(").unwrap() < stub, "the stub keeps its position");
+    }
+
+    #[test]
+    fn test_optimizer_runs_after_custom_passes() {
+        let options = CompilerOptions { profile: true, ..CompilerOptions::default() };
+        let mut compiler = Compiler::new_with_options(options);
+        compiler.add_pass(Box::new(RenameComponent { from: "App", to: "MainView" }));
+        compiler.compile_string_to_rust("fn App() -> Element { <div>Hi</div> }", "app.rsx").unwrap();
+
+        let passes: Vec<_> = compiler.timings()[0].passes.iter().map(|(name, _)| *name).collect();
+        assert_eq!(passes, ["lex", "parse", "typecheck", "analyze", "rename", "optimize", "codegen"]);
+    }
+
+    #[test]
+    fn test_recovered_parse_error_fails_compile() {
+        let source = "fn total() -> i32 {\n    let a = 1\n    a\n}";
+        let mut compiler = Compiler::new();
+        let err = compiler.compile_string(source, "total.rsx").unwrap_err();
+
+        assert_eq!(err.message(), "Missing ';'");
+        assert_eq!(err.help().unwrap().to_string(), "add ';'");
+        assert_eq!(err.span().offset(), source.find("1\n").unwrap() + 1);
+    }
+
+    #[test]
+    fn test_unused_import_warning() {
+        let source = "use math::double;\nuse math::half;\nuse math::*;\n\nmod math {\n    fn double(x: f64) -> f64 { x * 2 }\n    fn half(x: f64) -> f64 { x / 2 }\n}\n\nfn four() -> f64 { double(2) }";
+        let mut compiler = Compiler::new();
+        compiler.compile_string(source, "four.rsx").unwrap();
+
+        let [warning] = compiler.warnings().diagnostics() else {
+            panic!("Expected one warning, got {:?}", compiler.warnings().diagnostics());
+        };
+        assert_eq!(warning.severity, Severity::Warning);
+        assert_eq!(warning.message, "Unused import 'half'");
+        assert_eq!(warning.span.line, 2);
+    }
+
+    #[test]
+    fn test_compile_string_checked_returns_warnings() {
+        let source = "fn total() -> f64 {\n    let unused = 1;\n    let _ignored = 2;\n    let sum = 3;\n    sum\n}";
+        let mut compiler = Compiler::new();
+        let (result, warnings) = compiler.compile_string_checked(source, "total.rsx");
+
+        assert_eq!(result.unwrap().items.len(), 1);
+        let [warning] = warnings.as_slice() else {
+            panic!("Expected one warning, got {:?}", warnings);
+        };
+        assert_eq!(warning.message, "Unused variable 'unused'");
+        assert_eq!(warning.span.line, 2);
+
+        // Only this call's warnings are returned
+        let (result, warnings) = compiler.compile_string_checked("fn one() -> f64 { 1 }", "one.rsx");
+        assert!(result.is_ok());
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_incremental_checks_imports_first() {
+        let project = write_project(
+            &[
+                ("math.rsx", "fn double(x: f64) -> f64 { x * 2 }"),
+                ("app.rsx", "use math::double;\nfn four() -> f64 { double(2) }"),
+            ],
+        );
+        let root = project.path();
+        let math = root.join("math.rsx");
+        let app = root.join("app.rsx");
+
+        // The importing file comes first, but is checked after what it imports
+        let mut compiler = IncrementalCompiler::with_root(root);
+        compiler.compile_incremental(&[app.clone(), math.clone()]).unwrap();
+        assert_eq!(compiler.checked_files(), &[math, app]);
+    }
+
+    #[test]
+    fn test_incremental_rechecks_dependents_when_variant_payload_changes() {
+        let project = write_project(
+            &[
+                ("shapes.rsx", "enum Shape { Circle(f64) }"),
+                ("app.rsx", "use shapes::Shape;\nfn one() -> f64 { 1 }"),
+            ],
+        );
+        let root = project.path();
+        let shapes = root.join("shapes.rsx");
+        let app = root.join("app.rsx");
+
+        let mut compiler = IncrementalCompiler::with_root(root);
+        compiler.compile_incremental(&[shapes.clone(), app.clone()]).unwrap();
+
+        std::fs::write(&shapes, "enum Shape { Circle(f64, f64) }").unwrap();
+        compiler.compile_incremental(std::slice::from_ref(&shapes)).unwrap();
+        assert_eq!(compiler.checked_files(), &[shapes, app]);
+    }
+}
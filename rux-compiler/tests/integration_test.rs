@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod tests {
-    use rux_compiler::{Compiler, Lexer, Parser, TypeChecker};
+    use rux_compiler::{Lexer, Parser, TypeChecker};
 
     #[test]
     fn test_simple_component() {
@@ -0,0 +1,74 @@
+#[cfg(test)]
+mod tests {
+    use rux_compiler::{Lexer, Token};
+
+    #[test]
+    fn test_recovering_tokenizer_continues_past_bad_characters() {
+        let source = "let a = 1 ~ 2; let b = ` 3;";
+        let mut lexer = Lexer::new(source);
+        let (tokens, errors) = lexer.tokenize_recovering();
+
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].to_string().contains("Unexpected character: ~"));
+        assert!(errors[1].to_string().contains("Unexpected character: `"));
+
+        let kinds: Vec<Token> = tokens.iter().map(|t| t.token.clone()).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                Token::Let,
+                Token::Ident("a".to_string()),
+                Token::Eq,
+                Token::Number(1.0),
+                Token::Error,
+                Token::Number(2.0),
+                Token::Semicolon,
+                Token::Let,
+                Token::Ident("b".to_string()),
+                Token::Eq,
+                Token::Error,
+                Token::Number(3.0),
+                Token::Semicolon,
+                Token::Eof,
+            ]
+        );
+
+        let bad = &tokens[4].span;
+        assert_eq!(&source[bad.start..bad.end], "~");
+    }
+
+    #[test]
+    fn test_tokenize_still_fails_on_first_error() {
+        let mut lexer = Lexer::new("let a = ~ `;");
+        let err = lexer.tokenize().unwrap_err();
+        assert!(err.to_string().contains("Unexpected character: ~"));
+    }
+
+    #[test]
+    fn test_recovering_tokenizer_terminates_on_bad_jsx() {
+        let mut lexer = Lexer::new("fn App() -> Element { <div></ }");
+        let (tokens, errors) = lexer.tokenize_recovering();
+
+        assert!(!errors.is_empty());
+        assert_eq!(tokens.last().map(|t| &t.token), Some(&Token::Eof));
+    }
+
+    #[test]
+    fn test_doc_comments_are_tokens() {
+        let source = "/// A\n//! B\n// plain\n//// also plain\n/** C\n * D\n */\n/*! E */ /**/ fn";
+        let mut lexer = Lexer::new(source);
+        let kinds: Vec<Token> = lexer.tokenize().unwrap().into_iter().map(|t| t.token).collect();
+
+        assert_eq!(
+            kinds,
+            vec![
+                Token::DocComment(" A".to_string()),
+                Token::InnerDocComment(" B".to_string()),
+                Token::DocComment(" C\n D".to_string()),
+                Token::InnerDocComment(" E".to_string()),
+                Token::Fn,
+                Token::Eof,
+            ]
+        );
+    }
+}
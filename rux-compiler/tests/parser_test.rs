@@ -0,0 +1,399 @@
+#[cfg(test)]
+mod tests {
+    use rux_compiler::ast::{Expr, Item, JSXChild, JSXElement, JSXPropValue, Stmt, TypeKind, AST};
+    use miette::Diagnostic;
+    use rux_compiler::{Error, Lexer, Parser, Severity};
+
+    fn parse(source: &str) -> AST {
+        let mut lexer = Lexer::new(source);
+        let tokens = lexer.tokenize().unwrap();
+        let mut parser = Parser::new(tokens, source.to_string());
+        parser.parse().unwrap()
+    }
+
+    #[test]
+    fn test_function_where_clause() {
+        let ast = parse("fn f<T>(x: T) -> T where T: Clone { x }");
+
+        let function = match &ast.items[0] {
+            Item::Function(f) => f,
+            other => panic!("Expected function, got {:?}", other),
+        };
+        assert_eq!(function.generics, vec!["T".to_string()]);
+        assert_eq!(function.where_clause.len(), 1);
+
+        let predicate = &function.where_clause[0];
+        assert!(matches!(&predicate.ty.kind, TypeKind::Ident(name) if name == "T"));
+        assert_eq!(predicate.bounds.len(), 1);
+        assert!(matches!(&predicate.bounds[0].kind, TypeKind::Ident(name) if name == "Clone"));
+    }
+
+    #[test]
+    fn test_impl_where_clause() {
+        let ast = parse("impl<T> Show for Wrapper where T: Clone + Debug { fn show() {} }");
+
+        let impl_block = match &ast.items[0] {
+            Item::Impl(i) => i,
+            other => panic!("Expected impl, got {:?}", other),
+        };
+        assert_eq!(impl_block.generics, vec!["T".to_string()]);
+        assert_eq!(impl_block.trait_name.as_deref(), Some("Show"));
+        assert_eq!(impl_block.where_clause[0].bounds.len(), 2);
+    }
+
+    #[test]
+    fn test_item_attributes() {
+        let ast = parse("#[derive(Clone, Debug)] #[non_exhaustive] struct Point { x: f64 }");
+
+        let struct_def = match &ast.items[0] {
+            Item::Struct(s) => s,
+            other => panic!("Expected struct, got {:?}", other),
+        };
+        assert_eq!(struct_def.attributes.len(), 2);
+        assert_eq!(struct_def.attributes[0].name, "derive");
+        assert_eq!(struct_def.attributes[0].args.as_deref(), Some("Clone, Debug"));
+        assert_eq!(struct_def.attributes[1].name, "non_exhaustive");
+        assert!(struct_def.attributes[1].args.is_none());
+    }
+
+    #[test]
+    fn test_component_span_covers_body() {
+        let source = "  fn Card() -> Element {\n    <div>Hi</div>\n}\n";
+        let ast = parse(source);
+
+        let component = match &ast.items[0] {
+            Item::Component(c) => c,
+            other => panic!("Expected component, got {:?}", other),
+        };
+        let span = component.span;
+        assert_eq!(span.start, 2);
+        assert_eq!(span.end, source.trim_end().len());
+        assert!(source[span.start..span.end].starts_with("fn Card"));
+        assert!(source[span.start..span.end].ends_with('}'));
+        assert_eq!((span.line, span.column), (1, 3));
+    }
+
+    #[test]
+    fn test_jsx_element_span_covers_closing_tag() {
+        let source = "fn Card() -> Element { <div><span /></div> }";
+        let ast = parse(source);
+
+        let component = match &ast.items[0] {
+            Item::Component(c) => c,
+            other => panic!("Expected component, got {:?}", other),
+        };
+        let Expr::Block(block, _) = &component.body else {
+            panic!("Expected block body");
+        };
+        let Some(Stmt::Tail(Expr::JSXElement(element, span))) = block.statements.last() else {
+            panic!("Expected JSX tail expression");
+        };
+        assert_eq!(&source[span.start..span.end], "<div><span /></div>");
+
+        let JSXElement::WithChildren { children, .. } = element else {
+            panic!("Expected element with children");
+        };
+        let JSXChild::Element(JSXElement::SelfClosing { span, .. }) = &children[0] else {
+            panic!("Expected self-closing child");
+        };
+        assert_eq!(&source[span.start..span.end], "<span />");
+    }
+
+    #[test]
+    fn test_pretty_ast() {
+        let ast = parse("fn App(title: String) -> Element {\n    <div class=\"app\"><h1>{title}</h1></div>\n}");
+        let pretty = ast.pretty();
+
+        assert!(pretty.starts_with("Component(App) @1:1\n"));
+        assert!(pretty.contains("title: String"));
+        assert!(pretty.contains("JSX(div)"));
+        assert!(pretty.contains("class=String(\"app\")"));
+        assert!(pretty.contains("Variable(title)"));
+        assert!(!pretty.contains("Span"));
+        assert!(!pretty.contains("start:"));
+    }
+
+    #[test]
+    fn test_array_tuple_and_if_expressions() {
+        let ast = parse("fn pick(on: bool) -> String { let pairs = [(\"a\", on), (\"b\", true)]; let label = if on { \"a\" } else { \"b\" }; label }");
+        let Item::Function(function) = &ast.items[0] else {
+            panic!("Expected function");
+        };
+
+        let Some(Stmt::Let { value: Expr::Array(items, _), .. }) = function.body.statements.first() else {
+            panic!("Expected array literal");
+        };
+        assert_eq!(items.len(), 2);
+        assert!(matches!(&items[0], Expr::Tuple(pair, _) if pair.len() == 2));
+
+        let Some(Stmt::Let { value: Expr::If { else_, .. }, .. }) = function.body.statements.get(1) else {
+            panic!("Expected if expression");
+        };
+        assert!(else_.is_some());
+    }
+
+    fn jsx_children(source: &str) -> Vec<JSXChild> {
+        let ast = parse(source);
+        let Item::Component(component) = &ast.items[0] else {
+            panic!("Expected component");
+        };
+        let Expr::Block(block, _) = &component.body else {
+            panic!("Expected block body");
+        };
+        let Some(Stmt::Tail(Expr::JSXElement(JSXElement::WithChildren { children, .. }, _))) =
+            block.statements.last()
+        else {
+            panic!("Expected JSX element with children");
+        };
+        children.clone()
+    }
+
+    fn describe_children(children: &[JSXChild]) -> Vec<String> {
+        children
+            .iter()
+            .map(|child| match child {
+                JSXChild::Text(text, _) => format!("text {:?}", text),
+                JSXChild::Expr(Expr::Variable(name, _)) => format!("expr {}", name),
+                JSXChild::Expr(expr) => format!("expr {:?}", expr),
+                JSXChild::Element(_) => "element".to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_jsx_text_interpolation() {
+        let children = jsx_children("fn App(count: i32) -> Element { <p>Count: {count} times</p> }");
+        assert_eq!(
+            describe_children(&children),
+            vec!["text \"Count: \"", "expr count", "text \" times\""]
+        );
+
+        let children = jsx_children("fn App(a: i32, b: i32) -> Element { <p>{a} {b}<br />done</p> }");
+        assert_eq!(
+            describe_children(&children),
+            vec!["expr a", "text \" \"", "expr b", "element", "text \"done\""]
+        );
+    }
+
+    #[test]
+    fn test_jsx_text_drops_indentation() {
+        let source = "fn App(name: String) -> Element {\n    <p>\n        Hello,\n        {name}!\n    </p>\n}";
+        let children = jsx_children(source);
+        assert_eq!(
+            describe_children(&children),
+            vec!["text \"Hello,\"", "expr name", "text \"!\""]
+        );
+    }
+
+    #[test]
+    fn test_jsx_whitespace_collapses_runs() {
+        let children = jsx_children("fn App(n: i32) -> Element { <p>  Total:\t\t{n}   items  </p> }");
+        assert_eq!(
+            describe_children(&children),
+            vec!["text \" Total: \"", "expr n", "text \" items \""]
+        );
+    }
+
+    #[test]
+    fn test_jsx_whitespace_between_elements() {
+        let source = "fn App() -> Element {\n    <ul>\n        <li>One</li>\n\n        <li>Two</li> <li>Three</li>\n    </ul>\n}";
+        let children = jsx_children(source);
+        assert_eq!(
+            describe_children(&children),
+            vec!["element", "element", "text \" \"", "element"]
+        );
+    }
+
+    #[test]
+    fn test_jsx_multiline_text_joins_lines() {
+        let source = "fn App() -> Element {\n    <p>\n        Hello   there,\n\n        world\n    </p>\n}";
+        let children = jsx_children(source);
+        assert_eq!(describe_children(&children), vec!["text \"Hello there, world\""]);
+    }
+
+    #[test]
+    fn test_jsx_comment_is_ignored() {
+        let source = "fn App() -> Element {\n    <div>\n        <h1>Title</h1>\n        {/* the body */}\n        <p>Body</p>\n    </div>\n}";
+        let children = jsx_children(source);
+        assert_eq!(describe_children(&children), vec!["element", "element"]);
+
+        let children = jsx_children("fn App() -> Element { <p>{/* note */}Hi</p> }");
+        assert_eq!(describe_children(&children), vec!["text \"Hi\""]);
+    }
+    fn param_type(source: &str) -> rux_compiler::ast::Type {
+        match parse(source).items.remove(0) {
+            Item::Function(f) => f.params[0].param_type.clone(),
+            other => panic!("Expected function, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_boxed_trait_object_type() {
+        let ty = param_type("fn run(callback: Box<dyn Fn(i32) -> ()>) {}");
+
+        let TypeKind::Generic { name, args } = &ty.kind else {
+            panic!("Expected generic type, got {:?}", ty.kind);
+        };
+        assert_eq!(name, "Box");
+        let TypeKind::Dyn(inner) = &args[0].kind else {
+            panic!("Expected dyn type, got {:?}", args[0].kind);
+        };
+        assert!(matches!(&inner.kind, TypeKind::FnTrait { name, params, return_type: Some(_) }
+            if name == "Fn" && params.len() == 1));
+        assert_eq!(ty.to_string(), "Box<dyn Fn(i32) -> ()>");
+    }
+
+    #[test]
+    fn test_nested_generic_type() {
+        let ty = param_type("fn show(items: Option<Vec<String>>) {}");
+
+        let TypeKind::Option(inner) = &ty.kind else {
+            panic!("Expected option type, got {:?}", ty.kind);
+        };
+        assert!(matches!(&inner.kind, TypeKind::Generic { name, args } if name == "Vec" && args.len() == 1));
+        assert_eq!(ty.to_string(), "Option<Vec<String>>");
+
+        let ty = param_type("fn share(state: Option<Rc<RefCell<State>>>, render: impl Fn()) {}");
+        assert_eq!(ty.to_string(), "Option<Rc<RefCell<State>>>");
+    }
+
+    #[test]
+    fn test_jsx_bind_attribute() {
+        let ast = parse("fn Field(text: Signal<String>) -> Element { <input bind:value={text} /> }");
+
+        let Item::Component(component) = &ast.items[0] else {
+            panic!("Expected component, got {:?}", ast.items[0]);
+        };
+        let Expr::Block(block, _) = &component.body else {
+            panic!("Expected block body");
+        };
+        let Some(Stmt::Tail(Expr::JSXElement(JSXElement::SelfClosing { props, .. }, _))) = block.statements.last() else {
+            panic!("Expected JSX element, got {:?}", block.statements);
+        };
+        assert_eq!(props[0].name, "bind:value");
+        assert!(matches!(&props[0].value, JSXPropValue::Expr(Expr::Variable(name, _)) if name == "text"));
+    }
+
+    #[test]
+    fn test_jsx_bind_rejects_unknown_property() {
+        let source = "fn Field(text: Signal<String>) -> Element { <input bind:title={text} /> }";
+        let mut lexer = Lexer::new(source);
+        let tokens = lexer.tokenize().unwrap();
+        let err = Parser::new(tokens, source.to_string()).parse().unwrap_err();
+        assert!(err.to_string().contains("Cannot bind 'title'"), "{}", err);
+    }
+
+    #[test]
+    fn test_jsx_event_modifiers() {
+        let ast = parse("fn SaveForm() -> Element { <form onSubmit:prevent:stop={save} /> }");
+
+        let Item::Component(component) = &ast.items[0] else {
+            panic!("Expected component, got {:?}", ast.items[0]);
+        };
+        let Expr::Block(block, _) = &component.body else {
+            panic!("Expected block body");
+        };
+        let Some(Stmt::Tail(Expr::JSXElement(JSXElement::SelfClosing { props, .. }, _))) = block.statements.last() else {
+            panic!("Expected JSX element, got {:?}", block.statements);
+        };
+        assert_eq!(props[0].name, "onSubmit");
+        assert_eq!(props[0].modifiers, vec!["prevent", "stop"]);
+    }
+
+    #[test]
+    fn test_jsx_unknown_event_modifier() {
+        let source = "fn SaveForm() -> Element { <form onSubmit:later={save} /> }";
+        let mut lexer = Lexer::new(source);
+        let tokens = lexer.tokenize().unwrap();
+        let err = Parser::new(tokens, source.to_string()).parse().unwrap_err();
+        assert!(err.to_string().contains("Unknown event modifier 'later'"), "{}", err);
+    }
+
+    #[test]
+    fn test_unclosed_jsx_points_at_open_tag() {
+        let source = "fn App() -> Element { <div><span></div> }";
+        let mut lexer = Lexer::new(source);
+        let tokens = lexer.tokenize().unwrap();
+        let err = Parser::new(tokens, source.to_string()).parse().unwrap_err();
+
+        assert!(err.to_string().contains("Unclosed <span>: expected </span> but found </div>"), "{}", err);
+        let Error::Parser { span, .. } = err else {
+            panic!("Expected parser error, got {:?}", err);
+        };
+        assert_eq!(&source[span.offset()..span.offset() + span.len()], "<span");
+    }
+
+    #[test]
+    fn test_mismatched_closing_tag_suggests_open_tag() {
+        let source = "fn App() -> Element { <div></span> }";
+        let mut lexer = Lexer::new(source);
+        let tokens = lexer.tokenize().unwrap();
+        let err = Parser::new(tokens, source.to_string()).parse().unwrap_err();
+
+        assert_eq!(err.help().unwrap().to_string(), "<div> is closed with </div>");
+        let labels: Vec<_> = err.labels().unwrap().map(|label| (label.label().map(str::to_string), label.offset())).collect();
+        assert!(labels.contains(&(Some("opened here".to_string()), source.find("<div>").unwrap())), "{:?}", labels);
+        assert!(labels.contains(&(Some("closed here".to_string()), source.find("</span>").unwrap())), "{:?}", labels);
+    }
+
+    #[test]
+    fn test_missing_semicolon_is_recovered() {
+        let source = "fn total() -> i32 {\n    let a = 1\n    let b = 2;\n    a + b\n}";
+        let mut lexer = Lexer::new(source);
+        let tokens = lexer.tokenize().unwrap();
+        let mut parser = Parser::new(tokens, source.to_string());
+        let ast = parser.parse().unwrap();
+
+        let [missing] = parser.diagnostics() else {
+            panic!("Expected one diagnostic, got {:?}", parser.diagnostics());
+        };
+        assert_eq!(missing.severity, Severity::Error);
+        assert_eq!(missing.message, "Missing ';'");
+        assert_eq!(missing.help.as_deref(), Some("add ';'"));
+        let insert_at = source.find("1\n").unwrap() + 1;
+        assert_eq!((missing.span.start, missing.span.end), (insert_at, insert_at));
+        assert_eq!((missing.span.line, missing.span.column), (2, 14));
+
+        // Parsing went on past the missing semicolon
+        let Item::Function(function) = &ast.items[0] else {
+            panic!("Expected function, got {:?}", ast.items[0]);
+        };
+        assert_eq!(function.body.statements.len(), 3);
+    }
+
+    #[test]
+    fn test_missing_semicolon_mid_line_is_an_error() {
+        let source = "fn total() -> i32 { let a = 1 let b = 2; a + b }";
+        let mut lexer = Lexer::new(source);
+        let tokens = lexer.tokenize().unwrap();
+        let err = Parser::new(tokens, source.to_string()).parse().unwrap_err();
+        assert!(err.to_string().contains("Expected ';'"), "{}", err);
+    }
+
+    #[test]
+    fn test_tuple_index_access() {
+        let ast = parse("fn f(pair: (f64, f64), nested: ((f64, f64), f64)) -> f64 { pair.0 + nested.0.1 }");
+
+        let function = match &ast.items[0] {
+            Item::Function(f) => f,
+            other => panic!("Expected function, got {:?}", other),
+        };
+        let Some(Stmt::Tail(Expr::Binary { left, right, .. })) = function.body.statements.last() else {
+            panic!("Expected a binary tail, got {:?}", function.body.statements);
+        };
+        match left.as_ref() {
+            Expr::FieldAccess { object, field, .. } => {
+                assert!(matches!(object.as_ref(), Expr::Variable(name, _) if name == "pair"));
+                assert_eq!(field, "0");
+            }
+            other => panic!("Expected field access, got {:?}", other),
+        }
+        match right.as_ref() {
+            Expr::FieldAccess { object, field, .. } => {
+                assert_eq!(field, "1");
+                assert!(matches!(object.as_ref(), Expr::FieldAccess { field, .. } if field == "0"));
+            }
+            other => panic!("Expected field access, got {:?}", other),
+        }
+    }
+}
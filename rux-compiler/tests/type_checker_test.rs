@@ -0,0 +1,429 @@
+#[cfg(test)]
+mod tests {
+    use rux_compiler::{Lexer, Parser, TypeChecker};
+
+    fn check(source: &str) -> rux_compiler::Result<()> {
+        let mut lexer = Lexer::new(source);
+        let tokens = lexer.tokenize().unwrap();
+        let mut parser = Parser::new(tokens, source.to_string());
+        let ast = parser.parse().unwrap();
+
+        let mut type_checker = TypeChecker::new();
+        type_checker.check(&ast)
+    }
+
+    #[test]
+    fn test_non_exhaustive_enum_match() {
+        let source = r#"
+            enum Light { On, Off }
+
+            fn describe(light: Light) {
+                match light {
+                    Light::On => 1,
+                }
+            }
+        "#;
+
+        let err = check(source).unwrap_err();
+        assert!(err.to_string().contains("Non-exhaustive match"));
+        assert!(err.to_string().contains("Light::Off"));
+    }
+
+    #[test]
+    fn test_other_enum_variant_does_not_cover_match() {
+        let source = r#"
+            enum Light { On, Off }
+            enum Switch { On, Off }
+
+            fn describe(light: Light) {
+                match light {
+                    Light::On => 1,
+                    Switch::Off => 0,
+                }
+            }
+        "#;
+
+        let err = check(source).unwrap_err();
+        assert!(err.to_string().contains("missing Light::Off"), "{}", err);
+    }
+
+    #[test]
+    fn test_exhaustive_enum_match() {
+        let source = r#"
+            enum Light { On, Off }
+
+            fn describe(light: Light) {
+                let a = match light {
+                    Light::On => 1,
+                    Light::Off => 0,
+                };
+                let b = match light {
+                    Light::On => 1,
+                    _ => 0,
+                };
+            }
+        "#;
+
+        check(source).unwrap();
+    }
+
+    #[test]
+    fn test_string_concatenation() {
+        let source = r#"
+            fn greet(name: String) -> String {
+                "Hello, " + name
+            }
+        "#;
+
+        check(source).unwrap();
+    }
+
+    #[test]
+    fn test_string_plus_bool_is_error() {
+        let source = r#"
+            fn label() -> String {
+                "enabled: " + true
+            }
+        "#;
+
+        let err = check(source).unwrap_err();
+        assert!(err.to_string().contains("String concatenation"));
+    }
+
+    #[test]
+    fn test_imported_function_call() {
+        let source = r#"
+            use math::double;
+
+            mod math {
+                fn double(x: f64) -> f64 { x * 2 }
+            }
+
+            fn four() -> f64 {
+                double(2)
+            }
+        "#;
+
+        check(source).unwrap();
+    }
+
+    #[test]
+    fn test_unresolved_import_in_project() {
+        let source = "use math::missing;\nmod math { fn double(x: f64) -> f64 { x * 2 } }";
+        let mut lexer = Lexer::new(source);
+        let tokens = lexer.tokenize().unwrap();
+        let mut parser = Parser::new(tokens, source.to_string());
+        let ast = parser.parse().unwrap();
+
+        let mut type_checker = TypeChecker::new_for_project();
+        let err = type_checker.check(&ast).unwrap_err();
+        assert!(err.to_string().contains("Unresolved import 'math::missing'"));
+    }
+
+    #[test]
+    fn test_param_default_matches_type() {
+        check(r#"fn Button(label: String = "OK", size: f64 = 2) -> Element { <button>{label}</button> }"#).unwrap();
+
+        let err = check(r#"fn Button(label: String = true) -> Element { <button>{label}</button> }"#).unwrap_err();
+        assert!(err.to_string().contains("Default value for 'label' must be String"));
+    }
+
+    #[test]
+    fn test_component_children_in_scope() {
+        check(r#"fn Card() -> Element { let content = children; <div class="card">{content}</div> }"#).unwrap();
+    }
+
+    #[test]
+    fn test_range_for_binds_loop_variable() {
+        check("fn total() { for i in 0..3 { let next = i + 1; } }").unwrap();
+        assert!(check("fn total() { for i in 0..3 { let next = j + 1; } }").is_err());
+        check("fn total() { for i in 1..=3 { let doubled = i * 2; } }").unwrap();
+    }
+
+    #[test]
+    fn test_cannot_iterate_over_number() {
+        let err = check("fn total() { for i in 3 { } }").unwrap_err();
+        assert!(err.to_string().contains("Cannot iterate over"));
+
+        let err = check(r#"fn total() { for i in 0.."end" { } }"#).unwrap_err();
+        assert!(err.to_string().contains("Range bounds must be numbers"));
+    }
+
+    #[test]
+    fn test_option_and_result_patterns() {
+        check("fn get(value: Option<f64>) -> f64 { match value { Some(n) => n + 1, None => 0 } }").unwrap();
+        check("fn get(value: Result<f64, String>) -> f64 { match value { Ok(n) => n, Err(_) => 0 } }").unwrap();
+
+        let err = check("fn get(value: Option<f64>) -> f64 { match value { Some(n) => n } }").unwrap_err();
+        assert!(err.to_string().contains("missing Option::None"));
+
+        let err = check("fn get(value: f64) -> f64 { match value { Some(n) => n, _ => 0 } }").unwrap_err();
+        assert!(err.to_string().contains("Pattern 'Some(..)' does not match type f64"));
+    }
+
+    #[test]
+    fn test_enum_tuple_variant_pattern_binds_fields() {
+        let source = r#"
+            enum Shape { Circle(f64), Square(f64) }
+
+            fn size(shape: Shape) -> f64 {
+                match shape {
+                    Shape::Circle(r) => r * 2,
+                    Shape::Square(side) => side,
+                }
+            }
+        "#;
+
+        check(source).unwrap();
+    }
+
+    #[test]
+    fn test_component_block_must_end_in_element() {
+        let source = "fn App() -> Element { let x = 1; 42 }";
+
+        let err = check(source).unwrap_err();
+        assert!(err.to_string().contains("Component 'App' body type mismatch"));
+        assert_eq!(err.span().offset(), source.find("42").unwrap());
+
+        let err = check("fn App() -> Element { let x = 1; }").unwrap_err();
+        assert!(err.to_string().contains("found Unit"));
+    }
+
+    #[test]
+    fn test_impl_method_call() {
+        let source = r#"
+            struct Counter { count: f64 }
+
+            impl Counter {
+                fn increment(self: Counter, by: f64) -> f64 {
+                    by + 1
+                }
+            }
+
+            fn next(counter: Counter) -> f64 {
+                counter.increment(2)
+            }
+        "#;
+
+        check(source).unwrap();
+    }
+
+    #[test]
+    fn test_method_call_argument_mismatch() {
+        let source = r#"
+            struct Counter { count: f64 }
+
+            impl Counter {
+                fn increment(self: Counter, by: f64) -> f64 {
+                    by + 1
+                }
+            }
+
+            fn next(counter: Counter) -> f64 {
+                counter.increment("two")
+            }
+        "#;
+
+        let err = check(source).unwrap_err();
+        assert!(err.to_string().contains("must be f64, found String"), "{}", err);
+
+        let err = check(&source.replace("increment(\"two\")", "increment()")).unwrap_err();
+        assert!(err.to_string().contains("takes 1 argument(s), found 0"), "{}", err);
+    }
+
+    #[test]
+    fn test_trait_default_method_call() {
+        let source = r#"
+            struct Counter { count: f64 }
+
+            trait Reset {
+                fn reset(self: Self) -> Self {
+                    self
+                }
+            }
+
+            impl Reset for Counter {}
+
+            fn fresh(counter: Counter) -> Counter {
+                counter.reset()
+            }
+        "#;
+
+        check(source).unwrap();
+    }
+
+    #[test]
+    fn test_integer_literal_takes_annotated_type() {
+        let source = r#"
+            fn fade(alpha: u8) -> u8 {
+                alpha
+            }
+
+            fn scale(factor: i64) -> i64 {
+                factor
+            }
+
+            fn build() {
+                let faded = fade(5);
+                let scaled = scale(5);
+            }
+        "#;
+
+        check(source).unwrap();
+    }
+
+    #[test]
+    fn test_integer_literal_rejected_by_non_numeric_type() {
+        let source = r#"
+            fn toggle(on: bool) -> bool {
+                on
+            }
+
+            fn build() {
+                let flipped = toggle(5);
+            }
+        "#;
+
+        let err = check(source).unwrap_err();
+        assert!(err.to_string().contains("Expected bool, found integer literal 5"), "{}", err);
+    }
+
+    #[test]
+    fn test_integer_literal_out_of_range() {
+        let source = r#"
+            fn fade(alpha: u8) -> u8 {
+                alpha
+            }
+
+            fn build() {
+                let faded = fade(300);
+            }
+        "#;
+
+        let err = check(source).unwrap_err();
+        assert!(err.to_string().contains("Literal 300 is out of range for u8"), "{}", err);
+    }
+
+    #[test]
+    fn test_loop_takes_break_value_type() {
+        let source = r#"
+            fn toggle(on: bool) -> bool {
+                on
+            }
+
+            fn build() {
+                let done = loop {
+                    break true;
+                };
+                let flipped = toggle(done);
+            }
+        "#;
+
+        check(source).unwrap();
+    }
+
+    #[test]
+    fn test_loop_break_value_types_binding() {
+        let source = r#"
+            fn build() {
+                let label = loop {
+                    break "done";
+                };
+                let text = label + true;
+            }
+        "#;
+
+        let err = check(source).unwrap_err();
+        assert!(err.to_string().contains("String concatenation"), "{}", err);
+    }
+
+    #[test]
+    fn test_loop_breaks_must_match() {
+        let source = r#"
+            fn build(ready: bool) {
+                let value = loop {
+                    if ready {
+                        break 1;
+                    }
+                    break "no";
+                };
+            }
+        "#;
+
+        let err = check(source).unwrap_err();
+        assert!(err.to_string().contains("Loop breaks with mismatched types"), "{}", err);
+    }
+
+    #[test]
+    fn test_break_value_outside_loop_expression() {
+        let source = r#"
+            fn build(ready: bool) {
+                while ready {
+                    break 5;
+                }
+            }
+        "#;
+
+        let err = check(source).unwrap_err();
+        assert!(err.to_string().contains("break with a value only works inside loop"), "{}", err);
+    }
+
+    #[test]
+    fn test_nested_field_access() {
+        let source = r#"
+            struct Inner {
+                value: i32,
+            }
+
+            struct Outer {
+                inner: Inner,
+            }
+
+            fn read(outer: Outer) -> i32 {
+                outer.inner.value
+            }
+
+            fn total(outer: Outer) -> i32 {
+                let value = outer.inner.value;
+                value + 1
+            }
+        "#;
+
+        check(source).unwrap();
+    }
+
+    #[test]
+    fn test_unknown_nested_field() {
+        let source = r#"
+            struct Inner {
+                value: i32,
+            }
+
+            struct Outer {
+                inner: Inner,
+            }
+
+            fn read(outer: Outer) -> i32 {
+                outer.inner.missing
+            }
+        "#;
+
+        let err = check(source).unwrap_err();
+        assert!(err.to_string().contains("Unknown field 'missing' on struct Inner"), "{}", err);
+        let rux_compiler::Error::Type { span, .. } = err else {
+            panic!("expected a type error, found {:?}", err);
+        };
+        assert_eq!(&source[span.offset()..span.offset() + span.len()], "missing");
+    }
+
+    #[test]
+    fn test_integer_literal_return_value_out_of_range() {
+        let tail = check("fn f() -> u8 { 300 }").unwrap_err();
+        assert!(tail.to_string().contains("Literal 300 is out of range for u8"), "{}", tail);
+
+        let early = check("fn f(big: bool) -> u8 { if big { return 256; } 1 }").unwrap_err();
+        assert!(early.to_string().contains("Literal 256 is out of range for u8"), "{}", early);
+
+        check("fn f() -> u8 { 255 }").unwrap();
+    }
+}
@@ -0,0 +1,28 @@
+//! Rough timings for `diff` on list updates. Run with `cargo bench -p rux-core`.
+
+use rux_core::virtual_tree::{diff, VirtualNode};
+use std::hint::black_box;
+use std::time::Instant;
+
+fn list(keys: impl Iterator<Item = usize>) -> VirtualNode {
+    VirtualNode::element("ul")
+        .children(keys.map(|k| VirtualNode::element("li").key(k.to_string()).child(VirtualNode::text(k.to_string()))))
+        .build()
+}
+
+fn bench(name: &str, old: &VirtualNode, new: &VirtualNode) {
+    const RUNS: u32 = 200;
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        black_box(diff(black_box(old), black_box(new)));
+    }
+    println!("{:<24} {:>10.2?}/diff", name, start.elapsed() / RUNS);
+}
+
+fn main() {
+    let old = list(0..1000);
+    bench("unchanged 1000", &old, &list(0..1000));
+    bench("append 1000", &old, &list(0..1100));
+    bench("reverse 1000", &old, &list((0..1000).rev()));
+    bench("remove half 1000", &old, &list((0..1000).step_by(2)));
+}
@@ -0,0 +1,93 @@
+use crate::layout::{OVERFLOW_PROP, Z_INDEX_PROP};
+use crate::virtual_tree::PropValue;
+
+/// Attributes whose presence means true. `false` has to remove them, since
+/// even `disabled="false"` disables an element.
+const BOOLEAN_ATTRIBUTES: &[&str] = &[
+    "allowfullscreen",
+    "async",
+    "autofocus",
+    "autoplay",
+    "checked",
+    "controls",
+    "default",
+    "defer",
+    "disabled",
+    "formnovalidate",
+    "hidden",
+    "inert",
+    "ismap",
+    "itemscope",
+    "loop",
+    "multiple",
+    "muted",
+    "nomodule",
+    "novalidate",
+    "open",
+    "playsinline",
+    "readonly",
+    "required",
+    "reversed",
+    "selected",
+];
+
+/// Attributes taking the literal strings `"true"` and `"false"`
+const ENUMERATED_ATTRIBUTES: &[&str] = &["draggable", "contenteditable", "spellcheck"];
+
+/// What setting a prop does to a DOM attribute
+#[derive(Debug, Clone, PartialEq)]
+pub enum AttributeUpdate {
+    Set(String, String),
+    Remove(String),
+    /// Inline style property, set without touching the rest of `style`
+    Style(String, String),
+    /// Not an attribute, e.g. an event handler
+    Skip,
+}
+
+pub fn is_boolean_attribute(name: &str) -> bool {
+    BOOLEAN_ATTRIBUTES.contains(&name)
+}
+
+/// Enumerated attributes, plus `aria-*` and `data-*` which are strings
+pub fn is_enumerated_attribute(name: &str) -> bool {
+    ENUMERATED_ATTRIBUTES.contains(&name) || name.starts_with("aria-") || name.starts_with("data-")
+}
+
+/// The attribute change for setting `key` to `value`. Server rendering and
+/// the web renderer both go through this, so hydrated markup starts out
+/// with the attributes the client would set.
+pub fn attribute_update(key: &str, value: &PropValue) -> AttributeUpdate {
+    match value {
+        // Only applies to positioned elements and flex or grid items
+        PropValue::Number(n) if key == Z_INDEX_PROP => AttributeUpdate::Style("z-index".to_string(), (*n as i32).to_string()),
+        PropValue::String(s) if key == Z_INDEX_PROP => AttributeUpdate::Style("z-index".to_string(), s.clone()),
+        PropValue::String(s) if key == OVERFLOW_PROP => AttributeUpdate::Style("overflow".to_string(), s.clone()),
+        PropValue::String(_) | PropValue::Function(_) if key.starts_with("on") => {
+            // Event handlers would be attached as listeners
+            AttributeUpdate::Skip
+        }
+        PropValue::String(s) => AttributeUpdate::Set(key.to_string(), s.clone()),
+        PropValue::Function(_) => AttributeUpdate::Skip,
+        PropValue::Boolean(b) if is_enumerated_attribute(key) => AttributeUpdate::Set(key.to_string(), b.to_string()),
+        PropValue::Boolean(true) => AttributeUpdate::Set(key.to_string(), String::new()),
+        PropValue::Boolean(false) => AttributeUpdate::Remove(key.to_string()),
+        PropValue::Number(n) => AttributeUpdate::Set(key.to_string(), n.to_string()),
+        PropValue::Array(items) if key == "class" || key == "classList" => {
+            AttributeUpdate::Set("class".to_string(), class_list(items))
+        }
+        PropValue::Array(_) => AttributeUpdate::Set(key.to_string(), value.to_json()),
+    }
+}
+
+/// Space-separated class names; non-string items use their JSON form
+fn class_list(items: &[PropValue]) -> String {
+    items
+        .iter()
+        .map(|item| match item {
+            PropValue::String(s) => s.clone(),
+            other => other.to_json(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
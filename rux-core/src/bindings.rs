@@ -0,0 +1,88 @@
+use crate::signals::Signal;
+use crate::virtual_tree::PropValue;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Prefix of the handler names `bind_value` and `bind_checked` return
+const BINDING_PREFIX: &str = "bind:";
+
+/// Writes an element's input to its signal; false once the signal is gone
+type WriteBack = Box<dyn Fn(&str) -> bool>;
+
+thread_local! {
+    /// Write-backs of the bound signals, keyed by handler name. They hold
+    /// their signal weakly and go away once it is dropped.
+    static BINDINGS: RefCell<HashMap<String, WriteBack>> = RefCell::new(HashMap::new());
+}
+
+/// What a bound element reports when its handler runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BindingKind {
+    /// The element's `value`, for `bind:value`
+    Value,
+    /// Its `checked` state as `"true"` or `"false"`, for `bind:checked`
+    Checked,
+}
+
+impl BindingKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            BindingKind::Value => "value",
+            BindingKind::Checked => "checked",
+        }
+    }
+}
+
+/// Handler prop for `bind:value={signal}`: renderers that run it write the
+/// element's value back to `signal`. Input that doesn't parse as `T` is
+/// ignored.
+pub fn bind_value<T: FromStr + 'static>(signal: &Signal<T>) -> PropValue {
+    register(signal, BindingKind::Value, |input| input.parse().ok())
+}
+
+/// Handler prop for `bind:checked={signal}`, writing the element's checked
+/// state back to `signal`
+pub fn bind_checked(signal: &Signal<bool>) -> PropValue {
+    register(signal, BindingKind::Checked, |input| input.parse().ok())
+}
+
+fn register<T: 'static>(signal: &Signal<T>, kind: BindingKind, parse: fn(&str) -> Option<T>) -> PropValue {
+    let name = format!("{}{}:{}", BINDING_PREFIX, kind.as_str(), signal.id());
+    let signal = signal.downgrade();
+    let write_back = move |input: &str| {
+        let Some(signal) = signal.upgrade() else {
+            return false;
+        };
+        if let Some(value) = parse(input) {
+            signal.set(value);
+        }
+        true
+    };
+    BINDINGS.with(|bindings| bindings.borrow_mut().insert(name.clone(), Box::new(write_back)));
+    PropValue::Function(name)
+}
+
+/// What the element should report for `handler`, if it is a handler
+/// `bind_value` or `bind_checked` returned
+pub fn binding_kind(handler: &str) -> Option<BindingKind> {
+    let kind = handler.strip_prefix(BINDING_PREFIX)?.split(':').next()?;
+    [BindingKind::Value, BindingKind::Checked].into_iter().find(|k| k.as_str() == kind)
+}
+
+/// Writes `input`, what the element reported as `binding_kind` describes,
+/// to the signal bound under `handler`. Returns false if no live signal
+/// is bound under that name.
+pub fn write_binding(handler: &str, input: &str) -> bool {
+    // Taken out while it runs, since `set` may re-render and bind again
+    let Some(write_back) = BINDINGS.with(|bindings| bindings.borrow_mut().remove(handler)) else {
+        return false;
+    };
+    let written = write_back(input);
+    if written {
+        BINDINGS.with(|bindings| {
+            bindings.borrow_mut().entry(handler.to_string()).or_insert(write_back);
+        });
+    }
+    written
+}
@@ -1,3 +1,4 @@
+use crate::virtual_tree::{NodeId, PropValue, VirtualNode};
 use std::collections::HashMap;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -8,6 +9,20 @@ pub struct Rect {
     pub height: f32,
 }
 
+impl Rect {
+    /// The area inside both rects, empty if they don't overlap
+    pub fn intersect(&self, other: &Rect) -> Rect {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        Rect {
+            x,
+            y,
+            width: ((self.x + self.width).min(other.x + other.width) - x).max(0.0),
+            height: ((self.y + self.height).min(other.y + other.height) - y).max(0.0),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Size {
     pub width: f32,
@@ -56,6 +71,73 @@ impl Constraints {
             height: size.height.max(self.min_height).min(self.max_height),
         }
     }
+    
+    /// Sizes allowed by both `self` and `other`. When the ranges don't
+    /// overlap on an axis the result is tight at the smaller maximum, so it
+    /// never takes more space than either side allows.
+    pub fn intersect(&self, other: &Constraints) -> Constraints {
+        let max_width = self.max_width.min(other.max_width);
+        let max_height = self.max_height.min(other.max_height);
+        Constraints {
+            min_width: self.min_width.max(other.min_width).min(max_width),
+            max_width,
+            min_height: self.min_height.max(other.min_height).min(max_height),
+            max_height,
+        }
+    }
+    
+    /// The constraints left for content inside `insets`, e.g. padding.
+    /// Nothing goes below zero and an unbounded maximum stays unbounded.
+    pub fn deflate(&self, insets: EdgeInsets) -> Constraints {
+        let horizontal = insets.horizontal();
+        let vertical = insets.vertical();
+        let min_width = (self.min_width - horizontal).max(0.0);
+        let min_height = (self.min_height - vertical).max(0.0);
+        Constraints {
+            min_width,
+            max_width: (self.max_width - horizontal).max(min_width),
+            min_height,
+            max_height: (self.max_height - vertical).max(min_height),
+        }
+    }
+}
+
+/// Space around each side of a box, such as padding or margins
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct EdgeInsets {
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+    pub left: f32,
+}
+
+impl EdgeInsets {
+    pub fn new(top: f32, right: f32, bottom: f32, left: f32) -> Self {
+        Self {
+            top,
+            right,
+            bottom,
+            left,
+        }
+    }
+    
+    pub fn all(value: f32) -> Self {
+        Self::new(value, value, value, value)
+    }
+    
+    pub fn symmetric(horizontal: f32, vertical: f32) -> Self {
+        Self::new(vertical, horizontal, vertical, horizontal)
+    }
+    
+    /// Left plus right
+    pub fn horizontal(&self) -> f32 {
+        self.left + self.right
+    }
+    
+    /// Top plus bottom
+    pub fn vertical(&self) -> f32 {
+        self.top + self.bottom
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -101,28 +183,62 @@ impl FlexLayout {
     }
     
     pub fn layout(&self, constraints: Constraints, children: &[Box<dyn LayoutChild>]) -> Vec<Rect> {
+        self.layout_cached(constraints, children, &mut MeasureCache::new())
+    }
+    
+    /// Like `layout`, measuring children through `cache`
+    pub fn layout_cached(
+        &self,
+        constraints: Constraints,
+        children: &[Box<dyn LayoutChild>],
+        cache: &mut MeasureCache,
+    ) -> Vec<Rect> {
+        self.layout_with_text(constraints, children, cache, &Unmeasured)
+    }
+    
+    /// Like `layout_cached`, sizing text children with `text`
+    pub fn layout_with_text(
+        &self,
+        constraints: Constraints,
+        children: &[Box<dyn LayoutChild>],
+        cache: &mut MeasureCache,
+        text: &dyn TextMeasurer,
+    ) -> Vec<Rect> {
         match self.direction {
-            LayoutDirection::Horizontal => self.layout_horizontal(constraints, children),
-            LayoutDirection::Vertical => self.layout_vertical(constraints, children),
+            LayoutDirection::Horizontal => self.layout_horizontal(constraints, children, cache, text),
+            LayoutDirection::Vertical => self.layout_vertical(constraints, children, cache, text),
         }
     }
     
-    fn layout_horizontal(&self, constraints: Constraints, children: &[Box<dyn LayoutChild>]) -> Vec<Rect> {
+    fn layout_horizontal(
+        &self,
+        constraints: Constraints,
+        children: &[Box<dyn LayoutChild>],
+        cache: &mut MeasureCache,
+        text: &dyn TextMeasurer,
+    ) -> Vec<Rect> {
         let mut positions = Vec::new();
         let mut current_x: f32 = 0.0;
         let mut max_height: f32 = 0.0;
         
         // First pass: calculate sizes
-        let child_constraints = Constraints::new(
-            0.0,
-            constraints.max_width / children.len() as f32,
-            constraints.min_height,
-            constraints.max_height,
-        );
+        let preferred: Vec<f32> = children
+            .iter()
+            .map(|child| {
+                let declared = child.width().resolve(constraints.max_width);
+                declared.unwrap_or_else(|| cache.intrinsic_size_with_text(child.as_ref(), text).width)
+            })
+            .collect();
+        let limits = main_axis_limits(constraints.max_width, &preferred);
         
         let mut child_sizes: Vec<Size> = children
             .iter()
-            .map(|child| child.layout(child_constraints))
+            .zip(limits)
+            .map(|(child, limit)| {
+                let child_constraints = Constraints::new(0.0, limit, constraints.min_height, constraints.max_height);
+                let child_constraints = sized_constraints(child.as_ref(), child_constraints, available(constraints));
+                cache.measure_with_text(child.as_ref(), child_constraints, text)
+            })
             .collect();
         
         // Calculate total width
@@ -164,22 +280,35 @@ impl FlexLayout {
         positions
     }
     
-    fn layout_vertical(&self, constraints: Constraints, children: &[Box<dyn LayoutChild>]) -> Vec<Rect> {
+    fn layout_vertical(
+        &self,
+        constraints: Constraints,
+        children: &[Box<dyn LayoutChild>],
+        cache: &mut MeasureCache,
+        text: &dyn TextMeasurer,
+    ) -> Vec<Rect> {
         let mut positions = Vec::new();
         let mut current_y: f32 = 0.0;
         let mut max_width: f32 = 0.0;
         
         // First pass: calculate sizes
-        let child_constraints = Constraints::new(
-            constraints.min_width,
-            constraints.max_width,
-            0.0,
-            constraints.max_height / children.len() as f32,
-        );
+        let preferred: Vec<f32> = children
+            .iter()
+            .map(|child| {
+                let declared = child.height().resolve(constraints.max_height);
+                declared.unwrap_or_else(|| cache.intrinsic_size_with_text(child.as_ref(), text).height)
+            })
+            .collect();
+        let limits = main_axis_limits(constraints.max_height, &preferred);
         
         let mut child_sizes: Vec<Size> = children
             .iter()
-            .map(|child| child.layout(child_constraints))
+            .zip(limits)
+            .map(|(child, limit)| {
+                let child_constraints = Constraints::new(constraints.min_width, constraints.max_width, 0.0, limit);
+                let child_constraints = sized_constraints(child.as_ref(), child_constraints, available(constraints));
+                cache.measure_with_text(child.as_ref(), child_constraints, text)
+            })
             .collect();
         
         // Calculate total height
@@ -222,8 +351,294 @@ impl FlexLayout {
     }
 }
 
+/// Most of the main axis each flex child may take, given the size each
+/// prefers: declared or intrinsic. Fixed children, whose preferred size
+/// fits an equal share of `extent`, get exactly that; the rest split the
+/// space left over equally.
+fn main_axis_limits(extent: f32, preferred: &[f32]) -> Vec<f32> {
+    let share = extent / preferred.len() as f32;
+    let fixed: f32 = preferred.iter().filter(|&&size| size <= share).sum();
+    let flexible = preferred.iter().filter(|&&size| size > share).count();
+    let leftover = (extent - fixed) / flexible.max(1) as f32;
+    preferred
+        .iter()
+        .map(|&size| if size <= share { size } else { leftover })
+        .collect()
+}
+
 pub trait LayoutChild: std::fmt::Debug {
     fn layout(&self, constraints: Constraints) -> Size;
+    
+    /// `layout` for children whose size depends on their text, which
+    /// override this to measure it with `text`
+    fn layout_with_text(&self, constraints: Constraints, _text: &dyn TextMeasurer) -> Size {
+        self.layout(constraints)
+    }
+    
+    /// Natural size of the content when nothing constrains it, e.g. text
+    /// on a single line
+    fn intrinsic_size(&self) -> Size {
+        self.layout(Constraints::loose(Size {
+            width: f32::INFINITY,
+            height: f32::INFINITY,
+        }))
+    }
+    
+    /// `intrinsic_size` for children whose size depends on their text,
+    /// which override this to measure it with `text`
+    fn intrinsic_size_with_text(&self, _text: &dyn TextMeasurer) -> Size {
+        self.intrinsic_size()
+    }
+    
+    /// Declared width, resolved against the space the parent offers
+    fn width(&self) -> Length {
+        Length::Auto
+    }
+    
+    /// Declared height, resolved against the space the parent offers
+    fn height(&self) -> Length {
+        Length::Auto
+    }
+    
+    /// Position in the paint order among siblings, see `paint_order`
+    fn z_index(&self) -> i32 {
+        0
+    }
+    
+    /// Identifies the child to `MeasureCache`; children without an id are
+    /// measured every time
+    fn node_id(&self) -> Option<NodeId> {
+        None
+    }
+}
+
+/// A width or height as written in props: `12`, `"12px"`, `"50%"` or
+/// `"auto"`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Length {
+    Px(f32),
+    /// Percentage of the parent's extent on the same axis
+    Percent(f32),
+    /// The content's own size
+    Auto,
+}
+
+impl Length {
+    /// Parses a length prop. Bare numbers are pixels.
+    pub fn from_prop(value: &PropValue) -> Option<Length> {
+        match value {
+            PropValue::Number(n) => Some(Length::Px(*n as f32)),
+            PropValue::String(s) => {
+                let s = s.trim();
+                if s == "auto" {
+                    Some(Length::Auto)
+                } else if let Some(percent) = s.strip_suffix('%') {
+                    percent.trim().parse().ok().map(Length::Percent)
+                } else {
+                    s.strip_suffix("px").unwrap_or(s).trim().parse().ok().map(Length::Px)
+                }
+            }
+            _ => None,
+        }
+    }
+    
+    /// Size in pixels given the parent's extent, or `None` if the content
+    /// decides. Percentages of an unbounded extent are left to the content.
+    pub fn resolve(self, available: f32) -> Option<f32> {
+        match self {
+            Length::Px(px) => Some(px),
+            Length::Percent(percent) if available.is_finite() => Some(available * percent / 100.0),
+            Length::Percent(_) | Length::Auto => None,
+        }
+    }
+}
+
+/// A node's `width` and `height` props, `Auto` when missing or invalid
+pub fn declared_size(node: &VirtualNode) -> (Length, Length) {
+    let length = |name| node.props.get(name).and_then(Length::from_prop).unwrap_or(Length::Auto);
+    (length("width"), length("height"))
+}
+
+/// Space a parent laid out under `constraints` offers its children
+fn available(constraints: Constraints) -> Size {
+    Size {
+        width: constraints.max_width,
+        height: constraints.max_height,
+    }
+}
+
+/// `constraints` with each axis the child gives a length for fixed to that
+/// length, resolved against `available`
+fn sized_constraints(child: &dyn LayoutChild, constraints: Constraints, available: Size) -> Constraints {
+    let mut constraints = constraints;
+    if let Some(width) = child.width().resolve(available.width) {
+        constraints.min_width = width;
+        constraints.max_width = width;
+    }
+    if let Some(height) = child.height().resolve(available.height) {
+        constraints.min_height = height;
+        constraints.max_height = height;
+    }
+    constraints
+}
+
+/// Measures text for layout. Each platform measures differently, e.g.
+/// the web through a canvas and desktop from font metrics.
+pub trait TextMeasurer {
+    /// Size of `text` set at `font_size` pixels
+    fn measure(&self, text: &str, font_size: f32) -> Size;
+}
+
+/// Measures every text as empty, for layouts run without a measurer
+struct Unmeasured;
+
+impl TextMeasurer for Unmeasured {
+    fn measure(&self, _text: &str, _font_size: f32) -> Size {
+        Size { width: 0.0, height: 0.0 }
+    }
+}
+
+/// A run of text, sized by the `TextMeasurer` of the layout pass
+#[derive(Debug, Clone)]
+pub struct TextChild {
+    pub text: String,
+    pub font_size: f32,
+    pub node_id: Option<NodeId>,
+}
+
+impl LayoutChild for TextChild {
+    fn layout(&self, constraints: Constraints) -> Size {
+        self.layout_with_text(constraints, &Unmeasured)
+    }
+    
+    fn layout_with_text(&self, constraints: Constraints, text: &dyn TextMeasurer) -> Size {
+        constraints.constrain(text.measure(&self.text, self.font_size))
+    }
+    
+    fn intrinsic_size_with_text(&self, text: &dyn TextMeasurer) -> Size {
+        text.measure(&self.text, self.font_size)
+    }
+    
+    fn node_id(&self) -> Option<NodeId> {
+        self.node_id
+    }
+}
+
+/// Prop that moves a node up or down the paint order of its siblings
+pub const Z_INDEX_PROP: &str = "z_index";
+
+/// A node's `z_index` prop, or 0 without one. Fractions are truncated.
+pub fn z_index(node: &VirtualNode) -> i32 {
+    match node.props.get(Z_INDEX_PROP) {
+        Some(PropValue::Number(n)) => *n as i32,
+        Some(PropValue::String(s)) => s.trim().parse().unwrap_or(0),
+        _ => 0,
+    }
+}
+
+/// Prop deciding what happens to children drawn outside a node's bounds
+pub const OVERFLOW_PROP: &str = "overflow";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overflow {
+    /// Children may draw outside the node
+    Visible,
+    /// Children are clipped to the node
+    Hidden,
+    /// Children are clipped to the node, which can be scrolled
+    Scroll,
+}
+
+impl Overflow {
+    pub fn clips(self) -> bool {
+        self != Overflow::Visible
+    }
+}
+
+/// A node's `overflow` prop, or `Overflow::Visible` without a valid one
+pub fn overflow(node: &VirtualNode) -> Overflow {
+    match node.props.get(OVERFLOW_PROP) {
+        Some(PropValue::String(s)) => match s.trim() {
+            "hidden" => Overflow::Hidden,
+            "scroll" => Overflow::Scroll,
+            _ => Overflow::Visible,
+        },
+        _ => Overflow::Visible,
+    }
+}
+
+/// Indices in the order to paint siblings with the given z-indices: lowest
+/// first, so higher ones end up on top, keeping source order on ties
+pub fn paint_order(z_indices: impl IntoIterator<Item = i32>) -> Vec<usize> {
+    let mut order: Vec<(usize, i32)> = z_indices.into_iter().enumerate().collect();
+    order.sort_by_key(|&(_, z)| z);
+    order.into_iter().map(|(i, _)| i).collect()
+}
+
+/// Child sizes measured during a layout pass, keyed by node and
+/// constraints, so a child laid out again under identical constraints
+/// isn't measured again. Share one cache across the layouts of a pass and
+/// drop it afterwards, since it doesn't notice content changes.
+#[derive(Debug, Default)]
+pub struct MeasureCache {
+    sizes: HashMap<(NodeId, [u32; 4]), Size>,
+    intrinsic_sizes: HashMap<NodeId, Size>,
+}
+
+impl MeasureCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// `child.layout(constraints)`, or the size it returned last time
+    pub fn measure(&mut self, child: &dyn LayoutChild, constraints: Constraints) -> Size {
+        self.measure_with_text(child, constraints, &Unmeasured)
+    }
+    
+    /// `child.layout_with_text(constraints, text)`, or the size it
+    /// returned last time
+    pub fn measure_with_text(&mut self, child: &dyn LayoutChild, constraints: Constraints, text: &dyn TextMeasurer) -> Size {
+        let Some(node_id) = child.node_id() else {
+            return child.layout_with_text(constraints, text);
+        };
+        // Constraints are floats, so their bits make the key
+        let key = [
+            constraints.min_width.to_bits(),
+            constraints.max_width.to_bits(),
+            constraints.min_height.to_bits(),
+            constraints.max_height.to_bits(),
+        ];
+        *self
+            .sizes
+            .entry((node_id, key))
+            .or_insert_with(|| child.layout_with_text(constraints, text))
+    }
+    
+    /// `child.intrinsic_size_with_text(text)`, or the size it returned
+    /// last time
+    pub fn intrinsic_size_with_text(&mut self, child: &dyn LayoutChild, text: &dyn TextMeasurer) -> Size {
+        let Some(node_id) = child.node_id() else {
+            return child.intrinsic_size_with_text(text);
+        };
+        *self
+            .intrinsic_sizes
+            .entry(node_id)
+            .or_insert_with(|| child.intrinsic_size_with_text(text))
+    }
+    
+    /// Number of cached measurements
+    pub fn len(&self) -> usize {
+        self.sizes.len() + self.intrinsic_sizes.len()
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.sizes.is_empty() && self.intrinsic_sizes.is_empty()
+    }
+    
+    pub fn clear(&mut self) {
+        self.sizes.clear();
+        self.intrinsic_sizes.clear();
+    }
 }
 
 // Simple implementation for testing
@@ -264,10 +679,32 @@ impl StackLayout {
     }
     
     pub fn layout(&self, constraints: Constraints, children: &[Box<dyn LayoutChild>]) -> Vec<Rect> {
+        self.layout_cached(constraints, children, &mut MeasureCache::new())
+    }
+    
+    /// Like `layout`, measuring children through `cache`
+    pub fn layout_cached(
+        &self,
+        constraints: Constraints,
+        children: &[Box<dyn LayoutChild>],
+        cache: &mut MeasureCache,
+    ) -> Vec<Rect> {
+        self.layout_with_text(constraints, children, cache, &Unmeasured)
+    }
+    
+    /// Like `layout_cached`, sizing text children with `text`
+    pub fn layout_with_text(
+        &self,
+        constraints: Constraints,
+        children: &[Box<dyn LayoutChild>],
+        cache: &mut MeasureCache,
+        text: &dyn TextMeasurer,
+    ) -> Vec<Rect> {
         children
             .iter()
             .map(|child| {
-                let size = child.layout(constraints);
+                let child_constraints = sized_constraints(child.as_ref(), constraints, available(constraints));
+                let size = cache.measure_with_text(child.as_ref(), child_constraints, text);
                 let (x, y) = self.align_position(size, constraints);
                 Rect {
                     x,
@@ -279,6 +716,12 @@ impl StackLayout {
             .collect()
     }
     
+    /// Order to paint `children` in, honouring their z-indices. `layout`
+    /// returns rects in child order; paint them in this order instead.
+    pub fn paint_order(&self, children: &[Box<dyn LayoutChild>]) -> Vec<usize> {
+        paint_order(children.iter().map(|child| child.z_index()))
+    }
+    
     fn align_position(&self, size: Size, constraints: Constraints) -> (f32, f32) {
         match self.alignment {
             StackAlignment::TopStart => (0.0, 0.0),
@@ -306,6 +749,12 @@ impl StackLayout {
     }
 }
 
+impl Default for StackLayout {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct GridLayout {
     pub columns: usize,
@@ -323,6 +772,27 @@ impl GridLayout {
     }
     
     pub fn layout(&self, constraints: Constraints, children: &[Box<dyn LayoutChild>]) -> Vec<Rect> {
+        self.layout_cached(constraints, children, &mut MeasureCache::new())
+    }
+    
+    /// Like `layout`, measuring children through `cache`
+    pub fn layout_cached(
+        &self,
+        constraints: Constraints,
+        children: &[Box<dyn LayoutChild>],
+        cache: &mut MeasureCache,
+    ) -> Vec<Rect> {
+        self.layout_with_text(constraints, children, cache, &Unmeasured)
+    }
+    
+    /// Like `layout_cached`, sizing text children with `text`
+    pub fn layout_with_text(
+        &self,
+        constraints: Constraints,
+        children: &[Box<dyn LayoutChild>],
+        cache: &mut MeasureCache,
+        text: &dyn TextMeasurer,
+    ) -> Vec<Rect> {
         let cell_width = (constraints.max_width - self.spacing * (self.columns - 1) as f32) / self.columns as f32;
         let cell_height = (constraints.max_height - self.spacing * (self.rows - 1) as f32) / self.rows as f32;
         
@@ -337,7 +807,9 @@ impl GridLayout {
             let col = i % self.columns;
             let row = i / self.columns;
             
-            let size = child.layout(cell_constraints);
+            // Children fill their cell unless they declare a size
+            let child_constraints = sized_constraints(child.as_ref(), cell_constraints, available(cell_constraints));
+            let size = cache.measure_with_text(child.as_ref(), child_constraints, text);
             
             positions.push(Rect {
                 x: col as f32 * (cell_width + self.spacing),
@@ -5,13 +5,22 @@ pub mod virtual_tree;
 pub mod scheduler;
 pub mod renderer;
 pub mod layout;
+pub mod attributes;
+pub mod bindings;
 
-pub use signals::{Signal, SignalRegistry, create_signal, create_derived, create_computed};
-pub use virtual_tree::{VirtualNode, NodeId, NodeType, Patch, diff, apply_patches};
-pub use scheduler::{Scheduler, Priority, Fiber, FiberId, schedule_work, should_yield};
-pub use renderer::{Renderer, ElementId, RenderContext, apply_patches_to_renderer};
+pub use signals::{Signal, ReadSignal, WriteSignal, SignalRegistry, Effect, Resource, create_signal, create_derived, create_computed, create_effect, track_pending};
+pub use virtual_tree::{VirtualNode, VirtualNodeBuilder, NodeId, NodeType, PropValue, Patch, diff, apply_patches, assign_stable_ids};
+pub use scheduler::{Scheduler, DEFAULT_FRAME_BUDGET, Priority, Fiber, FiberId, schedule_work, schedule_idle, schedule_with_result, flush_scheduled_work, should_yield};
+pub use renderer::{Renderer, ElementId, RenderContext, StringRenderer, minify_html, RAW_HTML_WRAPPER, RAW_HTML_WRAPPER_STYLE, apply_patches_to_renderer};
+#[cfg(feature = "serde")]
+pub use virtual_tree::{encode_patches, decode_patches};
+#[cfg(feature = "serde")]
+pub use renderer::apply_serialized_patches;
+pub use bindings::{bind_checked, bind_value, binding_kind, write_binding, BindingKind};
+pub use attributes::{attribute_update, is_boolean_attribute, is_enumerated_attribute, AttributeUpdate};
 pub use layout::{
-    Rect, Size, Constraints, FlexLayout, StackLayout, GridLayout,
+    Rect, Size, Constraints, EdgeInsets, FlexLayout, StackLayout, GridLayout,
     LayoutDirection, MainAxisAlignment, CrossAxisAlignment, StackAlignment,
-    LayoutChild,
+    LayoutChild, Length, MeasureCache, TextChild, TextMeasurer, Z_INDEX_PROP, z_index, paint_order, declared_size,
+    Overflow, OVERFLOW_PROP, overflow,
 };
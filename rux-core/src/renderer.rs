@@ -1,4 +1,5 @@
-use crate::virtual_tree::{VirtualNode, NodeId, Patch};
+use crate::attributes::{attribute_update, is_boolean_attribute, AttributeUpdate};
+use crate::virtual_tree::{NodeType, VirtualNode, Patch};
 
 pub trait Renderer {
     fn create_element(&mut self, node: &VirtualNode) -> ElementId;
@@ -29,6 +30,266 @@ impl RenderContext {
     }
 }
 
+impl Default for RenderContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Elements that never have children or a closing tag
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track", "wbr",
+];
+
+/// Element `NodeType::RawHtml` content is mounted inside, on the server and
+/// the client alike, so hydration can match it up with its markup
+pub const RAW_HTML_WRAPPER: &str = "div";
+
+/// Style of the raw HTML wrapper, which keeps it out of the layout
+pub const RAW_HTML_WRAPPER_STYLE: &str = "display: contents";
+
+/// Renders trees to HTML, e.g. for server-side rendering. Text and
+/// attribute values are escaped, except `NodeType::RawHtml` content which
+/// is written unchanged inside a `RAW_HTML_WRAPPER`. Components should be expanded first; any left
+/// render only their children.
+#[derive(Debug, Default)]
+pub struct StringRenderer {
+    html: String,
+    minify: bool,
+}
+
+impl StringRenderer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Passes `NodeType::RawHtml` content through `minify_html` when
+    /// mounting. Text nodes are kept as is: any whitespace in them is
+    /// content, not formatting.
+    pub fn with_minify(mut self, minify: bool) -> Self {
+        self.minify = minify;
+        self
+    }
+    
+    /// HTML for the tree mounted last
+    pub fn html(&self) -> &str {
+        &self.html
+    }
+    
+    pub fn render(node: &VirtualNode) -> String {
+        let mut html = String::new();
+        write_html(node, &mut html, false);
+        html
+    }
+}
+
+impl Renderer for StringRenderer {
+    fn create_element(&mut self, _node: &VirtualNode) -> ElementId {
+        ElementId(0)
+    }
+    
+    fn update_element(&mut self, _element_id: ElementId, _patches: &[Patch]) {}
+    
+    fn remove_element(&mut self, _element_id: ElementId) {}
+    
+    fn mount(&mut self, _root: ElementId, node: &VirtualNode) {
+        self.html.clear();
+        write_html(node, &mut self.html, self.minify);
+    }
+    
+    fn unmount(&mut self, _root: ElementId) {
+        self.html.clear();
+    }
+}
+
+fn write_html(node: &VirtualNode, out: &mut String, minify: bool) {
+    match &node.node_type {
+        NodeType::Element(tag) => {
+            out.push('<');
+            out.push_str(tag);
+            
+            // Sorted so the output doesn't depend on `HashMap` order
+            let mut props: Vec<_> = node.props.iter().collect();
+            props.sort_by(|a, b| a.0.cmp(b.0));
+            let mut attributes = Vec::new();
+            let mut styles = Vec::new();
+            for (key, value) in props {
+                match attribute_update(key, value) {
+                    AttributeUpdate::Set(name, value) => attributes.push((name, value)),
+                    AttributeUpdate::Style(name, value) => styles.push(format!("{}: {}", name, value)),
+                    // Handlers only exist on the client
+                    AttributeUpdate::Remove(_) | AttributeUpdate::Skip => {}
+                }
+            }
+            // Style props go after any `style` attribute, as the client
+            // sets them on top of it
+            if !styles.is_empty() {
+                let styles = styles.join("; ");
+                match attributes.iter_mut().find(|(name, _)| name == "style") {
+                    Some((_, style)) => *style = format!("{}; {}", style.trim_end().trim_end_matches(';'), styles),
+                    None => attributes.push(("style".to_string(), styles)),
+                }
+            }
+            for (name, value) in attributes {
+                if value.is_empty() && is_boolean_attribute(&name) {
+                    out.push_str(&format!(" {}", name));
+                } else {
+                    out.push_str(&format!(" {}=\"{}\"", name, escape_html(&value)));
+                }
+            }
+            out.push('>');
+            
+            if VOID_ELEMENTS.contains(&tag.as_str()) {
+                return;
+            }
+            for child in &node.children {
+                write_html(child, out, minify);
+            }
+            out.push_str(&format!("</{}>", tag));
+        }
+        NodeType::Text(text) => out.push_str(&escape_html(text)),
+        NodeType::RawHtml(html) => {
+            out.push_str(&format!("<{} style=\"{}\">", RAW_HTML_WRAPPER, RAW_HTML_WRAPPER_STYLE));
+            if minify {
+                out.push_str(&minify_html(html));
+            } else {
+                out.push_str(html);
+            }
+            out.push_str(&format!("</{}>", RAW_HTML_WRAPPER));
+        }
+        NodeType::Component(_) | NodeType::Fragment | NodeType::Suspense { .. } => {
+            for child in &node.children {
+                write_html(child, out, minify);
+            }
+        }
+    }
+}
+
+/// Elements whose content `minify_html` copies unchanged
+const PRESERVE_WHITESPACE: &[&str] = &["pre", "textarea", "script", "style"];
+
+/// Elements that start a new line, so whitespace next to their tags never
+/// renders
+const BLOCK_ELEMENTS: &[&str] = &[
+    "address", "article", "aside", "blockquote", "body", "dd", "details", "dialog", "div", "dl", "dt",
+    "fieldset", "figcaption", "figure", "footer", "form", "h1", "h2", "h3", "h4", "h5", "h6", "head",
+    "header", "hgroup", "hr", "html", "li", "link", "main", "meta", "nav", "ol", "p", "pre", "section",
+    "summary", "table", "tbody", "td", "tfoot", "th", "thead", "title", "tr", "ul",
+];
+
+/// Removes formatting whitespace from HTML. Each whitespace run in text is
+/// collapsed to one space, since a space between inline elements still
+/// renders, and dropped when it touches a block-level tag, comment or
+/// doctype. Everything inside `<pre>`, `<textarea>`, `<script>` and
+/// `<style>` is copied unchanged.
+pub fn minify_html(html: &str) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut rest = html;
+    // Whether the last thing written was a block-level tag, or nothing
+    let mut after_block = true;
+    // A collapsed whitespace run, written once we know what follows it
+    let mut space = false;
+    
+    while !rest.is_empty() {
+        if rest.starts_with('<') {
+            let end = tag_end(rest);
+            let tag = &rest[..end];
+            after_block = is_block_tag(tag);
+            if space && !after_block {
+                out.push(' ');
+            }
+            space = false;
+            out.push_str(tag);
+            rest = &rest[end..];
+            
+            let name = tag_name(tag).to_ascii_lowercase();
+            if PRESERVE_WHITESPACE.contains(&name.as_str()) && !tag.ends_with("/>") {
+                let content = find_closing_tag(rest, &name).unwrap_or(rest.len());
+                out.push_str(&rest[..content]);
+                rest = &rest[content..];
+                after_block = false;
+            }
+        } else {
+            let end = rest.find('<').unwrap_or(rest.len());
+            for c in rest[..end].chars() {
+                if c.is_whitespace() {
+                    space = !after_block;
+                } else {
+                    if space {
+                        out.push(' ');
+                        space = false;
+                    }
+                    out.push(c);
+                    after_block = false;
+                }
+            }
+            rest = &rest[end..];
+        }
+    }
+    out
+}
+
+/// Whether whitespace next to `tag` can be dropped: block-level opening
+/// and closing tags, comments and doctypes
+fn is_block_tag(tag: &str) -> bool {
+    if tag.starts_with("<!") {
+        return true;
+    }
+    let name = tag.strip_prefix("</").unwrap_or(&tag[1..]);
+    let end = name.find(|c: char| !c.is_ascii_alphanumeric() && c != '-').unwrap_or(name.len());
+    let name = &name[..end];
+    BLOCK_ELEMENTS.iter().any(|block| block.eq_ignore_ascii_case(name))
+}
+
+/// Length of the tag, comment or doctype at the start of `html`, skipping
+/// `>` inside quoted attribute values
+fn tag_end(html: &str) -> usize {
+    if html.starts_with("<!--") {
+        return html.find("-->").map_or(html.len(), |i| i + 3);
+    }
+    let mut quote = None;
+    for (i, c) in html.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), c) if q == c => quote = None,
+            (None, '>') => return i + 1,
+            _ => {}
+        }
+    }
+    html.len()
+}
+
+/// Name of an opening tag, empty for closing tags, comments and doctypes
+fn tag_name(tag: &str) -> &str {
+    let name = &tag[1..];
+    let end = name.find(|c: char| !c.is_ascii_alphanumeric() && c != '-').unwrap_or(name.len());
+    &name[..end]
+}
+
+/// Offset of the `</name` that closes a preserved element, ignoring case
+fn find_closing_tag(html: &str, name: &str) -> Option<usize> {
+    html.match_indices("</").map(|(i, _)| i).find(|&i| {
+        html.as_bytes()[i + 2..]
+            .get(..name.len())
+            .is_some_and(|candidate| candidate.eq_ignore_ascii_case(name.as_bytes()))
+    })
+}
+
+fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
 pub fn apply_patches_to_renderer<R: Renderer>(
     renderer: &mut R,
     patches: &[Patch],
@@ -45,9 +306,9 @@ pub fn apply_patches_to_renderer<R: Renderer>(
                 // Update element properties
                 // Would need to map node_id to element_id
                 let _element_id = ElementId(node_id.0);
-                renderer.update_element(ElementId(0), &[patch.clone()]);
+                renderer.update_element(ElementId(0), std::slice::from_ref(patch));
             }
-            Patch::Insert { parent_id, index: _, node } => {
+            Patch::Insert { parent_id: _, index: _, node } => {
                 let _element_id = renderer.create_element(node);
                 // Would need to map NodeId to ElementId
                 renderer.mount(root_id, node);
@@ -62,3 +323,12 @@ pub fn apply_patches_to_renderer<R: Renderer>(
         }
     }
 }
+
+/// Decodes a patch list produced by `encode_patches` and applies it to the
+/// renderer's root, so the diff can run in a different process
+#[cfg(feature = "serde")]
+pub fn apply_serialized_patches<R: Renderer>(renderer: &mut R, bytes: &[u8]) -> Result<(), serde_json::Error> {
+    let patches = crate::virtual_tree::decode_patches(bytes)?;
+    apply_patches_to_renderer(renderer, &patches, ElementId(0));
+    Ok(())
+}
@@ -1,4 +1,7 @@
+use parking_lot::Mutex;
 use std::collections::VecDeque;
+use std::sync::mpsc::{self, Receiver};
+use std::sync::OnceLock;
 use std::time::{Duration, Instant};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -19,22 +22,80 @@ pub struct Fiber {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct FiberId(pub usize);
 
+impl FiberId {
+    /// Allocates a process-wide unique id
+    pub fn next() -> Self {
+        static COUNTER: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+        FiberId(COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed))
+    }
+}
+
+/// Time a frame may spend on work at 60fps
+pub const DEFAULT_FRAME_BUDGET: Duration = Duration::from_millis(16);
+
 pub struct Scheduler {
     work_queue: VecDeque<Fiber>,
+    /// `Priority::Idle` fibers, run only once `work_queue` is empty
+    idle_queue: VecDeque<Fiber>,
+    #[allow(dead_code)]
     current_fiber: Option<Fiber>,
     deadline: Option<Instant>,
+    frame_budget: Duration,
 }
 
 impl Scheduler {
     pub fn new() -> Self {
+        Self::with_frame_budget(DEFAULT_FRAME_BUDGET)
+    }
+    
+    /// Scheduler whose `run_frame` spends at most `budget` on work
+    pub fn with_frame_budget(budget: Duration) -> Self {
         Self {
             work_queue: VecDeque::new(),
+            idle_queue: VecDeque::new(),
             current_fiber: None,
             deadline: None,
+            frame_budget: budget,
+        }
+    }
+    
+    pub fn frame_budget(&self) -> Duration {
+        self.frame_budget
+    }
+    
+    /// Runs queued work for one frame that started now
+    pub fn run_frame(&mut self) {
+        self.run_frame_from(Instant::now());
+    }
+    
+    /// Runs queued work for a frame that started at `frame_start`, so time
+    /// already spent in the frame (e.g. on layout) counts against the budget
+    pub fn run_frame_from(&mut self, frame_start: Instant) {
+        self.work_loop(frame_start + self.frame_budget);
+    }
+    
+    /// Time left before the current deadline; the whole frame budget when
+    /// no work loop has run yet
+    pub fn remaining_budget(&self) -> Duration {
+        match self.deadline {
+            Some(deadline) => deadline.saturating_duration_since(Instant::now()),
+            None => self.frame_budget,
         }
     }
     
+    /// Number of fibers waiting to run, idle ones included
+    pub fn pending_work(&self) -> usize {
+        self.work_queue.len() + self.idle_queue.len()
+    }
+    
+    /// Queues a fiber. `Priority::Idle` fibers wait in a separate queue
+    /// that is only drained once no other work is left.
     pub fn schedule(&mut self, fiber: Fiber) {
+        if fiber.priority == Priority::Idle {
+            self.idle_queue.push_back(fiber);
+            return;
+        }
+        
         // Insert in priority order
         let priority = fiber.priority as usize;
         let mut insert_pos = 0;
@@ -48,13 +109,41 @@ impl Scheduler {
         self.work_queue.insert(insert_pos, fiber);
     }
     
+    /// Schedules background work, e.g. prefetching, to run when the
+    /// scheduler has nothing else to do and time remains in the frame
+    pub fn schedule_idle(&mut self, work: impl FnOnce() + Send + 'static) {
+        self.schedule(Fiber {
+            id: FiberId::next(),
+            priority: Priority::Idle,
+            work: Box::new(work),
+        });
+    }
+    
+    /// Schedules `work` and returns a channel that receives its result
+    /// once it has run. The work still runs if the receiver is dropped.
+    pub fn schedule_with_result<T: Send + 'static>(
+        &mut self,
+        priority: Priority,
+        work: impl FnOnce() -> T + Send + 'static,
+    ) -> Receiver<T> {
+        let (sender, receiver) = mpsc::channel();
+        self.schedule(Fiber {
+            id: FiberId::next(),
+            priority,
+            work: Box::new(move || {
+                let _ = sender.send(work());
+            }),
+        });
+        receiver
+    }
+    
     pub fn work_loop(&mut self, deadline: Instant) {
         self.deadline = Some(deadline);
         
-        while let Some(mut fiber) = self.get_next_unit_of_work() {
+        while let Some(fiber) = self.get_next_unit_of_work() {
             if !self.has_time_remaining() {
-                // Reschedule for later
-                self.schedule(fiber);
+                // Put it back at the front so it runs first next time
+                self.queue_for(fiber.priority).push_front(fiber);
                 break;
             }
             
@@ -64,28 +153,37 @@ impl Scheduler {
     }
     
     fn get_next_unit_of_work(&mut self) -> Option<Fiber> {
-        self.work_queue.pop_front()
+        self.work_queue.pop_front().or_else(|| self.idle_queue.pop_front())
     }
     
-    fn has_time_remaining(&self) -> bool {
-        if let Some(deadline) = self.deadline {
-            Instant::now() < deadline
-        } else {
-            true
+    fn queue_for(&mut self, priority: Priority) -> &mut VecDeque<Fiber> {
+        match priority {
+            Priority::Idle => &mut self.idle_queue,
+            _ => &mut self.work_queue,
         }
     }
     
+    fn has_time_remaining(&self) -> bool {
+        self.deadline.is_none() || !self.remaining_budget().is_zero()
+    }
+    
     pub fn should_yield(&self) -> bool {
         !self.has_time_remaining()
     }
     
     pub fn flush_work(&mut self) {
-        while let Some(fiber) = self.work_queue.pop_front() {
+        while let Some(fiber) = self.get_next_unit_of_work() {
             (fiber.work)();
         }
     }
 }
 
+impl Default for Scheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 pub struct TimeSlice {
     pub duration: Duration,
 }
@@ -100,14 +198,45 @@ impl TimeSlice {
     }
 }
 
+/// The scheduler behind `schedule_work`, run by `flush_scheduled_work`
+fn global_scheduler() -> &'static Mutex<Scheduler> {
+    static GLOBAL: OnceLock<Mutex<Scheduler>> = OnceLock::new();
+    GLOBAL.get_or_init(|| Mutex::new(Scheduler::new()))
+}
+
 pub fn schedule_work(priority: Priority, work: impl FnOnce() + Send + 'static) {
-    // Global scheduler (simplified)
-    let _fiber = Fiber {
-        id: FiberId(0), // Would generate unique ID
+    global_scheduler().lock().schedule(Fiber {
+        id: FiberId::next(),
         priority,
         work: Box::new(work),
-    };
-    // Would add to global scheduler
+    });
+}
+
+/// `schedule_work` at `Priority::Idle`, for work that should only run when
+/// nothing else is queued
+pub fn schedule_idle(work: impl FnOnce() + Send + 'static) {
+    global_scheduler().lock().schedule_idle(work);
+}
+
+/// `schedule_work` for work that produces a value, e.g. compiling off the
+/// main thread; the value arrives on the returned channel
+pub fn schedule_with_result<T: Send + 'static>(
+    priority: Priority,
+    work: impl FnOnce() -> T + Send + 'static,
+) -> Receiver<T> {
+    global_scheduler().lock().schedule_with_result(priority, work)
+}
+
+/// Runs all globally scheduled work in priority order, including work it
+/// schedules in turn. The lock is released while each fiber runs.
+pub fn flush_scheduled_work() {
+    loop {
+        let fiber = global_scheduler().lock().get_next_unit_of_work();
+        match fiber {
+            Some(fiber) => (fiber.work)(),
+            None => break,
+        }
+    }
 }
 
 pub fn should_yield() -> bool {
@@ -1,15 +1,30 @@
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
-use std::rc::Rc;
+use std::fmt;
+use std::rc::{Rc, Weak};
 use parking_lot::RwLock;
 
 pub type SignalId = usize;
 
+/// Effects subscribed to a signal
+type SubscriberList = RefCell<Vec<Rc<EffectInner>>>;
+type Subscribers = Rc<SubscriberList>;
+
+thread_local! {
+    /// The effect currently running, which signal reads subscribe
+    static OBSERVER: RefCell<Option<Rc<EffectInner>>> = const { RefCell::new(None) };
+    
+    /// One flag per `track_pending` call in progress, innermost last, set
+    /// when a pending resource is read
+    static PENDING: RefCell<Vec<bool>> = const { RefCell::new(Vec::new()) };
+}
+
 #[derive(Debug, Clone)]
 pub struct Signal<T> {
     id: SignalId,
     value: Rc<RefCell<T>>,
     dependents: Rc<RwLock<Vec<SignalId>>>,
+    subscribers: Subscribers,
 }
 
 impl<T> Signal<T> {
@@ -21,22 +36,40 @@ impl<T> Signal<T> {
             id,
             value: Rc::new(RefCell::new(value)),
             dependents: Rc::new(RwLock::new(Vec::new())),
+            subscribers: Rc::new(RefCell::new(Vec::new())),
         }
     }
     
+    /// Returns the current value. Inside an effect, the read also subscribes
+    /// the effect to this signal.
     pub fn get(&self) -> T
     where
         T: Clone,
     {
-        // Track dependency (simplified - would need current computation context)
+        self.track();
         self.value.borrow().clone()
     }
     
+    /// Stores `value` and re-runs subscribed effects, even if it equals the
+    /// current value. Use `set_if_changed` to skip no-op updates.
     pub fn set(&self, value: T) {
         *self.value.borrow_mut() = value;
         self.notify_dependents();
     }
     
+    /// Like `set`, but leaves the signal alone and notifies nobody when
+    /// `value` equals the current value. Returns whether it changed.
+    pub fn set_if_changed(&self, value: T) -> bool
+    where
+        T: PartialEq,
+    {
+        if *self.value.borrow() == value {
+            return false;
+        }
+        self.set(value);
+        true
+    }
+    
     pub fn update<F>(&self, f: F)
     where
         F: FnOnce(&mut T),
@@ -47,14 +80,313 @@ impl<T> Signal<T> {
     
     fn notify_dependents(&self) {
         let dependents = self.dependents.read();
-        for &dependent_id in dependents.iter() {
+        for &_dependent_id in dependents.iter() {
             // Notify dependent (simplified - would need signal registry)
         }
+        
+        // Snapshot first: re-running an effect rewrites its subscriptions
+        let subscribers: Vec<_> = self.subscribers.borrow().clone();
+        for effect in subscribers {
+            effect.run();
+        }
+    }
+    
+    fn track(&self) {
+        OBSERVER.with(|observer| {
+            let Some(effect) = observer.borrow().clone() else {
+                return;
+            };
+            let mut subscribers = self.subscribers.borrow_mut();
+            if !subscribers.iter().any(|e| e.id == effect.id) {
+                subscribers.push(effect.clone());
+                effect.sources.borrow_mut().push(Rc::downgrade(&self.subscribers));
+            }
+        });
     }
     
     pub fn id(&self) -> SignalId {
         self.id
     }
+    
+    /// A handle that doesn't keep the signal alive
+    pub(crate) fn downgrade(&self) -> WeakSignal<T> {
+        WeakSignal {
+            id: self.id,
+            value: Rc::downgrade(&self.value),
+            dependents: Rc::downgrade(&self.dependents),
+            subscribers: Rc::downgrade(&self.subscribers),
+        }
+    }
+    
+    /// Derived signal holding `f` applied to this signal's value, recomputed
+    /// whenever this signal changes. The effect doing so is disposed once
+    /// every handle to the result is dropped.
+    pub fn map<U>(&self, f: impl Fn(&T) -> U + 'static) -> ReadSignal<U>
+    where
+        T: Clone + 'static,
+        U: Clone + 'static,
+    {
+        self.derive(None, f)
+    }
+    
+    /// `map`, keeping `upstream` alive for as long as the result when this
+    /// signal is itself derived
+    fn derive<U>(&self, upstream: Option<Rc<DerivedEffect>>, f: impl Fn(&T) -> U + 'static) -> ReadSignal<U>
+    where
+        T: Clone + 'static,
+        U: Clone + 'static,
+    {
+        let derived: Rc<RefCell<Option<Signal<U>>>> = Rc::default();
+        let (source, target) = (self.clone(), derived.clone());
+        
+        let effect = create_effect(move || {
+            source.track();
+            let value = f(&source.value.borrow());
+            let existing = target.borrow().clone();
+            match existing {
+                Some(signal) => signal.set(value),
+                None => *target.borrow_mut() = Some(Signal::new(value)),
+            }
+        });
+        
+        let signal = derived.borrow().clone().expect("effects run when created");
+        ReadSignal {
+            signal,
+            effect: Some(Rc::new(DerivedEffect { effect, _upstream: upstream })),
+        }
+    }
+    
+    /// Handle that can read this signal but not change it
+    pub fn read_only(&self) -> ReadSignal<T>
+    where
+        T: Clone,
+    {
+        ReadSignal { signal: self.clone(), effect: None }
+    }
+    
+    /// Handle that can change this signal but not read it
+    pub fn write_only(&self) -> WriteSignal<T>
+    where
+        T: Clone,
+    {
+        WriteSignal { signal: self.clone() }
+    }
+    
+    /// Read and write halves of this signal, so a component can hand out
+    /// read access and keep the setter
+    pub fn split(&self) -> (ReadSignal<T>, WriteSignal<T>)
+    where
+        T: Clone,
+    {
+        (self.read_only(), self.write_only())
+    }
+}
+
+/// Read half of a signal. Reads inside an effect subscribe it as usual, but
+/// there is no way to write through it:
+///
+/// ```compile_fail
+/// let count = rux_core::create_signal(0);
+/// count.read_only().set(1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ReadSignal<T> {
+    signal: Signal<T>,
+    /// For a signal from `map`, the effect computing it
+    effect: Option<Rc<DerivedEffect>>,
+}
+
+/// A signal held by a registry that shouldn't keep it alive, such as the
+/// write-backs of `bind:` props
+pub(crate) struct WeakSignal<T> {
+    id: SignalId,
+    value: Weak<RefCell<T>>,
+    dependents: Weak<RwLock<Vec<SignalId>>>,
+    subscribers: Weak<SubscriberList>,
+}
+
+impl<T> WeakSignal<T> {
+    /// The signal, unless every handle to it has been dropped
+    pub(crate) fn upgrade(&self) -> Option<Signal<T>> {
+        Some(Signal {
+            id: self.id,
+            value: self.value.upgrade()?,
+            dependents: self.dependents.upgrade()?,
+            subscribers: self.subscribers.upgrade()?,
+        })
+    }
+}
+
+/// The effect behind a derived signal, shared by its handles and disposed
+/// when the last one is dropped. Holds the effect of the signal it was
+/// derived from in turn, so a chain of `map`s stays live while its end is.
+#[derive(Debug)]
+struct DerivedEffect {
+    effect: Effect,
+    _upstream: Option<Rc<DerivedEffect>>,
+}
+
+impl Drop for DerivedEffect {
+    fn drop(&mut self) {
+        self.effect.dispose();
+    }
+}
+
+impl<T> ReadSignal<T> {
+    pub fn get(&self) -> T
+    where
+        T: Clone,
+    {
+        self.signal.get()
+    }
+    
+    pub fn map<U>(&self, f: impl Fn(&T) -> U + 'static) -> ReadSignal<U>
+    where
+        T: Clone + 'static,
+        U: Clone + 'static,
+    {
+        self.signal.derive(self.effect.clone(), f)
+    }
+    
+    pub fn id(&self) -> SignalId {
+        self.signal.id()
+    }
+}
+
+/// Write half of a signal
+#[derive(Debug, Clone)]
+pub struct WriteSignal<T> {
+    signal: Signal<T>,
+}
+
+impl<T> WriteSignal<T> {
+    pub fn set(&self, value: T) {
+        self.signal.set(value);
+    }
+    
+    pub fn set_if_changed(&self, value: T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.signal.set_if_changed(value)
+    }
+    
+    pub fn update<F>(&self, f: F)
+    where
+        F: FnOnce(&mut T),
+    {
+        self.signal.update(f);
+    }
+    
+    pub fn id(&self) -> SignalId {
+        self.signal.id()
+    }
+}
+
+/// A side effect that re-runs whenever a signal it read changes
+#[derive(Debug, Clone)]
+pub struct Effect {
+    inner: Rc<EffectInner>,
+}
+
+struct EffectInner {
+    id: usize,
+    f: Box<dyn Fn()>,
+    sources: RefCell<Vec<Weak<SubscriberList>>>,
+    disposed: Cell<bool>,
+}
+
+impl fmt::Debug for EffectInner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Effect").field("id", &self.id).finish()
+    }
+}
+
+impl EffectInner {
+    /// Drops the subscriptions from the previous run and runs `f` again with
+    /// this effect as the observer, so only signals read this time are tracked
+    fn run(self: &Rc<Self>) {
+        if self.disposed.get() {
+            return;
+        }
+        self.unsubscribe();
+        
+        let previous = OBSERVER.with(|observer| observer.replace(Some(self.clone())));
+        (self.f)();
+        OBSERVER.with(|observer| *observer.borrow_mut() = previous);
+    }
+    
+    fn unsubscribe(&self) {
+        for source in self.sources.borrow_mut().drain(..) {
+            if let Some(subscribers) = source.upgrade() {
+                subscribers.borrow_mut().retain(|e| e.id != self.id);
+            }
+        }
+    }
+}
+
+impl Effect {
+    /// Stops the effect from running again and releases its subscriptions
+    pub fn dispose(&self) {
+        self.inner.disposed.set(true);
+        self.inner.unsubscribe();
+    }
+}
+
+/// A value that arrives asynchronously, e.g. from a fetch. Reading it while
+/// it is still pending suspends the nearest enclosing `track_pending`
+/// call, which is how suspense boundaries know to show their fallback.
+/// Reads subscribe effects like a signal's, so resolving re-runs them.
+#[derive(Debug, Clone)]
+pub struct Resource<T> {
+    value: Signal<Option<T>>,
+}
+
+impl<T: Clone> Resource<T> {
+    pub fn new() -> Self {
+        Self { value: Signal::new(None) }
+    }
+    
+    /// The loaded value, or `None` while pending
+    pub fn get(&self) -> Option<T> {
+        let value = self.value.get();
+        if value.is_none() {
+            PENDING.with(|pending| {
+                if let Some(current) = pending.borrow_mut().last_mut() {
+                    *current = true;
+                }
+            });
+        }
+        value
+    }
+    
+    pub fn is_pending(&self) -> bool {
+        self.value.value.borrow().is_none()
+    }
+    
+    pub fn resolve(&self, value: T) {
+        self.value.set(Some(value));
+    }
+    
+    /// Back to pending, e.g. before loading again
+    pub fn reset(&self) {
+        self.value.set(None);
+    }
+}
+
+impl<T: Clone> Default for Resource<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Runs `f`, also returning whether it read a pending `Resource`. Calls
+/// nest: a read only marks the innermost one.
+pub fn track_pending<R>(f: impl FnOnce() -> R) -> (R, bool) {
+    PENDING.with(|pending| pending.borrow_mut().push(false));
+    let result = f();
+    let pending = PENDING.with(|pending| pending.borrow_mut().pop()).unwrap_or(false);
+    (result, pending)
 }
 
 pub struct SignalRegistry {
@@ -77,7 +409,7 @@ impl SignalRegistry {
     pub fn add_dependency(&mut self, signal_id: SignalId, _dependent_id: SignalId) {
         self.dependency_graph
             .entry(signal_id)
-            .or_insert_with(Vec::new);
+            .or_default();
     }
     
     pub fn get_dependents(&self, signal_id: SignalId) -> Option<&Vec<SignalId>> {
@@ -85,6 +417,12 @@ impl SignalRegistry {
     }
 }
 
+impl Default for SignalRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 pub fn create_signal<T>(value: T) -> Signal<T> {
     Signal::new(value)
 }
@@ -106,3 +444,18 @@ where
     let value = signal.get();
     Signal::new(f(&value))
 }
+
+/// Runs `f` now and again whenever a signal it reads changes. Dependencies
+/// are re-collected on every run, so a signal that `f` stops reading no
+/// longer triggers it.
+pub fn create_effect(f: impl Fn() + 'static) -> Effect {
+    static COUNTER: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+    let inner = Rc::new(EffectInner {
+        id: COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed),
+        f: Box::new(f),
+        sources: RefCell::new(Vec::new()),
+        disposed: Cell::new(false),
+    });
+    inner.run();
+    Effect { inner }
+}
@@ -1,8 +1,9 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 
 // Add Clone to Patch for renderer usage
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VirtualNode {
     pub id: NodeId,
     pub node_type: NodeType,
@@ -12,25 +13,265 @@ pub struct VirtualNode {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NodeId(pub usize);
 
+impl NodeId {
+    /// First id handed out by `assign_stable_ids`. Stable ids live in the
+    /// top half of the range so they never collide with `next()`.
+    pub const STABLE_BASE: usize = 1 << (usize::BITS - 1);
+    
+    /// Allocates a process-wide unique id
+    pub fn next() -> Self {
+        static COUNTER: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+        NodeId(COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed))
+    }
+    
+    /// Whether the id was assigned from a node's tree position
+    pub fn is_stable(self) -> bool {
+        self.0 >= Self::STABLE_BASE
+    }
+}
+
+impl VirtualNode {
+    /// Starts building an element node, e.g.
+    /// `VirtualNode::element("div").prop("class", "x").child(...).build()`
+    pub fn element(tag: impl Into<String>) -> VirtualNodeBuilder {
+        VirtualNodeBuilder::new(NodeType::Element(tag.into()))
+    }
+    
+    pub fn component(name: impl Into<String>) -> VirtualNodeBuilder {
+        VirtualNodeBuilder::new(NodeType::Component(name.into()))
+    }
+    
+    pub fn fragment() -> VirtualNodeBuilder {
+        VirtualNodeBuilder::new(NodeType::Fragment)
+    }
+    
+    pub fn text(text: impl Into<String>) -> VirtualNode {
+        VirtualNodeBuilder::new(NodeType::Text(text.into())).build()
+    }
+    
+    /// Suspense boundary showing `fallback` while its children are pending
+    pub fn suspense(fallback: impl Into<VirtualNode>) -> VirtualNodeBuilder {
+        VirtualNodeBuilder::new(NodeType::Suspense {
+            fallback: Box::new(fallback.into()),
+        })
+    }
+    
+    /// Unescaped HTML content; see `NodeType::RawHtml` before using it
+    pub fn raw_html(html: impl Into<String>) -> VirtualNode {
+        VirtualNodeBuilder::new(NodeType::RawHtml(html.into())).build()
+    }
+}
+
+/// Queries over a tree, mainly for tests and debugging. Searches include
+/// the node itself and go depth first, in document order.
+impl VirtualNode {
+    /// Every element with tag `tag`
+    pub fn find_by_tag(&self, tag: &str) -> Vec<&VirtualNode> {
+        let mut found = Vec::new();
+        self.walk(&mut |node| {
+            if matches!(&node.node_type, NodeType::Element(t) if t == tag) {
+                found.push(node);
+            }
+        });
+        found
+    }
+    
+    /// The first node with key `key`
+    pub fn find_by_key(&self, key: &str) -> Option<&VirtualNode> {
+        if self.key.as_deref() == Some(key) {
+            return Some(self);
+        }
+        self.children.iter().find_map(|child| child.find_by_key(key))
+    }
+    
+    /// The text of every text node in the tree, concatenated. Raw HTML
+    /// and suspense fallbacks are not included.
+    pub fn text_content(&self) -> String {
+        let mut text = String::new();
+        self.walk(&mut |node| {
+            if let NodeType::Text(t) = &node.node_type {
+                text.push_str(t);
+            }
+        });
+        text
+    }
+    
+    fn walk<'a>(&'a self, visit: &mut impl FnMut(&'a VirtualNode)) {
+        visit(self);
+        for child in &self.children {
+            child.walk(visit);
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl VirtualNode {
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+    
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
+/// Fluent construction of a `VirtualNode`. The id is allocated when the
+/// builder is created, so a parent always gets a smaller id than its children.
+#[derive(Debug, Clone)]
+pub struct VirtualNodeBuilder {
+    node: VirtualNode,
+}
+
+impl VirtualNodeBuilder {
+    pub fn new(node_type: NodeType) -> Self {
+        Self {
+            node: VirtualNode {
+                id: NodeId::next(),
+                node_type,
+                props: HashMap::new(),
+                children: Vec::new(),
+                key: None,
+            },
+        }
+    }
+    
+    pub fn prop(mut self, name: impl Into<String>, value: impl Into<PropValue>) -> Self {
+        self.node.props.insert(name.into(), value.into());
+        self
+    }
+    
+    pub fn child(mut self, child: impl Into<VirtualNode>) -> Self {
+        self.node.children.push(child.into());
+        self
+    }
+    
+    pub fn children<I>(mut self, children: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<VirtualNode>,
+    {
+        self.node.children.extend(children.into_iter().map(Into::into));
+        self
+    }
+    
+    pub fn key(mut self, key: impl Into<String>) -> Self {
+        self.node.key = Some(key.into());
+        self
+    }
+    
+    pub fn build(self) -> VirtualNode {
+        self.node
+    }
+}
+
+impl From<VirtualNodeBuilder> for VirtualNode {
+    fn from(builder: VirtualNodeBuilder) -> Self {
+        builder.build()
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum NodeType {
     Element(String),
     Text(String),
     Component(String),
     Fragment,
+    /// HTML inserted as is, without escaping. Only use it for markup that
+    /// is trusted or has been sanitized: anything in it, `<script>` and
+    /// event handler attributes included, ends up live in the page.
+    RawHtml(String),
+    /// Boundary around content that may be waiting on a `Resource`.
+    /// Renderers draw its children like a fragment's; expanding the tree
+    /// through the runtime's registry swaps them for `fallback` while
+    /// anything rendered inside is pending.
+    Suspense {
+        fallback: Box<VirtualNode>,
+    },
 }
 
+/// Serialized externally tagged, e.g. `{"Number": 1.0}`. Function props
+/// hold the handler's name rather than a closure, so they round-trip as
+/// that name.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PropValue {
     String(String),
     Number(f64),
     Boolean(bool),
     Function(String), // Simplified
+    Array(Vec<PropValue>),
 }
 
-#[derive(Debug, Clone)]
+impl PropValue {
+    /// JSON encoding, used for list-valued props that end up in attributes
+    pub fn to_json(&self) -> String {
+        match self {
+            PropValue::String(s) | PropValue::Function(s) => json_string(s),
+            PropValue::Number(n) if n.is_finite() => n.to_string(),
+            PropValue::Number(_) => "null".to_string(),
+            PropValue::Boolean(b) => b.to_string(),
+            PropValue::Array(items) => {
+                let items: Vec<String> = items.iter().map(PropValue::to_json).collect();
+                format!("[{}]", items.join(","))
+            }
+        }
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+impl From<&str> for PropValue {
+    fn from(value: &str) -> Self {
+        PropValue::String(value.to_string())
+    }
+}
+
+impl From<String> for PropValue {
+    fn from(value: String) -> Self {
+        PropValue::String(value)
+    }
+}
+
+impl From<f64> for PropValue {
+    fn from(value: f64) -> Self {
+        PropValue::Number(value)
+    }
+}
+
+impl From<bool> for PropValue {
+    fn from(value: bool) -> Self {
+        PropValue::Boolean(value)
+    }
+}
+
+impl<T: Into<PropValue>> From<Vec<T>> for PropValue {
+    fn from(values: Vec<T>) -> Self {
+        PropValue::Array(values.into_iter().map(Into::into).collect())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Patch {
     Replace {
         node_id: NodeId,
@@ -55,10 +296,35 @@ pub enum Patch {
     },
 }
 
+/// Wire encoding of a patch list, for applying a diff in another process
+#[cfg(feature = "serde")]
+pub fn encode_patches(patches: &[Patch]) -> Result<Vec<u8>, serde_json::Error> {
+    serde_json::to_vec(patches)
+}
+
+#[cfg(feature = "serde")]
+pub fn decode_patches(bytes: &[u8]) -> Result<Vec<Patch>, serde_json::Error> {
+    serde_json::from_slice(bytes)
+}
+
+/// Numbers the tree by pre-order position, so the same tree shape always
+/// gets the same ids. Server rendering and client hydration use this to
+/// agree on ids without sharing an allocator.
+pub fn assign_stable_ids(tree: &mut VirtualNode) {
+    fn assign(node: &mut VirtualNode, next: &mut usize) {
+        node.id = NodeId(NodeId::STABLE_BASE + *next);
+        *next += 1;
+        for child in &mut node.children {
+            assign(child, next);
+        }
+    }
+    assign(tree, &mut 0);
+}
+
 pub fn diff(old: &VirtualNode, new: &VirtualNode) -> Vec<Patch> {
     let mut patches = Vec::new();
     
-    if old.node_type != new.node_type {
+    if old.node_type != new.node_type || old.key != new.key {
         patches.push(Patch::Replace {
             node_id: old.id,
             new_node: new.clone(),
@@ -66,9 +332,8 @@ pub fn diff(old: &VirtualNode, new: &VirtualNode) -> Vec<Patch> {
         return patches;
     }
     
-    // Diff props
-    let prop_patches = diff_props(&old.props, &new.props);
-    if !prop_patches.is_empty() {
+    // Diff props. Compared whole so removed props count as changes too.
+    if old.props != new.props {
         patches.push(Patch::UpdateProps {
             node_id: old.id,
             props: new.props.clone(),
@@ -76,12 +341,14 @@ pub fn diff(old: &VirtualNode, new: &VirtualNode) -> Vec<Patch> {
     }
     
     // Diff children
-    patches.extend(diff_children_with_keys(&old.children, &new.children));
+    patches.extend(diff_children_with_keys(old.id, &old.children, &new.children));
     
     patches
 }
 
-fn diff_props(old: &HashMap<String, PropValue>, new: &HashMap<String, PropValue>) -> Vec<(String, PropValue)> {
+/// Props that were added or changed between two prop maps, sorted by key so
+/// the result doesn't depend on `HashMap` iteration order
+pub fn diff_props(old: &HashMap<String, PropValue>, new: &HashMap<String, PropValue>) -> Vec<(String, PropValue)> {
     let mut changes = Vec::new();
     
     for (key, new_value) in new {
@@ -100,41 +367,43 @@ fn diff_props(old: &HashMap<String, PropValue>, new: &HashMap<String, PropValue>
         }
     }
     
+    changes.sort_by(|a, b| a.0.cmp(&b.0));
     changes
 }
 
-fn diff_children_with_keys(old: &[VirtualNode], new: &[VirtualNode]) -> Vec<Patch> {
+/// Patches turning `old` into `new` under the parent `parent_id`. Keyed
+/// children match an old child with the same key; unkeyed children match
+/// the unkeyed old children in order, and a keyed node never matches an
+/// unkeyed one. Patches apply in sequence, so each `Insert` and `Move`
+/// index is a position in the partly updated list.
+fn diff_children_with_keys(parent_id: NodeId, old: &[VirtualNode], new: &[VirtualNode]) -> Vec<Patch> {
     let mut patches = Vec::new();
     
-    // Build key maps
-    let old_key_map: HashMap<Option<&String>, usize> = old
-        .iter()
-        .enumerate()
-        .map(|(i, node)| (node.key.as_ref(), i))
-        .collect();
-    
-    // Track which old nodes have been matched
-    let mut old_matched = vec![false; old.len()];
-    
-    // First pass: match nodes by key
-    for new_node in new.iter() {
-        if let Some(key) = &new_node.key {
-            if let Some(&old_idx) = old_key_map.get(&Some(key)) {
-                if !old_matched[old_idx] {
-                    // Nodes match by key - diff them
-                    let node_patches = diff(&old[old_idx], new_node);
-                    patches.extend(node_patches);
-                    old_matched[old_idx] = true;
-                    continue;
-                }
-            }
+    // Build key maps. Duplicate keys match in order, like unkeyed nodes.
+    let mut old_key_map: HashMap<&String, VecDeque<usize>> = HashMap::new();
+    let mut old_unkeyed = VecDeque::new();
+    for (i, node) in old.iter().enumerate() {
+        match &node.key {
+            Some(key) => old_key_map.entry(key).or_default().push_back(i),
+            None => old_unkeyed.push_back(i),
         }
-        
-        // No match found - insert new node
-        // (Simplified - would need parent_id)
     }
     
-    // Second pass: remove unmatched old nodes
+    // Match each new node to an old one
+    let mut old_matched = vec![false; old.len()];
+    let matches: Vec<Option<usize>> = new
+        .iter()
+        .map(|new_node| {
+            let old_idx = match &new_node.key {
+                Some(key) => old_key_map.get_mut(key)?.pop_front(),
+                None => old_unkeyed.pop_front(),
+            }?;
+            old_matched[old_idx] = true;
+            Some(old_idx)
+        })
+        .collect();
+    
+    // Remove unmatched old nodes
     for (old_idx, matched) in old_matched.iter().enumerate() {
         if !matched {
             patches.push(Patch::Remove {
@@ -143,6 +412,41 @@ fn diff_children_with_keys(old: &[VirtualNode], new: &[VirtualNode]) -> Vec<Patc
         }
     }
     
+    // Put each new node in place, left to right, tracking the order the
+    // patches so far leave the children in
+    let mut current: Vec<NodeId> = old
+        .iter()
+        .zip(&old_matched)
+        .filter(|(_, matched)| **matched)
+        .map(|(node, _)| node.id)
+        .collect();
+    for (index, (new_node, old_idx)) in new.iter().zip(&matches).enumerate() {
+        match old_idx {
+            Some(old_idx) => {
+                let node_id = old[*old_idx].id;
+                if current[index] != node_id {
+                    let from = current.iter().position(|id| *id == node_id).unwrap_or(index);
+                    current.remove(from);
+                    current.insert(index, node_id);
+                    patches.push(Patch::Move {
+                        node_id,
+                        new_parent: parent_id,
+                        new_index: index,
+                    });
+                }
+                patches.extend(diff(&old[*old_idx], new_node));
+            }
+            None => {
+                current.insert(index, new_node.id);
+                patches.push(Patch::Insert {
+                    parent_id,
+                    index,
+                    node: new_node.clone(),
+                });
+            }
+        }
+    }
+    
     patches
 }
 
@@ -184,8 +488,9 @@ fn apply_patch(patch: &Patch, tree: &mut VirtualNode) {
             }
         }
         Patch::Move { node_id, new_parent, new_index } => {
-            // Find node, remove from old position, insert at new position
-            // (Simplified implementation)
+            if let Some(node) = find_and_detach(tree, *node_id) {
+                find_and_insert(tree, *new_parent, *new_index, &node);
+            }
         }
     }
 }
@@ -220,6 +525,14 @@ fn find_and_insert(tree: &mut VirtualNode, parent_id: NodeId, index: usize, node
     }
 }
 
+/// Removes the node `id` from below `tree` and returns it
+fn find_and_detach(tree: &mut VirtualNode, id: NodeId) -> Option<VirtualNode> {
+    if let Some(pos) = tree.children.iter().position(|child| child.id == id) {
+        return Some(tree.children.remove(pos));
+    }
+    tree.children.iter_mut().find_map(|child| find_and_detach(child, id))
+}
+
 fn find_and_remove(tree: &mut VirtualNode, id: NodeId) {
     tree.children.retain_mut(|child| {
         if child.id == id {
@@ -0,0 +1,63 @@
+#[cfg(test)]
+mod tests {
+    use rux_core::bindings::{bind_checked, bind_value, binding_kind, write_binding, BindingKind};
+    use rux_core::{create_effect, create_signal, PropValue};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn handler_name(prop: PropValue) -> String {
+        let PropValue::Function(name) = prop else {
+            panic!("expected a handler, got {:?}", prop);
+        };
+        name
+    }
+
+    #[test]
+    fn test_input_event_writes_bound_value_back() {
+        let name = create_signal(String::from("Ada"));
+        let handler = handler_name(bind_value(&name));
+        assert_eq!(binding_kind(&handler), Some(BindingKind::Value));
+
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let log = seen.clone();
+        let name_reader = name.clone();
+        let _effect = create_effect(move || log.borrow_mut().push(name_reader.get()));
+
+        // What a renderer does when the element fires `input`
+        assert!(write_binding(&handler, "Grace"));
+        assert_eq!(name.get(), "Grace");
+        assert_eq!(*seen.borrow(), ["Ada", "Grace"]);
+    }
+
+    #[test]
+    fn test_change_event_writes_checked_state_back() {
+        let agreed = create_signal(false);
+        let handler = handler_name(bind_checked(&agreed));
+        assert_eq!(binding_kind(&handler), Some(BindingKind::Checked));
+
+        assert!(write_binding(&handler, "true"));
+        assert!(agreed.get());
+    }
+
+    #[test]
+    fn test_unparseable_input_leaves_signal_alone() {
+        let age = create_signal(36u32);
+        let handler = handler_name(bind_value(&age));
+
+        assert!(write_binding(&handler, "forty"));
+        assert_eq!(age.get(), 36);
+        assert!(write_binding(&handler, "40"));
+        assert_eq!(age.get(), 40);
+    }
+
+    #[test]
+    fn test_binding_ends_with_its_signal() {
+        let name = create_signal(String::new());
+        let handler = handler_name(bind_value(&name));
+        drop(name);
+
+        assert!(!write_binding(&handler, "ignored"));
+        assert!(!write_binding(&handler, "ignored"));
+        assert_eq!(binding_kind("save"), None);
+    }
+}
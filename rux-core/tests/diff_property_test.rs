@@ -0,0 +1,51 @@
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+    use rux_core::virtual_tree::{apply_patches, assign_stable_ids, diff, VirtualNode};
+
+    /// Elements from a few tags, props and keys, so two random trees often
+    /// share keys and the diff has something to match. Shrinks towards
+    /// text leaves and fewer children.
+    fn tree() -> impl Strategy<Value = VirtualNode> {
+        let leaf = prop::sample::select(vec!["a", "b", "c"]).prop_map(VirtualNode::text);
+        leaf.prop_recursive(4, 32, 5, |inner| {
+            (
+                prop::sample::select(vec!["div", "span", "li"]),
+                prop::option::of(prop::sample::select(vec!["k1", "k2", "k3", "k4"])),
+                prop::option::of(prop::sample::select(vec!["red", "blue"])),
+                prop::collection::vec(inner, 0..5),
+            )
+                .prop_map(|(tag, key, class, children)| {
+                    let mut builder = VirtualNode::element(tag).children(children);
+                    if let Some(key) = key {
+                        builder = builder.key(key);
+                    }
+                    if let Some(class) = class {
+                        builder = builder.prop("class", class);
+                    }
+                    builder.build()
+                })
+        })
+    }
+
+    /// Ids differ between the patched and the new tree, so compare shapes
+    fn normalized(mut tree: VirtualNode) -> VirtualNode {
+        assign_stable_ids(&mut tree);
+        tree
+    }
+
+    proptest! {
+        #[test]
+        fn prop_patches_turn_old_tree_into_new(old in tree(), new in tree()) {
+            let patches = diff(&old, &new);
+            let mut patched = old.clone();
+            apply_patches(&patches, &mut patched);
+            prop_assert_eq!(normalized(patched), normalized(new));
+        }
+
+        #[test]
+        fn prop_diff_of_identical_trees_is_empty(old in tree()) {
+            prop_assert!(diff(&old, &old).is_empty());
+        }
+    }
+}
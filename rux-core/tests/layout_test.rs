@@ -0,0 +1,242 @@
+#[cfg(test)]
+mod tests {
+    use rux_core::layout::{
+        declared_size, paint_order, z_index, Constraints, EdgeInsets, FlexLayout, LayoutChild, LayoutDirection, MeasureCache, Size,
+        GridLayout, Length, StackLayout, TextChild, TextMeasurer,
+    };
+    use rux_core::virtual_tree::{NodeId, VirtualNode};
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    /// Fixed-size child that counts how often it's measured
+    #[derive(Debug)]
+    struct CountingChild {
+        id: Option<NodeId>,
+        calls: Rc<Cell<usize>>,
+    }
+
+    impl LayoutChild for CountingChild {
+        fn layout(&self, constraints: Constraints) -> Size {
+            self.calls.set(self.calls.get() + 1);
+            constraints.constrain(Size { width: 40.0, height: 20.0 })
+        }
+
+        fn node_id(&self) -> Option<NodeId> {
+            self.id
+        }
+    }
+
+    #[test]
+    fn test_intersect_overlapping_ranges() {
+        let parent = Constraints::new(0.0, 300.0, 50.0, 200.0);
+        let child = Constraints::new(100.0, 400.0, 0.0, 150.0);
+
+        assert_eq!(parent.intersect(&child), Constraints::new(100.0, 300.0, 50.0, 150.0));
+        assert_eq!(child.intersect(&parent), parent.intersect(&child));
+    }
+
+    #[test]
+    fn test_intersect_disjoint_ranges() {
+        let parent = Constraints::new(0.0, 100.0, 0.0, 100.0);
+        let child = Constraints::new(200.0, 300.0, 50.0, 80.0);
+
+        // Width collapses onto the smaller max; height overlaps normally
+        assert_eq!(parent.intersect(&child), Constraints::new(100.0, 100.0, 50.0, 80.0));
+    }
+
+    #[test]
+    fn test_deflate_by_insets() {
+        let constraints = Constraints::new(20.0, 200.0, 0.0, f32::INFINITY);
+        let deflated = constraints.deflate(EdgeInsets::symmetric(15.0, 10.0));
+
+        assert_eq!(deflated, Constraints::new(0.0, 170.0, 0.0, f32::INFINITY));
+        assert_eq!(
+            Constraints::tight(Size { width: 10.0, height: 10.0 }).deflate(EdgeInsets::all(8.0)),
+            Constraints::new(0.0, 0.0, 0.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn test_measure_cache_skips_repeat_measurements() {
+        let cached = Rc::new(Cell::new(0));
+        let uncached = Rc::new(Cell::new(0));
+        let children: Vec<Box<dyn LayoutChild>> = vec![
+            Box::new(CountingChild { id: Some(NodeId(1)), calls: cached.clone() }),
+            Box::new(CountingChild { id: None, calls: uncached.clone() }),
+        ];
+        let constraints = Constraints::loose(Size { width: 200.0, height: 100.0 });
+        let stack = StackLayout::new();
+
+        let mut cache = MeasureCache::new();
+        let first = stack.layout_cached(constraints, &children, &mut cache);
+        let second = stack.layout_cached(constraints, &children, &mut cache);
+        assert_eq!(first, second);
+        assert_eq!(cached.get(), 1);
+        assert_eq!(uncached.get(), 2);
+        assert_eq!(cache.len(), 1);
+
+        // Different constraints are a different measurement, and flex also
+        // asks for the intrinsic size
+        let flex = FlexLayout::new(LayoutDirection::Horizontal);
+        flex.layout_cached(constraints, &children, &mut cache);
+        assert_eq!(cached.get(), 3);
+        flex.layout_cached(constraints, &children, &mut cache);
+        assert_eq!(cached.get(), 3);
+
+        // Without a shared cache every layout measures again
+        stack.layout(constraints, &children);
+        stack.layout(constraints, &children);
+        assert_eq!(cached.get(), 5);
+    }
+
+    /// Wraps onto as many lines as the width requires, like text
+    #[derive(Debug)]
+    struct WrappingText {
+        chars: usize,
+    }
+
+    impl LayoutChild for WrappingText {
+        fn layout(&self, constraints: Constraints) -> Size {
+            let content = self.chars as f32 * 8.0;
+            let width = content.min(constraints.max_width);
+            let lines = (content / width).ceil();
+            constraints.constrain(Size { width, height: lines * 16.0 })
+        }
+    }
+
+    #[test]
+    fn test_text_intrinsic_size_is_one_line() {
+        let text = WrappingText { chars: 30 };
+
+        assert_eq!(text.intrinsic_size(), Size { width: 240.0, height: 16.0 });
+        let wrapped = text.layout(Constraints::loose(Size { width: 100.0, height: 100.0 }));
+        assert_eq!(wrapped, Size { width: 100.0, height: 48.0 });
+    }
+
+    #[test]
+    fn test_flex_gives_fixed_children_their_intrinsic_size() {
+        let children: Vec<Box<dyn LayoutChild>> = vec![
+            Box::new(CountingChild { id: None, calls: Rc::default() }),
+            Box::new(WrappingText { chars: 30 }),
+        ];
+        let constraints = Constraints::loose(Size { width: 300.0, height: 100.0 });
+
+        // The 40px child keeps its size and the text takes what's left,
+        // rather than each getting half and the text wrapping
+        let rects = FlexLayout::new(LayoutDirection::Horizontal).layout(constraints, &children);
+        assert_eq!((rects[0].width, rects[1].x), (40.0, 40.0));
+        assert_eq!((rects[1].width, rects[1].height), (240.0, 16.0));
+    }
+
+    #[derive(Debug)]
+    struct Layer {
+        z: i32,
+    }
+
+    impl LayoutChild for Layer {
+        fn layout(&self, constraints: Constraints) -> Size {
+            constraints.constrain(Size { width: 10.0, height: 10.0 })
+        }
+
+        fn z_index(&self) -> i32 {
+            self.z
+        }
+    }
+
+    #[test]
+    fn test_stack_paint_order_follows_z_index() {
+        let children: Vec<Box<dyn LayoutChild>> = vec![
+            Box::new(Layer { z: 10 }),
+            Box::new(Layer { z: -1 }),
+            Box::new(Layer { z: 0 }),
+        ];
+
+        assert_eq!(StackLayout::new().paint_order(&children), vec![1, 2, 0]);
+        // Equal z keeps source order
+        assert_eq!(paint_order([2, 1, 2, 1]), vec![1, 3, 0, 2]);
+    }
+
+    #[test]
+    fn test_z_index_prop() {
+        assert_eq!(z_index(&VirtualNode::element("div").prop("z_index", 3.0).build()), 3);
+        assert_eq!(z_index(&VirtualNode::element("div").prop("z_index", "-2").build()), -2);
+        assert_eq!(z_index(&VirtualNode::element("div").build()), 0);
+    }
+
+    /// Ten pixels per character, one font size per line
+    struct StubMeasurer;
+
+    impl TextMeasurer for StubMeasurer {
+        fn measure(&self, text: &str, font_size: f32) -> Size {
+            Size { width: text.len() as f32 * 10.0, height: font_size }
+        }
+    }
+
+    #[test]
+    fn test_text_measurer_sizes_text_children() {
+        let text = |text: &str, font_size| -> Box<dyn LayoutChild> {
+            Box::new(TextChild { text: text.to_string(), font_size, node_id: None })
+        };
+        let children = vec![text("Save", 16.0), text("Cancel", 12.0)];
+        let constraints = Constraints::loose(Size { width: 400.0, height: 100.0 });
+        let row = FlexLayout::new(LayoutDirection::Horizontal);
+
+        let rects = row.layout_with_text(constraints, &children, &mut MeasureCache::new(), &StubMeasurer);
+        assert_eq!((rects[0].width, rects[0].height), (40.0, 16.0));
+        // The second word starts where the first one's measured width ends
+        assert_eq!((rects[1].x, rects[1].width, rects[1].height), (40.0, 60.0, 12.0));
+
+        // Without a measurer text takes no space
+        let rects = row.layout(constraints, &children);
+        assert_eq!(rects[1].x, 0.0);
+    }
+
+    /// 300x40 unless it declares a width
+    #[derive(Debug)]
+    struct Sized {
+        width: Length,
+    }
+
+    impl LayoutChild for Sized {
+        fn layout(&self, constraints: Constraints) -> Size {
+            constraints.constrain(Size { width: 300.0, height: 40.0 })
+        }
+
+        fn width(&self) -> Length {
+            self.width
+        }
+    }
+
+    #[test]
+    fn test_percent_width_resolves_against_container() {
+        let children: Vec<Box<dyn LayoutChild>> = vec![Box::new(Sized { width: Length::Percent(50.0) })];
+        let container = Constraints::loose(Size { width: 200.0, height: 100.0 });
+
+        let rects = FlexLayout::new(LayoutDirection::Vertical).layout(container, &children);
+        assert_eq!((rects[0].width, rects[0].height), (100.0, 40.0));
+        let rects = StackLayout::new().layout(container, &children);
+        assert_eq!(rects[0].width, 100.0);
+
+        // Percentages of a grid cell, and auto filling the cell
+        let children: Vec<Box<dyn LayoutChild>> = vec![
+            Box::new(Sized { width: Length::Percent(50.0) }),
+            Box::new(Sized { width: Length::Auto }),
+        ];
+        let rects = GridLayout::new(2, 1).layout(container, &children);
+        assert_eq!((rects[0].width, rects[1].width), (50.0, 100.0));
+
+        let children: Vec<Box<dyn LayoutChild>> = vec![Box::new(Sized { width: Length::Px(120.0) })];
+        let rects = FlexLayout::new(LayoutDirection::Vertical).layout(container, &children);
+        assert_eq!(rects[0].width, 120.0);
+    }
+
+    #[test]
+    fn test_length_props() {
+        let node = VirtualNode::element("div").prop("width", "50%").prop("height", 24.0).build();
+        assert_eq!(declared_size(&node), (Length::Percent(50.0), Length::Px(24.0)));
+
+        let node = VirtualNode::element("div").prop("width", "12px").prop("height", "auto").build();
+        assert_eq!(declared_size(&node), (Length::Px(12.0), Length::Auto));
+        assert_eq!(declared_size(&VirtualNode::element("div").prop("width", "wide").build()).0, Length::Auto);
+    }
+}
@@ -0,0 +1,110 @@
+#[cfg(test)]
+mod tests {
+    use rux_core::renderer::{minify_html, ElementId, Renderer, StringRenderer};
+    use rux_core::virtual_tree::{PropValue, VirtualNode};
+
+    #[test]
+    fn test_raw_html_is_not_escaped() {
+        let tree = VirtualNode::element("article")
+            .prop("title", "a \"quoted\" <title>")
+            .child(VirtualNode::text("<b>escaped</b> & safe"))
+            .child(VirtualNode::raw_html("<p>Markdown <em>output</em></p>"))
+            .build();
+
+        assert_eq!(
+            StringRenderer::render(&tree),
+            "<article title=\"a &quot;quoted&quot; &lt;title&gt;\">&lt;b&gt;escaped&lt;/b&gt; &amp; safe<div style=\"display: contents\"><p>Markdown <em>output</em></p></div></article>"
+        );
+    }
+
+    #[test]
+    fn test_string_renderer_mount() {
+        let tree = VirtualNode::element("div")
+            .prop("hidden", true)
+            .prop("disabled", false)
+            .child(VirtualNode::element("br"))
+            .child(VirtualNode::fragment().child(VirtualNode::text("Hi")))
+            .build();
+
+        let mut renderer = StringRenderer::new();
+        renderer.mount(ElementId(0), &tree);
+        assert_eq!(renderer.html(), "<div hidden><br>Hi</div>");
+
+        renderer.unmount(ElementId(0));
+        assert_eq!(renderer.html(), "");
+    }
+
+    #[test]
+    fn test_string_renderer_sets_attributes_like_the_client() {
+        let tree = VirtualNode::element("div")
+            .prop("class", PropValue::Array(vec!["a".into(), "b".into()]))
+            .prop("draggable", false)
+            .prop("hidden", false)
+            .prop("onClick", "go")
+            .prop("style", "color: red;")
+            .prop("z_index", 5.0)
+            .build();
+
+        assert_eq!(
+            StringRenderer::render(&tree),
+            "<div class=\"a b\" draggable=\"false\" style=\"color: red; z-index: 5\"></div>"
+        );
+    }
+
+    /// Tags and non-blank text in document order, whitespace inside `<pre>`
+    /// kept exactly
+    fn structure(html: &str) -> Vec<String> {
+        let mut parts = Vec::new();
+        let mut in_pre = false;
+        for (i, segment) in html.split('<').enumerate() {
+            let (tag, text) = match segment.split_once('>') {
+                Some((tag, text)) if i > 0 => (Some(tag), text),
+                _ => (None, segment),
+            };
+            if let Some(tag) = tag {
+                in_pre = tag.starts_with("pre") || (in_pre && tag != "/pre");
+                parts.push(format!("<{}>", tag));
+            }
+            if in_pre {
+                parts.push(text.to_string());
+            } else if !text.trim().is_empty() {
+                parts.push(text.trim().to_string());
+            }
+        }
+        parts
+    }
+
+    #[test]
+    fn test_minify_html_keeps_structure() {
+        let pretty = "<!DOCTYPE html>\n<html>\n  <body>\n    <p>\n      Hello <b>there</b> <i>friend</i>\n    </p>\n    <pre>  keep\n    this </pre>\n    <textarea>\n  as typed\n</textarea>\n  </body>\n</html>\n";
+
+        let minified = minify_html(pretty);
+        assert!(minified.len() < pretty.len());
+        assert_eq!(structure(&minified), structure(pretty));
+        assert_eq!(
+            minified,
+            "<!DOCTYPE html><html><body><p>Hello <b>there</b> <i>friend</i></p><pre>  keep\n    this </pre><textarea>\n  as typed\n</textarea></body></html>"
+        );
+    }
+
+    #[test]
+    fn test_minify_html_collapses_whitespace_between_inline_tags() {
+        assert_eq!(minify_html("<b>a</b>\n<i>b</i>"), "<b>a</b> <i>b</i>");
+        assert_eq!(minify_html("<p>\n  one   two\n  <em>three</em>\n</p>\n<div> </div>"), "<p>one two <em>three</em></p><div></div>");
+        assert_eq!(minify_html("<PRE>a  </PRE>\n<p>b</p>"), "<PRE>a  </PRE><p>b</p>");
+    }
+
+    #[test]
+    fn test_string_renderer_minify() {
+        let tree = VirtualNode::element("ul")
+            .child(VirtualNode::text("\n  "))
+            .child(VirtualNode::element("li").child(VirtualNode::text(" One ")))
+            .child(VirtualNode::text("\n"))
+            .child(VirtualNode::raw_html("<li>\n  Two\n</li>\n"))
+            .build();
+
+        let mut renderer = StringRenderer::new().with_minify(true);
+        renderer.mount(ElementId(0), &tree);
+        assert_eq!(renderer.html(), "<ul>\n  <li> One </li>\n<div style=\"display: contents\"><li>Two</li></div></ul>");
+    }
+}
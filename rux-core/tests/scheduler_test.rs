@@ -0,0 +1,98 @@
+#[cfg(test)]
+mod tests {
+    use rux_core::scheduler::{
+        flush_scheduled_work, schedule_with_result, Fiber, FiberId, Priority, Scheduler, DEFAULT_FRAME_BUDGET,
+    };
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_frame_budget_yields_with_work_queued() {
+        let mut scheduler = Scheduler::with_frame_budget(Duration::from_millis(1));
+        let order = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        for i in 0..100 {
+            let log = order.clone();
+            scheduler.schedule(Fiber {
+                id: FiberId(i),
+                priority: Priority::Normal,
+                work: Box::new(move || {
+                    std::thread::sleep(Duration::from_micros(200));
+                    log.lock().push(i);
+                }),
+            });
+        }
+
+        let start = Instant::now();
+        scheduler.run_frame();
+        assert!(start.elapsed() >= Duration::from_millis(1));
+        assert!(scheduler.should_yield());
+        assert_eq!(scheduler.remaining_budget(), Duration::ZERO);
+
+        let first = order.lock().len();
+        assert!(first > 0 && first < 100, "ran {} fibers", first);
+        assert_eq!(*order.lock(), (0..first).collect::<Vec<_>>());
+        assert_eq!(scheduler.pending_work(), 100 - first);
+
+        // The next frame picks up with the first fiber that didn't run
+        scheduler.run_frame();
+        let second = order.lock().len();
+        assert!(second > first);
+        assert_eq!(*order.lock(), (0..second).collect::<Vec<_>>());
+        assert_eq!(scheduler.pending_work(), 100 - second);
+    }
+
+    #[test]
+    fn test_default_frame_budget() {
+        let scheduler = Scheduler::new();
+        assert_eq!(scheduler.frame_budget(), DEFAULT_FRAME_BUDGET);
+        assert_eq!(scheduler.remaining_budget(), DEFAULT_FRAME_BUDGET);
+        assert!(!scheduler.should_yield());
+    }
+
+    #[test]
+    fn test_scheduled_work_returns_result() {
+        let mut scheduler = Scheduler::new();
+        let low = scheduler.schedule_with_result(Priority::Low, || "low");
+        let sum = scheduler.schedule_with_result(Priority::Immediate, || (1..=10).sum::<u32>());
+        assert!(sum.try_recv().is_err());
+
+        scheduler.flush_work();
+        assert_eq!(sum.try_recv(), Ok(55));
+        assert_eq!(low.try_recv(), Ok("low"));
+    }
+
+    #[test]
+    fn test_global_scheduler_delivers_result() {
+        let receiver = schedule_with_result(Priority::Normal, || vec![1, 2, 3].into_iter().map(|n| n * 2).collect::<Vec<_>>());
+        flush_scheduled_work();
+        assert_eq!(receiver.recv_timeout(Duration::from_secs(1)), Ok(vec![2, 4, 6]));
+    }
+
+    #[test]
+    fn test_idle_work_runs_after_normal_work() {
+        let mut scheduler = Scheduler::new();
+        let order = Arc::new(parking_lot::Mutex::new(Vec::new()));
+
+        let log = order.clone();
+        scheduler.schedule_idle(move || log.lock().push("idle"));
+        for name in ["first", "second"] {
+            let log = order.clone();
+            scheduler.schedule(Fiber {
+                id: FiberId::next(),
+                priority: Priority::Normal,
+                work: Box::new(move || log.lock().push(name)),
+            });
+        }
+        let log = order.clone();
+        scheduler.schedule(Fiber {
+            id: FiberId::next(),
+            priority: Priority::Low,
+            work: Box::new(move || log.lock().push("low")),
+        });
+        assert_eq!(scheduler.pending_work(), 4);
+
+        scheduler.run_frame();
+        assert_eq!(*order.lock(), ["first", "second", "low", "idle"]);
+        assert_eq!(scheduler.pending_work(), 0);
+    }
+}
@@ -0,0 +1,119 @@
+#[cfg(test)]
+mod tests {
+    use rux_core::renderer::{apply_serialized_patches, ElementId, Renderer};
+    use rux_core::virtual_tree::{decode_patches, encode_patches, NodeId, NodeType, Patch, PropValue, VirtualNode};
+    use std::collections::HashMap;
+
+    /// Records the calls made on it
+    #[derive(Default)]
+    struct RecordingRenderer {
+        calls: Vec<String>,
+        patches: Vec<Patch>,
+    }
+
+    impl Renderer for RecordingRenderer {
+        fn create_element(&mut self, node: &VirtualNode) -> ElementId {
+            self.calls.push(format!("create {:?}", node.node_type));
+            ElementId(self.calls.len())
+        }
+
+        fn update_element(&mut self, element_id: ElementId, patches: &[Patch]) {
+            self.calls.push(format!("update {}", element_id.0));
+            self.patches.extend_from_slice(patches);
+        }
+
+        fn remove_element(&mut self, element_id: ElementId) {
+            self.calls.push(format!("remove {}", element_id.0));
+        }
+
+        fn mount(&mut self, root: ElementId, node: &VirtualNode) {
+            self.calls.push(format!("mount {:?} at {}", node.node_type, root.0));
+        }
+
+        fn unmount(&mut self, root: ElementId) {
+            self.calls.push(format!("unmount {}", root.0));
+        }
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let tree = VirtualNode::element("ul")
+            .prop("class", "list")
+            .prop("tabindex", 1.0)
+            .prop("hidden", false)
+            .prop("onclick", PropValue::Function("handle_click".to_string()))
+            .prop("items", vec!["a", "b"])
+            .child(VirtualNode::element("li").key("a").child(VirtualNode::text("First")))
+            .child(VirtualNode::fragment().child(VirtualNode::component("Counter")))
+            .build();
+
+        let json = tree.to_json().unwrap();
+        let parsed = VirtualNode::from_json(&json).unwrap();
+        assert_eq!(parsed, tree);
+    }
+
+    #[test]
+    fn test_function_prop_serializes_as_handler_name() {
+        let node = VirtualNode::element("button")
+            .prop("onclick", PropValue::Function("increment".to_string()))
+            .build();
+
+        let json = node.to_json().unwrap();
+        assert!(json.contains(r#"{"Function":"increment"}"#));
+        assert!(matches!(VirtualNode::from_json(&json).unwrap().node_type, NodeType::Element(tag) if tag == "button"));
+    }
+
+    #[test]
+    fn test_invalid_json_is_an_error() {
+        assert!(VirtualNode::from_json(r#"{"id": 1}"#).is_err());
+    }
+
+    #[test]
+    fn test_patches_round_trip_and_apply() {
+        let patches = vec![
+            Patch::Replace {
+                node_id: NodeId(1),
+                new_node: VirtualNode::element("section").build(),
+            },
+            Patch::UpdateProps {
+                node_id: NodeId(2),
+                props: HashMap::from([("class".to_string(), PropValue::from("active"))]),
+            },
+            Patch::Insert {
+                parent_id: NodeId(1),
+                index: 0,
+                node: VirtualNode::text("Hello"),
+            },
+            Patch::Remove { node_id: NodeId(3) },
+            Patch::Move {
+                node_id: NodeId(4),
+                new_parent: NodeId(1),
+                new_index: 2,
+            },
+        ];
+
+        let bytes = encode_patches(&patches).unwrap();
+        assert_eq!(decode_patches(&bytes).unwrap(), patches);
+
+        let mut renderer = RecordingRenderer::default();
+        apply_serialized_patches(&mut renderer, &bytes).unwrap();
+        assert_eq!(
+            renderer.calls,
+            vec![
+                "create Element(\"section\")",
+                "mount Element(\"section\") at 0",
+                "update 0",
+                "create Text(\"Hello\")",
+                "mount Text(\"Hello\") at 0",
+            ]
+        );
+        assert_eq!(renderer.patches, vec![patches[1].clone()]);
+    }
+
+    #[test]
+    fn test_corrupt_patch_bytes_are_rejected() {
+        let mut renderer = RecordingRenderer::default();
+        assert!(apply_serialized_patches(&mut renderer, b"[{\"Remove\": {}}]").is_err());
+        assert!(renderer.calls.is_empty());
+    }
+}
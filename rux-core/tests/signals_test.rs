@@ -0,0 +1,142 @@
+#[cfg(test)]
+mod tests {
+    use rux_core::signals::{create_effect, create_signal};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_effect_reruns_on_set() {
+        let count = create_signal(1);
+        let seen = Rc::new(RefCell::new(Vec::new()));
+
+        let effect_count = count.clone();
+        let effect_seen = seen.clone();
+        let _effect = create_effect(move || effect_seen.borrow_mut().push(effect_count.get()));
+        assert_eq!(*seen.borrow(), vec![1]);
+
+        count.set(2);
+        count.update(|n| *n += 1);
+        assert_eq!(*seen.borrow(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_effect_drops_stale_subscriptions() {
+        let use_a = create_signal(true);
+        let a = create_signal("a");
+        let b = create_signal("b");
+        let runs = Rc::new(RefCell::new(0));
+
+        let (flag, sa, sb, counter) = (use_a.clone(), a.clone(), b.clone(), runs.clone());
+        let _effect = create_effect(move || {
+            *counter.borrow_mut() += 1;
+            if flag.get() {
+                sa.get();
+            } else {
+                sb.get();
+            }
+        });
+        assert_eq!(*runs.borrow(), 1);
+
+        use_a.set(false);
+        assert_eq!(*runs.borrow(), 2);
+
+        // `a` is no longer read, so changing it must not re-run the effect
+        a.set("a2");
+        assert_eq!(*runs.borrow(), 2);
+        b.set("b2");
+        assert_eq!(*runs.borrow(), 3);
+    }
+
+    #[test]
+    fn test_disposed_effect_stops_running() {
+        let count = create_signal(0);
+        let runs = Rc::new(RefCell::new(0));
+
+        let (signal, counter) = (count.clone(), runs.clone());
+        let effect = create_effect(move || {
+            signal.get();
+            *counter.borrow_mut() += 1;
+        });
+        effect.dispose();
+
+        count.set(1);
+        assert_eq!(*runs.borrow(), 1);
+    }
+
+    #[test]
+    fn test_map_updates_after_set() {
+        let count = create_signal(2);
+        let doubled = count.map(|n| n * 2);
+        assert_eq!(doubled.get(), 4);
+
+        count.set(5);
+        assert_eq!(doubled.get(), 10);
+
+        let label = doubled.map(|n| format!("{} items", n));
+        count.set(1);
+        assert_eq!(label.get(), "2 items");
+    }
+
+    #[test]
+    fn test_map_stops_when_dropped() {
+        let count = create_signal(1);
+        let runs = Rc::new(RefCell::new(0));
+
+        let map_runs = runs.clone();
+        let doubled = count.map(move |n| {
+            *map_runs.borrow_mut() += 1;
+            n * 2
+        });
+        let label = doubled.map(|n| format!("{} items", n));
+        drop(doubled);
+
+        count.set(2);
+        assert_eq!(label.get(), "4 items", "a chain stays live while its end is");
+        assert_eq!(*runs.borrow(), 2);
+
+        drop(label);
+        count.set(3);
+        assert_eq!(*runs.borrow(), 2);
+    }
+
+    #[test]
+    fn test_read_signal_reflects_writes() {
+        let (count, set_count) = create_signal(1).split();
+        let label = count.map(|n| format!("#{}", n));
+        assert_eq!(count.get(), 1);
+
+        set_count.set(7);
+        assert_eq!(count.get(), 7);
+        assert_eq!(label.get(), "#7");
+
+        set_count.update(|n| *n += 1);
+        assert_eq!(count.get(), 8);
+        assert_eq!(count.id(), set_count.id());
+    }
+
+    #[test]
+    fn test_set_if_changed_skips_equal_values() {
+        let count = create_signal(1);
+        let runs = Rc::new(RefCell::new(0));
+
+        let effect_count = count.clone();
+        let effect_runs = runs.clone();
+        let _effect = create_effect(move || {
+            effect_count.get();
+            *effect_runs.borrow_mut() += 1;
+        });
+        let doubled = count.map(|n| n * 2);
+        assert_eq!(*runs.borrow(), 1);
+
+        assert!(!count.set_if_changed(1));
+        assert_eq!(*runs.borrow(), 1, "an equal value doesn't re-run dependents");
+
+        assert!(count.set_if_changed(2));
+        assert_eq!(*runs.borrow(), 2);
+        assert_eq!(doubled.get(), 4);
+
+        // `set` still notifies, for when dependents must re-run anyway
+        count.set(2);
+        assert_eq!(*runs.borrow(), 3);
+    }
+}
@@ -0,0 +1,220 @@
+#[cfg(test)]
+mod tests {
+    use rux_core::virtual_tree::{apply_patches, assign_stable_ids, diff, diff_props, NodeId, NodeType, Patch, PropValue, VirtualNode};
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_builder_matches_literal() {
+        let tree = VirtualNode::element("ul")
+            .prop("class", "list")
+            .child(VirtualNode::element("li").key("a").child(VirtualNode::text("First")))
+            .child(VirtualNode::element("li").key("b").prop("hidden", true))
+            .build();
+
+        let first = &tree.children[0];
+        let expected = VirtualNode {
+            id: tree.id,
+            node_type: NodeType::Element("ul".to_string()),
+            props: HashMap::from([("class".to_string(), PropValue::String("list".to_string()))]),
+            children: vec![
+                VirtualNode {
+                    id: first.id,
+                    node_type: NodeType::Element("li".to_string()),
+                    props: HashMap::new(),
+                    children: vec![VirtualNode {
+                        id: first.children[0].id,
+                        node_type: NodeType::Text("First".to_string()),
+                        props: HashMap::new(),
+                        children: vec![],
+                        key: None,
+                    }],
+                    key: Some("a".to_string()),
+                },
+                VirtualNode {
+                    id: tree.children[1].id,
+                    node_type: NodeType::Element("li".to_string()),
+                    props: HashMap::from([("hidden".to_string(), PropValue::Boolean(true))]),
+                    children: vec![],
+                    key: Some("b".to_string()),
+                },
+            ],
+            key: None,
+        };
+
+        assert_eq!(tree, expected);
+    }
+
+    #[test]
+    fn test_builder_allocates_unique_ids() {
+        let tree = VirtualNode::fragment()
+            .children(["a", "b", "c"].map(VirtualNode::text))
+            .build();
+
+        let mut ids: Vec<_> = tree.children.iter().map(|child| child.id).collect();
+        ids.push(tree.id);
+        ids.sort_by_key(|id| id.0);
+        ids.dedup();
+        assert_eq!(ids.len(), 4);
+        assert!(tree.children.iter().all(|child| child.id.0 > tree.id.0));
+    }
+
+    #[test]
+    fn test_diff_props_sorted_by_key() {
+        let old = VirtualNode::element("input")
+            .prop("value", "a")
+            .prop("class", "field")
+            .prop("disabled", false)
+            .build();
+        let new = VirtualNode::element("input")
+            .prop("value", "b")
+            .prop("class", "field wide")
+            .prop("disabled", true)
+            .prop("autofocus", true)
+            .prop("placeholder", "Name")
+            .build();
+
+        let changes = diff_props(&old.props, &new.props);
+        let keys: Vec<&str> = changes.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(keys, vec!["autofocus", "class", "disabled", "placeholder", "value"]);
+        assert_eq!(changes[4].1, PropValue::String("b".to_string()));
+    }
+
+    #[test]
+    fn test_diff_array_prop_gains_element() {
+        let old = VirtualNode::element("div")
+            .prop("class", vec!["a", "b"])
+            .prop("id", "list")
+            .build();
+        let mut new = old.clone();
+        new.props.insert("class".to_string(), vec!["a", "b", "c"].into());
+
+        let patches = diff(&old, &new);
+        assert_eq!(patches.len(), 1);
+        let Patch::UpdateProps { node_id, props } = &patches[0] else {
+            panic!("Expected UpdateProps, got {:?}", patches[0]);
+        };
+        assert_eq!(*node_id, old.id);
+        assert_eq!(
+            props["class"],
+            PropValue::Array(vec![
+                PropValue::String("a".to_string()),
+                PropValue::String("b".to_string()),
+                PropValue::String("c".to_string()),
+            ])
+        );
+
+        let changes = diff_props(&old.props, &new.props);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].0, "class");
+    }
+
+    #[test]
+    fn test_array_prop_json() {
+        let value = PropValue::Array(vec![
+            PropValue::from("a \"quoted\""),
+            PropValue::Number(1.5),
+            PropValue::Boolean(true),
+            PropValue::Array(vec![]),
+        ]);
+        assert_eq!(value.to_json(), r#"["a \"quoted\"",1.5,true,[]]"#);
+    }
+
+    fn ids(node: &VirtualNode) -> Vec<NodeId> {
+        let mut ids = vec![node.id];
+        for child in &node.children {
+            ids.extend(self::ids(child));
+        }
+        ids
+    }
+
+    #[test]
+    fn test_stable_ids_match_across_builds() {
+        let build = || {
+            VirtualNode::element("ul")
+                .child(VirtualNode::element("li").child(VirtualNode::text("One")))
+                .child(VirtualNode::element("li").child(VirtualNode::text("Two")))
+                .build()
+        };
+        let mut first = build();
+        let mut second = build();
+        assert_ne!(ids(&first), ids(&second));
+
+        assign_stable_ids(&mut first);
+        assign_stable_ids(&mut second);
+        assert_eq!(ids(&first), ids(&second));
+
+        let offsets: Vec<usize> = ids(&first).iter().map(|id| id.0 - NodeId::STABLE_BASE).collect();
+        assert_eq!(offsets, vec![0, 1, 2, 3, 4]);
+        assert!(ids(&first).iter().all(|id| id.is_stable()));
+        assert!(!NodeId::next().is_stable());
+    }
+
+    #[test]
+    fn test_raw_html_change_replaces_node() {
+        let old = VirtualNode::raw_html("<p>One</p>");
+        let new = VirtualNode::raw_html("<p>Two</p>");
+
+        assert_eq!(
+            diff(&old, &new),
+            vec![Patch::Replace { node_id: old.id, new_node: new.clone() }]
+        );
+        assert!(diff(&old, &VirtualNode::raw_html("<p>One</p>")).is_empty());
+    }
+
+    #[test]
+    fn test_tree_queries() {
+        let tree = VirtualNode::element("div")
+            .child(VirtualNode::element("h1").child(VirtualNode::text("Todo")))
+            .child(
+                VirtualNode::element("ul")
+                    .child(VirtualNode::element("li").key("milk").child(VirtualNode::text("Milk")))
+                    .child(
+                        VirtualNode::element("li")
+                            .key("eggs")
+                            .child(VirtualNode::text("Eggs, "))
+                            .child(VirtualNode::element("em").child(VirtualNode::text("a dozen"))),
+                    ),
+            )
+            .build();
+
+        assert_eq!(tree.find_by_tag("li").len(), 2);
+        assert_eq!(tree.find_by_tag("div"), vec![&tree]);
+        assert!(tree.find_by_tag("table").is_empty());
+
+        let eggs = tree.find_by_key("eggs").unwrap();
+        assert_eq!(eggs.text_content(), "Eggs, a dozen");
+        assert!(tree.find_by_key("bread").is_none());
+
+        assert_eq!(tree.text_content(), "TodoMilkEggs, a dozen");
+    }
+
+    #[test]
+    fn test_diff_mixed_keyed_and_unkeyed_children() {
+        let old = VirtualNode::element("ul")
+            .child(VirtualNode::element("li").key("a").child(VirtualNode::text("A")))
+            .child(VirtualNode::element("li").prop("class", "spacer"))
+            .child(VirtualNode::element("li").key("b").child(VirtualNode::text("B")))
+            .build();
+        let new = VirtualNode::element("ul")
+            .child(VirtualNode::element("li").key("b").child(VirtualNode::text("B")))
+            .child(VirtualNode::element("li").prop("class", "divider"))
+            .child(VirtualNode::element("li").key("a").child(VirtualNode::text("A")))
+            .build();
+
+        // Keyed nodes match by key and the unkeyed one by position among
+        // the unkeyed, so everything is moved or updated in place
+        let patches = diff(&old, &new);
+        assert!(patches.iter().all(|p| matches!(p, Patch::Move { .. } | Patch::UpdateProps { .. })), "{:?}", patches);
+        assert!(patches.contains(&Patch::UpdateProps { node_id: old.children[1].id, props: new.children[1].props.clone() }));
+
+        let mut patched = old.clone();
+        apply_patches(&patches, &mut patched);
+        let ids: Vec<NodeId> = patched.children.iter().map(|child| child.id).collect();
+        assert_eq!(ids, vec![old.children[2].id, old.children[1].id, old.children[0].id]);
+
+        let mut expected = new.clone();
+        assign_stable_ids(&mut patched);
+        assign_stable_ids(&mut expected);
+        assert_eq!(patched, expected);
+    }
+}
@@ -0,0 +1,96 @@
+use rux_core::layout::{overflow, paint_order, z_index, Rect};
+use rux_core::virtual_tree::{NodeId, VirtualNode};
+use std::collections::HashMap;
+
+/// Pixel rect for `RenderPass::set_scissor_rect`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScissorRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl ScissorRect {
+    /// The whole render target
+    pub fn full(target_width: u32, target_height: u32) -> Self {
+        Self { x: 0, y: 0, width: target_width, height: target_height }
+    }
+    
+    /// `rect` in whole pixels, kept inside the render target since wgpu
+    /// rejects scissor rects that extend past it
+    pub fn from_rect(rect: Rect, target_width: u32, target_height: u32) -> Self {
+        let clamp = |value: f32, max: u32| (value.max(0.0) as u32).min(max);
+        let x = clamp(rect.x.floor(), target_width);
+        let y = clamp(rect.y.floor(), target_height);
+        let right = clamp((rect.x + rect.width).ceil(), target_width);
+        let bottom = clamp((rect.y + rect.height).ceil(), target_height);
+        Self {
+            x,
+            y,
+            width: right.saturating_sub(x),
+            height: bottom.saturating_sub(y),
+        }
+    }
+}
+
+/// One step of drawing a tree
+#[derive(Debug, Clone, PartialEq)]
+pub enum DrawCommand<'a> {
+    Draw(&'a VirtualNode),
+    /// Clip what follows to this rect
+    SetScissor(ScissorRect),
+}
+
+/// The commands drawing `root` into a `target_width` by `target_height`
+/// target, given each node's rect from layout. Children of a node whose
+/// `overflow` clips are drawn under a scissor rect matching the node's
+/// rect, intersected with any clip it is already inside; the outer clip
+/// is restored after its subtree. Later draws cover earlier ones, so
+/// children go in paint order.
+pub fn draw_commands<'a>(
+    root: &'a VirtualNode,
+    layout: &HashMap<NodeId, Rect>,
+    target_width: u32,
+    target_height: u32,
+) -> Vec<DrawCommand<'a>> {
+    let mut commands = Vec::new();
+    let mut clips = Vec::new();
+    record(root, layout, (target_width, target_height), &mut clips, &mut commands);
+    commands
+}
+
+fn record<'a>(
+    node: &'a VirtualNode,
+    layout: &HashMap<NodeId, Rect>,
+    target: (u32, u32),
+    clips: &mut Vec<Rect>,
+    commands: &mut Vec<DrawCommand<'a>>,
+) {
+    commands.push(DrawCommand::Draw(node));
+    
+    let clip = match layout.get(&node.id) {
+        Some(rect) if overflow(node).clips() => Some(match clips.last() {
+            Some(outer) => rect.intersect(outer),
+            None => *rect,
+        }),
+        _ => None,
+    };
+    if let Some(clip) = clip {
+        clips.push(clip);
+        commands.push(DrawCommand::SetScissor(ScissorRect::from_rect(clip, target.0, target.1)));
+    }
+    
+    for i in paint_order(node.children.iter().map(z_index)) {
+        record(&node.children[i], layout, target, clips, commands);
+    }
+    
+    if clip.is_some() {
+        clips.pop();
+        let restored = match clips.last() {
+            Some(outer) => ScissorRect::from_rect(*outer, target.0, target.1),
+            None => ScissorRect::full(target.0, target.1),
+        };
+        commands.push(DrawCommand::SetScissor(restored));
+    }
+}
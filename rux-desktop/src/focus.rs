@@ -0,0 +1,167 @@
+use rux_core::virtual_tree::{NodeId, NodeType, PropValue, VirtualNode};
+use std::collections::HashMap;
+
+/// Elements that take focus without a `tabIndex`
+const NATURALLY_FOCUSABLE: &[&str] = &["button", "input", "select", "textarea"];
+
+/// A handler prop to run in response to an event, with the event's value:
+/// the key name for `onKeyDown`, the typed text for `onInput`. Run it
+/// inside `rux_runtime::dispatch_input` so the updates it causes render
+/// ahead of background work.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HandlerCall {
+    pub node_id: NodeId,
+    pub handler: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Handlers {
+    on_key_down: Option<String>,
+    on_input: Option<String>,
+}
+
+/// Which node has keyboard focus, and where Tab moves it. Follows the DOM
+/// rules: buttons, inputs, selects, textareas and links with an `href`
+/// are focusable, `tabIndex` overrides that, a negative `tabIndex` can be
+/// focused by clicking but is skipped by Tab, and positive ones come first.
+#[derive(Debug, Default)]
+pub struct FocusManager {
+    handlers: HashMap<NodeId, Handlers>,
+    tab_order: Vec<NodeId>,
+    focused: Option<NodeId>,
+}
+
+impl FocusManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Collects the focusable nodes of a newly mounted tree. Focus stays
+    /// where it was if that node is still focusable.
+    pub fn rebuild(&mut self, root: &VirtualNode) {
+        let mut found = Vec::new();
+        collect_focusable(root, &mut found);
+        
+        self.handlers.clear();
+        let mut ordered = Vec::new();
+        for (node, tab_index) in found {
+            let handler = |name: &str| match node.props.get(name) {
+                Some(PropValue::Function(handler) | PropValue::String(handler)) => Some(handler.clone()),
+                _ => None,
+            };
+            self.handlers.insert(node.id, Handlers {
+                on_key_down: handler("onKeyDown"),
+                on_input: handler("onInput"),
+            });
+            if tab_index >= 0 {
+                ordered.push((node.id, tab_index));
+            }
+        }
+        
+        // Positive indices first, ascending, then tree order; the sort is
+        // stable so ties keep tree order
+        ordered.sort_by_key(|&(_, tab_index)| if tab_index > 0 { tab_index } else { i32::MAX });
+        self.tab_order = ordered.into_iter().map(|(id, _)| id).collect();
+        
+        if self.focused.is_some_and(|id| !self.handlers.contains_key(&id)) {
+            self.focused = None;
+        }
+    }
+    
+    pub fn focused(&self) -> Option<NodeId> {
+        self.focused
+    }
+    
+    pub fn is_focusable(&self, node_id: NodeId) -> bool {
+        self.handlers.contains_key(&node_id)
+    }
+    
+    /// Focuses `node_id` if it can take focus, returning whether it did
+    pub fn focus(&mut self, node_id: NodeId) -> bool {
+        let focusable = self.is_focusable(node_id);
+        if focusable {
+            self.focused = Some(node_id);
+        }
+        focusable
+    }
+    
+    pub fn blur(&mut self) {
+        self.focused = None;
+    }
+    
+    /// A click focuses the clicked node, or clears focus if it isn't
+    /// focusable
+    pub fn click(&mut self, node_id: NodeId) {
+        if !self.focus(node_id) {
+            self.blur();
+        }
+    }
+    
+    /// Moves focus along the tab order, wrapping around at either end
+    pub fn focus_next(&mut self, backwards: bool) -> Option<NodeId> {
+        if self.tab_order.is_empty() {
+            return None;
+        }
+        let len = self.tab_order.len();
+        let position = self.focused.and_then(|id| self.tab_order.iter().position(|&n| n == id));
+        let next = match (position, backwards) {
+            (Some(i), false) => (i + 1) % len,
+            (Some(i), true) => (i + len - 1) % len,
+            (None, false) => 0,
+            (None, true) => len - 1,
+        };
+        self.focused = Some(self.tab_order[next]);
+        self.focused
+    }
+    
+    /// Handles a key press: Tab moves focus, anything else goes to the
+    /// focused node's `onKeyDown`
+    pub fn key_down(&mut self, key: &str, shift: bool) -> Option<HandlerCall> {
+        if key == "Tab" {
+            self.focus_next(shift);
+            return None;
+        }
+        self.dispatch(|handlers| handlers.on_key_down.as_ref(), key)
+    }
+    
+    /// Sends typed text to the focused node's `onInput`
+    pub fn input(&mut self, text: &str) -> Option<HandlerCall> {
+        self.dispatch(|handlers| handlers.on_input.as_ref(), text)
+    }
+    
+    fn dispatch(&self, handler: impl Fn(&Handlers) -> Option<&String>, value: &str) -> Option<HandlerCall> {
+        let node_id = self.focused?;
+        let handler = handler(self.handlers.get(&node_id)?)?;
+        Some(HandlerCall {
+            node_id,
+            handler: handler.clone(),
+            value: value.to_string(),
+        })
+    }
+}
+
+/// Focusable nodes in tree order, with their tab index
+fn collect_focusable<'a>(node: &'a VirtualNode, out: &mut Vec<(&'a VirtualNode, i32)>) {
+    if let Some(tab_index) = tab_index(node) {
+        out.push((node, tab_index));
+    }
+    for child in &node.children {
+        collect_focusable(child, out);
+    }
+}
+
+fn tab_index(node: &VirtualNode) -> Option<i32> {
+    let NodeType::Element(tag) = &node.node_type else {
+        return None;
+    };
+    if matches!(node.props.get("disabled"), Some(PropValue::Boolean(true))) {
+        return None;
+    }
+    match node.props.get("tabIndex").or_else(|| node.props.get("tabindex")) {
+        Some(PropValue::Number(n)) => Some(*n as i32),
+        _ if NATURALLY_FOCUSABLE.contains(&tag.as_str()) => Some(0),
+        _ if tag == "a" && node.props.contains_key("href") => Some(0),
+        _ => None,
+    }
+}
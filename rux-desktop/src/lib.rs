@@ -1,5 +1,11 @@
 // Desktop platform implementation for RUX
 
+pub mod clip;
+pub mod focus;
 pub mod renderer;
+pub mod text;
 
+pub use clip::{draw_commands, DrawCommand, ScissorRect};
+pub use focus::{FocusManager, HandlerCall};
 pub use renderer::DesktopRenderer;
+pub use text::FontMetrics;
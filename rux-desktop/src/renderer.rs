@@ -1,6 +1,11 @@
+use crate::clip::{draw_commands, DrawCommand};
+use crate::focus::{FocusManager, HandlerCall};
+use rux_core::layout::Rect;
 use rux_core::renderer::{Renderer, ElementId};
-use rux_core::virtual_tree::{VirtualNode, NodeId, Patch, NodeType, PropValue};
+use rux_core::virtual_tree::{VirtualNode, NodeId, Patch, NodeType};
 use wgpu::*;
+use winit::event::{ElementState, KeyEvent, WindowEvent};
+use winit::keyboard::Key;
 use winit::window::Window;
 use std::collections::HashMap;
 
@@ -13,6 +18,12 @@ pub struct DesktopRenderer {
     node_to_element: HashMap<NodeId, ElementId>,
     element_to_node: HashMap<ElementId, NodeId>,
     next_element_id: usize,
+    focus: FocusManager,
+    shift_held: bool,
+    /// The mounted tree, drawn by `render`
+    tree: Option<VirtualNode>,
+    /// Each node's rect from the last layout pass
+    layout: HashMap<NodeId, Rect>,
 }
 
 impl DesktopRenderer {
@@ -82,6 +93,10 @@ impl DesktopRenderer {
             node_to_element: HashMap::new(),
             element_to_node: HashMap::new(),
             next_element_id: 1,
+            focus: FocusManager::new(),
+            shift_held: false,
+            tree: None,
+            layout: HashMap::new(),
         })
     }
     
@@ -93,6 +108,53 @@ impl DesktopRenderer {
         }
     }
     
+    /// Node rects from a layout pass, which `render` clips `overflow`
+    /// nodes' children to
+    pub fn set_layout(&mut self, layout: HashMap<NodeId, Rect>) {
+        self.layout = layout;
+    }
+    
+    pub fn focused(&self) -> Option<NodeId> {
+        self.focus.focused()
+    }
+    
+    /// Focuses the node a click landed on. Hit testing is up to the caller.
+    pub fn click(&mut self, node_id: NodeId) {
+        self.focus.click(node_id);
+    }
+    
+    /// Routes keyboard input to the focused node, returning the handlers it
+    /// triggers. Tab and Shift+Tab move focus instead. winit 0.29 reports
+    /// typed text on the key event, which becomes an `onInput` call.
+    pub fn handle_window_event(&mut self, event: &WindowEvent) -> Vec<HandlerCall> {
+        match event {
+            WindowEvent::ModifiersChanged(modifiers) => {
+                self.shift_held = modifiers.state().shift_key();
+                Vec::new()
+            }
+            WindowEvent::KeyboardInput { event, .. } if event.state == ElementState::Pressed => {
+                self.handle_key(event)
+            }
+            _ => Vec::new(),
+        }
+    }
+    
+    fn handle_key(&mut self, event: &KeyEvent) -> Vec<HandlerCall> {
+        let key = match &event.logical_key {
+            Key::Named(named) => format!("{:?}", named),
+            Key::Character(text) => text.to_string(),
+            _ => return Vec::new(),
+        };
+        
+        let mut calls: Vec<HandlerCall> = self.focus.key_down(&key, self.shift_held).into_iter().collect();
+        if key != "Tab" {
+            if let Some(text) = &event.text {
+                calls.extend(self.focus.input(text));
+            }
+        }
+        calls
+    }
+    
     pub fn render(&mut self) -> Result<(), SurfaceError> {
         let output = self.surface.get_current_texture()?;
         let view = output
@@ -106,7 +168,7 @@ impl DesktopRenderer {
             });
         
         {
-            let _render_pass = encoder.begin_render_pass(&RenderPassDescriptor {
+            let mut render_pass = encoder.begin_render_pass(&RenderPassDescriptor {
                 label: Some("Render Pass"),
                 color_attachments: &[Some(RenderPassColorAttachment {
                     view: &view,
@@ -125,6 +187,17 @@ impl DesktopRenderer {
                 occlusion_query_set: None,
                 timestamp_writes: None,
             });
+            
+            if let Some(tree) = &self.tree {
+                for command in draw_commands(tree, &self.layout, self.config.width, self.config.height) {
+                    match command {
+                        DrawCommand::Draw(node) => self.render_virtual_node(node),
+                        DrawCommand::SetScissor(rect) => {
+                            render_pass.set_scissor_rect(rect.x, rect.y, rect.width, rect.height);
+                        }
+                    }
+                }
+            }
         }
         
         self.queue.submit(std::iter::once(encoder.finish()));
@@ -145,7 +218,7 @@ impl Renderer for DesktopRenderer {
         element_id
     }
     
-    fn update_element(&mut self, element_id: ElementId, patches: &[Patch]) {
+    fn update_element(&mut self, _element_id: ElementId, patches: &[Patch]) {
         // Apply patches to update GPU rendering
         // In a full implementation, this would update the GPU command buffer
         for patch in patches {
@@ -185,16 +258,19 @@ impl Renderer for DesktopRenderer {
         // Mount virtual tree to GPU
         // In a full implementation, this would create GPU resources and command buffers
         self.mount_recursive(root, node);
+        self.focus.rebuild(node);
+        self.tree = Some(node.clone());
     }
     
     fn unmount(&mut self, root: ElementId) {
         // Unmount virtual tree from GPU
         self.remove_element(root);
+        self.tree = None;
     }
 }
 
 impl DesktopRenderer {
-    fn mount_recursive(&mut self, parent: ElementId, node: &VirtualNode) {
+    fn mount_recursive(&mut self, _parent: ElementId, node: &VirtualNode) {
         // Create element for this node
         let element_id = self.create_element(node);
         
@@ -204,25 +280,29 @@ impl DesktopRenderer {
         }
     }
     
+    /// Draws one node; `draw_commands` decides the order and clipping
     fn render_virtual_node(&self, node: &VirtualNode) {
         // In a full implementation, this would:
         // 1. Create GPU resources (buffers, textures) based on node type
         // 2. Set up render pipeline
         // 3. Record draw commands
         match &node.node_type {
-            NodeType::Element(tag) => {
+            NodeType::Element(_tag) => {
                 // Render HTML element as GPU primitive
                 // Would use layout system to determine position/size
             }
-            NodeType::Text(text) => {
+            NodeType::Text(_text) => {
                 // Render text using font rendering
             }
             NodeType::Component(_) => {
                 // Render component (recursive)
             }
-            NodeType::Fragment => {
+            NodeType::Fragment | NodeType::Suspense { .. } => {
                 // Render fragment children
             }
+            NodeType::RawHtml(_html) => {
+                // No HTML engine on desktop; raw content isn't drawn
+            }
         }
     }
 }
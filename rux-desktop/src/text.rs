@@ -0,0 +1,33 @@
+use rux_core::layout::{Size, TextMeasurer};
+
+/// Measures text from a font's metrics, given as fractions of the font
+/// size. Every character advances by the same amount, which is exact for
+/// monospace fonts and close enough for proportional ones until glyph
+/// shaping is in place.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FontMetrics {
+    /// Horizontal advance of one character
+    pub advance: f32,
+    /// Distance between baselines
+    pub line_height: f32,
+}
+
+impl Default for FontMetrics {
+    fn default() -> Self {
+        Self {
+            advance: 0.6,
+            line_height: 1.2,
+        }
+    }
+}
+
+impl TextMeasurer for FontMetrics {
+    fn measure(&self, text: &str, font_size: f32) -> Size {
+        let longest = text.lines().map(|line| line.chars().count()).max().unwrap_or(0);
+        let lines = text.lines().count().max(1);
+        Size {
+            width: longest as f32 * self.advance * font_size,
+            height: lines as f32 * self.line_height * font_size,
+        }
+    }
+}
@@ -0,0 +1,64 @@
+#[cfg(test)]
+mod tests {
+    use rux_core::layout::Rect;
+    use rux_core::virtual_tree::VirtualNode;
+    use rux_desktop::{draw_commands, DrawCommand, ScissorRect};
+    use std::collections::HashMap;
+
+    fn rect(x: f32, y: f32, width: f32, height: f32) -> Rect {
+        Rect { x, y, width, height }
+    }
+
+    #[test]
+    fn test_overflow_hidden_scissors_children_to_node_rect() {
+        let inner = VirtualNode::element("p").build();
+        let panel = VirtualNode::element("div").prop("overflow", "hidden").child(inner.clone()).build();
+        let root = VirtualNode::element("main").child(panel.clone()).build();
+        let inner = &root.children[0].children[0];
+        let layout = HashMap::from([
+            (root.id, rect(0.0, 0.0, 800.0, 600.0)),
+            (panel.id, rect(10.0, 20.0, 100.0, 50.0)),
+            (inner.id, rect(10.0, 20.0, 300.0, 300.0)),
+        ]);
+
+        let commands = draw_commands(&root, &layout, 800, 600);
+        assert_eq!(
+            commands,
+            vec![
+                DrawCommand::Draw(&root),
+                DrawCommand::Draw(&root.children[0]),
+                DrawCommand::SetScissor(ScissorRect { x: 10, y: 20, width: 100, height: 50 }),
+                DrawCommand::Draw(inner),
+                DrawCommand::SetScissor(ScissorRect::full(800, 600)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_nested_clips_intersect_and_stay_on_target() {
+        let inner = VirtualNode::element("div").prop("overflow", "scroll").build();
+        let outer = VirtualNode::element("div").prop("overflow", "hidden").child(inner).build();
+        let inner = &outer.children[0];
+        let layout = HashMap::from([
+            (outer.id, rect(-10.0, 0.0, 100.0, 100.0)),
+            (inner.id, rect(50.0, 50.0, 100.0, 100.0)),
+        ]);
+
+        let scissors: Vec<ScissorRect> = draw_commands(&outer, &layout, 200, 200)
+            .into_iter()
+            .filter_map(|command| match command {
+                DrawCommand::SetScissor(rect) => Some(rect),
+                DrawCommand::Draw(_) => None,
+            })
+            .collect();
+        assert_eq!(
+            scissors,
+            vec![
+                ScissorRect { x: 0, y: 0, width: 90, height: 100 },
+                ScissorRect { x: 50, y: 50, width: 40, height: 50 },
+                ScissorRect { x: 0, y: 0, width: 90, height: 100 },
+                ScissorRect::full(200, 200),
+            ]
+        );
+    }
+}
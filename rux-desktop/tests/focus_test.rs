@@ -0,0 +1,68 @@
+#[cfg(test)]
+mod tests {
+    use rux_core::virtual_tree::{PropValue, VirtualNode};
+    use rux_desktop::{FocusManager, HandlerCall};
+
+    #[test]
+    fn test_tab_cycles_focus() {
+        let name = VirtualNode::element("input").prop("onKeyDown", PropValue::Function("name_key".to_string())).build();
+        let skipped = VirtualNode::element("div").prop("tabIndex", -1.0).build();
+        let first = VirtualNode::element("span").prop("tabIndex", 1.0).build();
+        let save = VirtualNode::element("button").build();
+        let disabled = VirtualNode::element("button").prop("disabled", true).build();
+        let root = VirtualNode::element("form")
+            .child(name.clone())
+            .child(VirtualNode::element("p").child(VirtualNode::text("Name")))
+            .child(skipped.clone())
+            .child(first.clone())
+            .child(save.clone())
+            .child(disabled.clone())
+            .build();
+
+        let mut focus = FocusManager::new();
+        focus.rebuild(&root);
+
+        // Positive tabIndex first, then tree order, wrapping around
+        let order: Vec<_> = (0..4).map(|_| focus.focus_next(false).unwrap()).collect();
+        assert_eq!(order, vec![first.id, name.id, save.id, first.id]);
+        assert_eq!(focus.focus_next(true), Some(save.id));
+
+        assert!(focus.key_down("Tab", true).is_none());
+        assert_eq!(focus.focused(), Some(name.id));
+
+        // Clicking focuses, even outside the tab order
+        focus.click(skipped.id);
+        assert_eq!(focus.focused(), Some(skipped.id));
+        focus.click(disabled.id);
+        assert_eq!(focus.focused(), None);
+    }
+
+    #[test]
+    fn test_keys_go_to_focused_node() {
+        let field = VirtualNode::element("input")
+            .prop("onKeyDown", PropValue::Function("on_key".to_string()))
+            .prop("onInput", PropValue::Function("on_text".to_string()))
+            .build();
+        let button = VirtualNode::element("button").build();
+        let root = VirtualNode::element("div").child(field.clone()).child(button.clone()).build();
+
+        let mut focus = FocusManager::new();
+        focus.rebuild(&root);
+        assert!(focus.key_down("Enter", false).is_none());
+
+        focus.click(field.id);
+        assert_eq!(
+            focus.key_down("Enter", false),
+            Some(HandlerCall { node_id: field.id, handler: "on_key".to_string(), value: "Enter".to_string() })
+        );
+        assert_eq!(focus.input("a").map(|call| call.handler), Some("on_text".to_string()));
+
+        // No handler on the button
+        focus.click(button.id);
+        assert!(focus.key_down("Enter", false).is_none());
+
+        // Focus survives a remount that keeps the node
+        focus.rebuild(&root);
+        assert_eq!(focus.focused(), Some(button.id));
+    }
+}
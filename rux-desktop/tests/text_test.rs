@@ -0,0 +1,14 @@
+#[cfg(test)]
+mod tests {
+    use rux_core::layout::{Size, TextMeasurer};
+    use rux_desktop::FontMetrics;
+
+    #[test]
+    fn test_font_metrics_measure_longest_line() {
+        let metrics = FontMetrics { advance: 0.5, line_height: 1.5 };
+
+        assert_eq!(metrics.measure("Hello", 10.0), Size { width: 25.0, height: 15.0 });
+        assert_eq!(metrics.measure("Hi\nthere", 10.0), Size { width: 25.0, height: 30.0 });
+        assert_eq!(metrics.measure("", 10.0), Size { width: 0.0, height: 15.0 });
+    }
+}
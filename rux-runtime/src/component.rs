@@ -134,12 +134,12 @@ pub fn use_state<T: 'static>(initial: T) -> (T, Box<dyn Fn(T)>) {
     (initial, setter)
 }
 
-pub fn useEffect(effect: impl Fn() + 'static, deps: Vec<Box<dyn Any>>) {
+pub fn use_effect(effect: impl Fn() + 'static, _deps: Vec<Box<dyn Any>>) {
     // Simplified - would need component context
     effect();
 }
 
-pub fn use_memo<T: 'static>(compute: impl Fn() -> T + 'static, deps: Vec<Box<dyn Any>>) -> T {
+pub fn use_memo<T: 'static>(compute: impl Fn() -> T + 'static, _deps: Vec<Box<dyn Any>>) -> T {
     // Simplified memoization
     compute()
 }
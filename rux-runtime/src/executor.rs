@@ -1,58 +1,247 @@
 use rux_core::virtual_tree::{VirtualNode, NodeType, PropValue};
-use rux_compiler::ast::{AST, Component, JSXElement, JSXChild, JSXProp, JSXPropValue, Expr, Literal};
+use rux_compiler::ast::{
+    BinaryOp, Block, Component, Expr, JSXChild, JSXElement, JSXProp, JSXPropValue, Literal, Stmt, UnaryOp,
+};
 use std::collections::HashMap;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ExecutorError {
+    #[error("Cannot render {0} as a JSX child")]
+    NotRenderable(&'static str),
+    #[error("Unknown component: {0}")]
+    UnknownComponent(String),
+    #[error("Component {0} renders itself")]
+    RecursiveComponent(String),
+    #[error("Cannot iterate over {0}")]
+    NotIterable(&'static str),
+}
+
+pub type Result<T> = std::result::Result<T, ExecutorError>;
 
 /// Converts RUX AST to VirtualNode for rendering
-pub struct ComponentExecutor;
+pub struct ComponentExecutor {
+    /// Values of the props in scope while rendering a component
+    scope: HashMap<String, Literal>,
+    /// What `{children}` renders inside the component
+    children: Vec<VirtualNode>,
+}
 
 impl ComponentExecutor {
     pub fn new() -> Self {
-        Self
+        Self {
+            scope: HashMap::new(),
+            children: Vec::new(),
+        }
     }
 
     /// Execute a component from AST and return a VirtualNode
-    pub fn execute_component(&self, component: &Component) -> VirtualNode {
+    pub fn execute_component(&self, component: &Component) -> Result<VirtualNode> {
+        self.execute_component_with_props(component, &HashMap::new())
+    }
+
+    /// Execute a component with the props given at its usage. Omitted props
+    /// fall back to their declared defaults.
+    pub fn execute_component_with_props(
+        &self,
+        component: &Component,
+        props: &HashMap<String, PropValue>,
+    ) -> Result<VirtualNode> {
+        self.execute_component_with_children(component, props, &[])
+    }
+
+    /// Execute a component with props and the JSX children passed to it,
+    /// which its body can place with `{children}`
+    pub fn execute_component_with_children(
+        &self,
+        component: &Component,
+        props: &HashMap<String, PropValue>,
+        children: &[VirtualNode],
+    ) -> Result<VirtualNode> {
+        let mut scope = HashMap::new();
+        for param in &component.props {
+            let value = match props.get(&param.name) {
+                Some(value) => prop_to_literal(value),
+                None => param.default.as_ref().and_then(|default| self.evaluate(default)),
+            };
+            if let Some(value) = value {
+                scope.insert(param.name.clone(), value);
+            }
+        }
+
         // Convert component body (JSX expression) to VirtualNode
-        self.expr_to_virtual_node(&component.body, 0)
+        let executor = ComponentExecutor {
+            scope,
+            children: children.to_vec(),
+        };
+        executor.expr_to_virtual_node(&component.body, 0)
     }
 
-    /// Convert an expression to a VirtualNode
-    fn expr_to_virtual_node(&self, expr: &Expr, node_id: usize) -> VirtualNode {
-        match expr {
-            Expr::JSXElement(jsx, _) => self.jsx_to_virtual_node(jsx, node_id),
-            Expr::Literal(lit, _) => {
-                VirtualNode {
-                    id: rux_core::virtual_tree::NodeId(node_id),
-                    node_type: NodeType::Text(self.literal_to_string(lit)),
-                    props: HashMap::new(),
-                    children: vec![],
-                    key: None,
-                }
+    /// Convert an expression to a VirtualNode. Non-element values are
+    /// rendered as text using their display form.
+    fn expr_to_virtual_node(&self, expr: &Expr, node_id: usize) -> Result<VirtualNode> {
+        let text = match expr {
+            Expr::JSXElement(jsx, _) => return self.jsx_to_virtual_node(jsx, node_id),
+            Expr::Block(block, _) if block.statements.iter().any(|s| matches!(s, Stmt::For { .. })) => {
+                return self.block_to_virtual_node(block, node_id);
             }
-            _ => {
-                // For other expressions, create a placeholder
-                VirtualNode {
+            Expr::Block(block, _) => match block.statements.last() {
+                Some(Stmt::Tail(tail)) => return self.expr_to_virtual_node(tail, node_id),
+                _ => "TODO".to_string(),
+            },
+            Expr::Tuple(..) => return Err(ExecutorError::NotRenderable("a tuple")),
+            Expr::Array(..) => return Err(ExecutorError::NotRenderable("an array")),
+            Expr::Struct { .. } => return Err(ExecutorError::NotRenderable("a struct")),
+            Expr::Lambda { .. } => return Err(ExecutorError::NotRenderable("a closure")),
+            Expr::Variable(name, _) if name == "children" && !self.scope.contains_key(name) => {
+                return Ok(VirtualNode {
                     id: rux_core::virtual_tree::NodeId(node_id),
-                    node_type: NodeType::Text("TODO".to_string()),
+                    node_type: NodeType::Fragment,
                     props: HashMap::new(),
-                    children: vec![],
+                    children: self.children.clone(),
                     key: None,
+                });
+            }
+            // Expressions that need runtime state can't be evaluated yet
+            _ => match self.evaluate(expr) {
+                Some(lit) => self.literal_to_string(&lit),
+                None => "TODO".to_string(),
+            },
+        };
+
+        Ok(VirtualNode {
+            id: rux_core::virtual_tree::NodeId(node_id),
+            node_type: NodeType::Text(text),
+            props: HashMap::new(),
+            children: vec![],
+            key: None,
+        })
+    }
+
+    /// Render a block containing `for` loops as a fragment. Each loop
+    /// renders its body once per item, and the tail expression, if any,
+    /// comes after the loops' output.
+    fn block_to_virtual_node(&self, block: &Block, node_id: usize) -> Result<VirtualNode> {
+        let mut nodes = Vec::new();
+        let mut current_id = node_id + 1;
+
+        for stmt in &block.statements {
+            match stmt {
+                Stmt::For { var, iter, body, .. } => {
+                    for item in self.iterate(iter)? {
+                        let mut scope = self.scope.clone();
+                        scope.insert(var.clone(), item);
+                        let executor = ComponentExecutor {
+                            scope,
+                            children: self.children.clone(),
+                        };
+                        let node = executor.stmt_to_virtual_node(body, current_id)?;
+                        current_id += self.count_nodes(&node);
+                        nodes.push(node);
+                    }
                 }
+                Stmt::Tail(tail) => {
+                    let node = self.expr_to_virtual_node(tail, current_id)?;
+                    current_id += self.count_nodes(&node);
+                    nodes.push(node);
+                }
+                // Other statements have nothing to render
+                _ => {}
+            }
+        }
+
+        Ok(VirtualNode {
+            id: rux_core::virtual_tree::NodeId(node_id),
+            node_type: NodeType::Fragment,
+            props: HashMap::new(),
+            children: nodes,
+            key: None,
+        })
+    }
+
+    fn stmt_to_virtual_node(&self, stmt: &Stmt, node_id: usize) -> Result<VirtualNode> {
+        match stmt {
+            Stmt::Block(block) => {
+                let span = block.span;
+                self.expr_to_virtual_node(&Expr::Block(block.clone(), span), node_id)
+            }
+            Stmt::Expr(expr) | Stmt::Tail(expr) => self.expr_to_virtual_node(expr, node_id),
+            _ => Err(ExecutorError::NotRenderable("a statement")),
+        }
+    }
+
+    /// The items a `for` loop visits. Only ranges with constant bounds can
+    /// be iterated so far.
+    fn iterate(&self, iter: &Expr) -> Result<Vec<Literal>> {
+        let Expr::Range { start, end, inclusive, .. } = iter else {
+            return Err(ExecutorError::NotIterable("a runtime value"));
+        };
+        let (Some(Literal::Number(start)), Some(Literal::Number(end))) = (self.evaluate(start), self.evaluate(end)) else {
+            return Err(ExecutorError::NotIterable("a range with non-constant bounds"));
+        };
+
+        let (start, end) = (start as i64, end as i64);
+        let items: Vec<Literal> = if *inclusive {
+            (start..=end).map(|i| Literal::Number(i as f64)).collect()
+        } else {
+            (start..end).map(|i| Literal::Number(i as f64)).collect()
+        };
+        Ok(items)
+    }
+
+    /// Evaluate a constant expression to a literal value
+    fn evaluate(&self, expr: &Expr) -> Option<Literal> {
+        match expr {
+            // The executor does all arithmetic in f64
+            Expr::Literal(Literal::Integer(n), _) => Some(Literal::Number(*n as f64)),
+            Expr::Literal(lit, _) => Some(lit.clone()),
+            Expr::Variable(name, _) => self.scope.get(name).cloned(),
+            Expr::Binary { left, op, right, .. } => {
+                let left = self.evaluate(left)?;
+                let right = self.evaluate(right)?;
+                self.evaluate_binary(&left, op, &right)
             }
+            Expr::Unary { op, expr, .. } => match (op, self.evaluate(expr)?) {
+                (UnaryOp::Neg, Literal::Number(n)) => Some(Literal::Number(-n)),
+                (UnaryOp::Not, Literal::Boolean(b)) => Some(Literal::Boolean(!b)),
+                _ => None,
+            },
+            _ => None,
         }
     }
 
+    fn evaluate_binary(&self, left: &Literal, op: &BinaryOp, right: &Literal) -> Option<Literal> {
+        let result = match (left, op, right) {
+            (Literal::Number(l), BinaryOp::Add, Literal::Number(r)) => Literal::Number(l + r),
+            (Literal::Number(l), BinaryOp::Sub, Literal::Number(r)) => Literal::Number(l - r),
+            (Literal::Number(l), BinaryOp::Mul, Literal::Number(r)) => Literal::Number(l * r),
+            (Literal::Number(l), BinaryOp::Div, Literal::Number(r)) if *r != 0.0 => Literal::Number(l / r),
+            (Literal::Number(l), BinaryOp::Rem, Literal::Number(r)) if *r != 0.0 => Literal::Number(l % r),
+            (Literal::Number(l), BinaryOp::Eq, Literal::Number(r)) => Literal::Boolean(l == r),
+            (Literal::Number(l), BinaryOp::Ne, Literal::Number(r)) => Literal::Boolean(l != r),
+            (Literal::Number(l), BinaryOp::Lt, Literal::Number(r)) => Literal::Boolean(l < r),
+            (Literal::Number(l), BinaryOp::Le, Literal::Number(r)) => Literal::Boolean(l <= r),
+            (Literal::Number(l), BinaryOp::Gt, Literal::Number(r)) => Literal::Boolean(l > r),
+            (Literal::Number(l), BinaryOp::Ge, Literal::Number(r)) => Literal::Boolean(l >= r),
+            (Literal::Boolean(l), BinaryOp::And, Literal::Boolean(r)) => Literal::Boolean(*l && *r),
+            (Literal::Boolean(l), BinaryOp::Or, Literal::Boolean(r)) => Literal::Boolean(*l || *r),
+            (Literal::String(l), BinaryOp::Add, Literal::String(r)) => Literal::String(format!("{}{}", l, r)),
+            _ => return None,
+        };
+        Some(result)
+    }
+
     /// Convert JSX element to VirtualNode
-    fn jsx_to_virtual_node(&self, jsx: &JSXElement, node_id: usize) -> VirtualNode {
+    fn jsx_to_virtual_node(&self, jsx: &JSXElement, node_id: usize) -> Result<VirtualNode> {
         match jsx {
             JSXElement::SelfClosing { tag, props, .. } => {
-                VirtualNode {
+                Ok(VirtualNode {
                     id: rux_core::virtual_tree::NodeId(node_id),
-                    node_type: NodeType::Element(tag.clone()),
+                    node_type: self.tag_node_type(tag),
                     props: self.jsx_props_to_props(props),
                     children: vec![],
                     key: None,
-                }
+                })
             }
             JSXElement::WithChildren { tag, props, children, .. } => {
                 let mut child_nodes = Vec::new();
@@ -71,29 +260,38 @@ impl ComponentExecutor {
                             current_id += 1;
                         }
                         JSXChild::Element(jsx) => {
-                            let child_node = self.jsx_to_virtual_node(jsx, current_id);
+                            let child_node = self.jsx_to_virtual_node(jsx, current_id)?;
                             current_id += self.count_nodes(&child_node);
                             child_nodes.push(child_node);
                         }
                         JSXChild::Expr(expr) => {
-                            let child_node = self.expr_to_virtual_node(expr, current_id);
+                            let child_node = self.expr_to_virtual_node(expr, current_id)?;
                             current_id += self.count_nodes(&child_node);
                             child_nodes.push(child_node);
                         }
                     }
                 }
 
-                VirtualNode {
+                Ok(VirtualNode {
                     id: rux_core::virtual_tree::NodeId(node_id),
-                    node_type: NodeType::Element(tag.clone()),
+                    node_type: self.tag_node_type(tag),
                     props: self.jsx_props_to_props(props),
                     children: child_nodes,
                     key: None,
-                }
+                })
             }
         }
     }
 
+    /// PascalCase tags refer to components, everything else is an element
+    fn tag_node_type(&self, tag: &str) -> NodeType {
+        if tag.starts_with(|c: char| c.is_ascii_uppercase()) {
+            NodeType::Component(tag.to_string())
+        } else {
+            NodeType::Element(tag.to_string())
+        }
+    }
+
     /// Convert JSX props to PropValue map
     fn jsx_props_to_props(&self, props: &[JSXProp]) -> HashMap<String, PropValue> {
         let mut result = HashMap::new();
@@ -101,16 +299,19 @@ impl ComponentExecutor {
             let value = match &prop.value {
                 JSXPropValue::Literal(lit) => match lit {
                     Literal::String(s) => PropValue::String(s.clone()),
+                    Literal::Integer(n) => PropValue::Number(*n as f64),
                     Literal::Number(n) => PropValue::Number(*n),
                     Literal::Boolean(b) => PropValue::Boolean(*b),
                     _ => PropValue::String("".to_string()),
                 },
                 JSXPropValue::Bool(b) => PropValue::Boolean(*b),
-                JSXPropValue::Expr(_) => {
-                    // For expressions, we'd need to evaluate them
-                    // For now, use a placeholder
-                    PropValue::String("TODO".to_string())
-                }
+                JSXPropValue::Expr(expr) => match self.evaluate(expr) {
+                    Some(Literal::String(s)) => PropValue::String(s),
+                    Some(Literal::Number(n)) => PropValue::Number(n),
+                    Some(Literal::Boolean(b)) => PropValue::Boolean(b),
+                    // Expressions that need runtime state can't be evaluated yet
+                    _ => PropValue::String("TODO".to_string()),
+                },
             };
             result.insert(prop.name.clone(), value);
         }
@@ -121,6 +322,7 @@ impl ComponentExecutor {
     fn literal_to_string(&self, lit: &Literal) -> String {
         match lit {
             Literal::String(s) => s.clone(),
+            Literal::Integer(n) => n.to_string(),
             Literal::Number(n) => n.to_string(),
             Literal::Boolean(b) => b.to_string(),
             Literal::Char(c) => c.to_string(),
@@ -134,6 +336,16 @@ impl ComponentExecutor {
     }
 }
 
+/// Scalar props as literals; other values can't appear in expressions yet
+fn prop_to_literal(value: &PropValue) -> Option<Literal> {
+    match value {
+        PropValue::String(s) => Some(Literal::String(s.clone())),
+        PropValue::Number(n) => Some(Literal::Number(*n)),
+        PropValue::Boolean(b) => Some(Literal::Boolean(*b)),
+        PropValue::Function(_) | PropValue::Array(_) => None,
+    }
+}
+
 impl Default for ComponentExecutor {
     fn default() -> Self {
         Self::new()
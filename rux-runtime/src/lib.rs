@@ -2,8 +2,18 @@
 
 pub mod component;
 pub mod executor;
+pub mod list;
+pub mod registry;
+pub mod resource;
+pub mod updates;
 
 pub use component::{
     ComponentInstance, ComponentId, ComponentState, Hook, StateHook, EffectHook,
-    use_state, useEffect, use_memo, use_callback,
+    use_state, use_effect, use_memo, use_callback,
+};
+pub use list::{keyed_list, KeyedList};
+pub use registry::ComponentRegistry;
+pub use resource::{create_resource, create_suspense_resource, poll_resources};
+pub use updates::{
+    dispatch_input, pending_render, request_render, request_update, update_priority, with_update_priority,
 };
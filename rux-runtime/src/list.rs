@@ -0,0 +1,105 @@
+use rux_core::signals::{create_effect, Effect, Signal};
+use rux_core::virtual_tree::{apply_patches, diff, Patch, VirtualNode};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// Children rendered from a signal of items, one node per item keyed by
+/// the item's key. When the items change, only the items that were added,
+/// removed, moved or changed produce patches: an unchanged item keeps the
+/// node it was rendered to, and the keys let `diff` match nodes across
+/// reorders.
+pub struct KeyedList<T> {
+    state: Rc<RefCell<ListState<T>>>,
+    effect: Effect,
+}
+
+struct ListState<T> {
+    /// Fragment with one child per item. Patches are applied to it rather
+    /// than replacing it, so its ids stay those of the mounted nodes.
+    root: VirtualNode,
+    /// Each item with its key, in the same order as `root`'s children
+    items: Vec<(String, T)>,
+    /// Patches made since the last `take_patches`
+    patches: Vec<Patch>,
+    mounted: bool,
+}
+
+/// Renders each item in `items` with `render`, keeping the rendered
+/// children up to date as the signal changes. `key` must identify an item
+/// across changes, e.g. a database id rather than its position.
+pub fn keyed_list<T, K>(
+    items: &Signal<Vec<T>>,
+    key: impl Fn(&T) -> K + 'static,
+    render: impl Fn(&T) -> VirtualNode + 'static,
+) -> KeyedList<T>
+where
+    T: Clone + PartialEq + 'static,
+    K: ToString,
+{
+    let state = Rc::new(RefCell::new(ListState {
+        root: VirtualNode::fragment().build(),
+        items: Vec::new(),
+        patches: Vec::new(),
+        mounted: false,
+    }));
+
+    let items = items.clone();
+    let list_state = state.clone();
+    let effect = create_effect(move || {
+        let items: Vec<(String, T)> = items.get().into_iter().map(|item| (key(&item).to_string(), item)).collect();
+        let mut state = list_state.borrow_mut();
+
+        // With duplicate keys only the first item is reused
+        let mut previous: HashMap<&str, usize> = HashMap::new();
+        for (i, (key, _)) in state.items.iter().enumerate() {
+            previous.entry(key.as_str()).or_insert(i);
+        }
+        let children: Vec<VirtualNode> = items
+            .iter()
+            .map(|(key, item)| match previous.get(key.as_str()) {
+                Some(&i) if state.items[i].1 == *item => state.root.children[i].clone(),
+                _ => {
+                    let mut node = render(item);
+                    node.key = Some(key.clone());
+                    node
+                }
+            })
+            .collect();
+
+        if state.mounted {
+            let next = VirtualNode {
+                children,
+                ..state.root.clone()
+            };
+            let patches = diff(&state.root, &next);
+            apply_patches(&patches, &mut state.root);
+            state.patches.extend(patches);
+        } else {
+            state.root.children = children;
+            state.mounted = true;
+        }
+        state.items = items;
+    });
+
+    KeyedList { state, effect }
+}
+
+impl<T> KeyedList<T> {
+    /// The fragment holding the rendered items, to mount under a parent
+    pub fn node(&self) -> VirtualNode {
+        self.state.borrow().root.clone()
+    }
+
+    /// Patches the item changes since the last call made to `node()`'s
+    /// tree, oldest first
+    pub fn take_patches(&self) -> Vec<Patch> {
+        std::mem::take(&mut self.state.borrow_mut().patches)
+    }
+}
+
+impl<T> Drop for KeyedList<T> {
+    fn drop(&mut self) {
+        self.effect.dispose();
+    }
+}
@@ -0,0 +1,163 @@
+use crate::executor::{ComponentExecutor, ExecutorError, Result};
+use rux_compiler::ast::Component;
+use rux_core::signals::track_pending;
+use rux_core::virtual_tree::{assign_stable_ids, NodeId, NodeType, PropValue, VirtualNode};
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+type RenderFn = Rc<dyn Fn(&HashMap<String, PropValue>, &[VirtualNode]) -> Result<VirtualNode>>;
+
+/// Components by name, used to expand `NodeType::Component` nodes into the
+/// element trees they render
+#[derive(Clone, Default)]
+pub struct ComponentRegistry {
+    components: HashMap<String, RenderFn>,
+}
+
+impl ComponentRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a render function taking the node's props
+    pub fn register(
+        &mut self,
+        name: impl Into<String>,
+        render: impl Fn(&HashMap<String, PropValue>) -> Result<VirtualNode> + 'static,
+    ) {
+        self.register_with_children(name, move |props, _children| render(props));
+    }
+
+    /// Registers a render function taking the node's props and the
+    /// already expanded children it was given
+    pub fn register_with_children(
+        &mut self,
+        name: impl Into<String>,
+        render: impl Fn(&HashMap<String, PropValue>, &[VirtualNode]) -> Result<VirtualNode> + 'static,
+    ) {
+        self.components.insert(name.into(), Rc::new(render));
+    }
+
+    /// Registers a component from its AST, rendered by the executor
+    pub fn register_component(&mut self, component: Component) {
+        let name = component.name.clone();
+        let executor = ComponentExecutor::new();
+        self.register_with_children(name, move |props, children| {
+            executor.execute_component_with_children(&component, props, children)
+        });
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.components.contains_key(name)
+    }
+
+    /// Replaces every component node in the tree with what it renders,
+    /// recursively; a component node's key is kept on its output. Each
+    /// component numbers its nodes on its own, so the result is renumbered
+    /// with `assign_stable_ids`: ids stay unique, and expanding the same
+    /// tree again gives the same ids, so it diffs cleanly against the
+    /// mounted one.
+    pub fn expand(&self, node: &VirtualNode) -> Result<VirtualNode> {
+        let mut expanded = self.expand_with_stack(node, &mut Vec::new(), false)?;
+        assign_stable_ids(&mut expanded);
+        Ok(expanded)
+    }
+
+    /// Like `expand`, for a subtree arriving in a patch against a mounted
+    /// tree. Renumbering it by position would collide with the mounted
+    /// ids, so the nodes of `node` keep theirs, a component's output takes
+    /// the component node's id, and the rest of what components render
+    /// gets fresh ids from `NodeId::next`.
+    pub fn expand_subtree(&self, node: &VirtualNode) -> Result<VirtualNode> {
+        self.expand_with_stack(node, &mut Vec::new(), true)
+    }
+
+    fn expand_with_stack(&self, node: &VirtualNode, stack: &mut Vec<String>, fresh_ids: bool) -> Result<VirtualNode> {
+        if let NodeType::Suspense { fallback } = &node.node_type {
+            return self.expand_suspense(node, fallback, stack, fresh_ids);
+        }
+        let NodeType::Component(name) = &node.node_type else {
+            let children = node
+                .children
+                .iter()
+                .map(|child| self.expand_with_stack(child, stack, fresh_ids))
+                .collect::<Result<Vec<_>>>()?;
+            return Ok(VirtualNode { children, ..node.clone() });
+        };
+
+        if stack.contains(name) {
+            return Err(ExecutorError::RecursiveComponent(name.clone()));
+        }
+        let render = self
+            .components
+            .get(name)
+            .ok_or_else(|| ExecutorError::UnknownComponent(name.clone()))?;
+
+        // Children belong to the caller, so they're expanded in its context
+        // before the component places them
+        let children = node
+            .children
+            .iter()
+            .map(|child| self.expand_with_stack(child, stack, fresh_ids))
+            .collect::<Result<Vec<_>>>()?;
+        let mut rendered = render(&node.props, &children)?;
+        if node.key.is_some() {
+            rendered.key = node.key.clone();
+        }
+        if fresh_ids {
+            let mut placed = HashSet::new();
+            for child in &children {
+                collect_ids(child, &mut placed);
+            }
+            renumber(&mut rendered, &placed);
+            rendered.id = node.id;
+        }
+
+        stack.push(name.clone());
+        let expanded = self.expand_with_stack(&rendered, stack, fresh_ids);
+        stack.pop();
+        expanded
+    }
+
+    /// Expands a suspense boundary's children, or its fallback instead
+    /// when rendering them read a pending resource
+    fn expand_suspense(
+        &self,
+        node: &VirtualNode,
+        fallback: &VirtualNode,
+        stack: &mut Vec<String>,
+        fresh_ids: bool,
+    ) -> Result<VirtualNode> {
+        let (children, pending) = track_pending(|| {
+            node.children
+                .iter()
+                .map(|child| self.expand_with_stack(child, stack, fresh_ids))
+                .collect::<Result<Vec<_>>>()
+        });
+        let children = if pending {
+            vec![self.expand_with_stack(fallback, stack, fresh_ids)?]
+        } else {
+            children?
+        };
+        Ok(VirtualNode { children, ..node.clone() })
+    }
+}
+
+fn collect_ids(node: &VirtualNode, ids: &mut HashSet<NodeId>) {
+    ids.insert(node.id);
+    for child in &node.children {
+        collect_ids(child, ids);
+    }
+}
+
+/// Gives every node outside `kept` a fresh id. Children a component was
+/// given are in `kept`, so they keep the ids their caller gave them.
+fn renumber(node: &mut VirtualNode, kept: &HashSet<NodeId>) {
+    if kept.contains(&node.id) {
+        return;
+    }
+    node.id = NodeId::next();
+    for child in &mut node.children {
+        renumber(child, kept);
+    }
+}
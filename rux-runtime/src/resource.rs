@@ -0,0 +1,137 @@
+use crate::updates::with_update_priority;
+use parking_lot::Mutex;
+use rux_core::scheduler::{schedule_work, Priority};
+use rux_core::signals::{Resource, Signal};
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::mpsc::{self, TryRecvError};
+use std::sync::Arc;
+use std::task::{Context, Wake, Waker};
+
+type BoxFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// Checks whether a resource's future has finished, setting its value if
+/// so. Returns true once there is nothing left to wait for.
+type Settle = Box<dyn FnMut() -> bool>;
+
+thread_local! {
+    /// Resources created on this thread whose value hasn't been set yet
+    static UNSETTLED: RefCell<Vec<Settle>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Signal holding the output of `fetcher`: `None` until the future
+/// completes, then `Some(value)`. The future is polled by fibers on the
+/// global scheduler, so it runs during `flush_scheduled_work`. Signals
+/// aren't thread-safe, so the value is handed back and set by
+/// `poll_resources` on the thread that created the resource; effects
+/// reading the signal re-run then.
+///
+/// Reading the signal doesn't suspend anything, so a `Suspense` boundary
+/// around its readers renders them with `None`. Use
+/// `create_suspense_resource` to have the boundary show its fallback.
+pub fn create_resource<T, F>(fetcher: F) -> Signal<Option<T>>
+where
+    T: Clone + Send + 'static,
+    F: Future<Output = T> + Send + 'static,
+{
+    let signal = Signal::new(None);
+    let target = signal.clone();
+    fetch(fetcher, move |value| target.set(Some(value)));
+    signal
+}
+
+/// Like `create_resource`, but returns a `Resource`: reading it before the
+/// future completes counts as pending, which is what `Suspense`
+/// boundaries observe to show their fallback. It resolves in
+/// `poll_resources` the same way.
+pub fn create_suspense_resource<T, F>(fetcher: F) -> Resource<T>
+where
+    T: Clone + Send + 'static,
+    F: Future<Output = T> + Send + 'static,
+{
+    let resource = Resource::new();
+    let target = resource.clone();
+    fetch(fetcher, move |value| target.resolve(value));
+    resource
+}
+
+/// Spawns `fetcher` and has `poll_resources` pass its output to `deliver`
+/// once it completes
+fn fetch<T, F>(fetcher: F, deliver: impl FnOnce(T) + 'static)
+where
+    T: Send + 'static,
+    F: Future<Output = T> + Send + 'static,
+{
+    let (sender, receiver) = mpsc::channel();
+    spawn(Box::pin(async move {
+        let _ = sender.send(fetcher.await);
+    }));
+
+    let mut deliver = Some(deliver);
+    let settle = move || match receiver.try_recv() {
+        Ok(value) => {
+            if let Some(deliver) = deliver.take() {
+                deliver(value);
+            }
+            true
+        }
+        Err(TryRecvError::Empty) => false,
+        // The future was dropped without finishing
+        Err(TryRecvError::Disconnected) => true,
+    };
+    UNSETTLED.with(|unsettled| unsettled.borrow_mut().push(Box::new(settle)));
+}
+
+/// Sets the values of this thread's resources whose futures have
+/// completed, returning how many were set. Call it after running
+/// scheduled work, e.g. once per frame. Updates the completions request
+/// through `request_update` render at `Priority::Normal`.
+pub fn poll_resources() -> usize {
+    with_update_priority(Priority::Normal, settle_resources)
+}
+
+fn settle_resources() -> usize {
+    // Taken out first: setting a signal runs effects, which may create
+    // resources of their own
+    let pending = UNSETTLED.with(|unsettled| std::mem::take(&mut *unsettled.borrow_mut()));
+    let before = pending.len();
+    let mut remaining: Vec<Settle> = pending.into_iter().filter_map(|mut settle| (!settle()).then_some(settle)).collect();
+    let settled = before - remaining.len();
+    UNSETTLED.with(|unsettled| {
+        let mut unsettled = unsettled.borrow_mut();
+        remaining.append(&mut unsettled);
+        *unsettled = remaining;
+    });
+    settled
+}
+
+/// A future driven by scheduler fibers. Waking it schedules another poll.
+struct Task {
+    future: Mutex<Option<BoxFuture>>,
+}
+
+impl Wake for Task {
+    fn wake(self: Arc<Self>) {
+        schedule_poll(self);
+    }
+}
+
+fn spawn(future: BoxFuture) {
+    schedule_poll(Arc::new(Task {
+        future: Mutex::new(Some(future)),
+    }));
+}
+
+fn schedule_poll(task: Arc<Task>) {
+    schedule_work(Priority::Normal, move || {
+        let mut slot = task.future.lock();
+        let Some(mut future) = slot.take() else {
+            return;
+        };
+        let waker = Waker::from(task.clone());
+        if future.as_mut().poll(&mut Context::from_waker(&waker)).is_pending() {
+            *slot = Some(future);
+        }
+    });
+}
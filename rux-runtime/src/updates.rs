@@ -0,0 +1,100 @@
+use crate::component::ComponentId;
+use parking_lot::Mutex;
+use rux_core::scheduler::{schedule_work, Priority};
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+
+type RenderFn = Box<dyn FnOnce() + Send>;
+
+/// A re-render waiting on the global scheduler. Only the fiber holding the
+/// current ticket runs it; fibers from coalesced requests find a newer
+/// ticket, or no entry at all, and do nothing.
+struct PendingRender {
+    priority: Priority,
+    ticket: u64,
+    render: RenderFn,
+}
+
+thread_local! {
+    /// Priority `request_update` schedules at on this thread
+    static UPDATE_PRIORITY: Cell<Priority> = const { Cell::new(Priority::Normal) };
+}
+
+fn pending_renders() -> &'static Mutex<HashMap<ComponentId, PendingRender>> {
+    static PENDING: OnceLock<Mutex<HashMap<ComponentId, PendingRender>>> = OnceLock::new();
+    PENDING.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Asks for component `id` to re-render by running `render` on the global
+/// scheduler at `priority`. Input handlers use `Priority::UserBlocking` so
+/// they preempt background updates such as resource completions at
+/// `Priority::Normal`; `request_update` picks between them.
+///
+/// Requests made before the component has re-rendered are coalesced: it
+/// renders once, with the latest `render`, at the most urgent priority
+/// asked for.
+pub fn request_render(id: ComponentId, priority: Priority, render: impl FnOnce() + Send + 'static) {
+    static NEXT_TICKET: AtomicU64 = AtomicU64::new(0);
+    
+    let ticket = NEXT_TICKET.fetch_add(1, Ordering::Relaxed);
+    let mut pending = pending_renders().lock();
+    match pending.get_mut(&id) {
+        Some(existing) => {
+            existing.render = Box::new(render);
+            if existing.priority <= priority {
+                // Already queued at least as urgently
+                return;
+            }
+            existing.priority = priority;
+            existing.ticket = ticket;
+        }
+        None => {
+            pending.insert(id, PendingRender { priority, ticket, render: Box::new(render) });
+        }
+    }
+    drop(pending);
+    
+    schedule_work(priority, move || {
+        let mut pending = pending_renders().lock();
+        if pending.get(&id).is_some_and(|entry| entry.ticket == ticket) {
+            let entry = pending.remove(&id).expect("checked above");
+            // Unlocked so the render can request further updates
+            drop(pending);
+            (entry.render)();
+        }
+    });
+}
+
+/// Asks for component `id` to re-render at the priority of whatever
+/// caused the update: `Priority::UserBlocking` inside `dispatch_input`,
+/// `Priority::Normal` otherwise, which includes resource completions set
+/// by `poll_resources`.
+pub fn request_update(id: ComponentId, render: impl FnOnce() + Send + 'static) {
+    request_render(id, update_priority(), render);
+}
+
+/// Runs an input handler. Updates it requests through `request_update`
+/// are rendered at `Priority::UserBlocking`.
+pub fn dispatch_input<R>(handler: impl FnOnce() -> R) -> R {
+    with_update_priority(Priority::UserBlocking, handler)
+}
+
+/// Runs `f` with `request_update` scheduling at `priority`
+pub fn with_update_priority<R>(priority: Priority, f: impl FnOnce() -> R) -> R {
+    let previous = UPDATE_PRIORITY.with(|current| current.replace(priority));
+    let result = f();
+    UPDATE_PRIORITY.with(|current| current.set(previous));
+    result
+}
+
+/// Priority `request_update` would schedule at right now
+pub fn update_priority() -> Priority {
+    UPDATE_PRIORITY.with(Cell::get)
+}
+
+/// Priority of the re-render queued for `id`, if one is waiting
+pub fn pending_render(id: ComponentId) -> Option<Priority> {
+    pending_renders().lock().get(&id).map(|entry| entry.priority)
+}
@@ -0,0 +1,64 @@
+#[cfg(test)]
+mod tests {
+    use rux_core::{create_signal, Patch, PropValue, VirtualNode};
+    use rux_runtime::keyed_list;
+
+    fn todo(id: u32, label: &str) -> (u32, String) {
+        (id, label.to_string())
+    }
+
+    fn render(item: &(u32, String)) -> VirtualNode {
+        VirtualNode::element("li")
+            .prop("label", item.1.as_str())
+            .child(VirtualNode::text(item.1.as_str()))
+            .build()
+    }
+
+    #[test]
+    fn test_changing_one_item_only_patches_that_item() {
+        let items = create_signal(vec![todo(1, "milk"), todo(2, "eggs"), todo(3, "bread")]);
+        let list = keyed_list(&items, |item| item.0, render);
+        let eggs = list.node().children[1].clone();
+        assert_eq!(eggs.key.as_deref(), Some("2"));
+
+        items.update(|items| items[1].1 = "flour".to_string());
+
+        let patches = list.take_patches();
+        assert_eq!(patches.len(), 2, "{:?}", patches);
+        for patch in &patches {
+            match patch {
+                Patch::UpdateProps { node_id, props } => {
+                    assert_eq!(*node_id, eggs.id);
+                    assert_eq!(props.get("label"), Some(&PropValue::String("flour".to_string())));
+                }
+                Patch::Replace { node_id, new_node } => {
+                    assert_eq!(*node_id, eggs.children[0].id);
+                    assert_eq!(new_node.text_content(), "flour");
+                }
+                other => panic!("unexpected patch {:?}", other),
+            }
+        }
+        assert_eq!(list.node().children[1].id, eggs.id, "the item keeps its mounted node");
+        assert!(list.take_patches().is_empty());
+    }
+
+    #[test]
+    fn test_reordering_and_inserting_items_reuses_their_nodes() {
+        let items = create_signal(vec![todo(1, "milk"), todo(2, "eggs")]);
+        let list = keyed_list(&items, |item| item.0, render);
+        let ids: Vec<_> = list.node().children.iter().map(|child| child.id).collect();
+
+        items.set(vec![todo(3, "bread"), todo(2, "eggs"), todo(1, "milk")]);
+
+        let patches = list.take_patches();
+        let inserts = patches.iter().filter(|patch| matches!(patch, Patch::Insert { .. })).count();
+        let moves = patches.iter().filter(|patch| matches!(patch, Patch::Move { .. })).count();
+        assert_eq!(inserts, 1, "{:?}", patches);
+        assert_eq!(inserts + moves, patches.len(), "{:?}", patches);
+
+        let children = list.node().children;
+        assert_eq!(children[1].id, ids[1]);
+        assert_eq!(children[2].id, ids[0]);
+        assert_eq!(children[0].text_content(), "bread");
+    }
+}
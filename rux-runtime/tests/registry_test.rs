@@ -0,0 +1,197 @@
+#[cfg(test)]
+mod tests {
+    use rux_compiler::ast::{Component, Item};
+    use rux_compiler::{Lexer, Parser};
+    use rux_core::virtual_tree::{diff, NodeType, PropValue, VirtualNode};
+    use rux_runtime::executor::{ComponentExecutor, ExecutorError};
+    use rux_runtime::ComponentRegistry;
+
+    fn parse_components(source: &str) -> Vec<Component> {
+        let mut lexer = Lexer::new(source);
+        let tokens = lexer.tokenize().unwrap();
+        let mut parser = Parser::new(tokens, source.to_string());
+        let ast = parser.parse().unwrap();
+
+        ast.items
+            .into_iter()
+            .filter_map(|item| match item {
+                Item::Component(c) => Some(c),
+                _ => None,
+            })
+            .collect()
+    }
+
+    // The web renderer expands trees through the registry before mounting;
+    // the DOM itself isn't available outside wasm, so test the expansion
+    #[test]
+    fn test_component_node_expands_to_its_children() {
+        let mut components = parse_components(
+            r#"
+            fn Badge() -> Element { <span>New</span> }
+            fn App() -> Element { <div><Badge /></div> }
+            "#,
+        );
+        let app = components.pop().unwrap();
+        let mut registry = ComponentRegistry::new();
+        for component in components {
+            registry.register_component(component);
+        }
+
+        let tree = ComponentExecutor::new().execute_component(&app).unwrap();
+        assert!(matches!(&tree.children[0].node_type, NodeType::Component(name) if name == "Badge"));
+
+        let expanded = registry.expand(&tree).unwrap();
+        let badge = &expanded.children[0];
+        assert_eq!(badge.node_type, NodeType::Element("span".to_string()));
+        assert_eq!(badge.children[0].node_type, NodeType::Text("New".to_string()));
+        let ids = [expanded.id, badge.id, badge.children[0].id];
+        assert!(ids[0] != ids[1] && ids[1] != ids[2] && ids[0] != ids[2]);
+
+        let again = registry.expand(&tree).unwrap();
+        assert_eq!(again, expanded);
+        assert!(diff(&expanded, &again).is_empty(), "re-expanding keeps the mounted ids");
+    }
+
+    #[test]
+    fn test_patched_component_expands_with_unique_ids() {
+        let mut registry = ComponentRegistry::new();
+        registry.register_with_children("Card", |_props, children| {
+            Ok(VirtualNode::element("section")
+                .child(VirtualNode::element("h2").child(VirtualNode::text("Card")))
+                .children(children.to_vec())
+                .build())
+        });
+        let body = VirtualNode::element("p").child(VirtualNode::text("body")).build();
+        let card = VirtualNode::component("Card").child(body.clone()).build();
+
+        // What a `Patch::Replace` carrying the card is mounted as
+        let expanded = registry.expand_subtree(&card).unwrap();
+
+        assert_eq!(expanded.node_type, NodeType::Element("section".to_string()));
+        assert_eq!(expanded.id, card.id, "later patches target the component node's id");
+        assert_eq!(expanded.children[1], body, "the children passed in keep their ids");
+        let heading = &expanded.children[0];
+        assert_eq!(heading.children[0].node_type, NodeType::Text("Card".to_string()));
+        let ids = [expanded.id, heading.id, heading.children[0].id, body.id, body.children[0].id];
+        let unique: std::collections::HashSet<_> = ids.iter().collect();
+        assert_eq!(unique.len(), ids.len(), "{:?}", ids);
+        assert!(ids.iter().all(|id| !id.is_stable()));
+    }
+
+    #[test]
+    fn test_render_fn_receives_props() {
+        let mut registry = ComponentRegistry::new();
+        registry.register("Greeting", |props| {
+            let name = match props.get("name") {
+                Some(PropValue::String(name)) => name.clone(),
+                _ => "stranger".to_string(),
+            };
+            Ok(VirtualNode::element("p").child(VirtualNode::text(format!("Hi {}", name))).build())
+        });
+
+        let tree = VirtualNode::component("Greeting").prop("name", "Ada").key("g").build();
+        let expanded = registry.expand(&tree).unwrap();
+        assert_eq!(expanded.children[0].node_type, NodeType::Text("Hi Ada".to_string()));
+        assert_eq!(expanded.key.as_deref(), Some("g"));
+    }
+
+    #[test]
+    fn test_unknown_and_recursive_components() {
+        let mut registry = ComponentRegistry::new();
+        let err = registry.expand(&VirtualNode::component("Missing").build()).unwrap_err();
+        assert!(matches!(err, ExecutorError::UnknownComponent(name) if name == "Missing"));
+
+        registry.register("Loop", |_| Ok(VirtualNode::component("Loop").build()));
+        let err = registry.expand(&VirtualNode::component("Loop").build()).unwrap_err();
+        assert!(matches!(err, ExecutorError::RecursiveComponent(name) if name == "Loop"));
+    }
+
+    #[test]
+    fn test_omitted_prop_uses_default() {
+        let mut components = parse_components(
+            r#"
+            fn Button(label: String = "OK") -> Element { <button>{label}</button> }
+            fn App() -> Element { <div><Button /><Button label="Save" /></div> }
+            "#,
+        );
+        let app = components.pop().unwrap();
+        let mut registry = ComponentRegistry::new();
+        for component in components {
+            registry.register_component(component);
+        }
+
+        let tree = ComponentExecutor::new().execute_component(&app).unwrap();
+        let expanded = registry.expand(&tree).unwrap();
+
+        let labels: Vec<&NodeType> = expanded
+            .children
+            .iter()
+            .map(|button| &button.children[0].node_type)
+            .collect();
+        assert_eq!(
+            labels,
+            vec![&NodeType::Text("OK".to_string()), &NodeType::Text("Save".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_children_prop() {
+        let mut components = parse_components(
+            r#"
+            fn Card() -> Element { <div class="card">{children}</div> }
+            fn App() -> Element { <main><Card><p>hi</p><Card>nested</Card></Card></main> }
+            "#,
+        );
+        let app = components.pop().unwrap();
+        let mut registry = ComponentRegistry::new();
+        for component in components {
+            registry.register_component(component);
+        }
+
+        let tree = ComponentExecutor::new().execute_component(&app).unwrap();
+        let expanded = registry.expand(&tree).unwrap();
+
+        let card = &expanded.children[0];
+        assert_eq!(card.node_type, NodeType::Element("div".to_string()));
+        assert_eq!(card.props["class"], PropValue::String("card".to_string()));
+        let slot = &card.children[0];
+        assert_eq!(slot.node_type, NodeType::Fragment);
+        assert_eq!(slot.children[0].node_type, NodeType::Element("p".to_string()));
+        assert_eq!(slot.children[0].children[0].node_type, NodeType::Text("hi".to_string()));
+
+        // A card passed as a child of a card isn't recursion
+        let inner = &slot.children[1];
+        assert_eq!(inner.node_type, NodeType::Element("div".to_string()));
+        assert_eq!(inner.children[0].children[0].node_type, NodeType::Text("nested".to_string()));
+    }
+
+    #[test]
+    fn test_suspense_shows_fallback_while_pending() {
+        let user = rux_core::Resource::<String>::new();
+        let mut registry = ComponentRegistry::new();
+        let profile_user = user.clone();
+        registry.register("Profile", move |_props| {
+            let name = profile_user.get().unwrap_or_default();
+            Ok(VirtualNode::element("p").child(VirtualNode::text(name)).build())
+        });
+
+        let tree = VirtualNode::suspense(VirtualNode::text("Loading..."))
+            .child(VirtualNode::component("Profile"))
+            .build();
+
+        let pending = registry.expand(&tree).unwrap();
+        assert!(matches!(pending.node_type, NodeType::Suspense { .. }));
+        assert_eq!(pending.children.len(), 1);
+        assert_eq!(pending.children[0].node_type, NodeType::Text("Loading...".to_string()));
+
+        user.resolve("Ada".to_string());
+        let resolved = registry.expand(&tree).unwrap();
+        let profile = &resolved.children[0];
+        assert_eq!(profile.node_type, NodeType::Element("p".to_string()));
+        assert_eq!(profile.children[0].node_type, NodeType::Text("Ada".to_string()));
+
+        user.reset();
+        let reloading = registry.expand(&tree).unwrap();
+        assert_eq!(reloading.children[0].node_type, NodeType::Text("Loading...".to_string()));
+    }
+}
@@ -0,0 +1,109 @@
+#[cfg(test)]
+mod tests {
+    use rux_core::{create_effect, flush_scheduled_work, NodeType, Priority, VirtualNode};
+    use rux_runtime::{
+        create_resource, create_suspense_resource, dispatch_input, pending_render, poll_resources, request_update,
+        ComponentId, ComponentRegistry,
+    };
+    use std::cell::RefCell;
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::rc::Rc;
+    use std::sync::Mutex;
+    use std::task::{Context, Poll};
+
+    /// The global scheduler is shared, so a test flushing it in parallel
+    /// could be running the other test's fibers
+    static SCHEDULER: Mutex<()> = Mutex::new(());
+
+    /// Pending on the first poll, after waking itself
+    struct YieldOnce(bool);
+
+    impl Future for YieldOnce {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+            if self.0 {
+                return Poll::Ready(());
+            }
+            self.0 = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+
+    #[test]
+    fn test_resource_resolves_after_scheduled_work() {
+        let _guard = SCHEDULER.lock().unwrap();
+        let answer = create_resource(async { 42 });
+        assert_eq!(answer.get(), None);
+
+        flush_scheduled_work();
+        assert_eq!(answer.get(), None, "the value is only set by poll_resources");
+        assert_eq!(poll_resources(), 1);
+        assert_eq!(answer.get(), Some(42));
+    }
+
+    #[test]
+    fn test_resource_completion_requests_normal_priority_update() {
+        let _guard = SCHEDULER.lock().unwrap();
+        let id = ComponentId(2208);
+        let answer = create_resource(async { 42 });
+
+        let reader = answer.clone();
+        let _effect = create_effect(move || {
+            if reader.get().is_some() {
+                request_update(id, || {});
+            }
+        });
+        flush_scheduled_work();
+
+        // Even when polled from an input handler
+        dispatch_input(poll_resources);
+        assert_eq!(pending_render(id), Some(Priority::Normal));
+
+        flush_scheduled_work();
+        assert_eq!(pending_render(id), None);
+    }
+
+    #[test]
+    fn test_resource_rerenders_readers_when_woken_future_completes() {
+        let _guard = SCHEDULER.lock().unwrap();
+        let greeting = create_resource(async {
+            YieldOnce(false).await;
+            "hello".to_string()
+        });
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let (reader, log) = (greeting.clone(), seen.clone());
+        let _effect = create_effect(move || log.borrow_mut().push(reader.get()));
+
+        flush_scheduled_work();
+        poll_resources();
+
+        assert_eq!(*seen.borrow(), [None, Some("hello".to_string())]);
+    }
+
+    #[test]
+    fn test_suspense_shows_fallback_while_resource_loads() {
+        let _guard = SCHEDULER.lock().unwrap();
+        let user = create_suspense_resource(async { "Ada".to_string() });
+        let mut registry = ComponentRegistry::new();
+        let profile_user = user.clone();
+        registry.register("Profile", move |_props| {
+            let name = profile_user.get().unwrap_or_default();
+            Ok(VirtualNode::element("p").child(VirtualNode::text(name)).build())
+        });
+        let tree = VirtualNode::suspense(VirtualNode::text("Loading..."))
+            .child(VirtualNode::component("Profile"))
+            .build();
+
+        let loading = registry.expand(&tree).unwrap();
+        assert_eq!(loading.children[0].node_type, NodeType::Text("Loading...".to_string()));
+
+        flush_scheduled_work();
+        assert_eq!(poll_resources(), 1);
+        let loaded = registry.expand(&tree).unwrap();
+        assert_eq!(loaded.children[0].node_type, NodeType::Element("p".to_string()));
+        assert_eq!(loaded.children[0].children[0].node_type, NodeType::Text("Ada".to_string()));
+    }
+}
@@ -0,0 +1,43 @@
+#[cfg(test)]
+mod tests {
+    use rux_core::{flush_scheduled_work, schedule_work, Priority};
+    use rux_runtime::{dispatch_input, pending_render, request_render, update_priority, with_update_priority, ComponentId};
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_coalesced_updates_run_once_at_highest_priority() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let id = ComponentId(1);
+
+        let background = log.clone();
+        schedule_work(Priority::Normal, move || background.lock().unwrap().push("background"));
+
+        let normal = log.clone();
+        request_render(id, Priority::Normal, move || normal.lock().unwrap().push("render"));
+        let input = log.clone();
+        request_render(id, Priority::UserBlocking, move || input.lock().unwrap().push("render"));
+        assert_eq!(pending_render(id), Some(Priority::UserBlocking));
+
+        // A less urgent request doesn't lower the queued priority
+        let resource = log.clone();
+        request_render(id, Priority::Low, move || resource.lock().unwrap().push("render"));
+        assert_eq!(pending_render(id), Some(Priority::UserBlocking));
+
+        flush_scheduled_work();
+
+        // One render, ahead of the earlier background work
+        assert_eq!(*log.lock().unwrap(), vec!["render", "background"]);
+        assert_eq!(pending_render(id), None);
+    }
+
+    #[test]
+    fn test_input_handlers_update_at_user_blocking() {
+        assert_eq!(update_priority(), Priority::Normal);
+        assert_eq!(dispatch_input(update_priority), Priority::UserBlocking);
+
+        // Work a handler triggers that isn't input itself goes back to normal
+        let nested = dispatch_input(|| with_update_priority(Priority::Normal, update_priority));
+        assert_eq!(nested, Priority::Normal);
+        assert_eq!(update_priority(), Priority::Normal);
+    }
+}
@@ -0,0 +1,30 @@
+use rux_core::virtual_tree::PropValue;
+
+pub use rux_core::attributes::{attribute_update, is_boolean_attribute, is_enumerated_attribute, AttributeUpdate};
+
+/// A DOM listener for an event handler prop. Modifiers come after the
+/// prop name, so `onClick:prevent:stop` listens for `click` and calls
+/// `preventDefault` and `stopPropagation` before the handler.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventListener {
+    pub event: String,
+    pub handler: String,
+    pub prevent_default: bool,
+    pub stop_propagation: bool,
+}
+
+/// The listener for `key`, if it is an event handler prop
+pub fn event_listener(key: &str, value: &PropValue) -> Option<EventListener> {
+    let (PropValue::String(handler) | PropValue::Function(handler)) = value else {
+        return None;
+    };
+    let mut parts = key.split(':');
+    let event = parts.next()?.strip_prefix("on").filter(|event| !event.is_empty())?;
+    let modifiers: Vec<&str> = parts.collect();
+    Some(EventListener {
+        event: event.to_lowercase(),
+        handler: handler.clone(),
+        prevent_default: modifiers.contains(&"prevent"),
+        stop_propagation: modifiers.contains(&"stop"),
+    })
+}
@@ -0,0 +1,262 @@
+use crate::transitions::Removal;
+use rux_core::renderer::{ElementId, RAW_HTML_WRAPPER};
+use rux_core::virtual_tree::{NodeId, NodeType, PropValue, VirtualNode};
+use std::collections::HashMap;
+
+/// Platform operations driven by `ElementTable`
+pub trait ElementBackend {
+    type Element: Clone;
+    type Error;
+    
+    /// Creates the element for one element or component node, props set
+    fn create_element(&mut self, node: &VirtualNode) -> Result<Self::Element, Self::Error>;
+    fn append_child(&mut self, parent: &Self::Element, child: &Self::Element) -> Result<(), Self::Error>;
+    fn append_text(&mut self, parent: &Self::Element, text: &str) -> Result<(), Self::Error>;
+    fn set_prop(&mut self, element: &Self::Element, key: &str, value: &PropValue) -> Result<(), Self::Error>;
+}
+
+/// Extra operations for adopting elements that already exist, such as
+/// server-rendered HTML
+pub trait HydrationBackend: ElementBackend {
+    /// Element children of `parent` in document order; text is not included
+    fn element_children(&mut self, parent: &Self::Element) -> Vec<Self::Element>;
+    fn tag_name(&mut self, element: &Self::Element) -> String;
+    fn replace_child(&mut self, parent: &Self::Element, new: &Self::Element, old: &Self::Element) -> Result<(), Self::Error>;
+    fn remove_child(&mut self, parent: &Self::Element, child: &Self::Element) -> Result<(), Self::Error>;
+    /// Wires up the event handler props of an adopted element. Other props
+    /// are left as the server rendered them.
+    fn attach_listeners(&mut self, element: &Self::Element, node: &VirtualNode) -> Result<(), Self::Error>;
+}
+
+/// Mapping between virtual nodes and the platform elements mounted for
+/// them. Generic over the element type so it works without a DOM.
+pub struct ElementTable<E> {
+    node_to_element: HashMap<NodeId, ElementId>,
+    element_to_node: HashMap<ElementId, NodeId>,
+    elements: HashMap<ElementId, E>,
+    /// Exit transitions of elements whose node has an `onExit` prop
+    exits: HashMap<ElementId, String>,
+    next_element_id: usize,
+}
+
+impl<E: Clone> ElementTable<E> {
+    pub fn new() -> Self {
+        Self {
+            node_to_element: HashMap::new(),
+            element_to_node: HashMap::new(),
+            elements: HashMap::new(),
+            exits: HashMap::new(),
+            // 0 is reserved for the root container
+            next_element_id: 1,
+        }
+    }
+    
+    /// Assigns a fresh id to `element` and records it as `node_id`'s element
+    pub fn register(&mut self, node_id: NodeId, element: E) -> ElementId {
+        let element_id = ElementId(self.next_element_id);
+        self.next_element_id += 1;
+        
+        self.node_to_element.insert(node_id, element_id);
+        self.element_to_node.insert(element_id, node_id);
+        self.elements.insert(element_id, element);
+        element_id
+    }
+    
+    /// Like `register`, but also remembers the node's exit transition
+    pub fn register_node(&mut self, node: &VirtualNode, element: E) -> ElementId {
+        let element_id = self.register(node.id, element);
+        if let Removal::AfterTransition(class) = Removal::for_node(node) {
+            self.exits.insert(element_id, class);
+        }
+        element_id
+    }
+    
+    /// Whether removing the element should wait for an exit transition
+    pub fn removal(&self, element_id: ElementId) -> Removal {
+        match self.exits.get(&element_id) {
+            Some(class) => Removal::AfterTransition(class.clone()),
+            None => Removal::Immediate,
+        }
+    }
+    
+    pub fn element(&self, element_id: ElementId) -> Option<&E> {
+        self.elements.get(&element_id)
+    }
+    
+    pub fn element_id(&self, node_id: NodeId) -> Option<ElementId> {
+        self.node_to_element.get(&node_id).copied()
+    }
+    
+    pub fn node_element(&self, node_id: NodeId) -> Option<&E> {
+        self.element_id(node_id).and_then(|id| self.elements.get(&id))
+    }
+    
+    /// Swaps the element behind an id, keeping its node mapping
+    pub fn replace(&mut self, element_id: ElementId, element: E) {
+        self.elements.insert(element_id, element);
+    }
+    
+    /// Forgets an element and its node mapping, returning the element
+    pub fn remove(&mut self, element_id: ElementId) -> Option<E> {
+        if let Some(node_id) = self.element_to_node.remove(&element_id) {
+            self.node_to_element.remove(&node_id);
+        }
+        self.exits.remove(&element_id);
+        self.elements.remove(&element_id)
+    }
+    
+    /// Creates elements for `node` and all of its descendants, registers
+    /// each one, and appends the result to `parent`. Text nodes become
+    /// platform text and fragments mount their children in place.
+    pub fn mount<B>(&mut self, backend: &mut B, parent: &E, node: &VirtualNode) -> Result<(), B::Error>
+    where
+        B: ElementBackend<Element = E>,
+    {
+        match &node.node_type {
+            NodeType::Element(_) | NodeType::Component(_) | NodeType::RawHtml(_) => {
+                let element = self.create_subtree(backend, node)?;
+                backend.append_child(parent, &element)
+            }
+            NodeType::Text(text) => backend.append_text(parent, text),
+            NodeType::Fragment | NodeType::Suspense { .. } => {
+                for child in &node.children {
+                    self.mount(backend, parent, child)?;
+                }
+                Ok(())
+            }
+        }
+    }
+    
+    /// Mounts `node` with all of its descendants in place of the element
+    /// registered as `old`, a child of `parent`, and forgets the old one
+    pub fn replace_subtree<B>(&mut self, backend: &mut B, parent: &E, old: ElementId, node: &VirtualNode) -> Result<(), B::Error>
+    where
+        B: HydrationBackend<Element = E>,
+    {
+        let Some(old_element) = self.remove(old) else {
+            return Ok(());
+        };
+        let element = self.create_subtree(backend, node)?;
+        backend.replace_child(parent, &element, &old_element)
+    }
+    
+    /// Creates and registers an element node with its descendants, without
+    /// attaching it anywhere
+    fn create_subtree<B>(&mut self, backend: &mut B, node: &VirtualNode) -> Result<E, B::Error>
+    where
+        B: ElementBackend<Element = E>,
+    {
+        let element = backend.create_element(node)?;
+        self.register_node(node, element.clone());
+        
+        for child in &node.children {
+            self.mount(backend, &element, child)?;
+        }
+        Ok(element)
+    }
+    
+    /// Adopts the elements already under `parent` for `nodes`, matching
+    /// them up by position and tag. Matching elements are registered and
+    /// get their listeners attached instead of being recreated; anything
+    /// that doesn't match is replaced or removed. Text is assumed to match.
+    /// Returns a description of each mismatch.
+    pub fn hydrate<B>(&mut self, backend: &mut B, parent: &E, nodes: &[VirtualNode]) -> Result<Vec<String>, B::Error>
+    where
+        B: HydrationBackend<Element = E>,
+    {
+        let mut mismatches = Vec::new();
+        self.hydrate_children(backend, parent, nodes, &mut mismatches)?;
+        Ok(mismatches)
+    }
+    
+    fn hydrate_children<B>(
+        &mut self,
+        backend: &mut B,
+        parent: &E,
+        nodes: &[VirtualNode],
+        mismatches: &mut Vec<String>,
+    ) -> Result<(), B::Error>
+    where
+        B: HydrationBackend<Element = E>,
+    {
+        let mut element_nodes = Vec::new();
+        flatten_element_nodes(nodes, &mut element_nodes);
+        let existing = backend.element_children(parent);
+        
+        for (i, node) in element_nodes.iter().enumerate() {
+            let expected = match &node.node_type {
+                NodeType::Element(tag) | NodeType::Component(tag) => tag.as_str(),
+                NodeType::RawHtml(_) => RAW_HTML_WRAPPER,
+                _ => continue,
+            };
+            
+            match existing.get(i) {
+                Some(element) if backend.tag_name(element).eq_ignore_ascii_case(expected) => {
+                    self.register_node(node, element.clone());
+                    // The server's raw markup is kept as it is
+                    if !matches!(node.node_type, NodeType::RawHtml(_)) {
+                        backend.attach_listeners(element, node)?;
+                        self.hydrate_children(backend, element, &node.children, mismatches)?;
+                    }
+                }
+                Some(element) => {
+                    mismatches.push(format!(
+                        "expected <{}> but found <{}>, replacing it",
+                        expected,
+                        backend.tag_name(element).to_lowercase()
+                    ));
+                    let replacement = self.create_subtree(backend, node)?;
+                    backend.replace_child(parent, &replacement, element)?;
+                }
+                None => {
+                    mismatches.push(format!("missing <{}>, creating it", expected));
+                    let element = self.create_subtree(backend, node)?;
+                    backend.append_child(parent, &element)?;
+                }
+            }
+        }
+        
+        for extra in existing.iter().skip(element_nodes.len()) {
+            mismatches.push(format!("unexpected <{}>, removing it", backend.tag_name(extra).to_lowercase()));
+            backend.remove_child(parent, extra)?;
+        }
+        Ok(())
+    }
+    
+    /// Sets `props` on the element mounted for `node_id`. Returns false if
+    /// the node has no element.
+    pub fn update_props<B>(
+        &self,
+        backend: &mut B,
+        node_id: NodeId,
+        props: &HashMap<String, PropValue>,
+    ) -> Result<bool, B::Error>
+    where
+        B: ElementBackend<Element = E>,
+    {
+        let Some(element) = self.node_element(node_id) else {
+            return Ok(false);
+        };
+        for (key, value) in props {
+            backend.set_prop(element, key, value)?;
+        }
+        Ok(true)
+    }
+}
+
+/// The nodes that produce elements, with fragments opened up in place
+fn flatten_element_nodes<'a>(nodes: &'a [VirtualNode], out: &mut Vec<&'a VirtualNode>) {
+    for node in nodes {
+        match node.node_type {
+            NodeType::Element(_) | NodeType::Component(_) | NodeType::RawHtml(_) => out.push(node),
+            NodeType::Fragment | NodeType::Suspense { .. } => flatten_element_nodes(&node.children, out),
+            NodeType::Text(_) => {}
+        }
+    }
+}
+
+impl<E: Clone> Default for ElementTable<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,5 +1,13 @@
 // Web platform implementation for RUX
 
+pub mod attributes;
+pub mod elements;
 pub mod renderer;
+pub mod text;
+pub mod transitions;
 
+pub use attributes::{attribute_update, event_listener, AttributeUpdate, EventListener};
+pub use elements::{ElementBackend, ElementTable, HydrationBackend};
 pub use renderer::{WebRenderer, init_rux_web, render_to_element};
+pub use text::CanvasTextMeasurer;
+pub use transitions::{enter_class, Removal};
@@ -1,49 +1,90 @@
-use rux_core::renderer::{Renderer, ElementId};
-use rux_core::virtual_tree::{VirtualNode, NodeId, Patch, PropValue, NodeType};
+use crate::attributes::{attribute_update, event_listener, AttributeUpdate};
+use crate::elements::{ElementBackend, ElementTable, HydrationBackend};
+use crate::transitions::{enter_class, Removal};
+use rux_core::bindings::{binding_kind, write_binding, BindingKind};
+use rux_core::renderer::{Renderer, ElementId, RAW_HTML_WRAPPER, RAW_HTML_WRAPPER_STYLE};
+use rux_core::virtual_tree::{VirtualNode, Patch, PropValue, NodeType};
+use rux_runtime::ComponentRegistry;
 use wasm_bindgen::prelude::*;
-use web_sys::{Document, Element, Text};
+use web_sys::{
+    AddEventListenerOptions, Document, Element, Event, HtmlElement, HtmlInputElement, HtmlSelectElement, HtmlTextAreaElement,
+};
+use std::cell::RefCell;
 use std::collections::HashMap;
+use std::rc::Rc;
+
+/// Event handlers by the name function props refer to them by
+type Handlers = Rc<RefCell<HashMap<String, Rc<dyn Fn(&Event)>>>>;
 
 pub struct WebRenderer {
     document: Document,
-    node_to_element: HashMap<NodeId, ElementId>,
-    element_to_node: HashMap<ElementId, NodeId>,
-    element_map: HashMap<ElementId, Element>,
+    elements: ElementTable<Element>,
     root_element: Option<Element>,
-    next_element_id: usize,
+    /// Expands component nodes before they are mounted
+    registry: Rc<ComponentRegistry>,
+    handlers: Handlers,
 }
 
-impl WebRenderer {
-    pub fn new() -> Result<Self, JsValue> {
-        let window = web_sys::window().ok_or_else(|| JsValue::from_str("No window"))?;
-        let document = window.document().ok_or_else(|| JsValue::from_str("No document"))?;
-        
-        Ok(Self {
-            document,
-            node_to_element: HashMap::new(),
-            element_to_node: HashMap::new(),
-            element_map: HashMap::new(),
-            root_element: None,
-            next_element_id: 1,
-        })
-    }
-    
-    pub fn mount_to_element_id(&mut self, element_id: &str, node: &VirtualNode) -> Result<(), JsValue> {
-        let container = self.document
-            .get_element_by_id(element_id)
-            .ok_or_else(|| JsValue::from_str("Element not found"))?;
-        
-        self.root_element = Some(container.clone());
-        
-        // Clear container
-        container.set_inner_html("");
-        
-        // Mount the virtual tree
-        self.mount(ElementId(0), node);
+/// DOM operations for the element table
+struct DomBackend<'a> {
+    document: &'a Document,
+    handlers: &'a Handlers,
+}
+
+impl DomBackend<'_> {
+    /// Listens for `key`'s event if it is a handler prop. The handler is
+    /// looked up by name when the event fires, so it can be registered
+    /// after the element is mounted.
+    fn add_listener(&self, element: &Element, key: &str, value: &PropValue) -> Result<(), JsValue> {
+        let Some(listener) = event_listener(key, value) else {
+            return Ok(());
+        };
+        let event_name = listener.event.clone();
+        let handlers = Rc::clone(self.handlers);
+        let callback = Closure::<dyn FnMut(Event)>::new(move |event: Event| {
+            if listener.prevent_default {
+                event.prevent_default();
+            }
+            if listener.stop_propagation {
+                event.stop_propagation();
+            }
+            let handler = handlers.borrow().get(&listener.handler).cloned();
+            if let Some(handler) = handler {
+                rux_runtime::dispatch_input(|| handler(&event));
+            } else if let Some(input) = binding_kind(&listener.handler).and_then(|kind| bound_input(&event, kind)) {
+                rux_runtime::dispatch_input(|| write_binding(&listener.handler, &input));
+            }
+        });
+        element.add_event_listener_with_callback(&event_name, callback.as_ref().unchecked_ref())?;
+        // The listener lives as long as the element
+        callback.forget();
         Ok(())
     }
+}
+
+/// What the element an event fired on reports for a `bind:` prop
+fn bound_input(event: &Event, kind: BindingKind) -> Option<String> {
+    let target = event.target()?;
+    if let Some(input) = target.dyn_ref::<HtmlInputElement>() {
+        return Some(match kind {
+            BindingKind::Value => input.value(),
+            BindingKind::Checked => input.checked().to_string(),
+        });
+    }
+    match kind {
+        BindingKind::Value => target
+            .dyn_ref::<HtmlTextAreaElement>()
+            .map(HtmlTextAreaElement::value)
+            .or_else(|| target.dyn_ref::<HtmlSelectElement>().map(HtmlSelectElement::value)),
+        BindingKind::Checked => None,
+    }
+}
+
+impl ElementBackend for DomBackend<'_> {
+    type Element = Element;
+    type Error = JsValue;
     
-    fn create_element_from_node(&mut self, node: &VirtualNode) -> Result<Element, JsValue> {
+    fn create_element(&mut self, node: &VirtualNode) -> Result<Element, JsValue> {
         match &node.node_type {
             NodeType::Element(tag) => {
                 let element = self.document.create_element(tag)?;
@@ -51,6 +92,7 @@ impl WebRenderer {
                 // Set attributes/props
                 for (key, value) in &node.props {
                     self.set_prop(&element, key, value)?;
+                    self.add_listener(&element, key, value)?;
                 }
                 
                 Ok(element)
@@ -62,12 +104,21 @@ impl WebRenderer {
                 div.set_text_content(Some(text));
                 Ok(div)
             }
+            NodeType::RawHtml(html) => {
+                // The markup goes in unescaped, in the same wrapper the
+                // server renders it in
+                let div = self.document.create_element(RAW_HTML_WRAPPER)?;
+                div.set_attribute("style", RAW_HTML_WRAPPER_STYLE)?;
+                div.set_inner_html(html);
+                Ok(div)
+            }
             NodeType::Component(_) => {
-                // Component rendering would go here
+                // Components are expanded through the registry before they
+                // are mounted, patches included, so this is only a fallback
                 let div = self.document.create_element("div")?;
                 Ok(div)
             }
-            NodeType::Fragment => {
+            NodeType::Fragment | NodeType::Suspense { .. } => {
                 // Fragments don't create elements
                 let div = self.document.create_element("div")?;
                 Ok(div)
@@ -75,102 +126,194 @@ impl WebRenderer {
         }
     }
     
-    fn set_prop(&self, element: &Element, key: &str, value: &PropValue) -> Result<(), JsValue> {
-        match value {
-            PropValue::String(s) => {
-                if key.starts_with("on") {
-                    // Event handler - simplified
-                    // In a real implementation, we'd set up event listeners
-                } else {
-                    element.set_attribute(key, s)?;
-                }
-            }
-            PropValue::Boolean(b) => {
-                if *b {
-                    element.set_attribute(key, "")?;
-                } else {
-                    element.remove_attribute(key);
+    fn append_child(&mut self, parent: &Element, child: &Element) -> Result<(), JsValue> {
+        parent.append_child(child)?;
+        Ok(())
+    }
+    
+    fn append_text(&mut self, parent: &Element, text: &str) -> Result<(), JsValue> {
+        let text_node = self.document.create_text_node(text);
+        parent.append_child(&text_node)?;
+        Ok(())
+    }
+    
+    fn set_prop(&mut self, element: &Element, key: &str, value: &PropValue) -> Result<(), JsValue> {
+        match attribute_update(key, value) {
+            AttributeUpdate::Set(name, value) => element.set_attribute(&name, &value)?,
+            AttributeUpdate::Remove(name) => element.remove_attribute(&name)?,
+            AttributeUpdate::Style(name, value) => {
+                if let Some(element) = element.dyn_ref::<HtmlElement>() {
+                    element.style().set_property(&name, &value)?;
                 }
             }
-            PropValue::Number(n) => {
-                element.set_attribute(key, &n.to_string())?;
-            }
-            PropValue::Function(_) => {
-                // Function props (event handlers) would be handled here
-            }
+            AttributeUpdate::Skip => {}
+        }
+        Ok(())
+    }
+}
+
+impl HydrationBackend for DomBackend<'_> {
+    fn element_children(&mut self, parent: &Element) -> Vec<Element> {
+        let children = parent.children();
+        (0..children.length()).filter_map(|i| children.item(i)).collect()
+    }
+    
+    fn tag_name(&mut self, element: &Element) -> String {
+        element.tag_name()
+    }
+    
+    fn replace_child(&mut self, parent: &Element, new: &Element, old: &Element) -> Result<(), JsValue> {
+        parent.replace_child(new, old)?;
+        Ok(())
+    }
+    
+    fn remove_child(&mut self, parent: &Element, child: &Element) -> Result<(), JsValue> {
+        parent.remove_child(child)?;
+        Ok(())
+    }
+    
+    fn attach_listeners(&mut self, element: &Element, node: &VirtualNode) -> Result<(), JsValue> {
+        for (key, value) in &node.props {
+            self.add_listener(element, key, value)?;
+        }
+        Ok(())
+    }
+}
+
+impl WebRenderer {
+    pub fn new() -> Result<Self, JsValue> {
+        let window = web_sys::window().ok_or_else(|| JsValue::from_str("No window"))?;
+        let document = window.document().ok_or_else(|| JsValue::from_str("No document"))?;
+        
+        Ok(Self {
+            document,
+            elements: ElementTable::new(),
+            root_element: None,
+            registry: Rc::new(ComponentRegistry::new()),
+            handlers: Rc::new(RefCell::new(HashMap::new())),
+        })
+    }
+    
+    /// Registers the handler that function props named `name` run, e.g.
+    /// `onClick={save}` runs the handler registered as `"save"`
+    pub fn on_event(&mut self, name: &str, handler: impl Fn(&Event) + 'static) {
+        self.handlers.borrow_mut().insert(name.to_string(), Rc::new(handler));
+    }
+    
+    /// Renderer that resolves component nodes through `registry`
+    pub fn with_registry(registry: Rc<ComponentRegistry>) -> Result<Self, JsValue> {
+        Ok(Self {
+            registry,
+            ..Self::new()?
+        })
+    }
+    
+    pub fn mount_to_element_id(&mut self, element_id: &str, node: &VirtualNode) -> Result<(), JsValue> {
+        let container = self.document
+            .get_element_by_id(element_id)
+            .ok_or_else(|| JsValue::from_str("Element not found"))?;
+        
+        self.root_element = Some(container.clone());
+        
+        // Clear container
+        container.set_inner_html("");
+        
+        // Expand components, then mount the concrete tree
+        let expanded = self.registry
+            .expand(node)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        self.mount_expanded(ElementId(0), &expanded)
+    }
+    
+    /// Takes over server-rendered HTML in the container instead of
+    /// rebuilding it. Elements matching the tree are kept; mismatches are
+    /// replaced and reported as console warnings.
+    pub fn hydrate_to_element_id(&mut self, element_id: &str, node: &VirtualNode) -> Result<(), JsValue> {
+        let container = self.document
+            .get_element_by_id(element_id)
+            .ok_or_else(|| JsValue::from_str("Element not found"))?;
+        
+        self.root_element = Some(container.clone());
+        
+        let expanded = self.registry
+            .expand(node)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let mut backend = DomBackend { document: &self.document, handlers: &self.handlers };
+        let mismatches = self.elements.hydrate(&mut backend, &container, std::slice::from_ref(&expanded))?;
+        for mismatch in mismatches {
+            web_sys::console::warn_1(&JsValue::from_str(&format!("Hydration mismatch: {}", mismatch)));
         }
         Ok(())
     }
     
+    fn backend(&self) -> DomBackend<'_> {
+        DomBackend { document: &self.document, handlers: &self.handlers }
+    }
+    
     fn apply_patches(&mut self, patches: &[Patch]) -> Result<(), JsValue> {
+        let mut backend = DomBackend { document: &self.document, handlers: &self.handlers };
+        
         for patch in patches {
             match patch {
                 Patch::Replace { node_id, new_node } => {
-                    if let Some(&element_id) = self.node_to_element.get(node_id) {
-                        let old_element_opt = self.element_map.get(&element_id).cloned();
-                        if let Some(old_element) = old_element_opt {
-                            let new_element = self.create_element_from_node(new_node)?;
-                            if let Some(parent) = old_element.parent_element() {
-                                parent.replace_child(&new_element, &old_element)?;
-                                self.element_map.insert(element_id, new_element);
-                            }
+                    if let Some(element_id) = self.elements.element_id(*node_id) {
+                        let parent = self.elements.element(element_id).and_then(|element| element.parent_element());
+                        if let Some(parent) = parent {
+                            // Mounted like the initial render, so the new
+                            // node's descendants exist and are registered
+                            let new_node = expand_subtree(&self.registry, new_node)?;
+                            self.elements.replace_subtree(&mut backend, &parent, element_id, &new_node)?;
                         }
                     }
                 }
                 Patch::UpdateProps { node_id, props } => {
-                    if let Some(&element_id) = self.node_to_element.get(node_id) {
-                        if let Some(element) = self.element_map.get(&element_id) {
-                            for (key, value) in props {
-                                self.set_prop(element, key, value)?;
+                    self.elements.update_props(&mut backend, *node_id, props)?;
+                }
+                Patch::Insert { parent_id, index, node } => {
+                    let parent_element_opt = self.elements.node_element(*parent_id).cloned();
+                    if let Some(parent_element) = parent_element_opt {
+                        let node = &expand_subtree(&self.registry, node)?;
+                        let children = parent_element.child_nodes();
+                        let before = children.item(*index as u32);
+                        let appended_from = children.length();
+                        // Registers the whole subtree so later patches can
+                        // target the inserted node's descendants. `mount`
+                        // appends, so the new nodes are then moved in front
+                        // of the child that was at `index`.
+                        self.elements.mount(&mut backend, &parent_element, node)?;
+                        if before.is_some() {
+                            let appended: Vec<_> = (appended_from..children.length())
+                                .filter_map(|i| children.item(i))
+                                .collect();
+                            for child in appended {
+                                parent_element.insert_before(&child, before.as_ref())?;
                             }
                         }
-                    }
-                }
-                Patch::Insert { parent_id, index: _, node } => {
-                    if let Some(&parent_element_id) = self.node_to_element.get(parent_id) {
-                        let parent_element_opt = self.element_map.get(&parent_element_id).cloned();
-                        if let Some(parent_element) = parent_element_opt {
-                            let new_element = self.create_element_from_node(node)?;
-                            let element_id = ElementId(self.next_element_id);
-                            self.next_element_id += 1;
-                            
-                            self.node_to_element.insert(node.id, element_id);
-                            self.element_to_node.insert(element_id, node.id);
-                            self.element_map.insert(element_id, new_element.clone());
-                            
-                            // Insert at index
-                            // For now, just append - proper index insertion would need more complex logic
-                            parent_element.append_child(&new_element)?;
+                        if let (Some(class), Some(element)) = (enter_class(node), self.elements.node_element(node.id)) {
+                            play_enter_transition(element, class)?;
                         }
                     }
                 }
                 Patch::Remove { node_id } => {
-                    if let Some(&element_id) = self.node_to_element.get(node_id) {
-                        if let Some(element) = self.element_map.remove(&element_id) {
-                            if let Some(parent) = element.parent_element() {
-                                parent.remove_child(&element)?;
-                            }
-                            self.node_to_element.remove(node_id);
-                            self.element_to_node.remove(&element_id);
+                    if let Some(element_id) = self.elements.element_id(*node_id) {
+                        let removal = self.elements.removal(element_id);
+                        if let Some(element) = self.elements.remove(element_id) {
+                            remove_from_parent(&element, removal)?;
                         }
                     }
                 }
-                Patch::Move { node_id, new_parent, new_index: _ } => {
-                    if let Some(&element_id) = self.node_to_element.get(node_id) {
-                        let element_opt = self.element_map.get(&element_id).cloned();
-                        if let Some(element) = element_opt {
-                            if let Some(&new_parent_element_id) = self.node_to_element.get(new_parent) {
-                                let new_parent_element_opt = self.element_map.get(&new_parent_element_id).cloned();
-                                if let Some(new_parent_element) = new_parent_element_opt {
-                                    // Remove from old position
-                                    if let Some(old_parent) = element.parent_element() {
-                                        old_parent.remove_child(&element)?;
-                                    }
-                                    // Insert at new position
-                                    // For now, just append - proper index insertion would need more complex logic
-                                    new_parent_element.append_child(&element)?;
-                                }
+                Patch::Move { node_id, new_parent, new_index } => {
+                    let element_opt = self.elements.node_element(*node_id).cloned();
+                    if let Some(element) = element_opt {
+                        let new_parent_element_opt = self.elements.node_element(*new_parent).cloned();
+                        if let Some(new_parent_element) = new_parent_element_opt {
+                            // Remove from old position
+                            if let Some(old_parent) = element.parent_element() {
+                                old_parent.remove_child(&element)?;
                             }
+                            // Indices are into the children left after the removal
+                            let before = new_parent_element.child_nodes().item(*new_index as u32);
+                            new_parent_element.insert_before(&element, before.as_ref())?;
                         }
                     }
                 }
@@ -182,93 +325,128 @@ impl WebRenderer {
 
 impl Renderer for WebRenderer {
     fn create_element(&mut self, node: &VirtualNode) -> ElementId {
-        let element_id = ElementId(self.next_element_id);
-        self.next_element_id += 1;
-        
         // Create DOM element
-        if let Ok(element) = self.create_element_from_node(node) {
-            self.node_to_element.insert(node.id, element_id);
-            self.element_to_node.insert(element_id, node.id);
-            self.element_map.insert(element_id, element);
+        match self.backend().create_element(node) {
+            Ok(element) => self.elements.register_node(node, element),
+            Err(_) => ElementId(0),
         }
-        
-        element_id
     }
     
-    fn update_element(&mut self, element_id: ElementId, patches: &[Patch]) {
+    fn update_element(&mut self, _element_id: ElementId, patches: &[Patch]) {
         // Apply patches to update DOM
         if let Err(e) = self.apply_patches(patches) {
-            // Log error - in WASM, we'd use wasm_bindgen::console::error
-            let _ = e;
+            web_sys::console::error_1(&e);
         }
     }
     
     fn remove_element(&mut self, element_id: ElementId) {
-        if let Some(element) = self.element_map.remove(&element_id) {
-            if let Some(node_id) = self.element_to_node.remove(&element_id) {
-                self.node_to_element.remove(&node_id);
-            }
-            if let Some(parent) = element.parent_element() {
-                let _ = parent.remove_child(&element);
-            }
+        let removal = self.elements.removal(element_id);
+        if let Some(element) = self.elements.remove(element_id) {
+            let _ = remove_from_parent(&element, removal);
         }
     }
     
     fn mount(&mut self, root: ElementId, node: &VirtualNode) {
-        let parent_opt = if root.0 == 0 {
-            self.root_element.clone()
-        } else {
-            self.element_map.get(&root).cloned()
-        };
-        
-        if let Some(parent) = parent_opt {
-            self.mount_recursive(&parent, node, 0);
+        let result = self.registry
+            .expand(node)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+            .and_then(|expanded| self.mount_expanded(root, &expanded));
+        if let Err(e) = result {
+            web_sys::console::error_1(&e);
         }
     }
     
     fn unmount(&mut self, root: ElementId) {
-        if let Some(element) = self.element_map.get(&root) {
+        if let Some(element) = self.elements.element(root) {
             if let Some(parent) = element.parent_element() {
                 let _ = parent.remove_child(element);
             }
         }
         self.remove_element(root);
     }
-    
+
 }
 
 impl WebRenderer {
-    fn mount_recursive(&mut self, parent: &Element, node: &VirtualNode, depth: usize) {
-        match &node.node_type {
-            NodeType::Element(_) | 
-            NodeType::Component(_) => {
-                if let Ok(element) = self.create_element_from_node(node) {
-                    let element_id = ElementId(self.next_element_id);
-                    self.next_element_id += 1;
-                    
-                    self.node_to_element.insert(node.id, element_id);
-                    self.element_to_node.insert(element_id, node.id);
-                    self.element_map.insert(element_id, element.clone());
-                    
-                    // Mount children
-                    for child in &node.children {
-                        self.mount_recursive(&element, child, depth + 1);
-                    }
-                    
-                    parent.append_child(&element).ok();
-                }
-            }
-            NodeType::Text(text) => {
-                let text_node = self.document.create_text_node(text);
-                parent.append_child(&text_node).ok();
-            }
-            NodeType::Fragment => {
-                for child in &node.children {
-                    self.mount_recursive(parent, child, depth);
+    /// Mounts a tree whose component nodes have already been expanded
+    fn mount_expanded(&mut self, root: ElementId, node: &VirtualNode) -> Result<(), JsValue> {
+        let parent_opt = if root.0 == 0 {
+            self.root_element.clone()
+        } else {
+            self.elements.element(root).cloned()
+        };
+        
+        if let Some(parent) = parent_opt {
+            let mut backend = DomBackend { document: &self.document, handlers: &self.handlers };
+            self.elements.mount(&mut backend, &parent, node)?;
+        }
+        Ok(())
+    }
+}
+
+/// Expands the components in a node delivered by a patch
+fn expand_subtree(registry: &ComponentRegistry, node: &VirtualNode) -> Result<VirtualNode, JsValue> {
+    registry.expand_subtree(node).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// Takes `element` out of its parent, once its exit transition has run
+/// if it has one
+fn remove_from_parent(element: &Element, removal: Removal) -> Result<(), JsValue> {
+    let Some(parent) = element.parent_element() else {
+        return Ok(());
+    };
+    match removal {
+        Removal::Immediate => {
+            parent.remove_child(element)?;
+        }
+        Removal::AfterTransition(class) => {
+            add_class(element, &class);
+            let leaving = element.clone();
+            let on_end = Closure::once_into_js(move || {
+                if let Some(parent) = leaving.parent_element() {
+                    let _ = parent.remove_child(&leaving);
                 }
-            }
+            });
+            let options = AddEventListenerOptions::new();
+            options.set_once(true);
+            element.add_event_listener_with_callback_and_add_event_listener_options(
+                "transitionend",
+                on_end.unchecked_ref(),
+                &options,
+            )?;
         }
     }
+    Ok(())
+}
+
+/// Applies the enter class and takes it off two frames later, once the
+/// element has been styled with it, so the element transitions in
+fn play_enter_transition(element: &Element, class: &str) -> Result<(), JsValue> {
+    let window = web_sys::window().ok_or_else(|| JsValue::from_str("No window"))?;
+    add_class(element, class);
+    
+    let entering = element.clone();
+    let class = class.to_string();
+    let next_window = window.clone();
+    let first_frame = Closure::once_into_js(move || {
+        let second_frame = Closure::once_into_js(move || remove_class(&entering, &class));
+        let _ = next_window.request_animation_frame(second_frame.unchecked_ref());
+    });
+    window.request_animation_frame(first_frame.unchecked_ref())?;
+    Ok(())
+}
+
+fn add_class(element: &Element, class: &str) {
+    let classes = element.class_name();
+    if !classes.split_whitespace().any(|existing| existing == class) {
+        element.set_class_name(classes.split_whitespace().chain([class]).collect::<Vec<_>>().join(" ").as_str());
+    }
+}
+
+fn remove_class(element: &Element, class: &str) {
+    let classes = element.class_name();
+    let kept: Vec<&str> = classes.split_whitespace().filter(|existing| *existing != class).collect();
+    element.set_class_name(&kept.join(" "));
 }
 
 #[wasm_bindgen]
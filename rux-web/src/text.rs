@@ -0,0 +1,46 @@
+use rux_core::layout::{Size, TextMeasurer};
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{CanvasRenderingContext2d, Document, HtmlCanvasElement};
+
+/// Line height as a multiple of the font size, matching the browser's
+/// `line-height: normal` for most fonts
+const LINE_HEIGHT: f32 = 1.2;
+
+/// Measures text with an offscreen canvas's `measureText`, so layout sees
+/// the same widths the browser renders
+pub struct CanvasTextMeasurer {
+    context: CanvasRenderingContext2d,
+    font_family: String,
+}
+
+impl CanvasTextMeasurer {
+    /// Measurer for text in `font_family`, a CSS font family list such as
+    /// `"Inter, sans-serif"`
+    pub fn new(document: &Document, font_family: impl Into<String>) -> Result<Self, JsValue> {
+        let canvas: HtmlCanvasElement = document.create_element("canvas")?.dyn_into()?;
+        let context = canvas
+            .get_context("2d")?
+            .ok_or_else(|| JsValue::from_str("canvas has no 2d context"))?
+            .dyn_into()?;
+        Ok(Self {
+            context,
+            font_family: font_family.into(),
+        })
+    }
+}
+
+impl TextMeasurer for CanvasTextMeasurer {
+    fn measure(&self, text: &str, font_size: f32) -> Size {
+        self.context.set_font(&format!("{}px {}", font_size, self.font_family));
+        let lines: Vec<&str> = text.lines().collect();
+        let width = lines
+            .iter()
+            .filter_map(|line| self.context.measure_text(line).ok())
+            .map(|metrics| metrics.width() as f32)
+            .fold(0.0, f32::max);
+        Size {
+            width,
+            height: lines.len().max(1) as f32 * font_size * LINE_HEIGHT,
+        }
+    }
+}
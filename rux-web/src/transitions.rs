@@ -0,0 +1,39 @@
+use rux_core::virtual_tree::{PropValue, VirtualNode};
+
+/// Prop naming a CSS class that is applied to a newly inserted element
+/// and taken off on the next frame, so it transitions into its normal style
+pub const ENTER_PROP: &str = "onEnter";
+
+/// Prop naming a CSS class that makes a removed element transition out.
+/// The element stays in the page until its `transitionend` fires, so the
+/// class has to start a transition.
+pub const EXIT_PROP: &str = "onExit";
+
+/// How an element leaves the page when its node is removed
+#[derive(Debug, Clone, PartialEq)]
+pub enum Removal {
+    Immediate,
+    /// Add the class, then remove the element once its transition ends
+    AfterTransition(String),
+}
+
+impl Removal {
+    pub fn for_node(node: &VirtualNode) -> Self {
+        match transition_class(node, EXIT_PROP) {
+            Some(class) => Removal::AfterTransition(class.to_string()),
+            None => Removal::Immediate,
+        }
+    }
+}
+
+/// The class to animate `node` in with, if it has one
+pub fn enter_class(node: &VirtualNode) -> Option<&str> {
+    transition_class(node, ENTER_PROP)
+}
+
+fn transition_class<'a>(node: &'a VirtualNode, prop: &str) -> Option<&'a str> {
+    match node.props.get(prop) {
+        Some(PropValue::String(class)) if !class.trim().is_empty() => Some(class.trim()),
+        _ => None,
+    }
+}
@@ -0,0 +1,71 @@
+#[cfg(test)]
+mod tests {
+    use rux_core::virtual_tree::PropValue;
+    use rux_web::{attribute_update, event_listener, AttributeUpdate, EventListener};
+
+    fn set(name: &str, value: &str) -> AttributeUpdate {
+        AttributeUpdate::Set(name.to_string(), value.to_string())
+    }
+
+    #[test]
+    fn test_enumerated_attribute_keeps_false() {
+        assert_eq!(attribute_update("draggable", &PropValue::Boolean(false)), set("draggable", "false"));
+        assert_eq!(attribute_update("draggable", &PropValue::Boolean(true)), set("draggable", "true"));
+        assert_eq!(attribute_update("aria-hidden", &PropValue::Boolean(false)), set("aria-hidden", "false"));
+    }
+
+    #[test]
+    fn test_boolean_attribute_removed_when_false() {
+        assert_eq!(
+            attribute_update("disabled", &PropValue::Boolean(false)),
+            AttributeUpdate::Remove("disabled".to_string())
+        );
+        assert_eq!(attribute_update("disabled", &PropValue::Boolean(true)), set("disabled", ""));
+    }
+
+    #[test]
+    fn test_other_prop_values() {
+        assert_eq!(attribute_update("onClick", &PropValue::String("go".to_string())), AttributeUpdate::Skip);
+        assert_eq!(attribute_update("tabindex", &PropValue::Number(1.0)), set("tabindex", "1"));
+        assert_eq!(
+            attribute_update("class", &PropValue::Array(vec!["a".into(), "b".into()])),
+            set("class", "a b")
+        );
+    }
+
+    #[test]
+    fn test_z_index_is_a_style() {
+        let style = AttributeUpdate::Style("z-index".to_string(), "5".to_string());
+        assert_eq!(attribute_update("z_index", &PropValue::Number(5.0)), style);
+        assert_eq!(attribute_update("z_index", &PropValue::String("5".to_string())), style);
+    }
+
+    #[test]
+    fn test_overflow_is_a_style() {
+        assert_eq!(
+            attribute_update("overflow", &PropValue::String("hidden".to_string())),
+            AttributeUpdate::Style("overflow".to_string(), "hidden".to_string())
+        );
+    }
+
+    #[test]
+    fn test_event_listener_records_modifiers() {
+        let handler = PropValue::Function("submit".to_string());
+        assert_eq!(
+            event_listener("onSubmit:prevent:stop", &handler),
+            Some(EventListener {
+                event: "submit".to_string(),
+                handler: "submit".to_string(),
+                prevent_default: true,
+                stop_propagation: true,
+            })
+        );
+
+        let plain = event_listener("onKeyDown", &handler).unwrap();
+        assert_eq!(plain.event, "keydown");
+        assert!(!plain.prevent_default && !plain.stop_propagation);
+
+        assert_eq!(event_listener("title", &PropValue::String("x".to_string())), None);
+        assert_eq!(event_listener("onClick", &PropValue::Boolean(true)), None);
+    }
+}
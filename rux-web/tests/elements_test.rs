@@ -0,0 +1,100 @@
+#[cfg(test)]
+mod tests {
+    use rux_core::virtual_tree::{NodeType, PropValue, VirtualNode};
+    use rux_web::{ElementBackend, ElementTable, Removal};
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+    use std::rc::Rc;
+
+    /// Stand-in for DOM elements, which don't exist outside wasm
+    #[derive(Debug, Default)]
+    struct FakeElement {
+        tag: String,
+        attributes: HashMap<String, PropValue>,
+        children: Vec<String>,
+    }
+
+    type Handle = Rc<RefCell<FakeElement>>;
+
+    struct FakeBackend;
+
+    impl ElementBackend for FakeBackend {
+        type Element = Handle;
+        type Error = ();
+
+        fn create_element(&mut self, node: &VirtualNode) -> Result<Handle, ()> {
+            let tag = match &node.node_type {
+                NodeType::Element(tag) | NodeType::Component(tag) => tag.clone(),
+                _ => return Err(()),
+            };
+            Ok(Rc::new(RefCell::new(FakeElement {
+                tag,
+                attributes: node.props.clone(),
+                children: Vec::new(),
+            })))
+        }
+
+        fn append_child(&mut self, parent: &Handle, child: &Handle) -> Result<(), ()> {
+            let tag = child.borrow().tag.clone();
+            parent.borrow_mut().children.push(tag);
+            Ok(())
+        }
+
+        fn append_text(&mut self, parent: &Handle, text: &str) -> Result<(), ()> {
+            parent.borrow_mut().children.push(format!("#{}", text));
+            Ok(())
+        }
+
+        fn set_prop(&mut self, element: &Handle, key: &str, value: &PropValue) -> Result<(), ()> {
+            element.borrow_mut().attributes.insert(key.to_string(), value.clone());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_insert_registers_whole_subtree() {
+        let mut backend = FakeBackend;
+        let mut table = ElementTable::new();
+
+        let root = VirtualNode::element("ul").build();
+        let container: Handle = Rc::default();
+        table.mount(&mut backend, &container, &root).unwrap();
+
+        let grandchild = VirtualNode::element("a").prop("href", "/old").build();
+        let inserted = VirtualNode::element("li")
+            .child(VirtualNode::element("span").child(grandchild.clone()).child(VirtualNode::text("label")))
+            .build();
+
+        // What the web renderer does for `Patch::Insert { parent_id: root.id, .. }`
+        let parent = table.node_element(root.id).cloned().unwrap();
+        table.mount(&mut backend, &parent, &inserted).unwrap();
+        assert_eq!(parent.borrow().children, vec!["li"]);
+
+        let props = HashMap::from([("href".to_string(), PropValue::String("/new".to_string()))]);
+        assert!(table.update_props(&mut backend, grandchild.id, &props).unwrap());
+
+        let link = table.node_element(grandchild.id).unwrap();
+        assert_eq!(link.borrow().attributes["href"], PropValue::String("/new".to_string()));
+        let span = table.node_element(inserted.children[0].id).unwrap();
+        assert_eq!(span.borrow().children, vec!["a", "#label"]);
+    }
+
+    #[test]
+    fn test_table_remembers_exit_transitions() {
+        let mut backend = FakeBackend;
+        let mut table = ElementTable::new();
+
+        let leaving = VirtualNode::element("li").prop("onExit", "fade-out").build();
+        let staying = VirtualNode::element("li").build();
+        let root = VirtualNode::element("ul").child(leaving.clone()).child(staying.clone()).build();
+        let container: Handle = Rc::default();
+        table.mount(&mut backend, &container, &root).unwrap();
+
+        let leaving_id = table.element_id(leaving.id).unwrap();
+        assert_eq!(table.removal(leaving_id), Removal::AfterTransition("fade-out".to_string()));
+        assert_eq!(table.removal(table.element_id(staying.id).unwrap()), Removal::Immediate);
+
+        table.remove(leaving_id);
+        assert_eq!(table.removal(leaving_id), Removal::Immediate);
+    }
+}
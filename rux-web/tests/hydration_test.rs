@@ -0,0 +1,198 @@
+#[cfg(test)]
+mod tests {
+    use rux_core::renderer::StringRenderer;
+    use rux_core::virtual_tree::{NodeType, PropValue, VirtualNode};
+    use rux_web::{ElementBackend, ElementTable, HydrationBackend};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Minimal DOM tree; text nodes aren't modelled since hydration skips them
+    #[derive(Debug, Default)]
+    struct FakeNode {
+        tag: String,
+        children: Vec<Handle>,
+        listeners_attached: bool,
+    }
+
+    type Handle = Rc<RefCell<FakeNode>>;
+
+    #[derive(Default)]
+    struct FakeDom {
+        created: usize,
+    }
+
+    fn element(tag: &str, children: Vec<Handle>) -> Handle {
+        Rc::new(RefCell::new(FakeNode {
+            tag: tag.to_uppercase(),
+            children,
+            listeners_attached: false,
+        }))
+    }
+
+    impl ElementBackend for FakeDom {
+        type Element = Handle;
+        type Error = ();
+
+        fn create_element(&mut self, node: &VirtualNode) -> Result<Handle, ()> {
+            let NodeType::Element(tag) = &node.node_type else {
+                return Err(());
+            };
+            self.created += 1;
+            Ok(element(tag, Vec::new()))
+        }
+
+        fn append_child(&mut self, parent: &Handle, child: &Handle) -> Result<(), ()> {
+            parent.borrow_mut().children.push(child.clone());
+            Ok(())
+        }
+
+        fn append_text(&mut self, _parent: &Handle, _text: &str) -> Result<(), ()> {
+            Ok(())
+        }
+
+        fn set_prop(&mut self, _element: &Handle, _key: &str, _value: &PropValue) -> Result<(), ()> {
+            Ok(())
+        }
+    }
+
+    impl HydrationBackend for FakeDom {
+        fn element_children(&mut self, parent: &Handle) -> Vec<Handle> {
+            parent.borrow().children.clone()
+        }
+
+        fn tag_name(&mut self, element: &Handle) -> String {
+            element.borrow().tag.clone()
+        }
+
+        fn replace_child(&mut self, parent: &Handle, new: &Handle, old: &Handle) -> Result<(), ()> {
+            let mut parent = parent.borrow_mut();
+            let index = parent.children.iter().position(|c| Rc::ptr_eq(c, old)).ok_or(())?;
+            parent.children[index] = new.clone();
+            Ok(())
+        }
+
+        fn remove_child(&mut self, parent: &Handle, child: &Handle) -> Result<(), ()> {
+            parent.borrow_mut().children.retain(|c| !Rc::ptr_eq(c, child));
+            Ok(())
+        }
+
+        fn attach_listeners(&mut self, element: &Handle, _node: &VirtualNode) -> Result<(), ()> {
+            element.borrow_mut().listeners_attached = true;
+            Ok(())
+        }
+    }
+
+    fn tree() -> VirtualNode {
+        VirtualNode::element("div")
+            .child(VirtualNode::element("h1").child(VirtualNode::text("Title")))
+            .child(VirtualNode::element("button").prop("onClick", "go"))
+            .build()
+    }
+
+    #[test]
+    fn test_hydrate_reuses_existing_elements() {
+        // What the server rendered for `tree()`
+        let button = element("button", Vec::new());
+        let root = element("div", vec![element("h1", Vec::new()), button.clone()]);
+        let container = element("main", vec![root.clone()]);
+
+        let node = tree();
+        let mut dom = FakeDom::default();
+        let mut table = ElementTable::new();
+        let mismatches = table.hydrate(&mut dom, &container, std::slice::from_ref(&node)).unwrap();
+
+        assert!(mismatches.is_empty());
+        assert_eq!(dom.created, 0);
+        assert!(Rc::ptr_eq(table.node_element(node.id).unwrap(), &root));
+        assert!(Rc::ptr_eq(table.node_element(node.children[1].id).unwrap(), &button));
+        assert!(button.borrow().listeners_attached);
+    }
+
+    #[test]
+    fn test_hydrate_replaces_mismatches() {
+        let stale = element("span", Vec::new());
+        let root = element("div", vec![element("h1", Vec::new()), stale.clone(), element("footer", Vec::new())]);
+        let container = element("main", vec![root.clone()]);
+
+        let node = tree();
+        let mut dom = FakeDom::default();
+        let mut table = ElementTable::new();
+        let mismatches = table.hydrate(&mut dom, &container, std::slice::from_ref(&node)).unwrap();
+
+        assert_eq!(
+            mismatches,
+            vec![
+                "expected <button> but found <span>, replacing it".to_string(),
+                "unexpected <footer>, removing it".to_string(),
+            ]
+        );
+        assert_eq!(dom.created, 1);
+
+        let children = root.borrow().children.clone();
+        assert_eq!(children.len(), 2);
+        assert_eq!(children[1].borrow().tag, "BUTTON");
+        assert!(Rc::ptr_eq(table.node_element(node.children[1].id).unwrap(), &children[1]));
+    }
+
+    #[test]
+    fn test_replace_mounts_the_whole_new_subtree() {
+        let node = tree();
+        let container = element("main", Vec::new());
+        let mut dom = FakeDom::default();
+        let mut table = ElementTable::new();
+        table.mount(&mut dom, &container, &node).unwrap();
+        let root = container.borrow().children[0].clone();
+
+        // What the web renderer does for `Patch::Replace` of the heading
+        let link = VirtualNode::element("a").prop("href", "/").build();
+        let nav = VirtualNode::element("nav").child(VirtualNode::element("li").child(link.clone())).build();
+        let heading = table.element_id(node.children[0].id).unwrap();
+        table.replace_subtree(&mut dom, &root, heading, &nav).unwrap();
+
+        let children = root.borrow().children.clone();
+        assert_eq!(children[0].borrow().tag, "NAV");
+        assert_eq!(children[0].borrow().children[0].borrow().children[0].borrow().tag, "A");
+        assert!(table.element_id(node.children[0].id).is_none());
+        assert!(Rc::ptr_eq(table.node_element(nav.id).unwrap(), &children[0]));
+        assert!(table.node_element(link.id).is_some(), "descendants are registered for later patches");
+    }
+
+    /// The elements in `html` as a fake DOM under a `<main>`, text dropped.
+    /// Handles only the nesting `StringRenderer` writes for the test tree.
+    fn parse_dom(html: &str) -> Handle {
+        let mut stack = vec![element("main", Vec::new())];
+        for tag in html.split('<').skip(1).filter_map(|part| part.split('>').next()) {
+            if tag.starts_with('/') {
+                stack.pop();
+            } else {
+                let name = tag.split_whitespace().next().unwrap();
+                let child = element(name, Vec::new());
+                stack.last().unwrap().borrow_mut().children.push(child.clone());
+                stack.push(child);
+            }
+        }
+        stack.swap_remove(0)
+    }
+
+    #[test]
+    fn test_hydrate_keeps_server_rendered_raw_html() {
+        let node = VirtualNode::element("article")
+            .child(VirtualNode::raw_html("<b>x</b>"))
+            .child(VirtualNode::element("p").child(VirtualNode::text("after")))
+            .build();
+        let container = parse_dom(&StringRenderer::render(&node));
+
+        let mut dom = FakeDom::default();
+        let mut table = ElementTable::new();
+        let mismatches = table.hydrate(&mut dom, &container, std::slice::from_ref(&node)).unwrap();
+
+        assert!(mismatches.is_empty(), "{:?}", mismatches);
+        assert_eq!(dom.created, 0);
+        let article = container.borrow().children[0].clone();
+        let children = article.borrow().children.clone();
+        let tags: Vec<_> = children.iter().map(|child| child.borrow().tag.clone()).collect();
+        assert_eq!(tags, ["DIV", "P"]);
+        assert_eq!(children[0].borrow().children[0].borrow().tag, "B");
+        assert!(Rc::ptr_eq(table.node_element(node.children[0].id).unwrap(), &children[0]));
+    }
+}
@@ -0,0 +1,25 @@
+#[cfg(test)]
+mod tests {
+    use rux_core::virtual_tree::VirtualNode;
+    use rux_web::{enter_class, Removal};
+
+    #[test]
+    fn test_exit_prop_defers_removal() {
+        let node = VirtualNode::element("li").prop("onExit", "fade-out").build();
+        assert_eq!(Removal::for_node(&node), Removal::AfterTransition("fade-out".to_string()));
+
+        let node = VirtualNode::element("li").build();
+        assert_eq!(Removal::for_node(&node), Removal::Immediate);
+
+        // Only a class name can drive a transition
+        let node = VirtualNode::element("li").prop("onExit", true).prop("onEnter", " ").build();
+        assert_eq!(Removal::for_node(&node), Removal::Immediate);
+        assert_eq!(enter_class(&node), None);
+    }
+
+    #[test]
+    fn test_enter_class() {
+        let node = VirtualNode::element("li").prop("onEnter", "fade-in").build();
+        assert_eq!(enter_class(&node), Some("fade-in"));
+    }
+}
@@ -50,7 +50,7 @@ fn Counter() -> Element {
     
     // Execute component
     let executor = ComponentExecutor::new();
-    let virtual_node = executor.execute_component(component);
+    let virtual_node = executor.execute_component(component).expect("Should execute");
     
     // Verify virtual tree structure
     match virtual_node.node_type {